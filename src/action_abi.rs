@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Capability hints for action execution in the host.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[allow(dead_code)]
 pub enum ActionCapability {
@@ -13,8 +13,35 @@ pub enum ActionCapability {
 	Execute,
 }
 
+impl ActionCapability {
+	/// Nama capability di sintaks kebijakan, mis. `requires: [Network, FileSystem]`.
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name.to_lowercase().as_str() {
+			"read" => Some(ActionCapability::Read),
+			"write" => Some(ActionCapability::Write),
+			"network" => Some(ActionCapability::Network),
+			"filesystem" | "file_system" => Some(ActionCapability::FileSystem),
+			"execute" => Some(ActionCapability::Execute),
+			_ => None,
+		}
+	}
+}
+
+impl std::fmt::Display for ActionCapability {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			ActionCapability::Read => "Read",
+			ActionCapability::Write => "Write",
+			ActionCapability::Network => "Network",
+			ActionCapability::FileSystem => "FileSystem",
+			ActionCapability::Execute => "Execute",
+		};
+		write!(f, "{}", name)
+	}
+}
+
 /// Optional metadata for action execution.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[allow(dead_code)]
 pub struct ActionMetadata {
 	pub timeout_ms: Option<u64>,
@@ -23,7 +50,7 @@ pub struct ActionMetadata {
 }
 
 /// Payload contract between policy decisions and host adapters.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct ActionPayload {
 	pub action: String,
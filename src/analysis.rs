@@ -0,0 +1,851 @@
+//! Analisis statis kondisi rule untuk linter: rule yang tidak pernah aktif,
+//! rule yang tertutup rule sebelumnya, aksi berlawanan yang bisa aktif bersamaan,
+//! dan arm `MATCH` yang tidak terjangkau.
+//!
+//! Setiap kondisi diubah ke DNF: daftar [`Region`], masing-masing berisi batasan
+//! per field (interval numerik dalam satuan dasar, himpunan string, atau boolean).
+//! Bagian yang tidak bisa dimodelkan (operator temporal, `COUNT`, perbandingan antar
+//! field) menjadi literal buram yang hanya bertentangan dengan negasinya sendiri.
+//! Temuan hanya dilaporkan jika pasti, beserta konteks saksi bila ada.
+
+use crate::ast::{
+    ActionCall, CombiningAlgorithm, CompareOp, Condition, FieldPath, MatchPattern, Operand, PathSegment, Policy,
+    PolicyMatchRule, Rule, RuleEffect, StandardRule,
+};
+use crate::units::{self, Quantity};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+// Batas ukuran DNF per kondisi; kondisi yang lebih besar dilewati
+const MAX_REGIONS: usize = 64;
+
+/// Pasangan awalan nama aksi yang saling berlawanan, mis. `StartPump`/`StopPump`.
+const OPPOSITES: &[(&str, &str)] = &[
+    ("Start", "Stop"),
+    ("Open", "Close"),
+    ("Lock", "Unlock"),
+    ("Enable", "Disable"),
+    ("Activate", "Deactivate"),
+    ("Increase", "Decrease"),
+    ("Increase", "Reduce"),
+    ("TurnOn", "TurnOff"),
+    ("Engage", "Disengage"),
+    ("Connect", "Disconnect"),
+    ("Arm", "Disarm"),
+    ("Grant", "Revoke"),
+    ("Approve", "Reject"),
+    ("Accept", "Reject"),
+    ("Allow", "Block"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// Kondisi rule tidak mungkin benar.
+    DeadRule,
+    /// Kondisi rule selalu benar sehingga cabang `ELSE` tidak pernah aktif.
+    DeadElse,
+    /// Dengan `first-match`, rule sebelumnya selalu cocok lebih dulu.
+    Shadowed,
+    /// Rule sebelumnya dengan aksi yang sama sudah mencakup kondisi ini.
+    Redundant,
+    /// Setiap kali rule ini aktif, rule sebelumnya (beraksi lain) juga aktif.
+    Subsumed,
+    /// Dua aksi yang saling berlawanan bisa aktif bersamaan.
+    Contradiction,
+    UnreachableArm,
+}
+
+/// Satu temuan analisis. `rules[0]` adalah rule/arm yang bermasalah; sisanya
+/// rule pembanding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    pub rules: Vec<String>,
+    /// Baris sumber rule pertama di `rules`.
+    pub line: usize,
+    pub message: String,
+    /// Konteks yang menunjukkan temuan, mis. nilai yang membuat dua aksi berlawanan aktif.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub witness: Option<Value>,
+    /// Kondisi buram (temporal, `COUNT`) yang juga harus benar agar saksi berlaku.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assuming: Vec<String>,
+}
+
+/// Analisis seluruh rule kebijakan. Rule di dalam `FOR`/`WHILE` dibandingkan
+/// dengan rule lain di badan loop yang sama.
+pub fn analyze(policy: &Policy) -> Vec<Finding> {
+    let analyzer = Analyzer::new(policy);
+    let mut findings = Vec::new();
+    let scope = Scope { prefix: String::new(), guard: vec![Region::default()], bindings: Vec::new() };
+    analyzer.analyze_group(&policy.rules, &scope, &mut findings);
+    findings
+}
+
+/// Apakah dua aksi saling berlawanan menurut nama (`StartMachine`/`StopMachine`).
+pub fn opposite_actions(a: &str, b: &str) -> bool {
+    OPPOSITES.iter().any(|(x, y)| {
+        let rest = |name: &str, prefix: &str| -> Option<String> {
+            let head = name.get(..prefix.len())?;
+            let tail = &name[prefix.len()..];
+            let boundary = tail.chars().next().is_none_or(|c| !c.is_lowercase());
+            (head.eq_ignore_ascii_case(prefix) && boundary).then(|| tail.trim_start_matches('_').to_lowercase())
+        };
+        let matches = |p: &str, q: &str| matches!((rest(a, p), rest(b, q)), (Some(l), Some(r)) if l == r);
+        matches(x, y) || matches(y, x)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bound {
+    value: f64,
+    inclusive: bool,
+}
+
+/// Interval dalam satuan dasar; `None` berarti tak terbatas.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct Interval {
+    lo: Option<Bound>,
+    hi: Option<Bound>,
+}
+
+impl Interval {
+    fn meet(&self, other: &Interval) -> Option<Interval> {
+        let lo = match (self.lo, other.lo) {
+            (Some(a), Some(b)) if a.value == b.value => Some(Bound { value: a.value, inclusive: a.inclusive && b.inclusive }),
+            (Some(a), Some(b)) => Some(if a.value > b.value { a } else { b }),
+            (a, b) => a.or(b),
+        };
+        let hi = match (self.hi, other.hi) {
+            (Some(a), Some(b)) if a.value == b.value => Some(Bound { value: a.value, inclusive: a.inclusive && b.inclusive }),
+            (Some(a), Some(b)) => Some(if a.value < b.value { a } else { b }),
+            (a, b) => a.or(b),
+        };
+        if let (Some(l), Some(h)) = (lo, hi) {
+            if l.value > h.value || (l.value == h.value && !(l.inclusive && h.inclusive)) {
+                return None;
+            }
+        }
+        Some(Interval { lo, hi })
+    }
+
+    fn contains(&self, other: &Interval) -> bool {
+        let lower = match (self.lo, other.lo) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(a), Some(b)) => a.value < b.value || (a.value == b.value && (a.inclusive || !b.inclusive)),
+        };
+        let upper = match (self.hi, other.hi) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(a), Some(b)) => a.value > b.value || (a.value == b.value && (a.inclusive || !b.inclusive)),
+        };
+        lower && upper
+    }
+
+    /// Nilai contoh di dalam interval; bilangan bulat bila memungkinkan.
+    fn sample(&self) -> f64 {
+        let inside = |v: f64| self.meet(&Interval { lo: Some(Bound { value: v, inclusive: true }), hi: Some(Bound { value: v, inclusive: true }) }).is_some();
+        let mut candidates = Vec::new();
+        if let Some(lo) = self.lo {
+            candidates.extend([lo.value, lo.value.ceil(), lo.value.floor() + 1.0]);
+        }
+        if let Some(hi) = self.hi {
+            candidates.extend([hi.value, hi.value.floor(), hi.value.ceil() - 1.0]);
+        }
+        candidates.push(0.0);
+        if let (Some(lo), Some(hi)) = (self.lo, self.hi) {
+            candidates.push((lo.value + hi.value) / 2.0);
+        }
+        candidates.into_iter().find(|v| inside(*v)).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum StrSet {
+    In(BTreeSet<String>),
+    NotIn(BTreeSet<String>),
+}
+
+impl StrSet {
+    fn meet(&self, other: &StrSet) -> Option<StrSet> {
+        let set = match (self, other) {
+            (StrSet::In(a), StrSet::In(b)) => StrSet::In(a.intersection(b).cloned().collect()),
+            (StrSet::In(a), StrSet::NotIn(b)) | (StrSet::NotIn(b), StrSet::In(a)) => {
+                StrSet::In(a.difference(b).cloned().collect())
+            }
+            (StrSet::NotIn(a), StrSet::NotIn(b)) => StrSet::NotIn(a.union(b).cloned().collect()),
+        };
+        match &set {
+            StrSet::In(values) if values.is_empty() => None,
+            _ => Some(set),
+        }
+    }
+
+    fn contains(&self, other: &StrSet) -> bool {
+        match (self, other) {
+            (StrSet::In(a), StrSet::In(b)) => b.is_subset(a),
+            (StrSet::In(_), StrSet::NotIn(_)) => false,
+            (StrSet::NotIn(a), StrSet::In(b)) => a.is_disjoint(b),
+            (StrSet::NotIn(a), StrSet::NotIn(b)) => a.is_subset(b),
+        }
+    }
+
+    fn sample(&self) -> String {
+        match self {
+            StrSet::In(values) => values.iter().next().cloned().unwrap_or_default(),
+            StrSet::NotIn(values) => (0..)
+                .map(|i| if i == 0 { "Other".to_string() } else { format!("Other{}", i) })
+                .find(|candidate| !values.contains(candidate))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Domain {
+    Num(Interval),
+    Str(StrSet),
+    Bool(bool),
+    /// Field tidak ada di konteks.
+    Absent,
+    /// Batasan dengan tipe campuran; tidak dipakai untuk klaim apa pun.
+    Unknown,
+}
+
+/// Batasan satu field. Perbandingan positif gagal jika field tidak ada, sehingga
+/// `required`; literal negatif juga terpenuhi oleh field yang tidak ada.
+#[derive(Debug, Clone, PartialEq)]
+struct FieldCons {
+    path: FieldPath,
+    unit: Option<String>,
+    domain: Domain,
+    required: bool,
+}
+
+impl FieldCons {
+    fn meet(&self, other: &FieldCons) -> Option<FieldCons> {
+        let required = self.required || other.required;
+        let domain = match (&self.domain, &other.domain) {
+            (Domain::Unknown, _) | (_, Domain::Unknown) => Some(Domain::Unknown),
+            (Domain::Absent, _) | (_, Domain::Absent) => None,
+            (Domain::Num(a), Domain::Num(b)) => a.meet(b).map(Domain::Num),
+            (Domain::Str(a), Domain::Str(b)) => a.meet(b).map(Domain::Str),
+            (Domain::Bool(a), Domain::Bool(b)) => (a == b).then_some(Domain::Bool(*a)),
+            _ => Some(Domain::Unknown),
+        };
+        let domain = match domain {
+            Some(domain) => domain,
+            None if required => return None,
+            None => Domain::Absent,
+        };
+        Some(FieldCons { domain, required, ..self.clone() })
+    }
+
+    fn contains(&self, other: &FieldCons) -> bool {
+        if other.domain == Domain::Absent || !other.required {
+            // Konteks tanpa field ini juga memenuhi `other`
+            if self.required {
+                return false;
+            }
+        }
+        match (&self.domain, &other.domain) {
+            (Domain::Unknown, _) | (_, Domain::Unknown) => false,
+            (_, Domain::Absent) => true,
+            (Domain::Absent, _) => false,
+            (Domain::Num(a), Domain::Num(b)) => a.contains(b),
+            (Domain::Str(a), Domain::Str(b)) => a.contains(b),
+            (Domain::Bool(a), Domain::Bool(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    fn sample(&self) -> Option<Value> {
+        match &self.domain {
+            Domain::Num(interval) => {
+                let base = interval.sample();
+                let raw = match self.unit.as_deref().and_then(units::lookup) {
+                    Some(def) => (base - def.offset) / def.scale,
+                    None => base,
+                };
+                Some(number(raw))
+            }
+            Domain::Str(set) => Some(Value::String(set.sample())),
+            Domain::Bool(b) => Some(Value::Bool(*b)),
+            Domain::Absent | Domain::Unknown => None,
+        }
+    }
+}
+
+/// Satu konjungsi dalam DNF.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Region {
+    fields: BTreeMap<String, FieldCons>,
+    /// Literal buram beserta polaritasnya.
+    opaque: BTreeMap<String, bool>,
+}
+
+impl Region {
+    fn meet(&self, other: &Region) -> Option<Region> {
+        let mut merged = self.clone();
+        for (key, cons) in &other.fields {
+            let next = match merged.fields.get(key) {
+                Some(existing) => existing.meet(cons)?,
+                None => cons.clone(),
+            };
+            merged.fields.insert(key.clone(), next);
+        }
+        for (key, positive) in &other.opaque {
+            if merged.opaque.insert(key.clone(), *positive).is_some_and(|prev| prev != *positive) {
+                return None;
+            }
+        }
+        Some(merged)
+    }
+
+    fn contains(&self, other: &Region) -> bool {
+        self.fields
+            .iter()
+            .all(|(key, cons)| other.fields.get(key).is_some_and(|o| cons.contains(o)))
+            && self.opaque.iter().all(|(key, positive)| other.opaque.get(key) == Some(positive))
+    }
+
+    fn is_exact(&self) -> bool {
+        self.fields.values().all(|cons| cons.domain != Domain::Unknown)
+    }
+
+    fn assuming(&self) -> Vec<String> {
+        self.opaque
+            .iter()
+            .map(|(key, positive)| if *positive { key.clone() } else { format!("NOT ({})", key) })
+            .collect()
+    }
+}
+
+/// Rule-rule dalam satu daftar (tingkat atas atau badan loop).
+struct Scope {
+    prefix: String,
+    /// Kondisi yang berlaku untuk seluruh grup, mis. kondisi `WHILE`.
+    guard: Vec<Region>,
+    /// `(iterator, koleksi)` dari loop `FOR` yang melingkupi, terluar lebih dulu.
+    bindings: Vec<(String, String)>,
+}
+
+/// Cabang `THEN`/`ELSE` sebuah rule beserta wilayah kondisinya.
+struct Branch<'a> {
+    label: String,
+    line: usize,
+    action: &'a ActionCall,
+    effect: RuleEffect,
+    priority: i64,
+    regions: Vec<Region>,
+}
+
+struct Analyzer {
+    combining: CombiningAlgorithm,
+    /// Nama field konteks; path lain di sisi kanan perbandingan adalah simbol (`Mode == Auto`).
+    fields: HashSet<String>,
+    /// Pasangan aksi yang dilarang keluar bersamaan oleh invarian `CONSTRAINT:`.
+    forbidden: Vec<(String, String)>,
+}
+
+impl Analyzer {
+    fn new(policy: &Policy) -> Self {
+        let mut fields: HashSet<String> = policy.schema.iter().map(|f| f.field.clone()).collect();
+        fields.extend(policy.units.iter().map(|u| u.field.clone()));
+        collect_field_names(&policy.rules, &mut fields);
+        let forbidden = crate::invariant::exclusive_pairs(policy.constraints.iter().filter_map(|c| c.invariant.as_ref()));
+        Analyzer { combining: policy.combining, fields, forbidden }
+    }
+
+    fn analyze_group(&self, rules: &[Rule], scope: &Scope, findings: &mut Vec<Finding>) {
+        let mut branches: Vec<(usize, Branch)> = Vec::new();
+        let mut fires: Vec<Option<(String, &ActionCall, Vec<Region>)>> = Vec::new();
+
+        for (index, rule) in rules.iter().enumerate() {
+            let label = format!("{}{}", scope.prefix, rule_label(rule, index));
+            match rule {
+                Rule::Standard(std_rule) => {
+                    let entry = self.analyze_rule(std_rule, &label, scope, &fires, findings);
+                    if let Some((then_regions, else_regions)) = &entry {
+                        branches.push((index, self.branch(&label, &std_rule.action, std_rule, then_regions)));
+                        if let (Some(else_action), Some(else_regions)) = (&std_rule.else_action, else_regions) {
+                            branches.push((index, self.branch(&format!("{} (ELSE)", label), else_action, std_rule, else_regions)));
+                        }
+                    }
+                    fires.push(entry.map(|(then_regions, _)| (label, &std_rule.action, then_regions)));
+                }
+                Rule::Match(match_rule) => {
+                    self.check_match(match_rule, &label, scope, findings);
+                    fires.push(None);
+                }
+                Rule::For(for_rule) => {
+                    let mut bindings = scope.bindings.clone();
+                    bindings.push((for_rule.iterator.clone(), for_rule.collection.clone()));
+                    let inner = Scope { prefix: format!("{}: ", label), guard: scope.guard.clone(), bindings };
+                    self.analyze_group(&for_rule.body, &inner, findings);
+                    fires.push(None);
+                }
+                Rule::While(while_rule) => {
+                    if let Some(guard) = self.dnf(&while_rule.condition, false).and_then(|d| product(&scope.guard, &d)) {
+                        let inner = Scope { prefix: format!("{}: ", label), guard, bindings: scope.bindings.clone() };
+                        self.analyze_group(&while_rule.body, &inner, findings);
+                    }
+                    fires.push(None);
+                }
+            }
+        }
+
+        self.check_contradictions(&branches, scope, findings);
+    }
+
+    /// Periksa satu rule terhadap rule sebelumnya; kembalikan wilayah `THEN`/`ELSE`-nya.
+    #[allow(clippy::type_complexity)]
+    fn analyze_rule(
+        &self,
+        rule: &StandardRule,
+        label: &str,
+        scope: &Scope,
+        earlier: &[Option<(String, &ActionCall, Vec<Region>)>],
+        findings: &mut Vec<Finding>,
+    ) -> Option<(Vec<Region>, Option<Vec<Region>>)> {
+        let then_regions = product(&scope.guard, &self.dnf(&rule.condition, false)?)?;
+        let else_regions = rule
+            .else_action
+            .as_ref()
+            .and_then(|_| product(&scope.guard, &self.dnf(&rule.condition, true)?));
+
+        if then_regions.is_empty() {
+            findings.push(Finding {
+                kind: FindingKind::DeadRule,
+                rules: vec![label.to_string()],
+                line: rule.line,
+                message: format!("{} can never fire: {}", label, self.explain_unsat(&rule.condition)),
+                witness: None,
+                assuming: Vec::new(),
+            });
+            return Some((then_regions, else_regions));
+        }
+        if else_regions.as_ref().is_some_and(|regions| regions.is_empty()) {
+            findings.push(Finding {
+                kind: FindingKind::DeadElse,
+                rules: vec![label.to_string()],
+                line: rule.line,
+                message: format!("ELSE of {} can never fire: condition '{}' is always true", label, rule.condition),
+                witness: then_regions.first().map(|r| self.witness(r, scope)),
+                assuming: Vec::new(),
+            });
+        }
+
+        let covering = earlier.iter().flatten().find(|(_, _, regions)| {
+            then_regions.iter().all(|inner| inner.is_exact() && regions.iter().any(|outer| outer.is_exact() && outer.contains(inner)))
+        });
+        if let Some((other, other_action, _)) = covering {
+            let (kind, message) = if self.combining == CombiningAlgorithm::FirstMatch {
+                (FindingKind::Shadowed, format!("{} never fires under first-match: {} always matches first", label, other))
+            } else if **other_action == rule.action {
+                (FindingKind::Redundant, format!("{} is redundant: {} already triggers {} whenever it matches", label, other, rule.action))
+            } else {
+                (FindingKind::Subsumed, format!("{} is subsumed by {}: whenever it fires, {} fires too", label, other, other))
+            };
+            findings.push(Finding {
+                kind,
+                rules: vec![label.to_string(), other.clone()],
+                line: rule.line,
+                message,
+                witness: Some(self.witness(&then_regions[0], scope)),
+                assuming: then_regions[0].assuming(),
+            });
+        }
+        Some((then_regions, else_regions))
+    }
+
+    fn branch<'a>(&self, label: &str, action: &'a ActionCall, rule: &StandardRule, regions: &[Region]) -> Branch<'a> {
+        Branch { label: label.to_string(), line: rule.line, action, effect: rule.effect, priority: rule.priority, regions: regions.to_vec() }
+    }
+
+    fn check_contradictions(&self, branches: &[(usize, Branch)], scope: &Scope, findings: &mut Vec<Finding>) {
+        for (i, (rule_a, a)) in branches.iter().enumerate() {
+            for (rule_b, b) in &branches[i + 1..] {
+                let forbidden = self.is_forbidden(&a.action.name, &b.action.name);
+                if rule_a == rule_b
+                    || !(forbidden || opposite_actions(&a.action.name, &b.action.name))
+                    || !self.can_co_fire(a, b)
+                {
+                    continue;
+                }
+                let overlap = a
+                    .regions
+                    .iter()
+                    .flat_map(|ra| b.regions.iter().filter_map(move |rb| ra.meet(rb)))
+                    .find(Region::is_exact);
+                if let Some(region) = overlap {
+                    findings.push(Finding {
+                        kind: FindingKind::Contradiction,
+                        rules: vec![b.label.clone(), a.label.clone()],
+                        line: b.line,
+                        message: format!(
+                            "{} ({}) and {} ({}) can trigger together{}",
+                            a.action.name,
+                            a.label,
+                            b.action.name,
+                            b.label,
+                            if forbidden { "; forbidden by a CONSTRAINT invariant, which vetoes them at runtime" } else { "" }
+                        ),
+                        witness: Some(self.witness(&region, scope)),
+                        assuming: region.assuming(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn is_forbidden(&self, a: &str, b: &str) -> bool {
+        self.forbidden.iter().any(|(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+
+    fn can_co_fire(&self, a: &Branch, b: &Branch) -> bool {
+        match self.combining {
+            CombiningAlgorithm::AllMatches => true,
+            CombiningAlgorithm::FirstMatch => false,
+            CombiningAlgorithm::DenyOverrides => a.effect == b.effect,
+            CombiningAlgorithm::PriorityOrdered => a.priority == b.priority,
+        }
+    }
+
+    fn check_match(&self, rule: &PolicyMatchRule, label: &str, scope: &Scope, findings: &mut Vec<Finding>) {
+        let mut wildcard: Option<usize> = None;
+        let mut seen: Vec<(usize, Literal)> = Vec::new();
+        for (index, arm) in rule.arms.iter().enumerate() {
+            let arm_label = format!("{} arm #{} ({})", label, index + 1, arm.pattern);
+            let literal = match &arm.pattern {
+                MatchPattern::Wildcard => None,
+                MatchPattern::Value { value } => self.literal(value),
+            };
+            let caught_by = wildcard
+                .map(|w| (w, "matches every value".to_string()))
+                .or_else(|| {
+                    let literal = literal.as_ref()?;
+                    seen.iter()
+                        .find(|(_, other)| other.same(literal))
+                        .map(|(k, _)| (*k, format!("already matches {}", arm.pattern)))
+                });
+            if let Some((earlier, reason)) = caught_by {
+                let witness = match (&rule.scrutinee, &arm.pattern) {
+                    (Operand::Path { path, .. }, MatchPattern::Value { value }) => {
+                        let mut root = Value::Object(Map::new());
+                        if let Some(v) = literal_json(value) {
+                            insert_path(&mut root, &bind_path(path, &scope.bindings), v);
+                        }
+                        Some(root)
+                    }
+                    _ => None,
+                };
+                findings.push(Finding {
+                    kind: FindingKind::UnreachableArm,
+                    rules: vec![arm_label.clone(), format!("{} arm #{}", label, earlier + 1)],
+                    line: rule.line,
+                    message: format!("{} is unreachable: arm #{} {}", arm_label, earlier + 1, reason),
+                    witness,
+                    assuming: Vec::new(),
+                });
+            }
+            match (&arm.pattern, literal) {
+                (MatchPattern::Wildcard, _) => wildcard = wildcard.or(Some(index)),
+                (_, Some(literal)) => seen.push((index, literal)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Ubah kondisi (atau negasinya) ke DNF. `None` jika terlalu besar.
+    fn dnf(&self, cond: &Condition, negated: bool) -> Option<Vec<Region>> {
+        match cond {
+            Condition::Const { value } => Some(if *value != negated { vec![Region::default()] } else { Vec::new() }),
+            Condition::Compare { left, op, right } => Some(self.compare(cond, left, *op, right, negated)),
+            Condition::In { value, set: Operand::List { items } } => {
+                let terms = items
+                    .iter()
+                    .map(|item| Condition::Compare { left: value.clone(), op: CompareOp::Eq, right: item.clone() })
+                    .collect();
+                self.dnf(&Condition::Or { terms }, negated)
+            }
+            Condition::Truthy { value: Operand::Path { path, unit } } => {
+                Some(vec![single(path, unit, Domain::Bool(!negated), !negated)])
+            }
+            Condition::Not { inner } => self.dnf(inner, !negated),
+            Condition::And { terms } | Condition::Or { terms } => {
+                let conjunctive = matches!(cond, Condition::And { .. }) != negated;
+                let mut acc = if conjunctive { vec![Region::default()] } else { Vec::new() };
+                for term in terms {
+                    let next = self.dnf(term, negated)?;
+                    acc = if conjunctive { product(&acc, &next)? } else { union(acc, next)? };
+                }
+                Some(acc)
+            }
+            // `X FOR 5s` dan `BECOMES X` hanya benar jika `X` benar saat ini
+            Condition::Sustained { inner, .. } | Condition::Becomes { inner } if !negated => {
+                product(&self.dnf(inner, false)?, &[opaque(cond, false)])
+            }
+            _ => Some(vec![opaque(cond, negated)]),
+        }
+    }
+
+    fn compare(&self, cond: &Condition, left: &Operand, op: CompareOp, right: &Operand, negated: bool) -> Vec<Region> {
+        let (path, unit, op, literal) = match (left, self.literal(right), self.literal(left), right) {
+            (Operand::Path { path, unit }, Some(literal), _, _) => (path, unit, op, literal),
+            (_, _, Some(literal), Operand::Path { path, unit }) if !self.is_symbol(right) => (path, unit, flip(op), literal),
+            _ => return vec![opaque(cond, negated)],
+        };
+        let op = if negated { negate(op) } else { op };
+        let required = !negated;
+        let domains = match literal {
+            Literal::Num(q) => {
+                let field_dimension = unit.as_deref().and_then(units::lookup).map(|d| d.dimension);
+                if matches!((field_dimension, q.dimension), (Some(f), Some(l)) if f != l) {
+                    return vec![opaque(cond, negated)];
+                }
+                let v = q.value;
+                let bound = |inclusive| Some(Bound { value: v, inclusive });
+                match op {
+                    CompareOp::Lt => vec![Interval { lo: None, hi: bound(false) }],
+                    CompareOp::Lte => vec![Interval { lo: None, hi: bound(true) }],
+                    CompareOp::Gt => vec![Interval { lo: bound(false), hi: None }],
+                    CompareOp::Gte => vec![Interval { lo: bound(true), hi: None }],
+                    CompareOp::Eq => vec![Interval { lo: bound(true), hi: bound(true) }],
+                    CompareOp::Neq => vec![Interval { lo: None, hi: bound(false) }, Interval { lo: bound(false), hi: None }],
+                }
+                .into_iter()
+                .map(Domain::Num)
+                .collect()
+            }
+            Literal::Str(s) => match op {
+                CompareOp::Eq => vec![Domain::Str(StrSet::In(BTreeSet::from([s])))],
+                CompareOp::Neq => vec![Domain::Str(StrSet::NotIn(BTreeSet::from([s])))],
+                _ => return vec![opaque(cond, negated)],
+            },
+            Literal::Bool(b) => match op {
+                CompareOp::Eq => vec![Domain::Bool(b)],
+                CompareOp::Neq => vec![Domain::Bool(!b)],
+                _ => return vec![opaque(cond, negated)],
+            },
+        };
+        domains.into_iter().map(|domain| single(path, unit, domain, required)).collect()
+    }
+
+    fn literal(&self, operand: &Operand) -> Option<Literal> {
+        match operand {
+            Operand::Number { value, unit } => Some(Literal::Num(Quantity::from_unit(*value, unit.as_deref()))),
+            Operand::Str { value } => Some(Literal::Str(value.clone())),
+            Operand::Bool { value } => Some(Literal::Bool(*value)),
+            Operand::Path { path, .. } if self.is_symbol(operand) => Some(Literal::Str(path.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Path satu segmen yang bukan field konteks dibaca evaluator sebagai string simbolik.
+    fn is_symbol(&self, operand: &Operand) -> bool {
+        match operand {
+            Operand::Path { path, unit: None } => {
+                matches!(path.segments.as_slice(), [PathSegment::Key(_)]) && !self.fields.contains(&path.to_string())
+            }
+            _ => false,
+        }
+    }
+
+    /// Jelaskan mengapa kondisi mustahil: pasangan term `AND` pertama yang bertentangan.
+    fn explain_unsat(&self, cond: &Condition) -> String {
+        if let Condition::And { terms } = cond {
+            for (i, a) in terms.iter().enumerate() {
+                for b in &terms[i + 1..] {
+                    let both = Condition::And { terms: vec![a.clone(), b.clone()] };
+                    if self.dnf(&both, false).is_some_and(|d| d.is_empty()) {
+                        return format!("'{}' contradicts '{}'", a, b);
+                    }
+                }
+            }
+        }
+        format!("condition '{}' is unsatisfiable", cond)
+    }
+
+    fn witness(&self, region: &Region, scope: &Scope) -> Value {
+        let mut root = Value::Object(Map::new());
+        for cons in region.fields.values() {
+            if let Some(value) = cons.sample() {
+                insert_path(&mut root, &bind_path(&cons.path, &scope.bindings), value);
+            }
+        }
+        root
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Literal {
+    Num(Quantity),
+    Str(String),
+    Bool(bool),
+}
+
+impl Literal {
+    fn same(&self, other: &Literal) -> bool {
+        match (self, other) {
+            (Literal::Num(a), Literal::Num(b)) => a.compatible(b) && (a.value - b.value).abs() <= 1e-9 * a.value.abs().max(1.0),
+            (Literal::Str(a), Literal::Str(b)) => a == b,
+            (Literal::Bool(a), Literal::Bool(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+pub(crate) fn rule_label(rule: &Rule, index: usize) -> String {
+    match rule {
+        Rule::Standard(r) => match &r.name {
+            Some(name) => format!("rule '{}'", name),
+            None => format!("rule #{}", index + 1),
+        },
+        Rule::For(f) => format!("FOR {} IN {}", f.iterator, f.collection),
+        Rule::While(_) => format!("WHILE #{}", index + 1),
+        Rule::Match(m) => format!("MATCH {}", m.scrutinee),
+    }
+}
+
+fn single(path: &FieldPath, unit: &Option<String>, domain: Domain, required: bool) -> Region {
+    let cons = FieldCons { path: path.clone(), unit: unit.clone(), domain, required };
+    Region { fields: BTreeMap::from([(path.to_string(), cons)]), opaque: BTreeMap::new() }
+}
+
+fn opaque(cond: &Condition, negated: bool) -> Region {
+    Region { fields: BTreeMap::new(), opaque: BTreeMap::from([(cond.to_string(), !negated)]) }
+}
+
+fn product(a: &[Region], b: &[Region]) -> Option<Vec<Region>> {
+    let regions: Vec<Region> = a.iter().flat_map(|x| b.iter().filter_map(move |y| x.meet(y))).collect();
+    (regions.len() <= MAX_REGIONS).then_some(regions)
+}
+
+fn union(mut a: Vec<Region>, b: Vec<Region>) -> Option<Vec<Region>> {
+    a.extend(b);
+    (a.len() <= MAX_REGIONS).then_some(a)
+}
+
+fn flip(op: CompareOp) -> CompareOp {
+    match op {
+        CompareOp::Lt => CompareOp::Gt,
+        CompareOp::Gt => CompareOp::Lt,
+        CompareOp::Lte => CompareOp::Gte,
+        CompareOp::Gte => CompareOp::Lte,
+        other => other,
+    }
+}
+
+fn negate(op: CompareOp) -> CompareOp {
+    match op {
+        CompareOp::Lt => CompareOp::Gte,
+        CompareOp::Gte => CompareOp::Lt,
+        CompareOp::Gt => CompareOp::Lte,
+        CompareOp::Lte => CompareOp::Gt,
+        CompareOp::Eq => CompareOp::Neq,
+        CompareOp::Neq => CompareOp::Eq,
+    }
+}
+
+fn collect_field_names(rules: &[Rule], fields: &mut HashSet<String>) {
+    fn from_condition(cond: &Condition, fields: &mut HashSet<String>) {
+        match cond {
+            Condition::Compare { left: Operand::Path { path, .. }, .. }
+            | Condition::In { value: Operand::Path { path, .. }, .. }
+            | Condition::Truthy { value: Operand::Path { path, .. } } => {
+                fields.insert(path.to_string());
+            }
+            Condition::Not { inner } | Condition::Sustained { inner, .. } | Condition::Becomes { inner } => {
+                from_condition(inner, fields)
+            }
+            Condition::Latch { set, reset } => {
+                from_condition(set, fields);
+                from_condition(reset, fields);
+            }
+            Condition::And { terms } | Condition::Or { terms } => terms.iter().for_each(|t| from_condition(t, fields)),
+            _ => {}
+        }
+    }
+    for rule in rules {
+        match rule {
+            Rule::Standard(r) => from_condition(&r.condition, fields),
+            Rule::For(f) => {
+                fields.insert(f.iterator.clone());
+                collect_field_names(&f.body, fields);
+            }
+            Rule::While(w) => {
+                from_condition(&w.condition, fields);
+                collect_field_names(&w.body, fields);
+            }
+            Rule::Match(m) => {
+                if let Operand::Path { path, .. } = &m.scrutinee {
+                    fields.insert(path.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Tulis ulang path berbasis iterator loop (`vehicle.battery`) menjadi elemen
+/// pertama koleksinya (`Vehicles[0].battery`).
+fn bind_path(path: &FieldPath, bindings: &[(String, String)]) -> FieldPath {
+    let mut path = path.clone();
+    for (iterator, collection) in bindings.iter().rev() {
+        if !matches!(path.segments.first(), Some(PathSegment::Key(k)) if k == iterator) {
+            continue;
+        }
+        let Ok(mut bound) = FieldPath::parse(collection) else { continue };
+        bound.segments.push(PathSegment::Index(0));
+        bound.segments.extend(path.segments.drain(1..));
+        path = bound;
+    }
+    path
+}
+
+pub(crate) fn insert_path(root: &mut Value, path: &FieldPath, value: Value) {
+    let mut node = root;
+    for segment in &path.segments {
+        node = match segment {
+            PathSegment::Key(key) => {
+                if !node.is_object() {
+                    *node = Value::Object(Map::new());
+                }
+                node.as_object_mut().expect("object node").entry(key.clone()).or_insert(Value::Null)
+            }
+            PathSegment::Index(i) => {
+                if !node.is_array() {
+                    *node = Value::Array(Vec::new());
+                }
+                let items = node.as_array_mut().expect("array node");
+                if items.len() <= *i {
+                    items.resize(*i + 1, Value::Null);
+                }
+                &mut items[*i]
+            }
+        };
+    }
+    *node = value;
+}
+
+fn literal_json(operand: &Operand) -> Option<Value> {
+    match operand {
+        Operand::Number { value, unit: None } => Some(number(*value)),
+        Operand::Number { value, unit: Some(unit) } => Some(Value::String(format!("{}{}", value, unit))),
+        Operand::Str { value } => Some(Value::String(value.clone())),
+        Operand::Bool { value } => Some(Value::Bool(*value)),
+        Operand::Path { path, .. } => Some(Value::String(path.to_string())),
+        _ => None,
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64((n * 1e6).round() / 1e6).map(Value::Number).unwrap_or(Value::Null)
+    }
+}
@@ -1,5 +1,9 @@
 // src/ast.rs
 
+use crate::action_abi::ActionCapability;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
 #[derive(Debug, Clone)]
 pub struct Policy {
     pub intent: Option<String>,
@@ -11,6 +15,193 @@ pub struct Policy {
     pub impacts: Vec<Impact>,
     pub traces: Vec<Trace>,
     pub reviews: Vec<Review>,
+    pub units: Vec<UnitDecl>,
+    pub schema: Vec<SchemaField>,
+    /// Capability yang dibutuhkan aksi, dari section `CAPABILITY:`.
+    pub capabilities: Vec<ActionRequirement>,
+    pub guard: GuardDecl,
+    /// Kasus uji dari section `EXPECT:`/`TEST:`; tidak diwarisi lewat komposisi.
+    pub tests: Vec<PolicyTest>,
+    pub combining: CombiningAlgorithm,
+    pub composition: Composition,
+}
+
+/// Komposisi yang belum di-resolve: `IMPORT:`, `EXTEND:`, dan rule `OVERRIDE`.
+/// Kosong setelah [`crate::compose::resolve_policy`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Composition {
+    pub imports: Vec<String>,
+    pub extends: Option<String>,
+    /// Nama rule yang ditandai `OVERRIDE` di file ini.
+    pub overrides: Vec<String>,
+}
+
+impl Composition {
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty() && self.extends.is_none() && self.overrides.is_empty()
+    }
+}
+
+/// Cara menggabungkan aksi dari rule-rule yang cocok menjadi `Decision.actions`
+/// (section `COMBINE:`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CombiningAlgorithm {
+    /// Semua aksi yang cocok, urut sesuai sumber (perilaku bawaan).
+    #[default]
+    AllMatches,
+    /// Hanya rule pertama yang cocok; evaluasi berhenti di situ.
+    FirstMatch,
+    /// Jika ada rule `DENY` yang cocok, hanya aksi rule `DENY` yang keluar.
+    DenyOverrides,
+    /// Hanya aksi dengan `PRIORITY` tertinggi di antara rule yang cocok.
+    PriorityOrdered,
+}
+
+impl CombiningAlgorithm {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().replace('_', "-").as_str() {
+            "all-matches" => Ok(CombiningAlgorithm::AllMatches),
+            "first-match" => Ok(CombiningAlgorithm::FirstMatch),
+            "deny-overrides" => Ok(CombiningAlgorithm::DenyOverrides),
+            "priority-ordered" => Ok(CombiningAlgorithm::PriorityOrdered),
+            other => Err(format!(
+                "Unknown combining algorithm '{}' (expected all-matches, first-match, deny-overrides or priority-ordered)",
+                other
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CombiningAlgorithm::AllMatches => "all-matches",
+            CombiningAlgorithm::FirstMatch => "first-match",
+            CombiningAlgorithm::DenyOverrides => "deny-overrides",
+            CombiningAlgorithm::PriorityOrdered => "priority-ordered",
+        }
+    }
+}
+
+impl fmt::Display for CombiningAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Deklarasi satuan field konteks dari section `UNITS:`, mis. `- Distance: m`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitDecl {
+    pub field: String,
+    pub unit: String,
+}
+
+/// Capability yang dibutuhkan sebuah aksi dari section `CAPABILITY:`,
+/// mis. `- UploadReport: [Network, FileSystem]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionRequirement {
+    pub action: String,
+    pub requires: Vec<ActionCapability>,
+}
+
+/// Deklarasi field konteks dari section `SCHEMA:`, mis.
+/// `- Temperature: number C [-40, 150] required`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaField {
+    pub field: String,
+    pub ty: FieldType,
+    /// Satuan field; juga didaftarkan sebagai [`UnitDecl`].
+    pub unit: Option<String>,
+    /// Batas inklusif dalam satuan field.
+    pub range: Option<(f64, f64)>,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Number,
+    Integer,
+    String,
+    Bool,
+    Array,
+    Object,
+    Any,
+}
+
+impl FieldType {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "number" | "float" => Ok(FieldType::Number),
+            "integer" | "int" => Ok(FieldType::Integer),
+            "string" | "text" => Ok(FieldType::String),
+            "bool" | "boolean" => Ok(FieldType::Bool),
+            "array" | "list" => Ok(FieldType::Array),
+            "object" => Ok(FieldType::Object),
+            "any" => Ok(FieldType::Any),
+            _ => Err(format!(
+                "Unknown field type '{}' (expected number, integer, string, bool, array, object or any)",
+                name
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldType::Number => "number",
+            FieldType::Integer => "integer",
+            FieldType::String => "string",
+            FieldType::Bool => "bool",
+            FieldType::Array => "array",
+            FieldType::Object => "object",
+            FieldType::Any => "any",
+        }
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, FieldType::Number | FieldType::Integer)
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Batas guard loop dari section `GUARD:`; field kosong memakai
+/// [`crate::runtime::RuntimeConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GuardDecl {
+    pub max_iterations: Option<usize>,
+    pub max_time_ms: Option<u128>,
+}
+
+impl GuardDecl {
+    pub fn is_empty(&self) -> bool {
+        self.max_iterations.is_none() && self.max_time_ms.is_none()
+    }
+}
+
+/// Satu kasus uji kebijakan, dari `EXPECT:`/`TEST:` atau file `<kebijakan>.tests.json`:
+/// konteks (inline atau file) beserta aksi yang diharapkan muncul dan tidak muncul.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyTest {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<serde_json::Value>,
+    /// File konteks JSON, relatif terhadap file kebijakan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_file: Option<String>,
+    #[serde(default)]
+    pub expect: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub absent: Vec<String>,
+    /// Aksi yang muncul harus persis `expect` (`ONLY`/`NONE`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exact: bool,
+    /// Status `guard_triggered` yang diharapkan; `None` berarti tidak diperiksa.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guard: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +214,8 @@ pub struct Review {
 pub struct Actor {
     pub role: String,
     pub primary: bool,
+    /// Capability dari `CAN [...]`; `None` berarti actor tidak dibatasi.
+    pub capabilities: Option<Vec<ActionCapability>>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,10 +233,67 @@ pub enum Rule {
     Match(PolicyMatchRule),
 }
 
+impl Rule {
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Rule::Standard(r) => r.name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Baris sumber tempat rule ditulis (baris `-` pembukanya).
+    pub fn line(&self) -> usize {
+        match self {
+            Rule::Standard(r) => r.line,
+            Rule::For(f) => f.line,
+            Rule::While(w) => w.line,
+            Rule::Match(m) => m.line,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StandardRule {
-    pub condition: String,
-    pub action: String,
+    /// Nama rule (`- name: IF ...`), bisa di-override oleh kebijakan turunan.
+    pub name: Option<String>,
+    pub condition: Condition,
+    pub action: ActionCall,
+    pub else_action: Option<ActionCall>,
+    pub priority: i64,
+    pub effect: RuleEffect,
+    /// Actor yang menjalankan aksi rule (`BY Role`); `None` berarti actor primary.
+    pub actor: Option<String>,
+    /// Baris sumber rule (1-based); 0 jika rule tidak berasal dari file.
+    pub line: usize,
+}
+
+/// Aksi rule: nama saja (`Brake`) atau dengan parameter bernama
+/// (`Brake(force: 0.8, timeout: 200ms)`). Parameter dievaluasi saat rule cocok.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionCall {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<ActionParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionParam {
+    pub name: String,
+    pub value: Operand,
+}
+
+impl ActionCall {
+    pub fn named(name: impl Into<String>) -> Self {
+        ActionCall { name: name.into(), params: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleEffect {
+    #[default]
+    Permit,
+    Deny,
 }
 
 #[derive(Debug, Clone)]
@@ -51,30 +301,388 @@ pub struct ForLoop {
     pub iterator: String,
     pub collection: String,
     pub body: Vec<Rule>,
+    pub line: usize,
 }
 
 #[derive(Debug, Clone)]
 pub struct WhileLoop {
-    pub condition: String,
+    pub condition: Condition,
     pub body: Vec<Rule>,
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PolicyMatchRule {
-    pub scrutinee: String,
+    pub scrutinee: Operand,
     pub arms: Vec<PolicyMatchArm>,
+    pub line: usize,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct PolicyMatchArm {
-    pub pattern: String,
-    pub action: String,
+    pub pattern: MatchPattern,
+    pub action: ActionCall,
+}
+
+/// Kondisi kebijakan yang sudah di-parse (`IF ...`, `WHILE ...`).
+/// Dipakai bersama oleh runtime, IR, dan IR interpreter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Condition {
+    Const { value: bool },
+    Compare { left: Operand, op: CompareOp, right: Operand },
+    In { value: Operand, set: Operand },
+    Truthy { value: Operand },
+    Not { inner: Box<Condition> },
+    And { terms: Vec<Condition> },
+    Or { terms: Vec<Condition> },
+    /// `cond FOR 5s`: benar jika `inner` terus benar selama `duration`.
+    Sustained { inner: Box<Condition>, duration: Operand },
+    /// `BECOMES cond`: benar hanya pada evaluasi saat `inner` berubah dari salah ke benar.
+    Becomes { inner: Box<Condition> },
+    /// `set UNTIL reset`: terkunci benar sejak `set` benar sampai `reset` benar (hysteresis).
+    Latch { set: Box<Condition>, reset: Box<Condition> },
+}
+
+impl Condition {
+    /// Kondisi tanpa sub-kondisi, aman ditulis tanpa kurung di depan operator postfix.
+    fn is_atomic(&self) -> bool {
+        matches!(
+            self,
+            Condition::Const { .. } | Condition::Compare { .. } | Condition::In { .. } | Condition::Truthy { .. }
+        )
+    }
+
+    /// Apakah kondisi memakai operator temporal (butuh status antar evaluasi).
+    pub fn is_temporal(&self) -> bool {
+        match self {
+            Condition::Sustained { .. } | Condition::Becomes { .. } | Condition::Latch { .. } => true,
+            Condition::Const { .. } => false,
+            Condition::Compare { left, right, .. } => left.is_temporal() || right.is_temporal(),
+            Condition::In { value, set } => value.is_temporal() || set.is_temporal(),
+            Condition::Truthy { value } => value.is_temporal(),
+            Condition::Not { inner } => inner.is_temporal(),
+            Condition::And { terms } | Condition::Or { terms } => terms.iter().any(Condition::is_temporal),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Operand {
+    /// Angka literal sebagaimana ditulis; `unit` adalah sufiks satuan (mis. `cm`, `km/h`).
+    Number {
+        value: f64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
+    },
+    Bool { value: bool },
+    Str { value: String },
+    /// Referensi field konteks; `unit` diisi dari deklarasi `UNITS:`.
+    Path {
+        path: FieldPath,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        unit: Option<String>,
+    },
+    List { items: Vec<Operand> },
+    Arith { left: Box<Operand>, op: ArithOp, right: Box<Operand> },
+    /// `COUNT(cond) WITHIN 1m`: jumlah evaluasi dengan `condition` benar dalam jendela waktu.
+    Count { condition: Box<Condition>, window: Box<Operand> },
+}
+
+impl Operand {
+    pub fn is_temporal(&self) -> bool {
+        match self {
+            Operand::Count { .. } => true,
+            Operand::List { items } => items.iter().any(Operand::is_temporal),
+            Operand::Arith { left, right, .. } => left.is_temporal() || right.is_temporal(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareOp {
+    Lt,
+    Gt,
+    Lte,
+    Gte,
+    Eq,
+    Neq,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MatchPattern {
+    Wildcard,
+    Value { value: Operand },
+}
+
+/// Dot-path ke dalam konteks JSON, misalnya `device.flags[0]`.
+/// Diserialisasi sebagai string agar IR tetap mudah dibaca.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct FieldPath {
+    pub segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl FieldPath {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        for part in raw.split('.') {
+            let (base, mut rest) = match part.find('[') {
+                Some(i) => (&part[..i], &part[i..]),
+                None => (part, ""),
+            };
+            if base.is_empty() && (segments.is_empty() || rest.is_empty()) {
+                return Err(format!("invalid field path '{}'", raw));
+            }
+            if !base.is_empty() {
+                segments.push(PathSegment::Key(base.to_string()));
+            }
+            while !rest.is_empty() {
+                let end = rest
+                    .find(']')
+                    .ok_or_else(|| format!("unterminated index in field path '{}'", raw))?;
+                let idx = rest[1..end]
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid index in field path '{}'", raw))?;
+                segments.push(PathSegment::Index(idx));
+                rest = &rest[end + 1..];
+                if !rest.is_empty() && !rest.starts_with('[') {
+                    return Err(format!("invalid field path '{}'", raw));
+                }
+            }
+        }
+        Ok(FieldPath { segments })
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, seg) in self.segments.iter().enumerate() {
+            match seg {
+                PathSegment::Key(k) if i == 0 => write!(f, "{}", k)?,
+                PathSegment::Key(k) => write!(f, ".{}", k)?,
+                PathSegment::Index(idx) => write!(f, "[{}]", idx)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<FieldPath> for String {
+    fn from(path: FieldPath) -> Self {
+        path.to_string()
+    }
+}
+
+impl TryFrom<String> for FieldPath {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        FieldPath::parse(&raw)
+    }
+}
+
+impl CompareOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CompareOp::Lt => "<",
+            CompareOp::Gt => ">",
+            CompareOp::Lte => "<=",
+            CompareOp::Gte => ">=",
+            CompareOp::Eq => "==",
+            CompareOp::Neq => "!=",
+        }
+    }
+}
+
+impl ArithOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ArithOp::Add => "+",
+            ArithOp::Sub => "-",
+            ArithOp::Mul => "*",
+            ArithOp::Div => "/",
+        }
+    }
+
+    fn binds_tighter(&self) -> bool {
+        matches!(self, ArithOp::Mul | ArithOp::Div)
+    }
+}
+
+impl fmt::Display for ActionCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.params.is_empty() {
+            return Ok(());
+        }
+        write!(f, "(")?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", param.name, param.value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Number { value, unit } => write!(f, "{}{}", value, unit.as_deref().unwrap_or("")),
+            Operand::Bool { value } => write!(f, "{}", value),
+            Operand::Str { value } => write!(f, "\"{}\"", value),
+            Operand::Path { path, .. } => write!(f, "{}", path),
+            Operand::List { items } => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Operand::Arith { left, op, right } => {
+                let wrap = |o: &Operand| match o {
+                    Operand::Arith { op: inner, .. } if op.binds_tighter() && !inner.binds_tighter() => {
+                        format!("({})", o)
+                    }
+                    _ => o.to_string(),
+                };
+                write!(f, "{} {} {}", wrap(left), op.symbol(), wrap(right))
+            }
+            Operand::Count { condition, window } => write!(f, "COUNT({}) WITHIN {}", condition, window),
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Const { value } => write!(f, "{}", value),
+            Condition::Compare { left, op, right } => write!(f, "{} {} {}", left, op.symbol(), right),
+            Condition::In { value, set } => write!(f, "{} IN {}", value, set),
+            Condition::Truthy { value } => write!(f, "{}", value),
+            Condition::Not { inner } => match inner.as_ref() {
+                Condition::And { .. } | Condition::Or { .. } => write!(f, "NOT ({})", inner),
+                _ => write!(f, "NOT {}", inner),
+            },
+            Condition::And { terms } => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " AND ")?;
+                    }
+                    match term {
+                        Condition::Or { .. } => write!(f, "({})", term)?,
+                        _ => write!(f, "{}", term)?,
+                    }
+                }
+                Ok(())
+            }
+            Condition::Or { terms } => {
+                for (i, term) in terms.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " OR ")?;
+                    }
+                    write!(f, "{}", term)?;
+                }
+                Ok(())
+            }
+            Condition::Sustained { inner, duration } => {
+                if inner.is_atomic() {
+                    write!(f, "{} FOR {}", inner, duration)
+                } else {
+                    write!(f, "({}) FOR {}", inner, duration)
+                }
+            }
+            Condition::Becomes { inner } => match inner.as_ref() {
+                Condition::And { .. } | Condition::Or { .. } => write!(f, "BECOMES ({})", inner),
+                _ => write!(f, "BECOMES {}", inner),
+            },
+            Condition::Latch { set, reset } => {
+                if set.is_atomic() {
+                    write!(f, "{}", set)?;
+                } else {
+                    write!(f, "({})", set)?;
+                }
+                match reset.as_ref() {
+                    Condition::And { .. } | Condition::Or { .. } => write!(f, " UNTIL ({})", reset),
+                    _ => write!(f, " UNTIL {}", reset),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for MatchPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchPattern::Wildcard => write!(f, "_"),
+            MatchPattern::Value { value } => write!(f, "{}", value),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Constraint {
     pub kind: String,
     pub description: String,
+    /// Invarian yang ditegakkan runtime; `None` untuk constraint berupa teks bebas.
+    pub invariant: Option<Invariant>,
+}
+
+/// Invarian `CONSTRAINT:` yang diperiksa setelah rule dievaluasi dan aksi digabung,
+/// mis. `- Ethical: NEVER (Accelerate AND HumanNearby) ELSE Brake`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invariant {
+    pub check: InvariantCheck,
+    /// Aksi pengganti saat invarian dilanggar (`... ELSE SafeStop`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<ActionCall>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InvariantCheck {
+    /// `NEVER cond`: di dalam `cond`, nama aksi yang dipicu bernilai benar.
+    Never { condition: Condition },
+    /// `MAX n OF [A, B]`: paling banyak `limit` aksi dari daftar boleh keluar bersamaan.
+    AtMost { limit: usize, actions: Vec<String> },
+}
+
+impl fmt::Display for Invariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.check {
+            InvariantCheck::Never { condition } if condition.is_atomic() => write!(f, "NEVER {}", condition)?,
+            InvariantCheck::Never { condition } => write!(f, "NEVER ({})", condition)?,
+            InvariantCheck::AtMost { limit, actions } => write!(f, "MAX {} OF [{}]", limit, actions.join(", "))?,
+        }
+        if let Some(fallback) = &self.fallback {
+            write!(f, " ELSE {}", fallback)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,337 @@
+//! Log audit keputusan yang append-only dan berantai hash (JSON Lines).
+//!
+//! Setiap record menyimpan hash kebijakan, hash konteks, `Decision`, trace id, dan
+//! hash record sebelumnya. Hash record adalah SHA-256 dari JSON record tanpa field
+//! `hash`/`hmac`, dan bisa ditandatangani HMAC-SHA256 dengan kunci rahasia. File
+//! `<log>.head` mencatat jumlah record dan hash terakhir, sehingga pemotongan ekor
+//! log juga terdeteksi oleh [`verify`].
+
+use crate::ast::Policy;
+use crate::ir::build_policy_ir;
+use crate::runtime::Decision;
+use crate::stdlib::{hash_sha256, hmac_sha256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// `prev_hash` record pertama.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Nomor urut, mulai dari 0.
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<u64>,
+    /// SHA-256 IR kebijakan yang sudah dikomposisi (`IMPORT:`/`EXTEND:` ikut dihitung).
+    pub policy_hash: String,
+    /// SHA-256 konteks saat keputusan dievaluasi.
+    pub context_hash: String,
+    pub decision: Decision,
+    pub prev_hash: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac: Option<String>,
+}
+
+/// Jumlah record dan hash terakhir, disimpan di `<log>.head`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditHead {
+    pub records: u64,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hmac: Option<String>,
+}
+
+impl AuditHead {
+    fn signed(records: u64, hash: String, key: Option<&str>) -> Self {
+        let hmac = key.map(|k| hmac_sha256(k, &format!("{}:{}", records, hash)));
+        AuditHead { records, hash, hmac }
+    }
+}
+
+/// Path file head untuk sebuah log audit: `decisions.jsonl` -> `decisions.jsonl.head`.
+pub fn head_path(log_path: &Path) -> PathBuf {
+    let mut name = log_path.as_os_str().to_owned();
+    name.push(".head");
+    PathBuf::from(name)
+}
+
+/// SHA-256 JSON kanonis (kunci objek terurut).
+pub fn canonical_hash(value: &Value) -> String {
+    hash_sha256(&value.to_string())
+}
+
+/// Hash kebijakan seperti yang dicatat di record audit.
+pub fn policy_hash(policy: &Policy) -> String {
+    canonical_hash(&serde_json::to_value(build_policy_ir(policy)).unwrap_or_default())
+}
+
+/// Hash record: SHA-256 dari record tanpa `hash` dan `hmac`.
+fn record_hash(record: &Value) -> String {
+    let mut body = record.clone();
+    if let Value::Object(fields) = &mut body {
+        fields.remove("hash");
+        fields.remove("hmac");
+    }
+    canonical_hash(&body)
+}
+
+/// Penulis log audit. Setiap [`AuditLog::append`] menambah satu baris dan memperbarui head.
+pub struct AuditLog {
+    path: PathBuf,
+    key: Option<String>,
+    next_seq: u64,
+    last_hash: String,
+    /// Panjang file setelah record terakhir yang kita ketahui; jika berbeda saat
+    /// `append`, proses lain sudah menambah record dan ekornya dibaca ulang.
+    len: u64,
+}
+
+impl AuditLog {
+    /// Buka log (atau mulai log baru) dan lanjutkan rantainya. Log yang tidak cocok
+    /// dengan file head-nya ditolak agar pemotongan tidak tertutup record baru.
+    pub fn open(path: impl AsRef<Path>, key: Option<&str>) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let key = key.map(str::to_string);
+        let mut log = AuditLog { path, key, next_seq: 0, last_hash: GENESIS_HASH.to_string(), len: 0 };
+        if !log.path.exists() {
+            return Ok(log);
+        }
+        let mut file = File::open(&log.path).map_err(|e| format!("cannot read '{}': {}", log.path.display(), e))?;
+        file.lock_shared().map_err(|e| format!("cannot lock '{}': {}", log.path.display(), e))?;
+        log.sync_tail(&mut file)?;
+        Ok(log)
+    }
+
+    /// Tambahkan satu keputusan. `context` adalah konteks yang dipakai untuk evaluasi.
+    /// Pembacaan ekor, penulisan record, dan penulisan head terjadi di bawah kunci
+    /// eksklusif pada file log, jadi beberapa proses boleh menulis log yang sama.
+    pub fn append(
+        &mut self,
+        policy_hash: &str,
+        context: &Value,
+        decision: &Decision,
+        timestamp_ms: u64,
+    ) -> Result<AuditRecord, String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("cannot open '{}': {}", self.path.display(), e))?;
+        // Kunci dilepas saat `file` di-drop
+        file.lock().map_err(|e| format!("cannot lock '{}': {}", self.path.display(), e))?;
+        self.sync_tail(&mut file)?;
+
+        let mut record = AuditRecord {
+            seq: self.next_seq,
+            timestamp_ms,
+            trace_id: crate::observability::current_trace().map(|t| t.as_u64()),
+            policy_hash: policy_hash.to_string(),
+            context_hash: canonical_hash(context),
+            decision: decision.clone(),
+            prev_hash: self.last_hash.clone(),
+            hash: String::new(),
+            hmac: None,
+        };
+        let mut value = serde_json::to_value(&record).map_err(|e| e.to_string())?;
+        record.hash = record_hash(&value);
+        record.hmac = self.key.as_deref().map(|k| hmac_sha256(k, &record.hash));
+        value["hash"] = Value::from(record.hash.clone());
+        if let Some(hmac) = &record.hmac {
+            value["hmac"] = Value::from(hmac.clone());
+        }
+
+        let line = format!("{}\n", value);
+        file.write_all(line.as_bytes()).map_err(|e| format!("cannot write '{}': {}", self.path.display(), e))?;
+        file.sync_data().map_err(|e| e.to_string())?;
+
+        self.next_seq += 1;
+        self.last_hash = record.hash.clone();
+        self.len += line.len() as u64;
+        let head = AuditHead::signed(self.next_seq, self.last_hash.clone(), self.key.as_deref());
+        write_head(&self.path, &head)?;
+        Ok(record)
+    }
+
+    /// Baca ulang record terakhir jika file berubah sejak terakhir dilihat, lalu
+    /// pastikan cocok dengan file head. `file` harus sudah dikunci.
+    fn sync_tail(&mut self, file: &mut File) -> Result<(), String> {
+        let len = file.metadata().map_err(|e| format!("cannot read '{}': {}", self.path.display(), e))?.len();
+        // File kosong tetap dicek: head yang mencatat record berarti log terpotong
+        if len == self.len && len > 0 {
+            return Ok(());
+        }
+        let (next_seq, last_hash) = match last_line(file, len).map_err(|e| format!("cannot read '{}': {}", self.path.display(), e))? {
+            Some(last) => {
+                let record: Value =
+                    serde_json::from_str(&last).map_err(|e| format!("last audit record is not valid JSON: {}", e))?;
+                let seq = record["seq"].as_u64().ok_or("last audit record has no 'seq'")?;
+                let hash = record["hash"].as_str().ok_or("last audit record has no 'hash'")?;
+                (seq + 1, hash.to_string())
+            }
+            None => (0, GENESIS_HASH.to_string()),
+        };
+        match read_head(&self.path)? {
+            Some(head) if head.records == next_seq && head.hash == last_hash => {}
+            None if next_seq == 0 => {}
+            _ => {
+                return Err(format!(
+                    "audit log '{}' does not match its head file; run `omnilang audit verify`",
+                    self.path.display()
+                ))
+            }
+        }
+        self.next_seq = next_seq;
+        self.last_hash = last_hash;
+        self.len = len;
+        Ok(())
+    }
+}
+
+/// Baris tidak kosong terakhir dari `file` (panjang `len`), dibaca dari ekor.
+fn last_line(file: &mut File, len: u64) -> std::io::Result<Option<String>> {
+    let mut window = 4096u64;
+    loop {
+        let start = len.saturating_sub(window);
+        let mut buf = Vec::with_capacity((len - start) as usize);
+        file.seek(SeekFrom::Start(start))?;
+        Read::by_ref(file).take(len - start).read_to_end(&mut buf)?;
+        let text = String::from_utf8_lossy(&buf);
+        let text = text.trim_end();
+        match text.rfind('\n') {
+            Some(pos) => return Ok(Some(text[pos + 1..].to_string())),
+            None if start == 0 => return Ok((!text.is_empty()).then(|| text.to_string())),
+            None => window *= 2,
+        }
+    }
+}
+
+/// Tulis head lewat file sementara lalu rename, agar pembaca tidak melihat head setengah jadi.
+fn write_head(log_path: &Path, head: &AuditHead) -> Result<(), String> {
+    let head_file = head_path(log_path);
+    let mut tmp = head_file.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, serde_json::to_string(head).map_err(|e| e.to_string())? + "\n")
+        .map_err(|e| format!("cannot write '{}': {}", tmp.display(), e))?;
+    fs::rename(&tmp, &head_file).map_err(|e| format!("cannot write '{}': {}", head_file.display(), e))
+}
+
+fn read_head(log_path: &Path) -> Result<Option<AuditHead>, String> {
+    let path = head_path(log_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| format!("invalid head file '{}': {}", path.display(), e))
+}
+
+/// Masalah yang ditemukan [`verify`]; `line` 0 berarti file head.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditIssue {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            0 => write!(f, "head: {}", self.message),
+            line => write!(f, "line {}: {}", line, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub records: u64,
+    /// Hash record terakhir, atau [`GENESIS_HASH`] untuk log kosong.
+    pub head: String,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl VerifyReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Periksa rantai hash, nomor urut, HMAC (jika `key` diberikan), dan file head.
+/// Record yang diubah, dihapus, disisipkan, atau dipotong dari ekor dilaporkan.
+pub fn verify(path: impl AsRef<Path>, key: Option<&str>) -> Result<VerifyReport, String> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    // Kunci bersama: penulis yang sedang menambah record ditunggu sampai head-nya ditulis
+    file.lock_shared().map_err(|e| format!("cannot lock '{}': {}", path.display(), e))?;
+    let mut report = verify_records(BufReader::new(&file), key)?;
+    let issue = match read_head(path) {
+        Ok(Some(head)) => {
+            if head.records != report.records || head.hash != report.head {
+                Some(format!(
+                    "expected {} records ending in {}, log has {} ending in {}",
+                    head.records, head.hash, report.records, report.head
+                ))
+            } else if key.is_some() && AuditHead::signed(head.records, head.hash.clone(), key) != head {
+                Some("HMAC mismatch".to_string())
+            } else {
+                None
+            }
+        }
+        Ok(None) if report.records > 0 => Some(format!("missing {}", head_path(path).display())),
+        Ok(None) => None,
+        Err(e) => Some(e),
+    };
+    if let Some(message) = issue {
+        report.issues.push(AuditIssue { line: 0, message });
+    }
+    Ok(report)
+}
+
+/// Periksa record dari `input` tanpa file head.
+pub fn verify_records<R: BufRead>(input: R, key: Option<&str>) -> Result<VerifyReport, String> {
+    let mut report = VerifyReport { head: GENESIS_HASH.to_string(), ..Default::default() };
+    let mut next_seq = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut issues = Vec::new();
+        match serde_json::from_str(&line) {
+            Ok(record @ Value::Object(_)) => {
+                let seq = record["seq"].as_u64();
+                if seq != Some(next_seq) {
+                    issues.push(format!("expected seq {}, found {}", next_seq, record["seq"]));
+                }
+                if record["prev_hash"].as_str() != Some(report.head.as_str()) {
+                    issues.push("prev_hash does not match the previous record".to_string());
+                }
+                let stored = record["hash"].as_str().unwrap_or_default().to_string();
+                if record_hash(&record) != stored {
+                    issues.push("record was modified (hash mismatch)".to_string());
+                }
+                if let Some(key) = key {
+                    match record["hmac"].as_str() {
+                        None => issues.push("record is not HMAC-signed".to_string()),
+                        Some(hmac) if hmac != hmac_sha256(key, &stored) => issues.push("HMAC mismatch".to_string()),
+                        Some(_) => {}
+                    }
+                }
+                next_seq = seq.unwrap_or(next_seq) + 1;
+                report.head = stored;
+            }
+            _ => issues.push("not a JSON audit record".to_string()),
+        }
+        report.records += 1;
+        report.issues.extend(issues.into_iter().map(|message| AuditIssue { line: index + 1, message }));
+    }
+    Ok(report)
+}
@@ -0,0 +1,120 @@
+//! Otorisasi aksi berbasis capability. Actor mendapat capability lewat
+//! `ACTOR:` (`- Primary: Role CAN [Execute, Network]`), aksi membutuhkan
+//! capability dari section `CAPABILITY:` dan parameter `requires:`, dan rule
+//! `BY Role` menentukan actor yang menjalankan aksinya.
+//!
+//! Actor tanpa `CAN` tidak dibatasi, sehingga kebijakan lama tetap berjalan sama.
+
+use crate::action_abi::ActionCapability;
+use crate::ast::{ActionCall, ActionRequirement, Policy, Rule};
+use crate::evaluator::declared_capabilities;
+use crate::runtime::{Decision, UnauthorizedAction};
+
+/// Actor yang dideklarasikan: peran, primary, dan capability `CAN [...]`.
+pub(crate) type Grant<'a> = (&'a str, bool, Option<&'a [ActionCapability]>);
+
+/// Tolak aksi keputusan yang actor-nya tidak memiliki semua capability yang
+/// dibutuhkan. Kebutuhan dari `CAPABILITY:` juga ditambahkan ke payload aksi.
+pub(crate) fn authorize<'a>(
+    actors: impl IntoIterator<Item = Grant<'a>>,
+    requirements: &[ActionRequirement],
+    decision: &mut Decision,
+) {
+    let actors: Vec<Grant> = actors.into_iter().collect();
+    let mut index = 0;
+    while index < decision.actions.len() {
+        let payload = &mut decision.payloads[index];
+        for capability in required_by(requirements, &payload.action) {
+            if !payload.required_capabilities.contains(capability) {
+                payload.required_capabilities.push(capability.clone());
+            }
+        }
+        let actor = acting_actor(&actors, decision.explanations[index].actor.as_deref());
+        let failure = actor.and_then(|actor| check(&actors, actor, &payload.action, &payload.required_capabilities));
+        match failure {
+            Some(failure) => decision.reject(index, failure),
+            None => index += 1,
+        }
+    }
+}
+
+/// Aksi rule yang pasti ditolak saat runtime, beserta baris rule-nya.
+/// Kebutuhan capability dibaca dari `CAPABILITY:` dan `requires:`.
+pub fn check_policy(policy: &Policy) -> Vec<(usize, UnauthorizedAction)> {
+    let actors: Vec<Grant> = policy
+        .actors
+        .iter()
+        .map(|a| (a.role.as_str(), a.primary, a.capabilities.as_deref()))
+        .collect();
+    let mut failures = Vec::new();
+    check_rules(&policy.rules, &actors, &policy.capabilities, &mut failures);
+    failures
+}
+
+fn check_rules(
+    rules: &[Rule],
+    actors: &[Grant],
+    requirements: &[ActionRequirement],
+    failures: &mut Vec<(usize, UnauthorizedAction)>,
+) {
+    for rule in rules {
+        let (line, actor, calls): (usize, Option<&str>, Vec<&ActionCall>) = match rule {
+            Rule::Standard(r) => (r.line, r.actor.as_deref(), std::iter::once(&r.action).chain(&r.else_action).collect()),
+            Rule::Match(m) => (m.line, None, m.arms.iter().map(|arm| &arm.action).collect()),
+            Rule::For(f) => {
+                check_rules(&f.body, actors, requirements, failures);
+                continue;
+            }
+            Rule::While(w) => {
+                check_rules(&w.body, actors, requirements, failures);
+                continue;
+            }
+        };
+        let Some(actor) = acting_actor(actors, actor) else {
+            continue;
+        };
+        for call in calls {
+            let mut required = declared_capabilities(call);
+            for capability in required_by(requirements, &call.name) {
+                if !required.contains(capability) {
+                    required.push(capability.clone());
+                }
+            }
+            if let Some(failure) = check(actors, actor, &call.name, &required) {
+                if !failures.iter().any(|(l, f)| *l == line && *f == failure) {
+                    failures.push((line, failure));
+                }
+            }
+        }
+    }
+}
+
+fn required_by<'r>(requirements: &'r [ActionRequirement], action: &str) -> &'r [ActionCapability] {
+    requirements
+        .iter()
+        .find(|r| r.action == action)
+        .map(|r| r.requires.as_slice())
+        .unwrap_or_default()
+}
+
+/// Actor dari `BY Role`, atau actor primary; `None` jika kebijakan tidak punya actor primary.
+fn acting_actor<'a>(actors: &[Grant<'a>], scoped: Option<&'a str>) -> Option<&'a str> {
+    scoped.or_else(|| actors.iter().find(|(_, primary, _)| *primary).map(|(role, _, _)| *role))
+}
+
+fn check(actors: &[Grant], actor: &str, action: &str, required: &[ActionCapability]) -> Option<UnauthorizedAction> {
+    let failure = |missing: Vec<ActionCapability>, undeclared: bool| UnauthorizedAction {
+        action: action.to_string(),
+        actor: actor.to_string(),
+        missing,
+        undeclared,
+    };
+    match actors.iter().find(|(role, _, _)| *role == actor) {
+        None => Some(failure(Vec::new(), true)),
+        Some((_, _, None)) => None,
+        Some((_, _, Some(granted))) => {
+            let missing: Vec<ActionCapability> = required.iter().filter(|c| !granted.contains(c)).cloned().collect();
+            (!missing.is_empty()).then(|| failure(missing, false))
+        }
+    }
+}
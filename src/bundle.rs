@@ -0,0 +1,164 @@
+//! Bundle kebijakan terkompilasi (`omnilang compile --target bundle`).
+//!
+//! Bundle adalah satu file JSON berisi IR beserta versi format, versi IR dan
+//! compiler, hash setiap file sumber (termasuk `IMPORT:`/`EXTEND:`), `SCHEMA:`
+//! yang dideklarasikan, dan tanda tangan HMAC opsional. [`PolicyBundle::parse`]
+//! menolak bundle yang formatnya tidak didukung, IR-nya berubah, atau
+//! tanda tangannya tidak sah, sebelum IR dijalankan.
+
+use crate::ast::{Policy, SchemaField};
+use crate::ir::{build_policy_ir, PolicyIR};
+use crate::optimizer::optimize;
+use crate::runtime::Decision;
+use crate::schema::SchemaViolation;
+use crate::security::{generate_checksum, verify_mac, SigningKey};
+use crate::temporal::TemporalState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// Penanda format di field `format`.
+pub const BUNDLE_FORMAT: &str = "omnilang-bundle";
+/// Versi format bundle yang ditulis dan dibaca compiler ini.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+/// Versi struktur [`PolicyIR`] yang ditulis compiler ini. Versi 2 menambah node
+/// hasil [`crate::optimizer`]; IR versi lama tetap bisa dijalankan.
+pub const IR_VERSION: u32 = 2;
+/// Versi IR tertua yang masih dibaca.
+pub const MIN_IR_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyBundle {
+    pub format: String,
+    pub format_version: u32,
+    pub ir_version: u32,
+    pub compiler_version: String,
+    pub intent: Option<String>,
+    /// File sumber dan SHA-256-nya; entri pertama adalah file yang dikompilasi.
+    pub sources: Vec<BundleSource>,
+    /// `SCHEMA:` kebijakan; konteks divalidasi terhadapnya sebelum dijalankan.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schema: Vec<SchemaField>,
+    /// SHA-256 JSON kanonis `ir`.
+    pub ir_sha256: String,
+    pub ir: PolicyIR,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<BundleSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSource {
+    pub file: String,
+    pub sha256: String,
+}
+
+/// HMAC-SHA256 atas JSON kanonis bundle tanpa field `signature`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    pub key_id: String,
+    pub signature: String,
+}
+
+impl PolicyBundle {
+    /// Kompilasi kebijakan yang sudah dikomposisi. `origin` adalah file sumbernya,
+    /// dipakai untuk mencatat hash file tersebut dan file `IMPORT:`/`EXTEND:`-nya.
+    pub fn build(policy: &Policy, origin: &Path) -> Result<Self, String> {
+        let mut ir = build_policy_ir(policy);
+        optimize(&mut ir);
+        let ir_value = serde_json::to_value(&ir).map_err(|e| format!("serialize IR failed: {}", e))?;
+        let mut sources = Vec::new();
+        for file in crate::compose::source_files(origin)? {
+            let content = std::fs::read_to_string(&file).map_err(|e| format!("cannot read '{}': {}", file.display(), e))?;
+            sources.push(BundleSource { file: file.display().to_string(), sha256: generate_checksum(&content) });
+        }
+        Ok(PolicyBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            format_version: BUNDLE_FORMAT_VERSION,
+            ir_version: IR_VERSION,
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            intent: policy.intent.clone(),
+            sources,
+            schema: policy.schema.clone(),
+            ir_sha256: generate_checksum(&ir_value.to_string()),
+            ir,
+            signature: None,
+        })
+    }
+
+    /// Tandatangani bundle; tanda tangan lama diganti.
+    pub fn sign(&mut self, key: &SigningKey) -> Result<(), String> {
+        self.signature = None;
+        let body = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        self.signature = Some(BundleSignature { key_id: key.id.clone(), signature: key.mac(&body.to_string()) });
+        Ok(())
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("serialize bundle failed: {}", e))
+    }
+
+    /// Baca dan periksa bundle: format dan versi, hash IR, lalu tanda tangan.
+    /// Dengan `keys`, bundle wajib ditandatangani salah satu kunci tersebut.
+    pub fn parse(text: &str, keys: Option<&[SigningKey]>) -> Result<Self, String> {
+        let mut raw: Value = serde_json::from_str(text).map_err(|e| format!("invalid bundle JSON: {}", e))?;
+        if raw["format"] != BUNDLE_FORMAT {
+            return Err(format!("not an OmniLang bundle (format {})", raw["format"]));
+        }
+        let version = raw["format_version"].as_u64().unwrap_or_default();
+        if version != u64::from(BUNDLE_FORMAT_VERSION) {
+            return Err(format!(
+                "unsupported bundle format version {} (this compiler reads version {})",
+                version, BUNDLE_FORMAT_VERSION
+            ));
+        }
+        let ir_version = raw["ir_version"].as_u64().unwrap_or_default();
+        if !(u64::from(MIN_IR_VERSION)..=u64::from(IR_VERSION)).contains(&ir_version) {
+            return Err(format!(
+                "bundle IR version {} is not supported (expected {} to {}); recompile with compiler {}",
+                ir_version,
+                MIN_IR_VERSION,
+                IR_VERSION,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        if generate_checksum(&raw["ir"].to_string()) != raw["ir_sha256"].as_str().unwrap_or_default() {
+            return Err("bundle IR was modified (ir_sha256 mismatch)".to_string());
+        }
+
+        let signature = raw.as_object_mut().and_then(|fields| fields.remove("signature"));
+        match (keys, signature) {
+            (Some(_), None) => return Err("bundle is not signed".to_string()),
+            (Some(keys), Some(signature)) => {
+                let signature: BundleSignature =
+                    serde_json::from_value(signature).map_err(|e| format!("invalid bundle signature: {}", e))?;
+                verify_mac(keys, &signature.key_id, &raw.to_string(), &signature.signature)
+                    .map_err(|e| format!("bundle {}", e))?;
+                raw["signature"] = serde_json::to_value(signature).map_err(|e| e.to_string())?;
+            }
+            (None, Some(signature)) => raw["signature"] = signature,
+            (None, None) => {}
+        }
+        serde_json::from_value(raw).map_err(|e| format!("invalid bundle: {}", e))
+    }
+
+    pub fn load(path: impl AsRef<Path>, keys: Option<&[SigningKey]>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+        Self::parse(&text, keys)
+    }
+
+    /// Validasi konteks terhadap `schema` bundle.
+    pub fn validate_context(&self, context: &Value) -> Result<(), Vec<SchemaViolation>> {
+        let violations = crate::schema::validate(&self.schema, context);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Jalankan IR bundle pada `now_ms` dengan state temporal milik pemanggil.
+    pub fn execute(&self, context: Value, temporal: &mut TemporalState, now_ms: u64) -> Decision {
+        crate::ir_interpreter::execute_ir_with_state(&self.ir, context, temporal, now_ms)
+    }
+}
@@ -1,82 +1,475 @@
+//! Cache keputusan untuk [`crate::runtime::Runtime`].
+//!
+//! Kunci cache adalah `policy_key:context_hash`: hash kebijakan (IR + konfigurasi
+//! runtime) dan SHA-256 JSON kanonis konteks. Tier memori adalah LRU berkapasitas
+//! tetap; tier persisten opsional berupa file JSONL append-only yang di-compact
+//! saat dibuka.
+
+use crate::ast::{ActionCall, InvariantCheck, Operand, Policy, Rule};
+use crate::audit::{canonical_hash, policy_hash};
+use crate::runtime::{Decision, RuntimeConfig};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use omnilang_core::runtime::Decision;
+/// Kapasitas default tier memori.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Hitungan hit/miss cache sejak dibuat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub memory_hits: u64,
+    pub disk_hits: u64,
+    pub misses: u64,
+    /// Eksekusi yang tidak memakai cache (kebijakan temporal).
+    pub bypassed: u64,
+    /// Entri yang dibuang dari tier memori karena kapasitas penuh.
+    pub evictions: u64,
+    /// Entri yang dihapus lewat invalidasi kebijakan.
+    pub invalidations: u64,
+    /// Jumlah entri saat ini (memori, atau file jika tier persisten aktif).
+    pub entries: u64,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// Tier tempat sebuah hit ditemukan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheTier {
+    Memory,
+    Disk,
+}
+
+impl std::fmt::Display for CacheTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CacheTier::Memory => "memory",
+            CacheTier::Disk => "disk",
+        })
+    }
+}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct CacheEntry {
-    ts_millis: u128,
+struct MemoryEntry {
+    policy: String,
+    stored_ms: u64,
+    tick: u64,
     decision: Decision,
 }
 
-#[derive(Debug, Clone)]
-pub struct EvalCache {
-    file: PathBuf,
-    ttl: Duration,
-    enabled: bool,
+/// Cache keputusan dua tier: LRU di memori, plus file JSONL opsional.
+pub struct DecisionCache {
+    capacity: usize,
+    ttl: Option<Duration>,
+    memory: HashMap<String, MemoryEntry>,
+    /// Urutan pemakaian: tick terkecil adalah entri yang paling lama tidak dipakai.
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    store: Option<CacheStore>,
+    /// Nama kebijakan -> kunci kebijakan terakhir (lihat [`DecisionCache::bind_policy`]).
+    bindings: BTreeMap<String, String>,
+    stats: CacheStats,
 }
 
-impl EvalCache {
-    pub fn new(file: PathBuf, ttl: Duration, enabled: bool) -> Self {
-        Self { file, ttl, enabled }
+impl DecisionCache {
+    /// Cache memori saja dengan paling banyak `capacity` entri (minimal 1).
+    pub fn new(capacity: usize) -> Self {
+        DecisionCache {
+            capacity: capacity.max(1),
+            ttl: None,
+            memory: HashMap::new(),
+            recency: BTreeMap::new(),
+            tick: 0,
+            store: None,
+            bindings: BTreeMap::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Entri lebih tua dari `ttl` dianggap miss.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
     }
 
-    pub fn get(&self, key: &str) -> Option<Decision> {
-        if !self.enabled {
-            return None;
+    /// Aktifkan tier persisten di `path`; file dibuat jika belum ada.
+    pub fn with_store(mut self, path: impl AsRef<Path>) -> Result<Self, String> {
+        let store = CacheStore::open(path.as_ref())?;
+        self.bindings = store.bindings.clone();
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let entries = match &self.store {
+            Some(store) => store.index.len(),
+            None => self.memory.len(),
+        };
+        CacheStats { entries: entries as u64, ..self.stats }
+    }
+
+    /// Catat eksekusi yang tidak bisa di-cache.
+    pub fn record_bypass(&mut self) {
+        self.stats.bypassed += 1;
+    }
+
+    pub fn get(&mut self, policy: &str, context_hash: &str) -> Option<(Decision, CacheTier)> {
+        let key = entry_key(policy, context_hash);
+        let now = now_ms();
+        if let Some(entry) = self.memory.get(&key) {
+            if self.expired(entry.stored_ms, now) {
+                self.remove_memory(&key);
+            } else {
+                let decision = entry.decision.clone();
+                self.touch(&key);
+                self.stats.hits += 1;
+                self.stats.memory_hits += 1;
+                return Some((decision, CacheTier::Memory));
+            }
         }
-        let map = self.read_all().ok()?;
-        let entry = map.get(key)?;
-        if Self::expired(entry, self.ttl) {
-            return None;
+        let stored = match &mut self.store {
+            Some(store) => store.get(&key),
+            None => None,
+        };
+        match stored {
+            Some((stored_ms, decision)) if !self.expired(stored_ms, now) => {
+                self.insert_memory(key, policy.to_string(), stored_ms, decision.clone());
+                self.stats.hits += 1;
+                self.stats.disk_hits += 1;
+                Some((decision, CacheTier::Disk))
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
         }
-        Some(entry.decision.clone())
     }
 
-    pub fn put(&self, key: &str, decision: &Decision) {
-        if !self.enabled {
-            return;
+    /// Simpan keputusan; tier persisten hanya menambah satu baris.
+    pub fn put(&mut self, policy: &str, context_hash: &str, decision: &Decision) -> Result<(), String> {
+        let key = entry_key(policy, context_hash);
+        let stored_ms = now_ms();
+        if let Some(store) = &mut self.store {
+            store.append_entry(&key, policy, stored_ms, decision)?;
         }
-        let mut map = self.read_all().unwrap_or_default();
-        let entry = CacheEntry {
-            ts_millis: Self::now_millis(),
-            decision: decision.clone(),
+        self.insert_memory(key, policy.to_string(), stored_ms, decision.clone());
+        Ok(())
+    }
+
+    /// Hapus semua entri milik `policy` dari kedua tier; mengembalikan jumlah entri yang dihapus.
+    pub fn invalidate_policy(&mut self, policy: &str) -> Result<usize, String> {
+        let stale: Vec<String> = self.memory.iter().filter(|(_, e)| e.policy == policy).map(|(k, _)| k.clone()).collect();
+        for key in &stale {
+            self.remove_memory(key);
+        }
+        let mut removed = stale.len();
+        if let Some(store) = &mut self.store {
+            removed = removed.max(store.invalidate(policy)?);
+        }
+        self.stats.invalidations += removed as u64;
+        Ok(removed)
+    }
+
+    /// Ikat nama kebijakan (mis. path file) ke `policy`. Jika nama itu sebelumnya
+    /// terikat ke hash lain, entri hash lama diinvalidasi karena kebijakannya berubah.
+    pub fn bind_policy(&mut self, name: &str, policy: &str) -> Result<usize, String> {
+        let previous = self.bindings.insert(name.to_string(), policy.to_string());
+        if previous.as_deref() == Some(policy) {
+            return Ok(0);
+        }
+        let removed = match &previous {
+            Some(old) => self.invalidate_policy(old)?,
+            None => 0,
         };
-        map.insert(key.to_string(), entry);
-        let _ = self.write_all(&map);
+        if let Some(store) = &mut self.store {
+            store.bind(name, policy)?;
+        }
+        Ok(removed)
     }
 
-    fn expired(entry: &CacheEntry, ttl: Duration) -> bool {
-        if ttl.as_millis() == 0 {
-            return false;
+    /// Kosongkan kedua tier.
+    pub fn clear(&mut self) -> Result<(), String> {
+        self.memory.clear();
+        self.recency.clear();
+        self.bindings.clear();
+        if let Some(store) = &mut self.store {
+            store.clear()?;
         }
-        let now = Self::now_millis();
-        now.saturating_sub(entry.ts_millis) > ttl.as_millis()
+        Ok(())
     }
 
-    fn now_millis() -> u128 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_millis())
-            .unwrap_or(0)
+    fn expired(&self, stored_ms: u64, now: u64) -> bool {
+        self.ttl.is_some_and(|ttl| u128::from(now.saturating_sub(stored_ms)) > ttl.as_millis())
+    }
+
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        if let Some(entry) = self.memory.get_mut(key) {
+            self.recency.remove(&entry.tick);
+            entry.tick = self.tick;
+            self.recency.insert(self.tick, key.to_string());
+        }
     }
 
-    fn read_all(&self) -> Result<HashMap<String, CacheEntry>, ()> {
-        if !self.file.exists() {
-            return Ok(HashMap::new());
+    fn insert_memory(&mut self, key: String, policy: String, stored_ms: u64, decision: Decision) {
+        self.remove_memory(&key);
+        while self.memory.len() >= self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.memory.remove(&oldest);
+            self.stats.evictions += 1;
         }
-        let raw = fs::read_to_string(&self.file).map_err(|_| ())?;
-        serde_json::from_str(&raw).map_err(|_| ())
+        self.tick += 1;
+        self.recency.insert(self.tick, key.clone());
+        self.memory.insert(key, MemoryEntry { policy, stored_ms, tick: self.tick, decision });
     }
 
-    fn write_all(&self, map: &HashMap<String, CacheEntry>) -> Result<(), ()> {
-        if let Some(parent) = self.file.parent() {
-            let _ = fs::create_dir_all(parent);
+    fn remove_memory(&mut self, key: &str) {
+        if let Some(entry) = self.memory.remove(key) {
+            self.recency.remove(&entry.tick);
         }
-        let blob = serde_json::to_string_pretty(map).map_err(|_| ())?;
-        fs::write(&self.file, blob).map_err(|_| ())
+    }
+}
+
+/// Kunci kebijakan untuk cache: hash IR kebijakan bersama konfigurasi runtime,
+/// karena batas guard runtime ikut menentukan keputusan.
+pub fn policy_key(policy: &Policy, config: &RuntimeConfig) -> String {
+    canonical_hash(&json!({ "policy": policy_hash(policy), "config": config }))
+}
+
+/// Hash konteks yang dipakai sebagai bagian kedua kunci cache.
+pub fn context_hash(context: &Value) -> String {
+    canonical_hash(context)
+}
+
+fn entry_key(policy: &str, context_hash: &str) -> String {
+    format!("{}:{}", policy, context_hash)
+}
+
+/// Keputusan kebijakan hanya bergantung pada konteks jika tidak ada operator
+/// temporal (FOR/BECOMES/UNTIL/COUNT), termasuk di parameter aksi dan invarian.
+pub fn is_cacheable(policy: &Policy) -> bool {
+    let invariants = policy.constraints.iter().filter_map(|c| c.invariant.as_ref());
+    !policy.rules.iter().any(rule_is_temporal)
+        && !invariants.clone().any(|i| matches!(&i.check, InvariantCheck::Never { condition } if condition.is_temporal()))
+        && !invariants.filter_map(|i| i.fallback.as_ref()).any(action_is_temporal)
+}
+
+fn rule_is_temporal(rule: &Rule) -> bool {
+    match rule {
+        Rule::Standard(r) => {
+            r.condition.is_temporal() || action_is_temporal(&r.action) || r.else_action.as_ref().is_some_and(action_is_temporal)
+        }
+        Rule::For(f) => f.body.iter().any(rule_is_temporal),
+        Rule::While(w) => w.condition.is_temporal() || w.body.iter().any(rule_is_temporal),
+        Rule::Match(m) => m.scrutinee.is_temporal() || m.arms.iter().any(|arm| action_is_temporal(&arm.action)),
+    }
+}
+
+fn action_is_temporal(action: &ActionCall) -> bool {
+    action.params.iter().any(|p| Operand::is_temporal(&p.value))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default()
+}
+
+/// Satu baris file cache.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum StoreLine {
+    Entry { key: String, policy: String, stored_ms: u64, decision: Box<Decision> },
+    /// Semua entri `policy` yang ditulis sebelum baris ini tidak berlaku lagi.
+    Invalidate { invalidate: String },
+    /// Nama kebijakan terikat ke hash `policy` (lihat [`DecisionCache::bind_policy`]).
+    Bind { bind: String, policy: String },
+}
+
+impl StoreLine {
+    /// Dibaca lewat `Value`: buffer serde untuk enum untagged tidak mendukung field u128 di `Decision`.
+    fn parse(line: &str) -> Option<StoreLine> {
+        let mut value: Value = serde_json::from_str(line).ok()?;
+        let text = |value: &Value, field: &str| value.get(field)?.as_str().map(str::to_string);
+        if let Some(invalidate) = text(&value, "invalidate") {
+            return Some(StoreLine::Invalidate { invalidate });
+        }
+        if let Some(bind) = text(&value, "bind") {
+            return Some(StoreLine::Bind { bind, policy: text(&value, "policy")? });
+        }
+        let decision = serde_json::from_value(value.get_mut("decision")?.take()).ok()?;
+        Some(StoreLine::Entry {
+            key: text(&value, "key")?,
+            policy: text(&value, "policy")?,
+            stored_ms: value.get("stored_ms")?.as_u64()?,
+            decision,
+        })
+    }
+}
+
+struct StoreEntry {
+    offset: u64,
+    policy: String,
+    stored_ms: u64,
+}
+
+/// Tier persisten: file JSONL append-only dengan indeks offset di memori.
+struct CacheStore {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    index: HashMap<String, StoreEntry>,
+    bindings: BTreeMap<String, String>,
+}
+
+impl CacheStore {
+    fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("cannot create dir {}: {}", parent.display(), e))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("cannot open cache file {}: {}", path.display(), e))?;
+        let mut store = CacheStore { path: path.to_path_buf(), file, len: 0, index: HashMap::new(), bindings: BTreeMap::new() };
+        let lines = store.load()?;
+        if lines > store.index.len() + store.bindings.len() {
+            store.compact()?;
+        }
+        Ok(store)
+    }
+
+    /// Bangun ulang indeks dari file; mengembalikan jumlah baris yang dibaca.
+    fn load(&mut self) -> Result<usize, String> {
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(0)).map_err(|e| format!("read cache file failed: {}", e))?;
+        let (mut offset, mut lines) = (0u64, 0usize);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line).map_err(|e| format!("read cache file failed: {}", e))?;
+            if read == 0 {
+                break;
+            }
+            lines += 1;
+            match StoreLine::parse(line.trim_end()) {
+                Some(StoreLine::Entry { key, policy, stored_ms, .. }) => {
+                    self.index.insert(key, StoreEntry { offset, policy, stored_ms });
+                }
+                Some(StoreLine::Invalidate { invalidate }) => self.index.retain(|_, e| e.policy != invalidate),
+                Some(StoreLine::Bind { bind, policy }) => {
+                    self.bindings.insert(bind, policy);
+                }
+                // Baris rusak (mis. penulisan terpotong) diabaikan dan hilang saat compact
+                None => {}
+            }
+            offset += read as u64;
+        }
+        self.len = offset;
+        Ok(lines)
+    }
+
+    fn get(&mut self, key: &str) -> Option<(u64, Decision)> {
+        let entry = self.index.get(key)?;
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(entry.offset)).ok()?;
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        match StoreLine::parse(line.trim_end())? {
+            StoreLine::Entry { decision, .. } => Some((entry.stored_ms, *decision)),
+            _ => None,
+        }
+    }
+
+    fn append_entry(&mut self, key: &str, policy: &str, stored_ms: u64, decision: &Decision) -> Result<(), String> {
+        let offset = self.append(&StoreLine::Entry { key: key.to_string(), policy: policy.to_string(), stored_ms, decision: Box::new(decision.clone()) })?;
+        self.index.insert(key.to_string(), StoreEntry { offset, policy: policy.to_string(), stored_ms });
+        Ok(())
+    }
+
+    fn invalidate(&mut self, policy: &str) -> Result<usize, String> {
+        let before = self.index.len();
+        self.index.retain(|_, e| e.policy != policy);
+        let removed = before - self.index.len();
+        if removed > 0 {
+            self.append(&StoreLine::Invalidate { invalidate: policy.to_string() })?;
+        }
+        Ok(removed)
+    }
+
+    fn bind(&mut self, name: &str, policy: &str) -> Result<(), String> {
+        self.append(&StoreLine::Bind { bind: name.to_string(), policy: policy.to_string() })?;
+        self.bindings.insert(name.to_string(), policy.to_string());
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.index.clear();
+        self.bindings.clear();
+        self.file.set_len(0).map_err(|e| format!("truncate cache file {} failed: {}", self.path.display(), e))?;
+        self.len = 0;
+        Ok(())
+    }
+
+    /// Tulis satu baris di akhir file; mengembalikan offset awalnya.
+    fn append(&mut self, line: &StoreLine) -> Result<u64, String> {
+        let mut text = serde_json::to_string(line).map_err(|e| format!("serialize cache entry failed: {}", e))?;
+        text.push('\n');
+        self.file
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("write cache file {} failed: {}", self.path.display(), e))?;
+        let offset = self.len;
+        self.len += text.len() as u64;
+        Ok(offset)
+    }
+
+    /// Tulis ulang file hanya dengan entri dan binding yang masih berlaku.
+    fn compact(&mut self) -> Result<(), String> {
+        let mut content = Vec::new();
+        (&self.file).seek(SeekFrom::Start(0)).map_err(|e| format!("read cache file failed: {}", e))?;
+        (&self.file).read_to_end(&mut content).map_err(|e| format!("read cache file failed: {}", e))?;
+        let mut live: Vec<(&String, &StoreEntry)> = self.index.iter().collect();
+        live.sort_by_key(|(_, entry)| entry.offset);
+
+        let mut out = String::new();
+        let mut index = HashMap::new();
+        for (key, entry) in live {
+            let start = entry.offset as usize;
+            let end = content[start..].iter().position(|b| *b == b'\n').map_or(content.len(), |p| start + p);
+            index.insert(key.clone(), StoreEntry { offset: out.len() as u64, policy: entry.policy.clone(), stored_ms: entry.stored_ms });
+            out.push_str(&String::from_utf8_lossy(&content[start..end]));
+            out.push('\n');
+        }
+        for (name, policy) in &self.bindings {
+            let line = serde_json::to_string(&StoreLine::Bind { bind: name.clone(), policy: policy.clone() })
+                .map_err(|e| format!("serialize cache entry failed: {}", e))?;
+            out.push_str(&line);
+            out.push('\n');
+        }
+
+        let tmp = self.path.with_extension("compact");
+        fs::write(&tmp, &out).map_err(|e| format!("write cache file {} failed: {}", tmp.display(), e))?;
+        fs::rename(&tmp, &self.path).map_err(|e| format!("replace cache file {} failed: {}", self.path.display(), e))?;
+        self.file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("cannot open cache file {}: {}", self.path.display(), e))?;
+        self.len = out.len() as u64;
+        self.index = index;
+        Ok(())
     }
 }
@@ -0,0 +1,270 @@
+//! Komposisi kebijakan deklaratif: `IMPORT:` kumpulan rule bersama, `EXTEND:`
+//! kebijakan dasar, dan `OVERRIDE` rule bernama.
+//!
+//! Path dirujuk relatif terhadap file yang merujuknya; jika tidak ada, dicari
+//! di `.omni_modules/<nama>/src/main.omni` seperti `import` pada Program.
+
+use crate::ast::{ActionRequirement, Policy, Rule, UnitDecl};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Baca, parse, dan resolve kebijakan dari file.
+pub fn load_policy(path: impl AsRef<Path>) -> Result<Policy, String> {
+    let path = path.as_ref();
+    let policy = parse_file(path)?;
+    resolve_policy(policy, path)
+}
+
+/// Gabungkan kebijakan dasar dan impor ke `policy` yang berasal dari file `origin`.
+/// Hasilnya tidak lagi memiliki [`crate::ast::Composition`] dan siap untuk `build_policy_ir`.
+pub fn resolve_policy(policy: Policy, origin: &Path) -> Result<Policy, String> {
+    let mut stack = vec![canonical(origin)];
+    resolve(policy, origin, None, &mut stack)
+}
+
+/// Seperti [`resolve_policy`], tetapi kebijakan dasar dan impor diambil dari `sources`
+/// (mis. hasil [`read_sources`] yang sudah diverifikasi), bukan dibaca dari disk.
+/// File yang tidak ada di `sources` ditolak.
+pub fn resolve_policy_from(policy: Policy, origin: &Path, sources: &Sources) -> Result<Policy, String> {
+    let mut stack = vec![canonical(origin)];
+    resolve(policy, origin, Some(sources), &mut stack)
+}
+
+fn resolve(mut policy: Policy, origin: &Path, sources: Option<&Sources>, stack: &mut Vec<PathBuf>) -> Result<Policy, String> {
+    let composition = std::mem::take(&mut policy.composition);
+    if composition.is_empty() {
+        return Ok(policy);
+    }
+    let dir = origin.parent().unwrap_or_else(|| Path::new(""));
+
+    // Rule warisan beserta asalnya, untuk pesan galat
+    let mut inherited: Vec<(Rule, String)> = Vec::new();
+    let mut base = None;
+    if let Some(target) = &composition.extends {
+        let base_policy = load_nested(dir, target, sources, stack)?;
+        inherited.extend(base_policy.rules.iter().map(|r| (r.clone(), target.clone())));
+        base = Some(base_policy);
+    }
+    let mut units = base.as_ref().map(|b| b.units.clone()).unwrap_or_default();
+    let mut schema = base.as_ref().map(|b| b.schema.clone()).unwrap_or_default();
+    let mut capabilities = base.as_ref().map(|b| b.capabilities.clone()).unwrap_or_default();
+    for target in &composition.imports {
+        let imported = load_nested(dir, target, sources, stack)?;
+        inherited.extend(imported.rules.into_iter().map(|r| (r, target.clone())));
+        units.extend(imported.units);
+        schema.extend(imported.schema);
+        capabilities.extend(imported.capabilities);
+    }
+
+    let mut origins: HashMap<String, String> = HashMap::new();
+    for (rule, from) in &inherited {
+        if let Some(name) = rule.name() {
+            if let Some(first) = origins.insert(name.to_string(), from.clone()) {
+                return Err(format!("Rule '{}' is defined in both '{}' and '{}'", name, first, from));
+            }
+        }
+    }
+
+    let mut rules: Vec<Rule> = inherited.into_iter().map(|(rule, _)| rule).collect();
+    for rule in std::mem::take(&mut policy.rules) {
+        let Some(name) = rule.name().map(str::to_string) else {
+            rules.push(rule);
+            continue;
+        };
+        let existing = rules.iter().position(|r| r.name() == Some(name.as_str()));
+        match (existing, composition.overrides.contains(&name)) {
+            (Some(index), true) => rules[index] = rule,
+            (None, true) => return Err(format!("OVERRIDE of unknown rule '{}'", name)),
+            (Some(_), false) => {
+                return Err(format!(
+                    "Rule '{}' is already defined in '{}'; use OVERRIDE to replace it",
+                    name, origins[&name]
+                ))
+            }
+            (None, false) => rules.push(rule),
+        }
+    }
+    policy.rules = rules;
+
+    units.extend(std::mem::take(&mut policy.units));
+    policy.units = merge_units(units)?;
+    schema.extend(std::mem::take(&mut policy.schema));
+    policy.schema = crate::schema::merge(schema)?;
+    capabilities.extend(std::mem::take(&mut policy.capabilities));
+    policy.capabilities = merge_capabilities(capabilities)?;
+
+    if let Some(base) = base {
+        policy.intent = policy.intent.or(base.intent);
+        policy.context = policy.context.or(base.context);
+        if policy.actors.is_empty() {
+            policy.actors = base.actors;
+        }
+        policy.guard.max_iterations = policy.guard.max_iterations.or(base.guard.max_iterations);
+        policy.guard.max_time_ms = policy.guard.max_time_ms.or(base.guard.max_time_ms);
+        if policy.combining == Default::default() {
+            policy.combining = base.combining;
+        }
+        policy.assumptions = [base.assumptions, policy.assumptions].concat();
+        policy.constraints = [base.constraints, policy.constraints].concat();
+        policy.impacts = [base.impacts, policy.impacts].concat();
+        policy.traces = [base.traces, policy.traces].concat();
+        policy.reviews = [base.reviews, policy.reviews].concat();
+    }
+    // Satuan dari kebijakan dasar/impor juga berlaku untuk rule di file ini
+    crate::units::apply_declared_units(&mut policy);
+    crate::units::check_policy_units(&policy)?;
+    Ok(policy)
+}
+
+/// File kebijakan beserta semua file `IMPORT:`/`EXTEND:` yang dirujuknya
+/// (langsung maupun tidak), masing-masing sekali.
+pub fn source_files(path: impl AsRef<Path>) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    collect_sources(path.as_ref(), &mut files)?;
+    Ok(files)
+}
+
+fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let key = canonical(path);
+    if files.iter().any(|f| canonical(f) == key) {
+        return Ok(());
+    }
+    files.push(path.to_path_buf());
+    let composition = parse_file(path)?.composition;
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for target in composition.extends.iter().chain(&composition.imports) {
+        collect_sources(&locate(dir, target)?, files)?;
+    }
+    Ok(())
+}
+
+/// Isi file kebijakan per path kanonik, dibaca sekali lewat [`read_sources`].
+#[derive(Debug, Clone, Default)]
+pub struct Sources {
+    files: HashMap<PathBuf, String>,
+}
+
+impl Sources {
+    pub fn insert(&mut self, path: &Path, source: String) {
+        self.files.insert(canonical(path), source);
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&str> {
+        self.files.get(&canonical(path)).map(String::as_str)
+    }
+}
+
+/// Kumpulkan kebijakan `path` (isinya `source`) beserta semua file `IMPORT:`/`EXTEND:`
+/// yang dirujuknya. Setiap file dibaca sekali lewat `read`, dan rujukan diambil dari
+/// isi yang dikembalikan `read`, sehingga pemeriksaan di `read` berlaku untuk isi yang dipakai.
+pub fn read_sources(
+    path: impl AsRef<Path>,
+    source: String,
+    mut read: impl FnMut(&Path) -> Result<String, String>,
+) -> Result<Sources, String> {
+    let mut sources = Sources::default();
+    read_nested(path.as_ref(), source, &mut read, &mut sources)?;
+    Ok(sources)
+}
+
+fn read_nested(
+    path: &Path,
+    source: String,
+    read: &mut impl FnMut(&Path) -> Result<String, String>,
+    sources: &mut Sources,
+) -> Result<(), String> {
+    let composition = parse_source(path, &source)?.composition;
+    sources.insert(path, source);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for target in composition.extends.iter().chain(&composition.imports) {
+        let nested = locate(dir, target)?;
+        if sources.get(&nested).is_none() {
+            let source = read(&nested)?;
+            read_nested(&nested, source, read, sources)?;
+        }
+    }
+    Ok(())
+}
+
+fn load_nested(dir: &Path, target: &str, sources: Option<&Sources>, stack: &mut Vec<PathBuf>) -> Result<Policy, String> {
+    let path = locate(dir, target)?;
+    let key = canonical(&path);
+    if stack.contains(&key) {
+        return Err(format!("Circular policy composition through '{}'", target));
+    }
+    let policy = match sources {
+        Some(sources) => {
+            let source = sources.get(&path).ok_or_else(|| format!("'{}' was not among the verified sources", path.display()))?;
+            parse_source(&path, source)?
+        }
+        None => parse_file(&path)?,
+    };
+    stack.push(key);
+    let resolved = resolve(policy, &path, sources, stack);
+    stack.pop();
+    resolved.map_err(|e| format!("In '{}': {}", target, e))
+}
+
+fn locate(dir: &Path, target: &str) -> Result<PathBuf, String> {
+    let local = dir.join(target);
+    if local.is_file() {
+        return Ok(local);
+    }
+    let module = PathBuf::from(".omni_modules").join(target).join("src").join("main.omni");
+    if module.is_file() {
+        return Ok(module);
+    }
+    Err(format!("Policy '{}' not found (looked in '{}')", target, dir.display()))
+}
+
+fn parse_file(path: &Path) -> Result<Policy, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Error reading policy '{}': {}", path.display(), e))?;
+    parse_source(path, &source)
+}
+
+fn parse_source(path: &Path, source: &str) -> Result<Policy, String> {
+    let tokens = crate::lexer::Lexer::new(source)
+        .tokenize()
+        .map_err(|e| format!("Lexer Error in '{}': {}", path.display(), e))?;
+    crate::parser::Parser::new(tokens)
+        .parse_policy()
+        .map_err(|e| format!("Parser Error in '{}': {}", path.display(), e))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Gabungkan `CAPABILITY:` dari kebijakan tersusun; satu aksi hanya boleh punya satu daftar kebutuhan.
+fn merge_capabilities(decls: Vec<ActionRequirement>) -> Result<Vec<ActionRequirement>, String> {
+    let mut merged: Vec<ActionRequirement> = Vec::new();
+    for decl in decls {
+        match merged.iter().find(|d| d.action == decl.action) {
+            Some(existing) if existing.requires != decl.requires => {
+                return Err(format!("Conflicting CAPABILITY entries for action '{}'", decl.action));
+            }
+            Some(_) => {}
+            None => merged.push(decl),
+        }
+    }
+    Ok(merged)
+}
+
+/// Deklarasi `UNITS:` yang sama boleh berulang; satuan berbeda untuk field yang sama tidak.
+fn merge_units(decls: Vec<UnitDecl>) -> Result<Vec<UnitDecl>, String> {
+    let mut merged: Vec<UnitDecl> = Vec::new();
+    for decl in decls {
+        match merged.iter().find(|d| d.field == decl.field) {
+            Some(existing) if existing.unit != decl.unit => {
+                return Err(format!(
+                    "Conflicting units for '{}': {} and {}",
+                    decl.field, existing.unit, decl.unit
+                ));
+            }
+            Some(_) => {}
+            None => merged.push(decl),
+        }
+    }
+    Ok(merged)
+}
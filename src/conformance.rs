@@ -0,0 +1,582 @@
+//! Uji konformansi diferensial antar engine eksekusi (`omnilang conformance`).
+//!
+//! Setiap kebijakan dijalankan lewat `Runtime` (AST), interpreter IR, modul WASM
+//! hasil [`crate::wasm_codegen`], dan (opsional) runner native pada konteks yang
+//! sama: konteks kosong, file konteks yang diberikan, dan konteks acak yang dibangun
+//! dari field dan literal di kebijakan. Engine pertama menjadi acuan; perbedaan
+//! aksi, status guard, atau jumlah rule yang dievaluasi dilaporkan sebagai
+//! [`Divergence`]. `ir-opt` dan `wasm-opt` menjalankan IR hasil [`crate::optimizer`],
+//! sehingga optimasi juga harus menghasilkan keputusan dan jumlah rule yang sama.
+
+use crate::ast::{Condition, FieldPath, MatchPattern, Operand, PathSegment, Policy, Rule};
+use crate::ir::{build_policy_ir, PolicyIR};
+use crate::runtime::{Decision, Runtime};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Engine {
+    Runtime,
+    Ir,
+    #[serde(rename = "ir-opt")]
+    IrOpt,
+    Wasm,
+    #[serde(rename = "wasm-opt")]
+    WasmOpt,
+    Native,
+}
+
+impl Engine {
+    /// Engine yang dijalankan tanpa `--engines`; runner native butuh `cargo build` per kebijakan.
+    pub const DEFAULT: [Engine; 5] = [Engine::Runtime, Engine::Ir, Engine::IrOpt, Engine::Wasm, Engine::WasmOpt];
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "runtime" => Ok(Engine::Runtime),
+            "ir" => Ok(Engine::Ir),
+            "ir-opt" => Ok(Engine::IrOpt),
+            "wasm" => Ok(Engine::Wasm),
+            "wasm-opt" => Ok(Engine::WasmOpt),
+            "native" => Ok(Engine::Native),
+            other => Err(format!("unknown engine '{}' (expected runtime, ir, ir-opt, wasm, wasm-opt or native)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Engine::Runtime => "runtime",
+            Engine::Ir => "ir",
+            Engine::IrOpt => "ir-opt",
+            Engine::Wasm => "wasm",
+            Engine::WasmOpt => "wasm-opt",
+            Engine::Native => "native",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConformanceOptions {
+    pub engines: Vec<Engine>,
+    /// Konteks bernama yang selalu dijalankan, selain konteks kosong.
+    pub contexts: Vec<(String, Value)>,
+    pub random_contexts: usize,
+    pub seed: u64,
+}
+
+impl Default for ConformanceOptions {
+    fn default() -> Self {
+        ConformanceOptions { engines: Engine::DEFAULT.to_vec(), contexts: Vec::new(), random_contexts: 20, seed: 1 }
+    }
+}
+
+/// Bagian keputusan yang dibandingkan antar engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub actions: Vec<String>,
+    pub guard_triggered: bool,
+    pub rules_evaluated: usize,
+}
+
+impl From<&Decision> for Outcome {
+    fn from(decision: &Decision) -> Self {
+        Outcome {
+            actions: decision.actions.clone(),
+            guard_triggered: decision.guard_triggered,
+            rules_evaluated: decision.metrics.rules_evaluated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Divergence {
+    pub policy: String,
+    /// `empty`, nama file konteks, atau `random #n`.
+    pub context: String,
+    pub input: Value,
+    pub reference: Engine,
+    pub engine: Engine,
+    pub expected: Outcome,
+    /// `None` jika engine gagal; alasannya ada di `error`.
+    pub actual: Option<Outcome>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Divergence {
+    /// Field yang berbeda: `actions`, `guard_triggered`, `rules_evaluated`.
+    pub fn fields(&self) -> Vec<&'static str> {
+        let Some(actual) = &self.actual else { return vec!["error"] };
+        let mut fields = Vec::new();
+        if actual.actions != self.expected.actions {
+            fields.push("actions");
+        }
+        if actual.guard_triggered != self.expected.guard_triggered {
+            fields.push("guard_triggered");
+        }
+        if actual.rules_evaluated != self.expected.rules_evaluated {
+            fields.push("rules_evaluated");
+        }
+        fields
+    }
+}
+
+/// Engine yang tidak bisa menjalankan kebijakan, mis. WASM untuk operator temporal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedEngine {
+    pub engine: Engine,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyReport {
+    pub policy: String,
+    pub contexts: usize,
+    pub engines: Vec<Engine>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<SkippedEngine>,
+}
+
+/// File yang bukan kebijakan atau gagal dimuat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    pub seed: u64,
+    pub policies: Vec<PolicyReport>,
+    pub divergences: Vec<Divergence>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_files: Vec<SkippedFile>,
+}
+
+impl ConformanceReport {
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Jalankan konformansi untuk file `.omni` dan direktori (tidak rekursif) di `paths`.
+pub fn run(paths: &[PathBuf], options: &ConformanceOptions) -> Result<ConformanceReport, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == "omni"))
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut report = ConformanceReport { seed: options.seed, ..Default::default() };
+    for file in files {
+        let name = file.display().to_string();
+        if is_program(&file) {
+            report.skipped_files.push(SkippedFile { file: name, reason: PROGRAM_FILE.to_string() });
+            continue;
+        }
+        match crate::compose::load_policy(&file) {
+            Ok(policy) => {
+                let (policy_report, divergences) = check_policy(&policy, &name, options);
+                report.policies.push(policy_report);
+                report.divergences.extend(divergences);
+            }
+            Err(reason) => report.skipped_files.push(SkippedFile { file: name, reason }),
+        }
+    }
+    Ok(report)
+}
+
+/// Alasan untuk file program yang dilewati.
+pub const PROGRAM_FILE: &str = "program (MODULE), not a policy";
+
+/// File program (`MODULE`) dijalankan `ProgramEvaluator`, bukan engine kebijakan.
+fn is_program(path: &std::path::Path) -> bool {
+    let source = std::fs::read_to_string(path).unwrap_or_default();
+    let tokens = crate::lexer::Lexer::new(&source).tokenize().unwrap_or_default();
+    tokens.iter().any(|t| matches!(t.token_type, crate::lexer::TokenType::Module))
+}
+
+/// Jalankan satu kebijakan lewat semua engine dan bandingkan dengan engine pertama.
+pub fn check_policy(policy: &Policy, name: &str, options: &ConformanceOptions) -> (PolicyReport, Vec<Divergence>) {
+    let ir = build_policy_ir(policy);
+    let mut optimized = ir.clone();
+    crate::optimizer::optimize(&mut optimized);
+    let mut contexts = vec![("empty".to_string(), Value::Object(Map::new()))];
+    contexts.extend(options.contexts.iter().cloned());
+    let generated = generate_contexts(policy, options.random_contexts, options.seed);
+    contexts.extend(generated.into_iter().enumerate().map(|(i, ctx)| (format!("random #{}", i + 1), ctx)));
+
+    let mut report = PolicyReport { policy: name.to_string(), contexts: contexts.len(), engines: Vec::new(), skipped: Vec::new() };
+    let mut runners = Vec::new();
+    for &engine in &options.engines {
+        match Runner::prepare(engine, policy, &ir, &optimized) {
+            Ok(runner) => {
+                report.engines.push(engine);
+                runners.push(runner);
+            }
+            Err(reason) => report.skipped.push(SkippedEngine { engine, reason }),
+        }
+    }
+
+    let mut divergences = Vec::new();
+    let Some((reference, others)) = runners.split_first() else { return (report, divergences) };
+    for (label, context) in &contexts {
+        let expected = match reference.execute(context) {
+            Ok(decision) => Outcome::from(&decision),
+            Err(reason) => {
+                report.skipped.push(SkippedEngine { engine: reference.engine(), reason });
+                continue;
+            }
+        };
+        for runner in others {
+            let (actual, error) = match runner.execute(context) {
+                Ok(decision) => (Some(Outcome::from(&decision)), None),
+                Err(e) => (None, Some(e)),
+            };
+            if actual.as_ref() != Some(&expected) {
+                divergences.push(Divergence {
+                    policy: name.to_string(),
+                    context: label.clone(),
+                    input: context.clone(),
+                    reference: reference.engine(),
+                    engine: runner.engine(),
+                    expected: expected.clone(),
+                    actual,
+                    error,
+                });
+            }
+        }
+    }
+    (report, divergences)
+}
+
+enum Runner<'a> {
+    Runtime(&'a Policy),
+    Ir(Engine, &'a PolicyIR),
+    Wasm(Engine, Vec<u8>),
+    Native(PathBuf),
+}
+
+impl<'a> Runner<'a> {
+    /// `optimized` adalah `ir` setelah [`crate::optimizer::optimize`].
+    fn prepare(engine: Engine, policy: &'a Policy, ir: &'a PolicyIR, optimized: &'a PolicyIR) -> Result<Self, String> {
+        match engine {
+            Engine::Runtime => Ok(Runner::Runtime(policy)),
+            Engine::Ir => Ok(Runner::Ir(engine, ir)),
+            Engine::IrOpt => Ok(Runner::Ir(engine, optimized)),
+            Engine::Wasm => crate::wasm_codegen::compile_wasm(ir).map(|bytes| Runner::Wasm(engine, bytes)),
+            Engine::WasmOpt => crate::wasm_codegen::compile_wasm(optimized).map(|bytes| Runner::Wasm(engine, bytes)),
+            Engine::Native => {
+                let json = serde_json::to_string(ir).map_err(|e| format!("serialize IR failed: {}", e))?;
+                let bytes = crate::codegen::generate_native(&json)?;
+                let path = std::env::temp_dir().join(format!("omnilang_conformance_{}", std::process::id()));
+                std::fs::write(&path, bytes).map_err(|e| format!("cannot write '{}': {}", path.display(), e))?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+                }
+                Ok(Runner::Native(path))
+            }
+        }
+    }
+
+    fn engine(&self) -> Engine {
+        match self {
+            Runner::Runtime(_) => Engine::Runtime,
+            Runner::Ir(engine, _) | Runner::Wasm(engine, _) => *engine,
+            Runner::Native(_) => Engine::Native,
+        }
+    }
+
+    fn execute(&self, context: &Value) -> Result<Decision, String> {
+        match self {
+            Runner::Runtime(policy) => {
+                let mut runtime = Runtime::new();
+                if let Value::Object(fields) = context {
+                    for (key, value) in fields {
+                        runtime.update_data(key, value.clone());
+                    }
+                }
+                Ok(runtime.execute_policy(policy))
+            }
+            Runner::Ir(_, ir) => Ok(crate::ir_interpreter::execute_ir(ir, context.clone())),
+            Runner::Wasm(_, bytes) => crate::wasm_codegen::execute_wasm(bytes, context.clone()),
+            Runner::Native(binary) => {
+                use std::io::Write as _;
+                use std::process::{Command, Stdio};
+                let mut child = Command::new(binary)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| format!("cannot run native runner: {}", e))?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(context.to_string().as_bytes()).map_err(|e| e.to_string())?;
+                }
+                let output = child.wait_with_output().map_err(|e| e.to_string())?;
+                if !output.status.success() {
+                    return Err(format!("native runner failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+                }
+                serde_json::from_slice(&output.stdout).map_err(|e| format!("invalid native runner output: {}", e))
+            }
+        }
+    }
+}
+
+impl Drop for Runner<'_> {
+    fn drop(&mut self) {
+        if let Runner::Native(path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Bentuk konteks yang dibaca kebijakan: field beserta contoh nilainya, dan koleksi
+/// `FOR` dengan bentuk elemennya. Path relatif terhadap akar bentuk; `""` adalah
+/// elemen itu sendiri (`FOR x IN Xs` dengan `IF x > 5`).
+#[derive(Debug, Default)]
+struct Shape {
+    fields: BTreeMap<String, Vec<Value>>,
+    collections: BTreeMap<String, Shape>,
+}
+
+/// Contoh nilai untuk field yang tidak dibandingkan dengan literal.
+fn default_samples() -> Vec<Value> {
+    vec![Value::from(0), Value::from(50), Value::from(100), Value::from(true), Value::from("x")]
+}
+
+struct ShapeBuilder {
+    root: Shape,
+    /// Variabel iterasi yang aktif dan path koleksinya dari akar.
+    scopes: Vec<(String, Vec<String>)>,
+}
+
+impl ShapeBuilder {
+    /// Bentuk pemilik path dan sisa path relatifnya.
+    fn locate(&mut self, path: &FieldPath) -> (&mut Shape, String) {
+        let first = match path.segments.first() {
+            Some(PathSegment::Key(key)) => Some(key.as_str()),
+            _ => None,
+        };
+        let scope = first.and_then(|first| self.scopes.iter().rev().find(|(iterator, _)| iterator == first));
+        let (keys, rest) = match scope {
+            Some((_, keys)) => (keys.clone(), FieldPath { segments: path.segments[1..].to_vec() }.to_string()),
+            None => (Vec::new(), path.to_string()),
+        };
+        let mut shape = &mut self.root;
+        for key in keys {
+            shape = shape.collections.entry(key).or_default();
+        }
+        (shape, rest)
+    }
+
+    fn sample(&mut self, path: &FieldPath, values: Vec<Value>) {
+        let (shape, rest) = self.locate(path);
+        shape.fields.entry(rest).or_default().extend(values);
+    }
+
+    fn rules(&mut self, rules: &[Rule]) {
+        for rule in rules {
+            match rule {
+                Rule::Standard(r) => self.condition(&r.condition),
+                Rule::For(f) => {
+                    let collection = FieldPath::parse(&f.collection).unwrap_or(FieldPath { segments: Vec::new() });
+                    let first = match collection.segments.first() {
+                        Some(PathSegment::Key(key)) => Some(key.clone()),
+                        _ => None,
+                    };
+                    let scope = first.and_then(|first| self.scopes.iter().rev().find(|(iterator, _)| *iterator == first).cloned());
+                    let keys = match scope {
+                        Some((_, mut keys)) => {
+                            keys.push(FieldPath { segments: collection.segments[1..].to_vec() }.to_string());
+                            keys
+                        }
+                        None => vec![f.collection.clone()],
+                    };
+                    self.scopes.push((f.iterator.clone(), keys));
+                    self.rules(&f.body);
+                    self.scopes.pop();
+                }
+                Rule::While(w) => {
+                    self.condition(&w.condition);
+                    self.rules(&w.body);
+                }
+                Rule::Match(m) => {
+                    let mut values: Vec<Value> = m
+                        .arms
+                        .iter()
+                        .filter_map(|arm| match &arm.pattern {
+                            MatchPattern::Value { value } => literal_samples(value).into_iter().next(),
+                            MatchPattern::Wildcard => None,
+                        })
+                        .collect();
+                    values.push(Value::from("other"));
+                    self.operand(&m.scrutinee, values);
+                }
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: &Condition) {
+        match condition {
+            Condition::Const { .. } => {}
+            Condition::Compare { left, right, .. } => {
+                let (left_samples, right_samples) = (literal_samples(right), literal_samples(left));
+                self.operand(left, left_samples);
+                self.operand(right, right_samples);
+            }
+            Condition::In { value, set } => {
+                let items = match set {
+                    Operand::List { items } => items.iter().flat_map(literal_samples).collect(),
+                    _ => Vec::new(),
+                };
+                self.operand(value, items);
+                self.operand(set, Vec::new());
+            }
+            Condition::Truthy { value } => self.operand(value, vec![Value::from(true), Value::from(false)]),
+            Condition::Not { inner } | Condition::Sustained { inner, .. } | Condition::Becomes { inner } => {
+                self.condition(inner)
+            }
+            Condition::Latch { set, reset } => {
+                self.condition(set);
+                self.condition(reset);
+            }
+            Condition::And { terms } | Condition::Or { terms } => terms.iter().for_each(|t| self.condition(t)),
+        }
+    }
+
+    fn operand(&mut self, operand: &Operand, samples: Vec<Value>) {
+        match operand {
+            Operand::Path { path, .. } => {
+                let samples = if samples.is_empty() { default_samples() } else { samples };
+                self.sample(path, samples);
+            }
+            Operand::Arith { left, right, .. } => {
+                self.operand(left, Vec::new());
+                self.operand(right, Vec::new());
+            }
+            Operand::List { items } => items.iter().for_each(|item| self.operand(item, Vec::new())),
+            Operand::Count { condition, .. } => self.condition(condition),
+            Operand::Number { .. } | Operand::Bool { .. } | Operand::Str { .. } => {}
+        }
+    }
+}
+
+/// Nilai di sekitar literal pembanding: batas angka dan kedua sisinya, atau teks lain.
+fn literal_samples(operand: &Operand) -> Vec<Value> {
+    match operand {
+        Operand::Number { value, unit: None } => {
+            vec![Value::from(*value), Value::from(value - 1.0), Value::from(value + 1.0)]
+        }
+        Operand::Number { value, unit: Some(unit) } => [*value, value - 1.0, value + 1.0]
+            .iter()
+            .map(|v| Value::from(format!("{}{}", v, unit)))
+            .collect(),
+        Operand::Bool { value } => vec![Value::from(*value), Value::from(!value)],
+        Operand::Str { value } => vec![Value::from(value.clone()), Value::from(format!("not {}", value))],
+        _ => Vec::new(),
+    }
+}
+
+/// Bangun `count` konteks acak dari field yang dibaca kebijakan; `seed` yang sama
+/// menghasilkan konteks yang sama.
+pub fn generate_contexts(policy: &Policy, count: usize, seed: u64) -> Vec<Value> {
+    let mut builder = ShapeBuilder { root: Shape::default(), scopes: Vec::new() };
+    builder.rules(&policy.rules);
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count).map(|_| generate(&builder.root, &mut rng)).collect()
+}
+
+fn generate(shape: &Shape, rng: &mut StdRng) -> Value {
+    let pick = |samples: &[Value], rng: &mut StdRng| samples[rng.gen_range(0..samples.len())].clone();
+    if let (Some(samples), true) = (shape.fields.get(""), shape.collections.is_empty() && shape.fields.len() == 1) {
+        return pick(samples, rng);
+    }
+    let mut root = Value::Object(Map::new());
+    for (path, samples) in &shape.fields {
+        let Ok(path) = FieldPath::parse(path) else { continue };
+        // Sebagian field sengaja tidak diisi agar jalur field hilang ikut diuji
+        if path.segments.is_empty() || samples.is_empty() || rng.gen_bool(0.1) {
+            continue;
+        }
+        crate::analysis::insert_path(&mut root, &path, pick(samples, rng));
+    }
+    for (path, element) in &shape.collections {
+        let Ok(path) = FieldPath::parse(path) else { continue };
+        if path.segments.is_empty() || rng.gen_bool(0.1) {
+            continue;
+        }
+        let items = (0..rng.gen_range(0..=3)).map(|_| generate(element, rng)).collect();
+        crate::analysis::insert_path(&mut root, &path, Value::Array(items));
+    }
+    root
+}
+
+/// Ringkasan teks: kebijakan, engine yang dilewati, lalu setiap perbedaan.
+pub fn render_text(report: &ConformanceReport) -> String {
+    let mut out = String::new();
+    let contexts: usize = report.policies.iter().map(|p| p.contexts).sum();
+    let _ = writeln!(out, "Conformance: {} policies, {} contexts (seed {})", report.policies.len(), contexts, report.seed);
+    let (programs, files): (Vec<&SkippedFile>, Vec<&SkippedFile>) =
+        report.skipped_files.iter().partition(|f| f.reason == PROGRAM_FILE);
+    if !programs.is_empty() {
+        let _ = writeln!(out, "  SKIP {} program files (MODULE)", programs.len());
+    }
+    for file in files {
+        let _ = writeln!(out, "  SKIP {}: {}", file.file, file.reason);
+    }
+    for policy in &report.policies {
+        for skipped in &policy.skipped {
+            let _ = writeln!(out, "  SKIP {} on {}: {}", policy.policy, skipped.engine, skipped.reason);
+        }
+    }
+    for divergence in &report.divergences {
+        let _ = writeln!(
+            out,
+            "  DIVERGENCE {} [{}]: {} differs from {} in {}",
+            divergence.policy,
+            divergence.context,
+            divergence.engine,
+            divergence.reference,
+            divergence.fields().join(", ")
+        );
+        let _ = writeln!(out, "    context: {}", divergence.input);
+        let _ = writeln!(out, "    {}: {}", divergence.reference, render_outcome(&divergence.expected));
+        match (&divergence.actual, &divergence.error) {
+            (Some(actual), _) => {
+                let _ = writeln!(out, "    {}: {}", divergence.engine, render_outcome(actual));
+            }
+            (None, error) => {
+                let _ = writeln!(out, "    {}: error: {}", divergence.engine, error.as_deref().unwrap_or("unknown"));
+            }
+        }
+    }
+    let _ = writeln!(out, "Result: {} divergences", report.divergences.len());
+    out
+}
+
+fn render_outcome(outcome: &Outcome) -> String {
+    format!(
+        "actions {:?}, guard_triggered {}, rules_evaluated {}",
+        outcome.actions, outcome.guard_triggered, outcome.rules_evaluated
+    )
+}
+
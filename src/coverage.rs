@@ -0,0 +1,314 @@
+//! Cakupan rule: rule, cabang `THEN`/`ELSE`, arm `MATCH`, badan loop, dan
+//! sub-kondisi yang benar-benar dijalankan selama serangkaian eksekusi kebijakan.
+//!
+//! [`Coverage::new`] menyusun kerangka dari kebijakan, lalu
+//! [`Runtime::execute_policy_with_coverage`](crate::runtime::Runtime::execute_policy_with_coverage)
+//! menambah hitungannya setiap kali dijalankan. Laporan tersedia sebagai teks,
+//! JSON (serde), dan format lcov.
+
+use crate::ast::{Condition, Policy, Rule};
+use crate::explain::ConditionTrace;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Coverage {
+    /// Jumlah eksekusi kebijakan yang tercatat.
+    pub runs: usize,
+    pub rules: Vec<RuleCoverage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleCoverage {
+    /// Posisi rule: `2` untuk rule kedua, `3.1` untuk rule pertama di badan rule 3.
+    pub id: String,
+    /// Label yang sama dengan temuan lint, mis. `FOR worker IN Workers: rule #1`.
+    pub label: String,
+    pub line: usize,
+    pub kind: RuleKind,
+    /// Berapa kali rule dievaluasi (tidak termasuk yang dilewati first-match atau loop kosong).
+    pub evaluated: u64,
+    pub branches: Vec<BranchCoverage>,
+    /// Sub-kondisi di bawah kondisi utama, urut pre-order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clauses: Vec<ClauseCoverage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    If,
+    For,
+    While,
+    Match,
+}
+
+/// Hasil rule: `THEN`/`ELSE`, badan loop dan keluarnya `WHILE`, atau satu arm `MATCH`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchCoverage {
+    pub name: String,
+    pub hits: u64,
+}
+
+/// Sub-kondisi tercakup jika pernah bernilai benar dan pernah bernilai salah.
+/// Term yang dilewati short-circuit tidak dihitung.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClauseCoverage {
+    pub expr: String,
+    pub true_hits: u64,
+    pub false_hits: u64,
+}
+
+impl ClauseCoverage {
+    pub fn covered(&self) -> bool {
+        self.true_hits > 0 && self.false_hits > 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    pub rules: usize,
+    pub rules_hit: usize,
+    pub branches: usize,
+    pub branches_hit: usize,
+    pub clauses: usize,
+    pub clauses_covered: usize,
+}
+
+impl CoverageSummary {
+    pub fn is_complete(&self) -> bool {
+        self.rules_hit == self.rules && self.branches_hit == self.branches && self.clauses_covered == self.clauses
+    }
+}
+
+impl Coverage {
+    /// Kerangka cakupan untuk semua rule kebijakan, termasuk rule di badan loop.
+    pub fn new(policy: &Policy) -> Self {
+        let mut rules = Vec::new();
+        collect_rules(&policy.rules, "", "", &mut rules);
+        Coverage { runs: 0, rules }
+    }
+
+    pub fn rule(&self, id: &str) -> Option<&RuleCoverage> {
+        self.rules.iter().find(|r| r.id == id)
+    }
+
+    /// Rule pada posisi `path` (indeks 0-based per tingkat loop).
+    pub(crate) fn rule_at(&mut self, path: &[usize]) -> Option<&mut RuleCoverage> {
+        let id = path_id(path);
+        self.rules.iter_mut().find(|r| r.id == id)
+    }
+
+    pub fn summary(&self) -> CoverageSummary {
+        let mut summary = CoverageSummary::default();
+        for rule in &self.rules {
+            summary.rules += 1;
+            summary.rules_hit += usize::from(rule.evaluated > 0);
+            summary.branches += rule.branches.len();
+            summary.branches_hit += rule.branches.iter().filter(|b| b.hits > 0).count();
+            summary.clauses += rule.clauses.len();
+            summary.clauses_covered += rule.clauses.iter().filter(|c| c.covered()).count();
+        }
+        summary
+    }
+}
+
+impl RuleCoverage {
+    pub(crate) fn hit_branch(&mut self, index: usize) {
+        if let Some(branch) = self.branches.get_mut(index) {
+            branch.hits += 1;
+        }
+    }
+
+    /// Catat hasil setiap sub-kondisi dari jejak evaluasi `condition`.
+    pub(crate) fn record_condition(&mut self, condition: &Condition, trace: &ConditionTrace) {
+        let mut next = 0;
+        record_clauses(condition, trace, &mut self.clauses, &mut next);
+    }
+}
+
+/// Laporan teks per rule; cabang dan sub-kondisi yang belum tercakup ditandai.
+pub fn render_text(coverage: &Coverage) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Coverage ({} runs):", coverage.runs);
+    for rule in &coverage.rules {
+        let marker = if rule.evaluated == 0 { "  <- not exercised" } else { "" };
+        let _ = writeln!(out, "  {} (line {}): evaluated {}{}", rule.label, rule.line, rule.evaluated, marker);
+        for branch in &rule.branches {
+            let marker = if branch.hits == 0 { "  <- not covered" } else { "" };
+            let _ = writeln!(out, "      {}: {}{}", branch.name, branch.hits, marker);
+        }
+        for clause in &rule.clauses {
+            let marker = if clause.covered() { "" } else { "  <- not covered" };
+            let _ = writeln!(
+                out,
+                "      clause {}: true {}, false {}{}",
+                clause.expr, clause.true_hits, clause.false_hits, marker
+            );
+        }
+    }
+    let s = coverage.summary();
+    let _ = writeln!(
+        out,
+        "Rules: {}/{} ({}), branches: {}/{} ({}), clauses: {}/{} ({})",
+        s.rules_hit,
+        s.rules,
+        percent(s.rules_hit, s.rules),
+        s.branches_hit,
+        s.branches,
+        percent(s.branches_hit, s.branches),
+        s.clauses_covered,
+        s.clauses,
+        percent(s.clauses_covered, s.clauses)
+    );
+    out
+}
+
+/// Satu record lcov untuk `source`: rule sebagai fungsi dan baris, cabang rule
+/// sebagai blok 0, dan setiap sub-kondisi sebagai blok benar/salah berikutnya.
+pub fn render_lcov(coverage: &Coverage, source: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "TN:");
+    let _ = writeln!(out, "SF:{}", source);
+    for rule in &coverage.rules {
+        let _ = writeln!(out, "FN:{},{}", rule.line, lcov_name(rule));
+    }
+    for rule in &coverage.rules {
+        let _ = writeln!(out, "FNDA:{},{}", rule.evaluated, lcov_name(rule));
+    }
+    let s = coverage.summary();
+    let _ = writeln!(out, "FNF:{}", s.rules);
+    let _ = writeln!(out, "FNH:{}", s.rules_hit);
+
+    let (mut found, mut hit) = (0, 0);
+    for rule in &coverage.rules {
+        let taken = |hits: u64| if rule.evaluated == 0 { "-".to_string() } else { hits.to_string() };
+        for (index, branch) in rule.branches.iter().enumerate() {
+            let _ = writeln!(out, "BRDA:{},0,{},{}", rule.line, index, taken(branch.hits));
+            found += 1;
+            hit += usize::from(branch.hits > 0);
+        }
+        for (index, clause) in rule.clauses.iter().enumerate() {
+            for (outcome, hits) in [clause.true_hits, clause.false_hits].into_iter().enumerate() {
+                let _ = writeln!(out, "BRDA:{},{},{},{}", rule.line, index + 1, outcome, taken(hits));
+                found += 1;
+                hit += usize::from(hits > 0);
+            }
+        }
+    }
+    let _ = writeln!(out, "BRF:{}", found);
+    let _ = writeln!(out, "BRH:{}", hit);
+    for rule in &coverage.rules {
+        let _ = writeln!(out, "DA:{},{}", rule.line, rule.evaluated);
+    }
+    let _ = writeln!(out, "LF:{}", s.rules);
+    let _ = writeln!(out, "LH:{}", s.rules_hit);
+    let _ = writeln!(out, "end_of_record");
+    out
+}
+
+fn percent(part: usize, total: usize) -> String {
+    if total == 0 {
+        return "n/a".to_string();
+    }
+    format!("{:.1}%", part as f64 * 100.0 / total as f64)
+}
+
+// Nama fungsi lcov tidak boleh memuat koma
+fn lcov_name(rule: &RuleCoverage) -> String {
+    format!("{} {}", rule.id, rule.label.replace(',', ";"))
+}
+
+fn path_id(path: &[usize]) -> String {
+    path.iter().map(|i| (i + 1).to_string()).collect::<Vec<_>>().join(".")
+}
+
+fn collect_rules(rules: &[Rule], id_prefix: &str, label_prefix: &str, out: &mut Vec<RuleCoverage>) {
+    for (index, rule) in rules.iter().enumerate() {
+        let id = format!("{}{}", id_prefix, index + 1);
+        let label = format!("{}{}", label_prefix, crate::analysis::rule_label(rule, index));
+        let (kind, branches, clauses) = match rule {
+            Rule::Standard(r) => {
+                let otherwise = match &r.else_action {
+                    Some(action) => format!("ELSE {}", action),
+                    None => "ELSE (no action)".to_string(),
+                };
+                (RuleKind::If, vec![format!("THEN {}", r.action), otherwise], clauses_of(&r.condition))
+            }
+            Rule::For(_) => (RuleKind::For, vec!["body".to_string()], Vec::new()),
+            Rule::While(w) => (RuleKind::While, vec!["body".to_string(), "exit".to_string()], clauses_of(&w.condition)),
+            Rule::Match(m) => {
+                let arms = m
+                    .arms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, arm)| format!("arm #{} {} => {}", k + 1, arm.pattern, arm.action))
+                    .collect();
+                (RuleKind::Match, arms, Vec::new())
+            }
+        };
+        out.push(RuleCoverage {
+            id: id.clone(),
+            label: label.clone(),
+            line: rule.line(),
+            kind,
+            evaluated: 0,
+            branches: branches.into_iter().map(|name| BranchCoverage { name, hits: 0 }).collect(),
+            clauses,
+        });
+        match rule {
+            Rule::For(f) => collect_rules(&f.body, &format!("{}.", id), &format!("{}: ", label), out),
+            Rule::While(w) => collect_rules(&w.body, &format!("{}.", id), &format!("{}: ", label), out),
+            _ => {}
+        }
+    }
+}
+
+/// Sub-kondisi langsung, dalam urutan evaluasi evaluator.
+fn children(cond: &Condition) -> Vec<&Condition> {
+    match cond {
+        Condition::Not { inner } | Condition::Sustained { inner, .. } | Condition::Becomes { inner } => vec![inner],
+        Condition::And { terms } | Condition::Or { terms } => terms.iter().collect(),
+        Condition::Latch { set, reset } => vec![set, reset],
+        Condition::Const { .. } | Condition::Compare { .. } | Condition::In { .. } | Condition::Truthy { .. } => {
+            Vec::new()
+        }
+    }
+}
+
+fn clauses_of(cond: &Condition) -> Vec<ClauseCoverage> {
+    fn walk(cond: &Condition, out: &mut Vec<ClauseCoverage>) {
+        for child in children(cond) {
+            out.push(ClauseCoverage { expr: child.to_string(), true_hits: 0, false_hits: 0 });
+            walk(child, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(cond, &mut out);
+    out
+}
+
+fn subtree_size(cond: &Condition) -> usize {
+    children(cond).into_iter().map(|child| 1 + subtree_size(child)).sum()
+}
+
+// `trace.children` hanya memuat term yang dievaluasi, berurutan dari awal
+fn record_clauses(cond: &Condition, trace: &ConditionTrace, clauses: &mut [ClauseCoverage], next: &mut usize) {
+    for (index, child) in children(cond).into_iter().enumerate() {
+        let slot = *next;
+        *next += 1;
+        match trace.children.get(index) {
+            Some(child_trace) => {
+                if let Some(clause) = clauses.get_mut(slot) {
+                    if child_trace.result {
+                        clause.true_hits += 1;
+                    } else {
+                        clause.false_hits += 1;
+                    }
+                }
+                record_clauses(child, child_trace, clauses, next);
+            }
+            None => *next += subtree_size(child),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::ast::Policy;
-use crate::codegen::{generate_native, generate_wasm};
+use crate::codegen::generate_native;
 use crate::ir::build_policy_ir;
+use crate::optimizer::optimize;
+use crate::wasm_codegen::compile_wasm;
 use std::fs;
 use std::path::Path;
 
@@ -12,7 +14,8 @@ pub enum CompileTarget {
 }
 
 pub fn emit(policy: &Policy, target: CompileTarget, out_path: &str) -> Result<(), String> {
-    let ir = build_policy_ir(policy);
+    let mut ir = build_policy_ir(policy);
+    optimize(&mut ir);
     let ir_value = serde_json::to_value(&ir).map_err(|e| format!("serialize IR failed: {}", e))?;
 
     let output_bytes: Vec<u8> = match target {
@@ -24,11 +27,7 @@ pub fn emit(policy: &Policy, target: CompileTarget, out_path: &str) -> Result<()
                 .map_err(|e| format!("serialize IR failed: {}", e))?;
             generate_native(&json_str)?
         }
-        CompileTarget::Wasm => {
-            let json_str = serde_json::to_string(&ir_value)
-                .map_err(|e| format!("serialize IR failed: {}", e))?;
-            generate_wasm(&json_str)?
-        }
+        CompileTarget::Wasm => compile_wasm(&ir)?,
     };
 
     let path = Path::new(out_path);
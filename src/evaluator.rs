@@ -1,238 +1,518 @@
+use crate::action_abi::{ActionCapability, ActionMetadata, ActionPayload};
+use crate::ast::{ActionCall, ArithOp, CompareOp, Condition, FieldPath, MatchPattern, Operand, PathSegment};
+use crate::explain::ConditionTrace;
+use crate::temporal::TemporalState;
+use crate::units::{Dimension, Quantity};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, PartialEq)]
 enum Atom {
-    Number(f64),
+    Number(Quantity),
     Bool(bool),
     Str(String),
 }
 
-fn parse_number_with_unit(raw: &str) -> Option<f64> {
-    let lower = raw.to_lowercase();
+/// Status temporal yang dipakai satu evaluasi beserta scope kuncinya (iterasi FOR),
+/// dan jejak penjelasan jika diminta.
+struct EvalCtx<'a> {
+    state: &'a mut TemporalState,
+    scope: &'a str,
+    trace: Option<Trace>,
+}
 
-    if lower.ends_with('%') {
-        let num: String = lower
-            .trim_end_matches('%')
-            .chars()
-            .filter(|c| c.is_ascii_digit() || *c == '.')
-            .collect();
-        return num.parse::<f64>().ok().map(|v| v / 100.0);
-    }
+#[derive(Default)]
+struct Trace {
+    nodes: Vec<ConditionTrace>,
+    values: BTreeMap<String, Value>,
+}
 
-    if lower.ends_with("km") {
-        let num = lower.trim_end_matches("km");
-        return num.parse::<f64>().ok().map(|v| v * 1000.0);
+impl<'a> EvalCtx<'a> {
+    fn new(state: &'a mut TemporalState, scope: &'a str) -> Self {
+        EvalCtx { state, scope, trace: None }
     }
-    if lower.ends_with("cm") {
-        let num = lower.trim_end_matches("cm");
-        return num.parse::<f64>().ok().map(|v| v / 100.0);
-    }
-    if lower.ends_with("mm") {
-        let num = lower.trim_end_matches("mm");
-        return num.parse::<f64>().ok().map(|v| v / 1000.0);
+
+    fn tracing(state: &'a mut TemporalState, scope: &'a str) -> Self {
+        EvalCtx { state, scope, trace: Some(Trace::default()) }
     }
-    if lower.ends_with('m') {
-        let num = lower.trim_end_matches('m');
-        return num.parse::<f64>().ok();
+
+    fn key(&self, node: &dyn std::fmt::Display) -> String {
+        format!("{}{}", self.scope, node)
     }
 
-    if lower.ends_with("ms") {
-        let num = lower.trim_end_matches("ms");
-        return num.parse::<f64>().ok().map(|v| v / 1000.0);
+    fn record(&mut self, name: &dyn std::fmt::Display, value: Value) {
+        if let Some(trace) = &mut self.trace {
+            trace.values.insert(name.to_string(), value);
+        }
     }
-    if lower.ends_with('s') {
-        let num = lower.trim_end_matches('s');
-        return num.parse::<f64>().ok();
+
+    fn forget(&mut self, name: &dyn std::fmt::Display) {
+        if let Some(trace) = &mut self.trace {
+            trace.values.remove(&name.to_string());
+        }
     }
-    if lower.ends_with('h') {
-        let num = lower.trim_end_matches('h');
-        return num.parse::<f64>().ok().map(|v| v * 3600.0);
+}
+
+/// Evaluasi kondisi dari teks mentah (di-parse setiap kali dipanggil).
+/// Kode runtime sebaiknya memakai [`eval_condition`] dengan kondisi yang sudah di-parse.
+pub fn evaluate_condition(expr: &str, data: &Value) -> bool {
+    match crate::parser::parse_condition(expr) {
+        Ok(cond) => eval_condition(&cond, data),
+        Err(e) => {
+            eprintln!("Warning: Invalid condition format '{}': {}", expr, e);
+            false
+        }
     }
+}
 
-    let numeric_part: String = raw
-        .chars()
-        .filter(|c| c.is_ascii_digit() || *c == '.')
-        .collect();
-    numeric_part.parse().ok()
+/// Evaluasi kondisi yang sudah di-parse: OR/AND/NOT, dot-path, IN array, dan literal.
+/// Operator temporal dievaluasi tanpa riwayat; pakai [`eval_condition_in`] untuk status antar evaluasi.
+pub fn eval_condition(cond: &Condition, data: &Value) -> bool {
+    eval_condition_in(cond, data, &mut TemporalState::default(), "")
 }
 
-/// Evaluasi kondisi dengan dukungan OR/AND/NOT, dot-path, IN array, dan literal array JSON.
-pub fn evaluate_condition(expr: &str, data: &Value) -> bool {
-    eval_or(expr, data)
+/// Seperti [`eval_condition`], dengan status temporal yang bertahan antar evaluasi.
+/// `scope` membedakan node yang sama di iterasi FOR yang berbeda.
+pub fn eval_condition_in(cond: &Condition, data: &Value, state: &mut TemporalState, scope: &str) -> bool {
+    eval_cond(cond, data, &mut EvalCtx::new(state, scope))
+}
+
+/// Seperti [`eval_condition_in`], sekaligus mencatat hasil setiap sub-kondisi dan nilai yang dibaca.
+pub fn explain_condition_in(
+    cond: &Condition,
+    data: &Value,
+    state: &mut TemporalState,
+    scope: &str,
+) -> ConditionTrace {
+    let mut ctx = EvalCtx::tracing(state, scope);
+    eval_cond(cond, data, &mut ctx);
+    ctx.trace.and_then(|mut trace| trace.nodes.pop()).unwrap_or_default()
 }
 
-fn eval_or(expr: &str, data: &Value) -> bool {
-    let parts = split_ci(expr, "OR");
-    if parts.len() > 1 {
-        return parts.iter().any(|p| eval_and(p, data));
+fn eval_cond(cond: &Condition, data: &Value, t: &mut EvalCtx) -> bool {
+    let Some(trace) = &mut t.trace else {
+        return eval_cond_node(cond, data, t);
+    };
+    let mark = trace.nodes.len();
+    let outer_values = std::mem::take(&mut trace.values);
+    let result = eval_cond_node(cond, data, t);
+    if let Some(trace) = &mut t.trace {
+        let children = trace.nodes.split_off(mark);
+        let values = std::mem::replace(&mut trace.values, outer_values);
+        trace.nodes.push(ConditionTrace { expr: cond.to_string(), result, values, children });
     }
-    eval_and(expr, data)
+    result
 }
 
-fn eval_and(expr: &str, data: &Value) -> bool {
-    let parts = split_ci(expr, "AND");
-    if parts.len() > 1 {
-        return parts.iter().all(|p| eval_not(p, data));
+fn eval_cond_node(cond: &Condition, data: &Value, t: &mut EvalCtx) -> bool {
+    match cond {
+        Condition::Const { value } => *value,
+        Condition::Compare { left, op, right } => {
+            let left_atom = match eval_operand(left, data, t) {
+                Some(atom) => atom,
+                None => {
+                    warn_missing(left);
+                    return false;
+                }
+            };
+            match eval_reference(right, data, t) {
+                Some(right_atom) => compare_atoms(*op, &left_atom, &right_atom),
+                None => {
+                    eprintln!("Warning: Reference '{}' not found or unsupported", right);
+                    false
+                }
+            }
+        }
+        Condition::In { value, set } => {
+            let left_atom = match eval_operand(value, data, t) {
+                Some(atom) => atom,
+                None => {
+                    warn_missing(value);
+                    return false;
+                }
+            };
+            match set {
+                Operand::List { items } => items
+                    .iter()
+                    .filter_map(|item| eval_reference(item, data, t))
+                    .any(|item| compare_atoms(CompareOp::Eq, &left_atom, &item)),
+                Operand::Path { path, .. } => match resolve_field(data, path) {
+                    Some(Value::Array(arr)) => {
+                        t.record(path, Value::Array(arr.clone()));
+                        arr.iter().any(|v| atom_eq_json(&left_atom, v))
+                    }
+                    _ => eval_reference(set, data, t)
+                        .map(|atom| compare_atoms(CompareOp::Eq, &left_atom, &atom))
+                        .unwrap_or(false),
+                },
+                _ => match eval_reference(set, data, t) {
+                    Some(atom) => compare_atoms(CompareOp::Eq, &left_atom, &atom),
+                    None => {
+                        eprintln!("Warning: IN expects array reference or literal, got '{}'", set);
+                        false
+                    }
+                },
+            }
+        }
+        Condition::Truthy { value } => match eval_operand(value, data, t) {
+            Some(Atom::Bool(b)) => b,
+            Some(Atom::Number(q)) => q.value != 0.0,
+            Some(Atom::Str(s)) => !s.is_empty(),
+            None => {
+                warn_missing(value);
+                false
+            }
+        },
+        Condition::Not { inner } => !eval_cond(inner, data, t),
+        // Term temporal harus selalu dievaluasi agar statusnya maju, jadi tanpa short-circuit
+        Condition::And { terms } if cond.is_temporal() => {
+            terms.iter().fold(true, |acc, term| acc & eval_cond(term, data, t))
+        }
+        Condition::Or { terms } if cond.is_temporal() => {
+            terms.iter().fold(false, |acc, term| acc | eval_cond(term, data, t))
+        }
+        Condition::And { terms } => terms.iter().all(|term| eval_cond(term, data, t)),
+        Condition::Or { terms } => terms.iter().any(|term| eval_cond(term, data, t)),
+        Condition::Sustained { inner, duration } => {
+            let active = eval_cond(inner, data, t);
+            let Some(duration_ms) = eval_duration_ms(duration, data, t) else {
+                eprintln!("Warning: '{}' is not a duration", duration);
+                return false;
+            };
+            let key = t.key(cond);
+            t.state.sustained(&key, active, duration_ms)
+        }
+        Condition::Becomes { inner } => {
+            let active = eval_cond(inner, data, t);
+            let key = t.key(cond);
+            t.state.rising_edge(&key, active)
+        }
+        Condition::Latch { set, reset } => {
+            let set_active = eval_cond(set, data, t);
+            let reset_active = eval_cond(reset, data, t);
+            let key = t.key(cond);
+            t.state.latch(&key, set_active, reset_active)
+        }
     }
-    eval_not(expr, data)
 }
 
-fn eval_not(expr: &str, data: &Value) -> bool {
-    let trimmed = expr.trim();
-    if trimmed.to_uppercase().starts_with("NOT ") {
-        return !eval_simple(&trimmed[4..], data);
+/// Kunci jump table untuk nilai operand di konteks: `s:<teks>` atau `b:<bool>`.
+/// Angka (termasuk string bersatuan) dan field yang tidak ada tidak punya kunci,
+/// sehingga tidak pernah sama dengan literal yang punya kunci.
+pub(crate) fn operand_key(operand: &Operand, data: &Value) -> Option<String> {
+    atom_key(eval_operand(operand, data, &mut EvalCtx::new(&mut TemporalState::default(), ""))?)
+}
+
+/// Kunci literal pembanding (kanan `==` atau pola `MATCH`) yang setara dengan
+/// [`operand_key`]. Nama simbolik satu segmen (`Auto`) mendapat kunci teksnya;
+/// pemanggil harus kembali ke evaluasi biasa jika nama itu ada di konteks.
+pub(crate) fn literal_key(operand: &Operand) -> Option<String> {
+    match operand {
+        Operand::Path { path, .. } if matches!(path.segments.as_slice(), [PathSegment::Key(_)]) => {
+            atom_key(Atom::Str(path.to_string()))
+        }
+        Operand::Str { .. } | Operand::Bool { .. } => {
+            let atom = eval_operand(operand, &Value::Null, &mut EvalCtx::new(&mut TemporalState::default(), ""))?;
+            atom_key(atom)
+        }
+        _ => None,
     }
-    eval_simple(trimmed, data)
 }
 
-fn eval_simple(condition: &str, data: &Value) -> bool {
-    let parts: Vec<&str> = condition.split_whitespace().collect();
-    if parts.len() < 3 {
-        eprintln!("Warning: Invalid condition format '{}'", condition);
-        return false;
+fn atom_key(atom: Atom) -> Option<String> {
+    match atom {
+        Atom::Str(s) => Some(format!("s:{}", s)),
+        Atom::Bool(b) => Some(format!("b:{}", b)),
+        Atom::Number(_) => None,
     }
+}
 
-    let var_path = parts[0];
-    let op = parts[1];
-    let value_str = parts[2];
+/// Cocokkan nilai scrutinee terhadap pola arm `MATCH`.
+pub fn match_pattern(scrutinee: &Operand, pattern: &MatchPattern, data: &Value) -> bool {
+    match_pattern_in(scrutinee, pattern, data, &mut TemporalState::default(), "")
+}
+
+pub fn match_pattern_in(
+    scrutinee: &Operand,
+    pattern: &MatchPattern,
+    data: &Value,
+    state: &mut TemporalState,
+    scope: &str,
+) -> bool {
+    eval_pattern(scrutinee, pattern, data, &mut EvalCtx::new(state, scope))
+}
+
+/// Seperti [`match_pattern_in`], dengan jejak nilai scrutinee untuk penjelasan.
+pub fn explain_match_in(
+    scrutinee: &Operand,
+    pattern: &MatchPattern,
+    data: &Value,
+    state: &mut TemporalState,
+    scope: &str,
+) -> ConditionTrace {
+    let mut ctx = EvalCtx::tracing(state, scope);
+    let result = eval_pattern(scrutinee, pattern, data, &mut ctx);
+    ConditionTrace {
+        expr: format!("{} matches {}", scrutinee, pattern),
+        result,
+        values: ctx.trace.map(|trace| trace.values).unwrap_or_default(),
+        children: Vec::new(),
+    }
+}
 
-    let left_atom = resolve_atom(var_path, data);
-    if left_atom.is_none() {
-        // Suppress noisy warnings for nested/indexed paths; keep warning for top-level misses
-        if !(var_path.contains('.') || var_path.contains('[')) {
-            eprintln!("Warning: Variable '{}' not found in context", var_path);
+fn eval_pattern(scrutinee: &Operand, pattern: &MatchPattern, data: &Value, t: &mut EvalCtx) -> bool {
+    match pattern {
+        MatchPattern::Wildcard => true,
+        MatchPattern::Value { value } => {
+            let left = match eval_operand(scrutinee, data, t) {
+                Some(atom) => atom,
+                None => return false,
+            };
+            eval_reference(value, data, t)
+                .map(|right| compare_atoms(CompareOp::Eq, &left, &right))
+                .unwrap_or(false)
         }
-        return false;
     }
-    let left_atom = left_atom.unwrap();
+}
 
-    let ref_atom = resolve_reference_atom(value_str, data);
-    let ref_array = resolve_array(value_str, data);
+/// Bangun `ActionPayload` dari aksi rule dengan parameter yang dievaluasi terhadap konteks.
+/// Parameter `timeout`, `retry`, `priority`, dan `requires` masuk ke metadata/capability;
+/// sisanya ke `params` (angka bersatuan dalam satuan dasar, lihat [`crate::units`]).
+pub fn build_action_payload(call: &ActionCall, data: &Value) -> ActionPayload {
+    build_action_payload_in(call, data, &mut TemporalState::default(), "")
+}
 
-    match op {
-        "<" | ">" | "<=" | ">=" | "==" | "=" | "!=" => {
-            if let Some(atom) = ref_atom {
-                compare_atoms(op, &left_atom, &atom)
-            } else {
-                eprintln!("Warning: Reference '{}' not found or unsupported", value_str);
-                false
-            }
-        }
-        "IN" | "in" => {
-            if let Some(arr) = ref_array {
-                arr.iter().any(|v| atom_eq_json(&left_atom, v))
-            } else if let Some(atom) = ref_atom {
-                compare_atoms("==", &left_atom, &atom)
-            } else {
-                eprintln!("Warning: IN expects array reference or literal, got '{}'", value_str);
-                false
+pub fn build_action_payload_in(
+    call: &ActionCall,
+    data: &Value,
+    state: &mut TemporalState,
+    scope: &str,
+) -> ActionPayload {
+    let t = &mut EvalCtx::new(state, scope);
+    let mut params = HashMap::new();
+    let mut metadata = ActionMetadata::default();
+    let mut required_capabilities = Vec::new();
+
+    for param in &call.params {
+        match param.name.as_str() {
+            "timeout" => match eval_reference(&param.value, data, t) {
+                Some(Atom::Number(q)) if q.dimension.is_none() || q.dimension == Some(Dimension::Time) => {
+                    metadata.timeout_ms = Some((q.value * 1000.0).round().max(0.0) as u64);
+                }
+                _ => eprintln!("Warning: Action '{}' timeout '{}' is not a duration", call.name, param.value),
+            },
+            "retry" => metadata.retry = eval_integer(call, &param.value, data, t, u32::MAX as f64).map(|n| n as u32),
+            "priority" => metadata.priority = eval_integer(call, &param.value, data, t, u8::MAX as f64).map(|n| n as u8),
+            "requires" => required_capabilities.extend(requires_param(&param.value)),
+            _ => {
+                params.insert(param.name.clone(), eval_operand_json(&param.value, data, t));
             }
         }
-        _ => {
-            eprintln!("Warning: Unknown operator '{}' in condition '{}'", op, condition);
-            false
-        }
+    }
+
+    ActionPayload {
+        action: call.name.clone(),
+        params,
+        required_capabilities,
+        metadata,
     }
 }
 
-fn resolve_atom(path: &str, root: &Value) -> Option<Atom> {
-    let v = resolve_path(root, path)?;
-    to_atom(v)
+/// Capability dari parameter `requires: [Network, FileSystem]`; tidak bergantung konteks.
+pub(crate) fn declared_capabilities(call: &ActionCall) -> Vec<ActionCapability> {
+    call.params
+        .iter()
+        .filter(|p| p.name == "requires")
+        .flat_map(|p| requires_param(&p.value))
+        .collect()
+}
+
+fn requires_param(value: &Operand) -> Vec<ActionCapability> {
+    let items = match value {
+        Operand::List { items } => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    items
+        .iter()
+        .filter_map(|item| ActionCapability::from_name(&item.to_string()))
+        .collect()
 }
 
-fn resolve_reference_atom(raw: &str, root: &Value) -> Option<Atom> {
-    let trimmed = raw.trim_matches('"');
-    match trimmed.to_lowercase().as_str() {
-        "true" => Some(Atom::Bool(true)),
-        "false" => Some(Atom::Bool(false)),
+fn eval_integer(call: &ActionCall, operand: &Operand, data: &Value, t: &mut EvalCtx, max: f64) -> Option<f64> {
+    match eval_reference(operand, data, t) {
+        Some(Atom::Number(q)) if q.value.fract() == 0.0 && (0.0..=max).contains(&q.value) => Some(q.value),
         _ => {
-            if let Some(num) = parse_number_with_unit(trimmed) {
-                return Some(Atom::Number(num));
-            }
-            resolve_atom(raw, root).or_else(|| Some(Atom::Str(trimmed.to_string())))
+            eprintln!("Warning: Action '{}' expects an integer for '{}'", call.name, operand);
+            None
         }
     }
 }
 
-fn resolve_array(path: &str, root: &Value) -> Option<Vec<Value>> {
-    if let Some(arr) = parse_array_literal(path) {
-        return Some(arr);
+/// Nilai JSON sebuah operand; referensi yang tidak ada menjadi string simbolik seperti di kondisi.
+fn eval_operand_json(operand: &Operand, data: &Value, t: &mut EvalCtx) -> Value {
+    match operand {
+        Operand::List { items } => Value::Array(items.iter().map(|i| eval_operand_json(i, data, t)).collect()),
+        // Objek/array dari konteks diteruskan apa adanya
+        Operand::Path { path, unit: None } => match resolve_field(data, path) {
+            Some(v @ (Value::Object(_) | Value::Array(_))) => v.clone(),
+            _ => atom_to_json(eval_reference(operand, data, t)),
+        },
+        _ => atom_to_json(eval_reference(operand, data, t)),
     }
-    let v = resolve_path(root, path)?;
-    v.as_array().cloned()
 }
 
-fn parse_array_literal(raw: &str) -> Option<Vec<Value>> {
-    let t = raw.trim();
-    if t.starts_with('[') && t.ends_with(']') {
-        if let Ok(val) = serde_json::from_str::<Value>(t) {
-            return val.as_array().cloned();
+fn atom_to_json(atom: Option<Atom>) -> Value {
+    match atom {
+        Some(Atom::Number(q)) if q.value.fract() == 0.0 && q.value.abs() < 9.0e15 => Value::from(q.value as i64),
+        Some(Atom::Number(q)) => serde_json::Number::from_f64(q.value)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        Some(Atom::Bool(b)) => Value::Bool(b),
+        Some(Atom::Str(s)) => Value::String(s),
+        None => Value::Null,
+    }
+}
+
+fn warn_missing(operand: &Operand) {
+    // Suppress noisy warnings for nested/indexed paths; keep warning for top-level misses
+    if let Operand::Path { path, .. } = operand {
+        if path.segments.len() == 1 {
+            eprintln!("Warning: Variable '{}' not found in context", path);
         }
     }
-    None
 }
 
-fn resolve_path<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
-    let mut current = root;
-    for segment in path.split('.') {
-        if segment.is_empty() {
-            return None;
+fn eval_operand(operand: &Operand, data: &Value, t: &mut EvalCtx) -> Option<Atom> {
+    match operand {
+        Operand::Number { value, unit } => Some(Atom::Number(Quantity::from_unit(*value, unit.as_deref()))),
+        Operand::Bool { value } => Some(Atom::Bool(*value)),
+        Operand::Str { value } => to_atom(&Value::String(value.clone())),
+        Operand::Path { path, unit } => {
+            let raw = resolve_field(data, path);
+            t.record(path, raw.cloned().unwrap_or(Value::Null));
+            match raw.and_then(to_atom)? {
+                // Angka konteks tanpa satuan memakai satuan deklarasi `UNITS:`
+                Atom::Number(q) if q.dimension.is_none() && unit.is_some() => {
+                    Some(Atom::Number(Quantity::from_unit(q.value, unit.as_deref())))
+                }
+                atom => Some(atom),
+            }
         }
-        let mut base = segment;
-        let mut indexes: Vec<usize> = Vec::new();
-        while let Some(start) = base.find('[') {
-            let end = base[start + 1..].find(']')? + start + 1;
-            let idx_str = &base[start + 1..end];
-            let idx = idx_str.parse::<usize>().ok()?;
-            indexes.push(idx);
-            base = &base[..start];
+        Operand::List { .. } => None,
+        Operand::Arith { left, op, right } => {
+            match (eval_operand(left, data, t)?, eval_operand(right, data, t)?) {
+                (Atom::Number(l), Atom::Number(r)) => {
+                    let (value, dimension) = match op {
+                        ArithOp::Add | ArithOp::Sub if !l.compatible(&r) => {
+                            eprintln!("Warning: Unit mismatch in '{}'", operand);
+                            return None;
+                        }
+                        ArithOp::Add => (l.value + r.value, l.dimension.or(r.dimension)),
+                        ArithOp::Sub => (l.value - r.value, l.dimension.or(r.dimension)),
+                        ArithOp::Mul => (l.value * r.value, l.dimension.xor(r.dimension)),
+                        ArithOp::Div => (
+                            l.value / r.value,
+                            if r.dimension.is_none() { l.dimension } else { None },
+                        ),
+                    };
+                    Some(Atom::Number(Quantity { value, dimension }))
+                }
+                _ => None,
+            }
         }
-
-        if !base.is_empty() {
-            current = current.get(base)?;
+        Operand::Count { condition, window } => {
+            let hit = eval_cond(condition, data, t);
+            let window_ms = eval_duration_ms(window, data, t)?;
+            let key = t.key(operand);
+            let count = t.state.count(&key, hit, window_ms);
+            t.record(operand, Value::from(count));
+            Some(Atom::Number(Quantity::bare(count as f64)))
         }
-        for idx in indexes {
-            current = current.get(idx)?;
+    }
+}
+
+/// Durasi operator temporal dalam milidetik; angka tanpa satuan berarti detik.
+fn eval_duration_ms(operand: &Operand, data: &Value, t: &mut EvalCtx) -> Option<u64> {
+    match eval_operand(operand, data, t)? {
+        Atom::Number(q) if q.dimension.is_none() || q.dimension == Some(Dimension::Time) => {
+            Some((q.value * 1000.0).round().max(0.0) as u64)
         }
+        _ => None,
+    }
+}
+
+/// Right-hand side of a comparison: a bare name that is not in the context is
+/// treated as a symbolic string (e.g. `Mode == Auto`).
+fn eval_reference(operand: &Operand, data: &Value, t: &mut EvalCtx) -> Option<Atom> {
+    match operand {
+        Operand::Path { path, .. } => eval_operand(operand, data, t).or_else(|| {
+            // Nama simbolik, bukan field konteks yang hilang
+            t.forget(path);
+            Some(Atom::Str(path.to_string()))
+        }),
+        _ => eval_operand(operand, data, t),
+    }
+}
+
+pub(crate) fn resolve_field<'a>(root: &'a Value, path: &FieldPath) -> Option<&'a Value> {
+    let mut current = root;
+    for seg in &path.segments {
+        current = match seg {
+            PathSegment::Key(k) => current.get(k.as_str())?,
+            PathSegment::Index(i) => current.get(*i)?,
+        };
     }
     Some(current)
 }
 
+
 fn to_atom(v: &Value) -> Option<Atom> {
     if let Some(n) = v.as_f64() {
-        return Some(Atom::Number(n));
+        return Some(Atom::Number(Quantity::bare(n)));
     }
     if let Some(b) = v.as_bool() {
         return Some(Atom::Bool(b));
     }
     if let Some(s) = v.as_str() {
-        if let Some(n) = parse_number_with_unit(s) {
-            return Some(Atom::Number(n));
+        if let Some(q) = crate::units::parse_quantity(s) {
+            return Some(Atom::Number(q));
         }
         return Some(Atom::Str(s.to_string()));
     }
     None
 }
 
-fn compare_atoms(op: &str, left: &Atom, right: &Atom) -> bool {
+fn compare_atoms(op: CompareOp, left: &Atom, right: &Atom) -> bool {
     match (left, right) {
-        (Atom::Number(l), Atom::Number(r)) => match op {
-            "<" => l < r,
-            ">" => l > r,
-            "<=" => l <= r,
-            ">=" => l >= r,
-            "==" | "=" => l == r,
-            "!=" => l != r,
-            _ => false,
-        },
+        (Atom::Number(l), Atom::Number(r)) => {
+            if !l.compatible(r) {
+                eprintln!(
+                    "Warning: Cannot compare {} with {}",
+                    l.dimension.map(|d| d.to_string()).unwrap_or_default(),
+                    r.dimension.map(|d| d.to_string()).unwrap_or_default()
+                );
+                return false;
+            }
+            let (l, r) = (l.value, r.value);
+            // Konversi satuan (mis. 250cm -> 2.5m) bisa menyisakan galat pembulatan
+            let eq = (l - r).abs() <= 1e-9 * l.abs().max(r.abs()).max(1.0);
+            match op {
+                CompareOp::Lt => l < r && !eq,
+                CompareOp::Gt => l > r && !eq,
+                CompareOp::Lte => l < r || eq,
+                CompareOp::Gte => l > r || eq,
+                CompareOp::Eq => eq,
+                CompareOp::Neq => !eq,
+            }
+        }
         (Atom::Bool(l), Atom::Bool(r)) => match op {
-            "==" | "=" => l == r,
-            "!=" => l != r,
+            CompareOp::Eq => l == r,
+            CompareOp::Neq => l != r,
             _ => false,
         },
         (Atom::Str(l), Atom::Str(r)) => match op {
-            "==" | "=" => l == r,
-            "!=" => l != r,
+            CompareOp::Eq => l == r,
+            CompareOp::Neq => l != r,
             _ => false,
         },
         _ => false,
@@ -240,30 +520,7 @@ fn compare_atoms(op: &str, left: &Atom, right: &Atom) -> bool {
 }
 
 fn atom_eq_json(atom: &Atom, v: &Value) -> bool {
-    match (atom, v) {
-        (Atom::Number(l), Value::Number(r)) => r.as_f64().map(|rv| *l == rv).unwrap_or(false),
-        (Atom::Bool(l), Value::Bool(r)) => l == r,
-        (Atom::Str(l), Value::String(r)) => l == r,
-        _ => false,
-    }
-}
-
-fn split_ci(expr: &str, sep: &str) -> Vec<String> {
-    let mut chunks = Vec::new();
-    let mut current = Vec::new();
-    for tok in expr.split_whitespace() {
-        if tok.eq_ignore_ascii_case(sep) {
-            if !current.is_empty() {
-                chunks.push(current.join(" "));
-                current.clear();
-            }
-        } else {
-            current.push(tok.to_string());
-        }
-    }
-    if !current.is_empty() {
-        chunks.push(current.join(" "));
-    }
-    chunks.retain(|s| !s.trim().is_empty());
-    chunks
+    to_atom(v)
+        .map(|other| compare_atoms(CompareOp::Eq, atom, &other))
+        .unwrap_or(false)
 }
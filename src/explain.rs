@@ -0,0 +1,187 @@
+//! Penjelasan keputusan: untuk setiap aksi yang dipicu, rule mana yang cocok,
+//! sub-kondisi mana yang benar/salah beserta nilai konteks yang dibaca, dan
+//! iterasi loop tempat rule itu dievaluasi.
+
+use crate::ast::{ActionCall, Condition, MatchPattern, Operand, RuleEffect};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Hasil evaluasi satu node kondisi. Term yang tidak dievaluasi karena
+/// short-circuit (`AND`/`OR`) tidak muncul di `children`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConditionTrace {
+    pub expr: String,
+    pub result: bool,
+    /// Nilai yang dibaca node ini: field konteks (`null` jika tidak ada) dan hasil `COUNT`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub values: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<ConditionTrace>,
+}
+
+/// Cabang rule yang menghasilkan aksi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Branch {
+    Then,
+    Else,
+    MatchArm,
+    /// Aksi pengganti dari invarian `CONSTRAINT:` yang dilanggar.
+    Constraint,
+}
+
+/// Satu tingkat loop yang membungkus rule saat dievaluasi.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopFrame {
+    /// `FOR worker IN Workers` atau `WHILE cond`.
+    pub header: String,
+    /// Variabel iterasi FOR; WHILE tidak punya.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterator: Option<String>,
+    /// Indeks iterasi, mulai dari 0.
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+impl LoopFrame {
+    pub fn for_each(iterator: &str, collection: &str, index: usize, value: &Value) -> Self {
+        LoopFrame {
+            header: format!("FOR {} IN {}", iterator, collection),
+            iterator: Some(iterator.to_string()),
+            index,
+            value: Some(value.clone()),
+        }
+    }
+
+    pub fn while_loop(condition: &Condition, index: usize) -> Self {
+        LoopFrame {
+            header: format!("WHILE {}", condition),
+            iterator: None,
+            index,
+            value: None,
+        }
+    }
+}
+
+/// Scope kunci status temporal untuk tumpukan loop: hanya iterasi FOR yang membedakan node.
+pub fn temporal_scope(loops: &[LoopFrame]) -> String {
+    loops
+        .iter()
+        .filter_map(|frame| {
+            frame
+                .iterator
+                .as_ref()
+                .map(|it| format!("{}#{}/", it, frame.index))
+        })
+        .collect()
+}
+
+/// Penjelasan untuk satu entri `Decision.actions`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+    pub action: String,
+    pub rule: String,
+    pub branch: Branch,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i64,
+    #[serde(default, skip_serializing_if = "is_permit")]
+    pub effect: RuleEffect,
+    pub condition: ConditionTrace,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loops: Vec<LoopFrame>,
+    /// Actor rule dari `BY Role`; tanpa `BY`, aksi dijalankan actor primary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+}
+
+impl Explanation {
+    /// Penjelasan arm `MATCH` yang cocok; arm selalu berprioritas 0 dan `Permit`.
+    pub fn match_arm(
+        scrutinee: &Operand,
+        pattern: &MatchPattern,
+        action: &ActionCall,
+        condition: ConditionTrace,
+        loops: &[LoopFrame],
+    ) -> Self {
+        Explanation {
+            action: action.name.clone(),
+            rule: format!("MATCH {} {{ {} => {} }}", scrutinee, pattern, action),
+            branch: Branch::MatchArm,
+            priority: 0,
+            effect: RuleEffect::Permit,
+            condition,
+            loops: loops.to_vec(),
+            actor: None,
+        }
+    }
+}
+
+fn is_zero(n: &i64) -> bool {
+    *n == 0
+}
+
+fn is_permit(effect: &RuleEffect) -> bool {
+    *effect == RuleEffect::Permit
+}
+
+/// Teks rule `IF ... THEN ... [ELSE ...]` untuk [`Explanation::rule`].
+pub fn rule_text(condition: &Condition, action: &ActionCall, else_action: Option<&ActionCall>) -> String {
+    match else_action {
+        Some(else_action) => format!("IF {} THEN {} ELSE {}", condition, action, else_action),
+        None => format!("IF {} THEN {}", condition, action),
+    }
+}
+
+/// Render penjelasan sebagai teks berindentasi untuk CLI `explain`.
+pub fn render_text(explanations: &[Explanation]) -> String {
+    let mut out = String::new();
+    if explanations.is_empty() {
+        out.push_str("No actions triggered.\n");
+    }
+    for (i, exp) in explanations.iter().enumerate() {
+        let _ = writeln!(out, "{}. {}", i + 1, exp.action);
+        let _ = writeln!(out, "   rule:   {}", exp.rule);
+        let mut branch = match exp.branch {
+            Branch::Then => "THEN".to_string(),
+            Branch::Else => "ELSE (condition was false)".to_string(),
+            Branch::MatchArm => "MATCH arm".to_string(),
+            Branch::Constraint => "CONSTRAINT fallback".to_string(),
+        };
+        if exp.effect == RuleEffect::Deny {
+            branch.push_str(", DENY");
+        }
+        if exp.priority != 0 {
+            let _ = write!(branch, ", PRIORITY {}", exp.priority);
+        }
+        if let Some(actor) = &exp.actor {
+            let _ = write!(branch, ", BY {}", actor);
+        }
+        let _ = writeln!(out, "   branch: {}", branch);
+        for frame in &exp.loops {
+            let _ = write!(out, "   loop:   {} [iteration {}]", frame.header, frame.index);
+            if let (Some(it), Some(value)) = (&frame.iterator, &frame.value) {
+                let _ = write!(out, " {} = {}", it, value);
+            }
+            out.push('\n');
+        }
+        out.push_str("   why:\n");
+        render_node(&mut out, &exp.condition, 2);
+    }
+    out
+}
+
+fn render_node(out: &mut String, node: &ConditionTrace, depth: usize) {
+    let mark = if node.result { "true " } else { "false" };
+    let _ = write!(out, "{}[{}] {}", "  ".repeat(depth + 1), mark, node.expr);
+    if !node.values.is_empty() {
+        let values: Vec<String> = node.values.iter().map(|(k, v)| format!("{} = {}", k, v)).collect();
+        let _ = write!(out, "  ({})", values.join(", "));
+    }
+    out.push('\n');
+    for child in &node.children {
+        render_node(out, child, depth + 1);
+    }
+}
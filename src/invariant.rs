@@ -0,0 +1,183 @@
+//! Penegakan invarian `CONSTRAINT:` (`NEVER ...`, `MAX n OF [...]`) pada aksi
+//! keputusan, setelah combining algorithm. Runtime dan IR interpreter memakai
+//! fungsi yang sama agar aksi yang diveto selalu identik.
+
+use crate::ast::{Condition, Invariant, InvariantCheck, Operand, PathSegment, RuleEffect};
+use crate::evaluator::{build_action_payload_in, explain_condition_in};
+use crate::explain::{Branch, ConditionTrace, Explanation};
+use crate::runtime::{ConstraintViolation, Decision};
+use crate::temporal::TemporalState;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+// Status temporal invarian terpisah dari node rule yang teksnya sama
+const TEMPORAL_SCOPE: &str = "CONSTRAINT:";
+
+/// Terapkan invarian sesuai urutan sumber. Setiap invarian melihat aksi yang
+/// tersisa setelah invarian sebelumnya, termasuk aksi penggantinya.
+///
+/// `NEVER cond` memveto aksi yang disebut di `cond` ketika `cond` benar;
+/// `MAX n OF [...]` mempertahankan `n` aksi pertama dari daftar sesuai urutan keputusan.
+pub(crate) fn enforce<'a>(
+    invariants: impl IntoIterator<Item = (&'a str, &'a Invariant)>,
+    ctx: &Value,
+    temporal: &mut TemporalState,
+    decision: &mut Decision,
+) {
+    for (kind, invariant) in invariants {
+        let (vetoed, trace) = match &invariant.check {
+            InvariantCheck::Never { condition } => {
+                let view = action_view(condition, ctx, &decision.actions);
+                let trace = explain_condition_in(condition, &view, temporal, TEMPORAL_SCOPE);
+                if !trace.result {
+                    continue;
+                }
+                let names = referenced_names(condition);
+                let vetoed: Vec<String> = decision.actions.iter().filter(|a| names.contains(*a)).cloned().collect();
+                (vetoed, trace)
+            }
+            InvariantCheck::AtMost { limit, actions } => {
+                let present: Vec<String> = decision.actions.iter().filter(|a| actions.contains(a)).cloned().collect();
+                if present.len() <= *limit {
+                    continue;
+                }
+                let trace = ConditionTrace {
+                    expr: format!("{} of [{}] triggered", present.len(), actions.join(", ")),
+                    result: true,
+                    ..Default::default()
+                };
+                (present[*limit..].to_vec(), trace)
+            }
+        };
+
+        for action in &vetoed {
+            decision.veto(action);
+        }
+        let replacement = invariant.fallback.as_ref().map(|fallback| {
+            let payload = build_action_payload_in(fallback, ctx, temporal, TEMPORAL_SCOPE);
+            let explanation = Explanation {
+                action: fallback.name.clone(),
+                rule: format!("CONSTRAINT {}: {}", kind, invariant),
+                branch: Branch::Constraint,
+                priority: 0,
+                effect: RuleEffect::Permit,
+                condition: trace,
+                loops: Vec::new(),
+                actor: None,
+            };
+            decision.push_action(payload, explanation);
+            fallback.name.clone()
+        });
+        decision.violate(ConstraintViolation {
+            kind: kind.to_string(),
+            constraint: invariant.to_string(),
+            vetoed,
+            replacement,
+        });
+    }
+}
+
+/// Pasangan aksi yang tidak boleh keluar bersamaan: `NEVER (A AND B)` dengan dua
+/// nama polos, atau dua aksi dari `MAX 1 OF [...]`.
+pub fn exclusive_pairs<'a>(invariants: impl IntoIterator<Item = &'a Invariant>) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for invariant in invariants {
+        match &invariant.check {
+            InvariantCheck::Never { condition: Condition::And { terms } } if terms.len() == 2 => {
+                if let (Some(a), Some(b)) = (bare_name(&terms[0]), bare_name(&terms[1])) {
+                    pairs.push((a.to_string(), b.to_string()));
+                }
+            }
+            InvariantCheck::AtMost { limit: 1, actions } => {
+                for (i, a) in actions.iter().enumerate() {
+                    for b in &actions[i + 1..] {
+                        pairs.push((a.clone(), b.clone()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    pairs
+}
+
+fn bare_name(cond: &Condition) -> Option<&str> {
+    match cond {
+        Condition::Truthy { value: Operand::Path { path, .. } } => match path.segments.as_slice() {
+            [PathSegment::Key(name)] => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Konteks untuk `NEVER`: aksi yang dipicu bernilai `true` (menimpa field bernama sama),
+/// dan nama polos yang tidak ada di konteks bernilai `false`.
+fn action_view(condition: &Condition, ctx: &Value, actions: &[String]) -> Value {
+    let mut view = ctx.clone();
+    if let Some(fields) = view.as_object_mut() {
+        for name in truthy_names(condition) {
+            fields.entry(name).or_insert(Value::Bool(false));
+        }
+        for action in actions {
+            fields.insert(action.clone(), Value::Bool(true));
+        }
+    }
+    view
+}
+
+fn truthy_names(cond: &Condition) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    visit(cond, &mut |c| {
+        if let Some(name) = bare_name(c) {
+            names.insert(name.to_string());
+        }
+    });
+    names
+}
+
+fn referenced_names(cond: &Condition) -> BTreeSet<String> {
+    fn operand_names(operand: &Operand, names: &mut BTreeSet<String>) {
+        match operand {
+            Operand::Path { path, .. } => {
+                if let Some(PathSegment::Key(name)) = path.segments.first() {
+                    names.insert(name.clone());
+                }
+            }
+            Operand::List { items } => items.iter().for_each(|item| operand_names(item, names)),
+            Operand::Arith { left, right, .. } => {
+                operand_names(left, names);
+                operand_names(right, names);
+            }
+            Operand::Count { condition, .. } => names.extend(referenced_names(condition)),
+            Operand::Number { .. } | Operand::Bool { .. } | Operand::Str { .. } => {}
+        }
+    }
+    let mut names = BTreeSet::new();
+    visit(cond, &mut |c| match c {
+        Condition::Compare { left, right, .. } => {
+            operand_names(left, &mut names);
+            operand_names(right, &mut names);
+        }
+        Condition::In { value, set } => {
+            operand_names(value, &mut names);
+            operand_names(set, &mut names);
+        }
+        Condition::Truthy { value } => operand_names(value, &mut names),
+        _ => {}
+    });
+    names
+}
+
+fn visit(cond: &Condition, f: &mut dyn FnMut(&Condition)) {
+    f(cond);
+    match cond {
+        Condition::Not { inner } | Condition::Sustained { inner, .. } | Condition::Becomes { inner } => visit(inner, f),
+        Condition::And { terms } | Condition::Or { terms } => terms.iter().for_each(|t| visit(t, f)),
+        Condition::Latch { set, reset } => {
+            visit(set, f);
+            visit(reset, f);
+        }
+        Condition::Const { .. } | Condition::Compare { .. } | Condition::In { .. } | Condition::Truthy { .. } => {}
+    }
+}
@@ -1,9 +1,8 @@
-use crate::ast;
+use crate::action_abi::ActionCapability;
+use crate::ast::{self, ActionCall, CombiningAlgorithm, Condition, MatchPattern, Operand, RuleEffect};
+use crate::runtime::RuntimeConfig;
 use serde::{Deserialize, Serialize};
-
-// Mirror runtime guard limits for IR metadata (non-authoritative, informational)
-const MAX_LOOP_ITERATIONS: usize = 50;
-const MAX_LOOP_TIME_MS: u128 = 1_000;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyIR {
@@ -17,12 +16,26 @@ pub struct PolicyIR {
     pub impacts: Vec<ImpactIR>,
     pub traces: Vec<TraceIR>,
     pub reviews: Vec<ReviewIR>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capabilities: Vec<ast::ActionRequirement>,
+    // Field bernilai bawaan tidak diserialisasi agar IR kebijakan lama tetap stabil
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub combining: CombiningAlgorithm,
+    /// Batas guard efektif untuk semua loop kebijakan ini.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub guard: GuardMeta,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActorIR {
     pub role: String,
     pub primary: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<ActionCapability>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,12 +52,26 @@ pub enum RuleIR {
     For(ForLoopIR),
     While(WhileLoopIR),
     Match(PolicyMatchRuleIR),
+    /// Hasil [`crate::optimizer`]: rule berurutan dengan term `AND` pertama yang sama.
+    Hoisted(HoistedIR),
+    /// Hasil [`crate::optimizer`]: rantai rule `Field == literal` pada field yang sama.
+    Dispatch(DispatchIR),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardRuleIR {
-    pub condition: String,
-    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub condition: Condition,
+    pub action: ActionCall,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub else_action: Option<ActionCall>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub priority: i64,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub effect: RuleEffect,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,33 +84,102 @@ pub struct ForLoopIR {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhileLoopIR {
-    pub condition: String,
+    pub condition: Condition,
     pub body: Vec<RuleIR>,
     pub guard: GuardMeta,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyMatchRuleIR {
-    pub scrutinee: String,
+    pub scrutinee: Operand,
     pub arms: Vec<PolicyMatchArmIR>,
+    /// Jump table arm, diisi [`crate::optimizer`] jika semua pola adalah literal teks/boolean.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<JumpTableIR>,
 }
 
+/// Indeks arm atau rule per kunci nilai (`s:<teks>`/`b:<bool>`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JumpTableIR {
+    pub cases: BTreeMap<String, Vec<usize>>,
+    /// Arm wildcard untuk nilai tanpa kasus; rantai rule tidak punya.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<usize>,
+    /// Nama simbolik pada pola; jika salah satunya ada di konteks, evaluasi kembali linear.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+}
+
+/// Rule tanpa ELSE yang semuanya diawali `guard AND ...`. Jika `guard` salah,
+/// seluruh `body` dilewati; jika benar, setiap rule dievaluasi utuh seperti biasa.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PolicyMatchArmIR {
-    pub pattern: String,
-    pub action: String,
+pub struct HoistedIR {
+    pub guard: Condition,
+    pub body: Vec<RuleIR>,
 }
 
+/// Rule tanpa ELSE yang masing-masing mensyaratkan `field == literal`. `field`
+/// dibaca sekali dan hanya rule pada kasus yang cocok yang dievaluasi.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DispatchIR {
+    pub field: Operand,
+    pub table: JumpTableIR,
+    pub rules: Vec<RuleIR>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyMatchArmIR {
+    pub pattern: MatchPattern,
+    pub action: ActionCall,
+}
+
+/// Batas guard loop yang berlaku, hasil [`RuntimeConfig::guard_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct GuardMeta {
     pub max_iterations: usize,
+    #[serde(with = "millis")]
     pub max_time_ms: u128,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub source: GuardSource,
+}
+
+/// `max_time_ms` ditulis sebagai u64: serde tidak bisa membaca u128 di dalam
+/// enum bertag seperti [`RuleIR`], jadi IR berisi loop gagal dimuat ulang.
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(u64::try_from(*value).unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        u64::deserialize(deserializer).map(u128::from)
+    }
+}
+
+impl Default for GuardMeta {
+    fn default() -> Self {
+        RuntimeConfig::default().guard_for(&ast::GuardDecl::default())
+    }
+}
+
+/// Asal batas guard: bawaan runtime, section `GUARD:` kebijakan, atau
+/// permintaan `GUARD:` yang dipangkas ke batas tertinggi runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardSource {
+    #[default]
+    Runtime,
+    Policy,
+    Clamped,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConstraintIR {
     pub kind: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invariant: Option<ast::Invariant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,10 +201,16 @@ pub struct ReviewIR {
 }
 
 pub fn build_policy_ir(policy: &ast::Policy) -> PolicyIR {
-    let rules_tree: Vec<RuleIR> = policy.rules.iter().map(build_rule_ir).collect();
+    build_policy_ir_with_config(policy, &RuntimeConfig::default())
+}
+
+/// Bangun IR dengan batas guard dari `config` dan section `GUARD:` kebijakan.
+pub fn build_policy_ir_with_config(policy: &ast::Policy, config: &RuntimeConfig) -> PolicyIR {
+    let guard = config.guard_for(&policy.guard);
+    let rules_tree: Vec<RuleIR> = policy.rules.iter().map(|r| build_rule_ir(r, guard)).collect();
     let mut flat_rules: Vec<RuleIR> = Vec::new();
     for r in &policy.rules {
-        flatten_rule_ir(r, &mut flat_rules);
+        flatten_rule_ir(r, guard, &mut flat_rules);
     }
 
     PolicyIR {
@@ -119,6 +221,7 @@ pub fn build_policy_ir(policy: &ast::Policy) -> PolicyIR {
             .map(|a| ActorIR {
                 role: a.role.clone(),
                 primary: a.primary,
+                capabilities: a.capabilities.clone(),
             })
             .collect(),
         context: policy.context.as_ref().map(|c| ContextIR {
@@ -135,6 +238,7 @@ pub fn build_policy_ir(policy: &ast::Policy) -> PolicyIR {
             .map(|c| ConstraintIR {
                 kind: c.kind.clone(),
                 description: c.description.clone(),
+                invariant: c.invariant.clone(),
             })
             .collect(),
         impacts: policy
@@ -161,31 +265,33 @@ pub fn build_policy_ir(policy: &ast::Policy) -> PolicyIR {
                 criteria: r.criteria.clone(),
             })
             .collect(),
+        capabilities: policy.capabilities.clone(),
+        combining: policy.combining,
+        guard,
     }
 }
 
-fn build_rule_ir(rule: &ast::Rule) -> RuleIR {
+fn build_rule_ir(rule: &ast::Rule, guard: GuardMeta) -> RuleIR {
     match rule {
         ast::Rule::Standard(r) => RuleIR::Standard(StandardRuleIR {
+            name: r.name.clone(),
             condition: r.condition.clone(),
             action: r.action.clone(),
+            else_action: r.else_action.clone(),
+            priority: r.priority,
+            effect: r.effect,
+            actor: r.actor.clone(),
         }),
         ast::Rule::For(f) => RuleIR::For(ForLoopIR {
             iterator: f.iterator.clone(),
             collection: f.collection.clone(),
-            body: f.body.iter().map(build_rule_ir).collect(),
-            guard: GuardMeta {
-                max_iterations: MAX_LOOP_ITERATIONS,
-                max_time_ms: MAX_LOOP_TIME_MS,
-            },
+            body: f.body.iter().map(|r| build_rule_ir(r, guard)).collect(),
+            guard,
         }),
         ast::Rule::While(w) => RuleIR::While(WhileLoopIR {
             condition: w.condition.clone(),
-            body: w.body.iter().map(build_rule_ir).collect(),
-            guard: GuardMeta {
-                max_iterations: MAX_LOOP_ITERATIONS,
-                max_time_ms: MAX_LOOP_TIME_MS,
-            },
+            body: w.body.iter().map(|r| build_rule_ir(r, guard)).collect(),
+            guard,
         }),
         ast::Rule::Match(m) => RuleIR::Match(PolicyMatchRuleIR {
             scrutinee: m.scrutinee.clone(),
@@ -197,42 +303,37 @@ fn build_rule_ir(rule: &ast::Rule) -> RuleIR {
                     action: a.action.clone(),
                 })
                 .collect(),
+            table: None,
         }),
     }
 }
 
-fn flatten_rule_ir(rule: &ast::Rule, out: &mut Vec<RuleIR>) {
+fn flatten_rule_ir(rule: &ast::Rule, guard: GuardMeta, out: &mut Vec<RuleIR>) {
     match rule {
-        ast::Rule::Standard(_) => out.push(build_rule_ir(rule)),
+        ast::Rule::Standard(_) => out.push(build_rule_ir(rule, guard)),
         ast::Rule::For(f) => {
             let ir = RuleIR::For(ForLoopIR {
                 iterator: f.iterator.clone(),
                 collection: f.collection.clone(),
                 body: Vec::new(), // flattened separately
-                guard: GuardMeta {
-                    max_iterations: MAX_LOOP_ITERATIONS,
-                    max_time_ms: MAX_LOOP_TIME_MS,
-                },
+                guard,
             });
             out.push(ir);
             for sub in &f.body {
-                flatten_rule_ir(sub, out);
+                flatten_rule_ir(sub, guard, out);
             }
         }
         ast::Rule::While(w) => {
             let ir = RuleIR::While(WhileLoopIR {
                 condition: w.condition.clone(),
                 body: Vec::new(), // flattened separately
-                guard: GuardMeta {
-                    max_iterations: MAX_LOOP_ITERATIONS,
-                    max_time_ms: MAX_LOOP_TIME_MS,
-                },
+                guard,
             });
             out.push(ir);
             for sub in &w.body {
-                flatten_rule_ir(sub, out);
+                flatten_rule_ir(sub, guard, out);
             }
         }
-        ast::Rule::Match(_) => out.push(build_rule_ir(rule)),
+        ast::Rule::Match(_) => out.push(build_rule_ir(rule, guard)),
     }
 }
@@ -2,41 +2,87 @@ use std::time::Instant;
 
 use serde_json::Value;
 
-use crate::evaluator::evaluate_condition;
+use crate::evaluator::{build_action_payload_in, eval_condition_in, explain_condition_in, explain_match_in, operand_key};
+use crate::explain::{rule_text, temporal_scope, Branch, Explanation, LoopFrame};
 use crate::ir::{PolicyIR, RuleIR};
-use crate::runtime::{Decision, DecisionMetrics};
+use crate::ast::{ActionCall, CombiningAlgorithm};
+use crate::runtime::{resolve_loop_elements, Decision, GuardLimit};
+use crate::temporal::TemporalState;
 
 /// Execute a PolicyIR against a JSON context using the existing evaluator semantics.
+/// Temporal operators see no history; use [`execute_ir_with_state`] for stateful policies.
 pub fn execute_ir(policy_ir: &PolicyIR, context: Value) -> Decision {
+    execute_ir_with_state(policy_ir, context, &mut TemporalState::new(), 0)
+}
+
+/// Execute one evaluation at `now_ms`, advancing the caller-owned temporal state.
+pub fn execute_ir_with_state(
+    policy_ir: &PolicyIR,
+    context: Value,
+    temporal: &mut TemporalState,
+    now_ms: u64,
+) -> Decision {
     let start = Instant::now();
+    temporal.begin(now_ms);
     let mut ctx = context;
-    let mut decision = Decision {
-        actions: Vec::new(),
-        logs: Vec::new(),
-        guard_triggered: false,
-        metrics: DecisionMetrics::default(),
-        traces: Vec::new(),
-    };
+    let mut decision = Decision::new(policy_ir.guard);
 
     for rule in &policy_ir.rules {
-        eval_rule(rule, &mut ctx, &mut decision);
+        eval_rule(rule, &mut ctx, &mut decision, temporal, &mut Vec::new());
+        if policy_ir.combining == CombiningAlgorithm::FirstMatch && !decision.fired.is_empty() {
+            break;
+        }
     }
-
+    finish_decision(policy_ir, &ctx, temporal, &mut decision);
     decision.metrics.duration_ms = start.elapsed().as_millis();
     decision
 }
 
-fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
+/// Tahap setelah semua rule dievaluasi: combining, otorisasi, lalu invarian `CONSTRAINT:`.
+pub(crate) fn finish_decision(policy_ir: &PolicyIR, ctx: &Value, temporal: &mut TemporalState, decision: &mut Decision) {
+    decision.apply_combining(policy_ir.combining);
+    let actors = || policy_ir.actors.iter().map(|a| (a.role.as_str(), a.primary, a.capabilities.as_deref()));
+    crate::authorization::authorize(actors(), &policy_ir.capabilities, decision);
+    let invariants = policy_ir.constraints.iter().filter_map(|c| Some((c.kind.as_str(), c.invariant.as_ref()?)));
+    crate::invariant::enforce(invariants, ctx, temporal, decision);
+    crate::authorization::authorize(actors(), &policy_ir.capabilities, decision);
+}
+
+fn eval_rule(
+    rule: &RuleIR,
+    ctx: &mut Value,
+    decision: &mut Decision,
+    temporal: &mut TemporalState,
+    loops: &mut Vec<LoopFrame>,
+) {
+    let scope = temporal_scope(loops);
     match rule {
         RuleIR::Standard(r) => {
             decision.metrics.rules_evaluated += 1;
             decision
                 .logs
                 .push(format!("Checking Rule: IF {} ...", r.condition));
-            let triggered = evaluate_condition(&r.condition, ctx);
+            let trace = explain_condition_in(&r.condition, ctx, temporal, &scope);
+            let triggered = trace.result;
+            let explain = |action: &ActionCall, branch: Branch, loops: &[LoopFrame]| Explanation {
+                action: action.name.clone(),
+                rule: rule_text(&r.condition, &r.action, r.else_action.as_ref()),
+                branch,
+                priority: r.priority,
+                effect: r.effect,
+                condition: trace,
+                loops: loops.to_vec(),
+                actor: r.actor.clone(),
+            };
             if triggered {
                 decision.logs.push(format!("MATCH -> THEN {}", r.action));
-                decision.actions.push(r.action.clone());
+                let payload = build_action_payload_in(&r.action, ctx, temporal, &scope);
+                decision.fire(payload, explain(&r.action, Branch::Then, loops));
+                decision.metrics.actions_triggered += 1;
+            } else if let Some(else_action) = &r.else_action {
+                decision.logs.push(format!("No match -> ELSE {}", else_action));
+                let payload = build_action_payload_in(else_action, ctx, temporal, &scope);
+                decision.fire(payload, explain(else_action, Branch::Else, loops));
                 decision.metrics.actions_triggered += 1;
             } else {
                 decision.logs.push("No match".to_string());
@@ -50,6 +96,7 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
             let start_time = Instant::now();
             let mut iter_count: usize = 0;
             let elements = resolve_loop_elements(ctx, &loop_ir.collection);
+            let total = elements.len();
             for element in elements {
                 iter_count += 1;
                 if iter_count > loop_ir.guard.max_iterations {
@@ -59,6 +106,7 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
                         "Guard hit: loop iteration limit exceeded ({})",
                         loop_ir.guard.max_iterations
                     ));
+                    decision.truncate(&loop_ir.collection, total, iter_count - 1, GuardLimit::Iterations);
                     break;
                 }
                 if start_time.elapsed().as_millis() > loop_ir.guard.max_time_ms {
@@ -68,6 +116,7 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
                         "Guard hit: loop time exceeded ({} ms)",
                         loop_ir.guard.max_time_ms
                     ));
+                    decision.truncate(&loop_ir.collection, total, iter_count - 1, GuardLimit::Time);
                     break;
                 }
 
@@ -78,9 +127,11 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
                 decision
                     .logs
                     .push(format!("Iteration {} for {}", iter_count, loop_ir.iterator));
+                loops.push(LoopFrame::for_each(&loop_ir.iterator, &loop_ir.collection, iter_count - 1, &element));
                 for sub_rule in &loop_ir.body {
-                    eval_rule(sub_rule, ctx, decision);
+                    eval_rule(sub_rule, ctx, decision, temporal, loops);
                 }
+                loops.pop();
             }
         }
         RuleIR::While(loop_ir) => {
@@ -91,7 +142,7 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
             let start_time = Instant::now();
             let mut iter_count: usize = 0;
 
-            while evaluate_condition(&loop_ir.condition, ctx) {
+            while eval_condition_in(&loop_ir.condition, ctx, temporal, &scope) {
                 iter_count += 1;
                 if iter_count > loop_ir.guard.max_iterations {
                     decision.guard_triggered = true;
@@ -114,9 +165,11 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
                 decision
                     .logs
                     .push(format!("Condition met. Iteration {}", iter_count));
+                loops.push(LoopFrame::while_loop(&loop_ir.condition, iter_count - 1));
                 for sub_rule in &loop_ir.body {
-                    eval_rule(sub_rule, ctx, decision);
+                    eval_rule(sub_rule, ctx, decision, temporal, loops);
                 }
+                loops.pop();
             }
         }
         RuleIR::Match(match_ir) => {
@@ -124,50 +177,60 @@ fn eval_rule(rule: &RuleIR, ctx: &mut Value, decision: &mut Decision) {
             decision
                 .logs
                 .push(format!("[MATCH] {}", match_ir.scrutinee));
-            
-            for arm in &match_ir.arms {
-                let condition = format!("{} == {}", match_ir.scrutinee, arm.pattern);
-                if evaluate_condition(&condition, ctx) {
+
+            let candidates: Vec<usize> = match &match_ir.table {
+                Some(table) if table.symbols.iter().all(|s| ctx.get(s).is_none()) => {
+                    let chosen = operand_key(&match_ir.scrutinee, ctx).and_then(|key| table.cases.get(&key)?.first().copied());
+                    chosen.or(table.default).into_iter().collect()
+                }
+                _ => (0..match_ir.arms.len()).collect(),
+            };
+            for arm in candidates.into_iter().map(|i| &match_ir.arms[i]) {
+                let trace = explain_match_in(&match_ir.scrutinee, &arm.pattern, ctx, temporal, &scope);
+                if trace.result {
                     decision.logs.push(format!("-> Match arm: {} => {}", arm.pattern, arm.action));
-                    decision.actions.push(arm.action.clone());
+                    let payload = build_action_payload_in(&arm.action, ctx, temporal, &scope);
+                    let explanation = Explanation::match_arm(&match_ir.scrutinee, &arm.pattern, &arm.action, trace, loops);
+                    decision.fire(payload, explanation);
                     decision.metrics.actions_triggered += 1;
                     return;
                 }
             }
             decision.logs.push("-> No match found".to_string());
         }
-    }
-}
-
-fn resolve_loop_elements(data: &Value, collection_path: &str) -> Vec<Value> {
-    if let Some(arr) = resolve_array_path(data, collection_path) {
-        return arr.into_iter().take(50).collect();
-    }
-    vec![Value::Null, Value::Null, Value::Null]
-}
-
-fn resolve_array_path(root: &Value, path: &str) -> Option<Vec<Value>> {
-    let mut current = root;
-    for segment in path.split('.') {
-        if segment.is_empty() {
-            return None;
-        }
-        let mut base = segment;
-        let mut indexes: Vec<usize> = Vec::new();
-        while let Some(start) = base.find('[') {
-            let end = base[start + 1..].find(']')? + start + 1;
-            let idx_str = &base[start + 1..end];
-            let idx = idx_str.parse::<usize>().ok()?;
-            indexes.push(idx);
-            base = &base[..start];
-        }
-
-        if !base.is_empty() {
-            current = current.get(base)?;
+        RuleIR::Hoisted(block) => {
+            if eval_condition_in(&block.guard, ctx, temporal, &scope) {
+                for sub_rule in &block.body {
+                    eval_rule(sub_rule, ctx, decision, temporal, loops);
+                }
+            } else {
+                // Runtime tetap mengevaluasi (dan menghitung) setiap rule yang dilewati
+                decision.metrics.rules_evaluated += block.body.len();
+                decision
+                    .logs
+                    .push(format!("Skipped {} rules: {} is false", block.body.len(), block.guard));
+            }
         }
-        for idx in indexes {
-            current = current.get(idx)?;
+        RuleIR::Dispatch(dispatch) => {
+            let table = &dispatch.table;
+            if table.symbols.iter().any(|s| ctx.get(s).is_some()) {
+                for sub_rule in &dispatch.rules {
+                    eval_rule(sub_rule, ctx, decision, temporal, loops);
+                }
+                return;
+            }
+            let key = operand_key(&dispatch.field, ctx);
+            let selected = key.as_ref().and_then(|key| table.cases.get(key)).cloned().unwrap_or_default();
+            decision.logs.push(format!(
+                "[DISPATCH] {} -> {} of {} rules",
+                dispatch.field,
+                selected.len(),
+                dispatch.rules.len()
+            ));
+            decision.metrics.rules_evaluated += dispatch.rules.len() - selected.len();
+            for index in selected {
+                eval_rule(&dispatch.rules[index], ctx, decision, temporal, loops);
+            }
         }
     }
-    current.as_array().cloned()
 }
@@ -10,6 +10,17 @@ pub enum TokenType {
     Impact,
     Trace,
     Review,
+    Units,
+    Schema,
+    Guard,
+    /// `EXPECT:` atau `TEST:` (kasus uji kebijakan).
+    Expect,
+    Combine,
+    /// `IMPORT:` kebijakan; `import` tanpa ':' tetap [`TokenType::Import`] milik Program.
+    Imports,
+    Extend,
+    /// `CAPABILITY:` (capability yang dibutuhkan aksi).
+    Capability,
 
     // Sub-Keywords
     Primary,
@@ -247,17 +258,27 @@ impl Lexer {
                     let s = self.read_identifier();
                     let s_lower = s.to_lowercase();
                     let next_non_ws = self.peek_non_whitespace();
+                    // Header seksi hanya di awal baris, agar item seperti `- Test: ...` tetap teks biasa
+                    let line_start = tokens.last().is_none_or(|t| t.line != start_line);
                     let token_type = match s_lower.as_str() {
                         // Section headers (case-insensitive) only when followed by ':'
-                        "intent" if matches!(next_non_ws, Some(':')) => TokenType::Intent,
-                        "actor" if matches!(next_non_ws, Some(':')) => TokenType::Actor,
-                        "context" if matches!(next_non_ws, Some(':')) => TokenType::Context,
-                        "assumption" if matches!(next_non_ws, Some(':')) => TokenType::Assumption,
-                        "rule" if matches!(next_non_ws, Some(':')) => TokenType::Rule,
-                        "constraint" if matches!(next_non_ws, Some(':')) => TokenType::Constraint,
-                        "impact" if matches!(next_non_ws, Some(':')) => TokenType::Impact,
-                        "trace" if matches!(next_non_ws, Some(':')) => TokenType::Trace,
-                        "review" if matches!(next_non_ws, Some(':')) => TokenType::Review,
+                        "intent" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Intent,
+                        "actor" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Actor,
+                        "context" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Context,
+                        "assumption" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Assumption,
+                        "rule" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Rule,
+                        "constraint" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Constraint,
+                        "impact" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Impact,
+                        "trace" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Trace,
+                        "review" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Review,
+                        "units" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Units,
+                        "schema" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Schema,
+                        "guard" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Guard,
+                        "expect" | "test" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Expect,
+                        "combine" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Combine,
+                        "import" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Imports,
+                        "extend" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Extend,
+                        "capability" | "capabilities" if matches!(next_non_ws, Some(':')) && line_start => TokenType::Capability,
 
                         // Sub-keys (case-insensitive, tolerate hyphen variants)
                         "primary" => TokenType::Primary,
@@ -3,11 +3,32 @@ pub mod checker;
 pub mod lexer;
 pub mod parser;
 pub mod types;
+pub mod units;
+pub mod schema;
+pub mod analysis;
+pub mod invariant;
+pub mod authorization;
+pub mod temporal;
+pub mod explain;
+pub mod compose;
 pub mod evaluator;
 pub mod runtime;
+pub mod cache;
+pub mod metrics;
+pub mod simulate;
+pub mod watch;
+pub mod audit;
+pub mod testing;
+pub mod coverage;
+pub mod linter;
 pub mod ir;
 pub mod ir_interpreter;
+pub mod optimizer;
+pub mod wasm_codegen;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod conformance;
 pub mod emitter;
+pub mod bundle;
 pub mod codegen;
 pub mod stdlib;
 pub mod error;
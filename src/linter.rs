@@ -1,30 +1,49 @@
 //! Enhanced linter with comprehensive rule set for OmniLang policies
 
-use omnilang_core::ast;
-use serde_json::Value;
+use crate::analysis::{self, FindingKind};
+use crate::{ast, authorization, schema, units};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::collections::HashSet;
 
 /// Lint rule severity levels
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Error,
     Warning,
     Info,
 }
 
+impl Severity {
+    /// Level SARIF: `error`, `warning`, atau `note`.
+    pub fn sarif_level(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "note",
+        }
+    }
+}
+
 /// Individual lint finding
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
 pub struct LintFinding {
     pub rule: String,
     pub severity: Severity,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub suggestion: Option<String>,
+    /// Konteks contoh yang menunjukkan temuan (analisis rule)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness: Option<Value>,
 }
 
 /// Complete lint result
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize)]
 pub struct LintResult {
     pub findings: Vec<LintFinding>,
     pub has_errors: bool,
@@ -53,9 +72,64 @@ impl LintResult {
     pub fn is_clean(&self) -> bool {
         self.findings.is_empty()
     }
+
+    /// Laporan SARIF 2.1.0 untuk code scanning; `uri` adalah path file kebijakan.
+    /// Saran dan konteks saksi disimpan di `properties` tiap result.
+    pub fn to_sarif(&self, uri: &str) -> Value {
+        let mut rules: Vec<&str> = Vec::new();
+        for finding in &self.findings {
+            if !rules.contains(&finding.rule.as_str()) {
+                rules.push(&finding.rule);
+            }
+        }
+        let results: Vec<Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut location = json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } });
+                if let Some(line) = finding.line {
+                    location["physicalLocation"]["region"] = json!({ "startLine": line });
+                }
+                let mut result = json!({
+                    "ruleId": finding.rule,
+                    "ruleIndex": rules.iter().position(|r| *r == finding.rule),
+                    "level": finding.severity.sarif_level(),
+                    "message": { "text": finding.message },
+                    "locations": [location],
+                });
+                let mut properties = serde_json::Map::new();
+                if let Some(suggestion) = &finding.suggestion {
+                    properties.insert("suggestion".to_string(), json!(suggestion));
+                }
+                if let Some(witness) = &finding.witness {
+                    properties.insert("witness".to_string(), witness.clone());
+                }
+                if !properties.is_empty() {
+                    result["properties"] = Value::Object(properties);
+                }
+                result
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "omnilang",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                    }
+                },
+                "results": results,
+            }]
+        })
+    }
 }
 
 /// Main linter implementation
+#[derive(Default)]
 pub struct Linter;
 
 impl Linter {
@@ -73,10 +147,16 @@ impl Linter {
         self.check_loop_safety(policy, &mut result);
         self.check_unused_sections(policy, &mut result);
         self.check_action_capabilities(policy, &mut result);
+        self.check_units(policy, &mut result);
+        self.check_schema_fields(policy, &mut result);
 
         // Context validation
         if let Some(ctx) = context {
-            self.check_context_fields(policy, ctx, &mut result);
+            if policy.schema.is_empty() {
+                self.check_context_fields(policy, ctx, &mut result);
+            } else {
+                self.check_context_schema(policy, ctx, &mut result);
+            }
             self.check_context_usage(policy, ctx, &mut result);
         }
 
@@ -97,6 +177,7 @@ impl Linter {
                 message: "Policy must have INTENT section".to_string(),
                 line: None,
                 suggestion: Some("Add INTENT section at the beginning".to_string()),
+                witness: None,
             });
         }
 
@@ -108,6 +189,7 @@ impl Linter {
                 message: "Policy must have at least one ACTOR".to_string(),
                 line: None,
                 suggestion: Some("Add ACTOR section with primary and secondary roles".to_string()),
+                witness: None,
             });
         }
 
@@ -119,20 +201,25 @@ impl Linter {
                 message: "Policy should have at least one RULE".to_string(),
                 line: None,
                 suggestion: Some("Add RULE section with IF/THEN logic".to_string()),
+                witness: None,
             });
         }
     }
 
     /// Check that loops have proper guard coverage
     fn check_guard_coverage(&self, policy: &ast::Policy, result: &mut LintResult) {
+        if !policy.guard.is_empty() {
+            return;
+        }
         for rule in &policy.rules {
             if let ast::Rule::For(_) | ast::Rule::While(_) | ast::Rule::Match(_) = rule {
                 result.add_finding(LintFinding {
                     rule: "guard-coverage".to_string(),
                     severity: Severity::Info,
                     message: "Complex rule detected - ensure guard limits or logic are optimal".to_string(),
-                    line: None,
-                    suggestion: Some("Configure appropriate safety guards for this rule type".to_string()),
+                    line: Some(rule.line()),
+                    suggestion: Some("Declare loop limits under GUARD: (MaxIterations, MaxTime)".to_string()),
+                    witness: None,
                 });
             }
         }
@@ -148,8 +235,9 @@ impl Linter {
                             rule: "loop-safety".to_string(),
                             severity: Severity::Warning,
                             message: format!("WHILE loop condition '{}' may not terminate", while_loop.condition),
-                            line: None,
+                            line: Some(while_loop.line),
                             suggestion: Some("Add counter or time-based termination condition".to_string()),
+                            witness: None,
                         });
                     }
                 }
@@ -159,8 +247,9 @@ impl Linter {
                             rule: "loop-safety".to_string(),
                             severity: Severity::Warning,
                             message: format!("FOR loop over '{}' may process large collections", for_loop.collection),
-                            line: None,
+                            line: Some(for_loop.line),
                             suggestion: Some("Consider limiting collection size or adding early termination".to_string()),
+                            witness: None,
                         });
                     }
                 }
@@ -179,6 +268,7 @@ impl Linter {
                 message: "ASSUMPTION section is empty".to_string(),
                 line: None,
                 suggestion: Some("Add environmental assumptions or remove section".to_string()),
+                witness: None,
             });
         }
 
@@ -189,61 +279,34 @@ impl Linter {
                 message: "CONSTRAINT section is empty".to_string(),
                 line: None,
                 suggestion: Some("Add legal, ethical, or technical constraints".to_string()),
+                witness: None,
             });
         }
     }
 
-    /// Check action capabilities alignment
+    /// Check that each rule's actor is granted the capabilities its actions require
     fn check_action_capabilities(&self, policy: &ast::Policy, result: &mut LintResult) {
-        // Extract actions from rules
-        let mut actions = HashSet::new();
-        for rule in &policy.rules {
-            match rule {
-                ast::Rule::Standard(std_rule) => {
-                    actions.insert(std_rule.action.clone());
-                }
-                ast::Rule::For(for_rule) => {
-                    for sub_rule in &for_rule.body {
-                        if let ast::Rule::Standard(std_rule) = sub_rule {
-                            actions.insert(std_rule.action.clone());
-                        }
-                    }
-                }
-                ast::Rule::While(while_rule) => {
-                    for sub_rule in &while_rule.body {
-                        if let ast::Rule::Standard(std_rule) = sub_rule {
-                            actions.insert(std_rule.action.clone());
-                        }
-                    }
-                }
-                ast::Rule::Match(match_rule) => {
-                    for arm in &match_rule.arms {
-                        actions.insert(arm.action.clone());
-                    }
-                }
-            }
-        }
-
-        // Check for actions without clear capabilities
-        for action in actions {
-            if action.contains("Network") || action.contains("network") {
-                result.add_finding(LintFinding {
-                    rule: "action-capabilities".to_string(),
-                    severity: Severity::Warning,
-                    message: format!("Action '{}' requires Network capability", action),
-                    line: None,
-                    suggestion: Some("Ensure Network capability is granted to executing actor".to_string()),
-                });
-            }
-            if action.contains("File") || action.contains("file") {
-                result.add_finding(LintFinding {
-                    rule: "action-capabilities".to_string(),
-                    severity: Severity::Warning,
-                    message: format!("Action '{}' requires FileSystem capability", action),
-                    line: None,
-                    suggestion: Some("Ensure FileSystem capability is granted to executing actor".to_string()),
-                });
-            }
+        for (line, failure) in authorization::check_policy(policy) {
+            let missing: Vec<String> = failure.missing.iter().map(|c| c.to_string()).collect();
+            let (reason, suggestion) = if failure.undeclared {
+                (
+                    format!("actor '{}' is not declared in ACTOR", failure.actor),
+                    format!("Declare '{}' under ACTOR: or fix the BY clause", failure.actor),
+                )
+            } else {
+                (
+                    format!("actor '{}' lacks {}", failure.actor, missing.join(", ")),
+                    format!("Grant {} to '{}' with CAN [...] or run the rule BY another actor", missing.join(", "), failure.actor),
+                )
+            };
+            result.add_finding(LintFinding {
+                rule: "action-capabilities".to_string(),
+                severity: Severity::Warning,
+                message: format!("Action '{}' at line {} is always rejected: {}", failure.action, line, reason),
+                line: Some(line),
+                suggestion: Some(suggestion),
+                witness: None,
+            });
         }
     }
 
@@ -257,7 +320,7 @@ impl Linter {
                     self.extract_fields_from_condition(&std_rule.condition, &mut referenced_fields);
                 }
                 ast::Rule::For(for_rule) => {
-                    self.extract_fields_from_condition(&for_rule.collection, &mut referenced_fields);
+                    referenced_fields.insert(for_rule.collection.clone());
                     for sub_rule in &for_rule.body {
                         if let ast::Rule::Standard(std_rule) = sub_rule {
                             self.extract_fields_from_condition(&std_rule.condition, &mut referenced_fields);
@@ -273,10 +336,7 @@ impl Linter {
                     }
                 }
                 ast::Rule::Match(match_rule) => {
-                    referenced_fields.insert(match_rule.scrutinee.clone());
-                    for arm in &match_rule.arms {
-                        referenced_fields.insert(arm.pattern.clone());
-                    }
+                    self.extract_fields_from_operand(&match_rule.scrutinee, &mut referenced_fields);
                 }
             }
         }
@@ -290,11 +350,83 @@ impl Linter {
                     message: format!("Context field '{}' is referenced but not provided", field),
                     line: None,
                     suggestion: Some(format!("Add '{}' to context JSON", field)),
+                    witness: None,
                 });
             }
         }
     }
 
+    /// Fields read by rules must be declared in SCHEMA (loop iterators excluded)
+    fn check_schema_fields(&self, policy: &ast::Policy, result: &mut LintResult) {
+        if policy.schema.is_empty() {
+            return;
+        }
+        let mut fields = HashSet::new();
+        self.collect_rule_fields(&policy.rules, &mut Vec::new(), &mut fields);
+
+        let declared = |path: &str| {
+            policy.schema.iter().any(|decl| {
+                path == decl.field
+                    || path.starts_with(&format!("{}.", decl.field))
+                    || decl.field.starts_with(&format!("{}.", path))
+            })
+        };
+        let mut undeclared: Vec<String> = fields.into_iter().filter(|f| !declared(f)).collect();
+        undeclared.sort();
+        for field in undeclared {
+            let suggestion = schema::closest(&field, policy.schema.iter().map(|d| d.field.as_str()));
+            result.add_finding(LintFinding {
+                rule: "schema-undeclared".to_string(),
+                severity: Severity::Error,
+                message: format!("Field '{}' is used by a rule but not declared in SCHEMA", field),
+                line: None,
+                suggestion: Some(match suggestion {
+                    Some(s) => format!("Did you mean '{}'?", s),
+                    None => format!("Declare it under SCHEMA: (e.g. '- {}: number')", field),
+                }),
+                witness: None,
+            });
+        }
+    }
+
+    fn collect_rule_fields(&self, rules: &[ast::Rule], iterators: &mut Vec<String>, fields: &mut HashSet<String>) {
+        for rule in rules {
+            let mut used = HashSet::new();
+            match rule {
+                ast::Rule::Standard(std_rule) => self.extract_fields_from_condition(&std_rule.condition, &mut used),
+                ast::Rule::For(for_rule) => {
+                    used.insert(for_rule.collection.clone());
+                    iterators.push(for_rule.iterator.clone());
+                    self.collect_rule_fields(&for_rule.body, iterators, fields);
+                    iterators.pop();
+                }
+                ast::Rule::While(while_rule) => {
+                    self.extract_fields_from_condition(&while_rule.condition, &mut used);
+                    self.collect_rule_fields(&while_rule.body, iterators, fields);
+                }
+                ast::Rule::Match(match_rule) => self.extract_fields_from_operand(&match_rule.scrutinee, &mut used),
+            }
+            fields.extend(used.into_iter().filter(|path| {
+                let root = path.split(['.', '[']).next().unwrap_or(path);
+                !iterators.iter().any(|it| it == root)
+            }));
+        }
+    }
+
+    /// Validate the supplied context against the declared SCHEMA
+    fn check_context_schema(&self, policy: &ast::Policy, context: &Value, result: &mut LintResult) {
+        for violation in schema::validate(&policy.schema, context) {
+            result.add_finding(LintFinding {
+                rule: "schema-validation".to_string(),
+                severity: Severity::Error,
+                message: format!("Context {}", violation),
+                line: None,
+                suggestion: None,
+                witness: None,
+            });
+        }
+    }
+
     /// Check context field usage patterns
     fn check_context_usage(&self, policy: &ast::Policy, context: &Value, result: &mut LintResult) {
         // Check for unused context fields
@@ -308,13 +440,13 @@ impl Linter {
                     self.extract_fields_from_condition(&std_rule.condition, &mut used_fields);
                 }
                 ast::Rule::For(for_rule) => {
-                    self.extract_fields_from_condition(&for_rule.collection, &mut used_fields);
+                    used_fields.insert(for_rule.collection.clone());
                 }
                 ast::Rule::While(while_rule) => {
                     self.extract_fields_from_condition(&while_rule.condition, &mut used_fields);
                 }
                 ast::Rule::Match(match_rule) => {
-                    used_fields.insert(match_rule.scrutinee.clone());
+                    self.extract_fields_from_operand(&match_rule.scrutinee, &mut used_fields);
                 }
             }
         }
@@ -327,26 +459,130 @@ impl Linter {
                     message: format!("Context field '{}' is provided but never used", field),
                     line: None,
                     suggestion: Some("Remove unused field or add rule that uses it".to_string()),
+                    witness: None,
                 });
             }
         }
     }
 
-    /// Check rule logic consistency
+    /// Check rule logic: constant conditions, then interval analysis for dead,
+    /// subsumed and contradictory rules and unreachable MATCH arms
     fn check_rule_logic(&self, policy: &ast::Policy, result: &mut LintResult) {
         for rule in &policy.rules {
             if let ast::Rule::Standard(std_rule) = rule {
-                if std_rule.condition.contains("true") && std_rule.condition.contains("==") {
+                if self.is_constant_condition(&std_rule.condition) {
                     result.add_finding(LintFinding {
                         rule: "rule-logic".to_string(),
                         severity: Severity::Warning,
                         message: "Rule condition may be always true".to_string(),
-                        line: None,
+                        line: Some(std_rule.line),
                         suggestion: Some("Review condition logic for correctness".to_string()),
+                        witness: None,
                     });
                 }
             }
         }
+
+        for finding in analysis::analyze(policy) {
+            let (rule, severity) = match finding.kind {
+                FindingKind::DeadRule | FindingKind::DeadElse | FindingKind::Shadowed => ("dead-rule", Severity::Warning),
+                FindingKind::Redundant => ("subsumed-rule", Severity::Warning),
+                FindingKind::Subsumed => ("subsumed-rule", Severity::Info),
+                FindingKind::Contradiction => ("contradictory-actions", Severity::Warning),
+                FindingKind::UnreachableArm => ("unreachable-arm", Severity::Warning),
+            };
+            let suggestion = (!finding.assuming.is_empty())
+                .then(|| format!("Witness also assumes {}", finding.assuming.join(", ")));
+            result.add_finding(LintFinding {
+                rule: rule.to_string(),
+                severity,
+                message: finding.message,
+                line: Some(finding.line),
+                suggestion,
+                witness: finding.witness,
+            });
+        }
+    }
+
+    /// Check unit suffixes and fields compared against dimensioned literals
+    fn check_units(&self, policy: &ast::Policy, result: &mut LintResult) {
+        let mut conditions = Vec::new();
+        self.collect_conditions(&policy.rules, &mut conditions);
+
+        let mut unknown = HashSet::new();
+        let mut undeclared = HashSet::new();
+        for condition in conditions {
+            self.collect_unit_usage(condition, &mut unknown, &mut undeclared);
+        }
+
+        for unit in unknown {
+            result.add_finding(LintFinding {
+                rule: "unit-unknown".to_string(),
+                severity: Severity::Warning,
+                message: format!("Unit '{}' is not in the unit registry and is compared as a plain number", unit),
+                line: None,
+                suggestion: Some("Use a registered unit (m, s, km/h, C, V, A, bar, %) or drop the suffix".to_string()),
+                witness: None,
+            });
+        }
+        for field in undeclared {
+            result.add_finding(LintFinding {
+                rule: "unit-undeclared".to_string(),
+                severity: Severity::Info,
+                message: format!("Field '{}' is compared with a unit literal but has no declared unit", field),
+                line: None,
+                suggestion: Some(format!("Declare it under UNITS: (e.g. '- {}: m'); bare values are read in the base unit", field)),
+                witness: None,
+            });
+        }
+    }
+
+    fn collect_conditions<'a>(&self, rules: &'a [ast::Rule], out: &mut Vec<&'a ast::Condition>) {
+        for rule in rules {
+            match rule {
+                ast::Rule::Standard(std_rule) => out.push(&std_rule.condition),
+                ast::Rule::For(for_rule) => self.collect_conditions(&for_rule.body, out),
+                ast::Rule::While(while_rule) => {
+                    out.push(&while_rule.condition);
+                    self.collect_conditions(&while_rule.body, out);
+                }
+                ast::Rule::Match(_) => {}
+            }
+        }
+    }
+
+    fn collect_unit_usage(
+        &self,
+        condition: &ast::Condition,
+        unknown: &mut HashSet<String>,
+        undeclared: &mut HashSet<String>,
+    ) {
+        match condition {
+            ast::Condition::Compare { left, right, .. } => {
+                for (operand, other) in [(left, right), (right, left)] {
+                    if let ast::Operand::Number { unit: Some(unit), .. } = operand {
+                        if units::lookup(unit).is_none() {
+                            unknown.insert(unit.clone());
+                        } else if let ast::Operand::Path { path, unit: None } = other {
+                            undeclared.insert(path.to_string());
+                        }
+                    }
+                }
+            }
+            ast::Condition::Not { inner }
+            | ast::Condition::Becomes { inner }
+            | ast::Condition::Sustained { inner, .. } => self.collect_unit_usage(inner, unknown, undeclared),
+            ast::Condition::Latch { set, reset } => {
+                self.collect_unit_usage(set, unknown, undeclared);
+                self.collect_unit_usage(reset, unknown, undeclared);
+            }
+            ast::Condition::And { terms } | ast::Condition::Or { terms } => {
+                for term in terms {
+                    self.collect_unit_usage(term, unknown, undeclared);
+                }
+            }
+            _ => {}
+        }
     }
 
     /// Check constraint consistency
@@ -367,6 +603,7 @@ impl Linter {
                         message: format!("Unknown constraint kind: '{}'", constraint.kind),
                         line: None,
                         suggestion: Some("Use 'Legal', 'Technical', or 'Ethical'".to_string()),
+                        witness: None,
                     });
                 }
             }
@@ -380,6 +617,7 @@ impl Linter {
                 message: "Consider adding Legal constraints".to_string(),
                 line: None,
                 suggestion: Some("Add CONSTRAINT with kind 'Legal'".to_string()),
+                witness: None,
             });
         }
 
@@ -390,6 +628,7 @@ impl Linter {
                 message: "Consider adding Technical constraints".to_string(),
                 line: None,
                 suggestion: Some("Add CONSTRAINT with kind 'Technical'".to_string()),
+                witness: None,
             });
         }
 
@@ -400,13 +639,15 @@ impl Linter {
                 message: "Consider adding Ethical constraints".to_string(),
                 line: None,
                 suggestion: Some("Add CONSTRAINT with kind 'Ethical'".to_string()),
+                witness: None,
             });
         }
     }
 
     // Helper methods
-    fn is_terminating_condition(&self, condition: &str) -> bool {
+    fn is_terminating_condition(&self, condition: &ast::Condition) -> bool {
         // Simple heuristic: conditions with counters or time limits are likely terminating
+        let condition = condition.to_string();
         condition.contains("count") ||
         condition.contains("time") ||
         condition.contains("limit") ||
@@ -414,16 +655,62 @@ impl Linter {
         condition.contains(">")
     }
 
-    fn extract_fields_from_condition(&self, condition: &str, fields: &mut HashSet<String>) {
-        // Simple field extraction - in real implementation, use proper AST analysis
-        for part in condition.split_whitespace() {
-            if part.contains('.') && !part.contains('\"') && !part.contains('\'') {
-                // Likely a field reference like "sensor.value" or "context.field"
-                let field = part.trim_matches(|c: char| !c.is_alphanumeric() && c != '.');
-                if !field.is_empty() && field.contains('.') {
-                    fields.insert(field.to_string());
+    fn is_constant_condition(&self, condition: &ast::Condition) -> bool {
+        match condition {
+            ast::Condition::Const { value } => *value,
+            ast::Condition::Compare { left, right, .. } => {
+                let mut fields = HashSet::new();
+                self.extract_fields_from_operand(left, &mut fields);
+                self.extract_fields_from_operand(right, &mut fields);
+                fields.is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    fn extract_fields_from_condition(&self, condition: &ast::Condition, fields: &mut HashSet<String>) {
+        match condition {
+            ast::Condition::Const { .. } => {}
+            ast::Condition::Compare { left, right, .. } => {
+                self.extract_fields_from_operand(left, fields);
+                self.extract_fields_from_operand(right, fields);
+            }
+            ast::Condition::In { value, set } => {
+                self.extract_fields_from_operand(value, fields);
+                self.extract_fields_from_operand(set, fields);
+            }
+            ast::Condition::Truthy { value } => self.extract_fields_from_operand(value, fields),
+            ast::Condition::Not { inner }
+            | ast::Condition::Becomes { inner }
+            | ast::Condition::Sustained { inner, .. } => self.extract_fields_from_condition(inner, fields),
+            ast::Condition::Latch { set, reset } => {
+                self.extract_fields_from_condition(set, fields);
+                self.extract_fields_from_condition(reset, fields);
+            }
+            ast::Condition::And { terms } | ast::Condition::Or { terms } => {
+                for term in terms {
+                    self.extract_fields_from_condition(term, fields);
+                }
+            }
+        }
+    }
+
+    fn extract_fields_from_operand(&self, operand: &ast::Operand, fields: &mut HashSet<String>) {
+        match operand {
+            ast::Operand::Path { path, .. } => {
+                fields.insert(path.to_string());
+            }
+            ast::Operand::List { items } => {
+                for item in items {
+                    self.extract_fields_from_operand(item, fields);
                 }
             }
+            ast::Operand::Arith { left, right, .. } => {
+                self.extract_fields_from_operand(left, fields);
+                self.extract_fields_from_operand(right, fields);
+            }
+            ast::Operand::Count { condition, .. } => self.extract_fields_from_condition(condition, fields),
+            ast::Operand::Number { .. } | ast::Operand::Bool { .. } | ast::Operand::Str { .. } => {}
         }
     }
 
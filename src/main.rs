@@ -2,16 +2,33 @@ use std::env;
 use std::fs;
 use std::process;
 
-mod metrics;
-mod linter;
 
+use omnilang_core::bundle::PolicyBundle;
 use omnilang_core::emitter::{emit, CompileTarget};
 use omnilang_core::lexer::Lexer;
 use omnilang_core::parser::Parser;
-use omnilang_core::runtime::Runtime;
-use metrics::record_decision;
-use linter::Linter;
+use omnilang_core::coverage::{self, Coverage};
+use omnilang_core::runtime::{Runtime, RuntimeConfig};
+use omnilang_core::security::SigningKey;
+use omnilang_core::testing::SuiteReport;
+use omnilang_core::metrics::{MetricsRegistry, MetricsSource, SnapshotWriter};
+use omnilang_core::linter::Linter;
 use omnilang_core::observability::{init_global_logger, set_global_trace, TraceId};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Diaktifkan `exec --format json`: stdout hanya berisi Decision, jadi pesan galat ke stderr.
+static ERRORS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// `println!` untuk pesan galat dan peringatan; ke stderr jika [`ERRORS_TO_STDERR`] aktif.
+macro_rules! error_println {
+	($($arg:tt)*) => {
+		if ERRORS_TO_STDERR.load(Ordering::Relaxed) {
+			eprintln!($($arg)*);
+		} else {
+			println!($($arg)*);
+		}
+	};
+}
 
 fn main() {
 	// Initialize global logger for structured logging
@@ -30,9 +47,18 @@ fn main() {
 	let exit_code = match command {
 		"compile" => handle_compile(&args[1..]),
 		"exec" => handle_exec(&args[1..]),
+		"explain" => handle_explain(&args[1..]),
+		"simulate" => handle_simulate(&args[1..]),
+		"watch" => handle_watch(&args[1..]),
 		"lint" => handle_lint(&args[1..]),
+		"schema" => handle_schema(&args[1..]),
+		"audit" => handle_audit(&args[1..]),
+		"sign" => handle_sign(&args[1..]),
+		"verify" => handle_verify(&args[1..]),
+		"run-bundle" => handle_run_bundle(&args[1..]),
+		"conformance" => handle_conformance(&args[1..]),
 		"test" => handle_test(&args[1..]),
-		"metrics" => handle_metrics(),
+		"metrics" => handle_metrics(&args[1..]),
 		"demo-action" => handle_demo_action(&args[1..]),
 		"lsp" => handle_lsp(),
 		"serve" => handle_serve(&args[1..]),
@@ -59,12 +85,42 @@ fn main() {
 fn print_usage() {
 	println!("OmniLang CLI v1.2.2");
 	println!("Usage:");
-	println!("  omnilang exec <file.omni> [--context <context.json>]  Execute a policy");
-	println!("  omnilang compile <file.omni> [--target <wasm|json>]   Compile to IR or WASM");
-	println!("  omnilang lint <file.omni>                             Check for policy debt");
-	println!("  omnilang test <file.omni>                             Run policy assertions");
-	println!("  omnilang metrics                                      Show execution performance");
+	println!("  omnilang exec <file.omni> [--context <context.json>] [--config <runtime.json>] [--format <text|json>]");
+	println!("                [--audit-log <audit.jsonl>] [--audit-key <key-file>] [--require-signature --key <key-file>]");
+	println!("                [--cache <entries>] [--cache-file <cache.jsonl>] [--metrics-file <metrics.json>]");
+	println!("                                                        Execute a policy");
+	println!("  omnilang explain <file.omni> [--context <context.json>] [--config <runtime.json>] [--format <text|json>]");
+	println!("                                                        Explain why each action fired");
+	println!("  omnilang simulate <file.omni> <series.jsonl|csv> [--context <base.json>] [--config <runtime.json>]");
+	println!("                    [--interval <ms>] [--format <text|json>]  Replay a recorded time series");
+	println!("  omnilang watch <file.omni> [--context <base.json>] [--config <runtime.json>] [--changes-only]");
+	println!("                 [--socket <path>] [--audit-log <audit.jsonl>] [--audit-key <key-file>]");
+	println!("                 [--cache <entries>] [--cache-file <cache.jsonl>] [--metrics-file <metrics.json>] [--metrics-port <port>]");
+	println!("                                                        Evaluate JSON Lines context patches from stdin or a Unix socket");
+	println!("  omnilang compile <file.omni> [--target <ir|wasm|native|bundle>] [--output <file>] [--key <key-file>]");
+	println!("                                                        Compile to IR, WASM, native code or a versioned bundle");
+	println!("  omnilang run-bundle <bundle.json> [--context <context.json>] [--format <text|json>] [--info]");
+	println!("                      [--require-signature --key <key-file>] [--metrics-file <metrics.json>]");
+	println!("                                                        Check and execute a compiled bundle");
+	println!("  omnilang conformance [<file.omni|dir>...] [--engines <runtime,ir,ir-opt,wasm,wasm-opt,native>] [--context <context.json>]");
+	println!("                       [--random <n>] [--seed <n>] [--format <text|json>]");
+	println!("                                                        Compare decisions across execution engines; exits 1 on divergence");
+	println!("  omnilang lint <file.omni> [--context <context.json>] [--format <text|json|sarif>]");
+	println!("                                                        Check for policy debt; exits 1 on errors");
+	println!("  omnilang schema <file.omni> [--output <file.json>]    Export SCHEMA as JSON Schema");
+	println!("  omnilang audit verify <audit.jsonl> [--key <key-file>] Check the decision audit chain for tampering");
+	println!("  omnilang sign <file>... --key <key-file>              Write detached <file>.sig signatures");
+	println!("  omnilang sign --generate-key <key-file>               Create a new signing key");
+	println!("  omnilang verify <file>... --key <key-file>            Check signatures, including IMPORT:/EXTEND: files");
+	println!("  omnilang test <file.omni>... [--config <runtime.json>] Run EXPECT:/TEST: cases and <file>.tests.json");
+	println!("                [--format <text|junit>]                 Print results as text or JUnit XML");
+	println!("                [--coverage] [--coverage-format <text|json|lcov>] [--coverage-output <file>]");
+	println!("                                                        Report exercised rules, branches and clauses");
+	println!("  omnilang metrics [<metrics.json>] [--format <text|openmetrics|json>]");
+	println!("                                                        Show a persisted metrics snapshot");
 	println!("  omnilang serve <file.omni> [--port <port>] [--hui <port>] Run an RPC Mesh worker");
+	println!("                 [--require-signature --key <key-file>] Refuse unsigned or modified files");
+	println!("                 [--metrics-port <port>] [--metrics-file <metrics.json>] Expose a metrics snapshot on /metrics");
 	println!("  omnilang pkg <init|install|build>                     OmniLang Package Manager");
 }
 
@@ -77,21 +133,66 @@ fn handle_exec(args: &[String]) -> i32 {
 
 	let file_path = &args[file_idx];
 	let mut context_path = None;
+	let mut config_path = None;
+	let mut format = "text";
+	let mut audit_path = None;
+	let mut audit_key = None;
+	let mut require_signature = false;
+	let mut key_paths = Vec::new();
+	let mut cache = CacheOptions::default();
+	let mut metrics_path = None;
 
 	let mut i = file_idx + 1;
 	while i < args.len() {
 		if args[i] == "--context" && i + 1 < args.len() {
 			context_path = Some(&args[i + 1]);
 			i += 2;
+		} else if args[i] == "--config" && i + 1 < args.len() {
+			config_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else if args[i] == "--audit-log" && i + 1 < args.len() {
+			audit_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--audit-key" && i + 1 < args.len() {
+			audit_key = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--require-signature" {
+			require_signature = true;
+			i += 1;
+		} else if args[i] == "--key" && i + 1 < args.len() {
+			key_paths.push(&args[i + 1]);
+			i += 2;
+		} else if cache.parse_flag(args, i) {
+			i += 2;
+		} else if args[i] == "--metrics-file" && i + 1 < args.len() {
+			metrics_path = Some(&args[i + 1]);
+			i += 2;
 		} else {
 			i += 1;
 		}
 	}
+	if format != "text" && format != "json" {
+		println!("Error: Unknown format '{}' (expected text or json)", format);
+		return 1;
+	}
+	if format == "json" {
+		ERRORS_TO_STDERR.store(true, Ordering::Relaxed);
+	}
+	let mut verified = None;
+	if require_signature {
+		match check_signatures(file_path, &key_paths) {
+			Ok(sources) => verified = Some(sources),
+			Err(code) => return code,
+		}
+	}
 
-	let source = match fs::read_to_string(file_path) {
+	let source = match read_source(file_path, verified.as_ref()) {
 		Ok(s) => s,
 		Err(e) => {
-			println!("Error reading file: {}", e);
+			error_println!("Error reading file: {}", e);
 			return 1;
 		}
 	};
@@ -100,7 +201,7 @@ fn handle_exec(args: &[String]) -> i32 {
 	let tokens = match lexer.tokenize() {
 		Ok(t) => t,
 		Err(e) => {
-			println!("Lexer Error: {}", e);
+			error_println!("Lexer Error: {}", e);
 			return 1;
 		}
 	};
@@ -114,14 +215,14 @@ fn handle_exec(args: &[String]) -> i32 {
 		let program = match parser.parse_program() {
 			Ok(p) => p,
 			Err(e) => {
-				println!("Program Parser Error: {}", e);
+				error_println!("Program Parser Error: {}", e);
 				return 1;
 			}
 		};
 
 		let mut evaluator = omnilang_core::program_evaluator::ProgramEvaluator::new();
 		if let Err(e) = evaluator.evaluate_program(&program) {
-			println!("Execution Error: {}", e);
+			error_println!("Execution Error: {}", e);
 			return 1;
 		}
 
@@ -142,31 +243,268 @@ fn handle_exec(args: &[String]) -> i32 {
 	let policy = match parser.parse_policy() {
 		Ok(p) => p,
 		Err(e) => {
-			println!("Policy Parser Error: {}", e);
+			error_println!("Policy Parser Error: {}", e);
 			return 1;
 		}
 	};
+	let policy = match resolve_composition(policy, file_path, verified.as_ref()) {
+		Ok(p) => p,
+		Err(code) => return code,
+	};
 
-	let mut runtime = Runtime::new();
+	let mut runtime = match runtime_with_config(config_path) {
+		Ok(r) => r,
+		Err(code) => return code,
+	};
 	if let Some(cp) = context_path {
 		if let Err(e) = runtime.load_context_from_file(cp) {
-			println!("Warning: Could not load context: {}", e);
+			error_println!("Warning: Could not load context: {}", e);
+		} else if !context_matches_schema(&runtime, &policy) {
+			return 1;
 		}
 	}
 
+	let mut audit = match audit_path {
+		Some(path) => match open_audit_log(path, audit_key) {
+			Ok(log) => Some(log),
+			Err(code) => return code,
+		},
+		None => None,
+	};
+
+	if let Err(code) = cache.enable(&mut runtime, file_path, &policy) {
+		return code;
+	}
+
+	let context = runtime.context_snapshot();
+	let started = std::time::Instant::now();
 	let decision = runtime.execute_policy(&policy);
+	let label = omnilang_core::metrics::policy_label(policy.intent.as_deref(), file_path);
+	persist_metrics(metrics_path, &label, &decision, started.elapsed());
+	if let Some(log) = &mut audit {
+		let now_ms = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|d| d.as_millis() as u64)
+			.unwrap_or_default();
+		let policy_hash = omnilang_core::audit::policy_hash(&policy);
+		if let Err(e) = log.append(&policy_hash, &context, &decision, now_ms) {
+			error_println!("Audit Error: {}", e);
+			return 1;
+		}
+	}
+	if format == "json" {
+		println!("{}", serde_json::to_string_pretty(&decision).unwrap_or_default());
+		return 0;
+	}
+	print_decision(&decision);
+	if let Some(stats) = runtime.cache_stats() {
+		let outcome = if stats.memory_hits > 0 {
+			"hit (memory)"
+		} else if stats.disk_hits > 0 {
+			"hit (disk)"
+		} else if stats.bypassed > 0 {
+			"bypassed (temporal policy)"
+		} else {
+			"miss"
+		};
+		println!("Cache: {}, {} entries", outcome, stats.entries);
+	}
+
+	0
+}
+
+/// Keluaran teks `exec`/`run-bundle`: aksi, payload, penolakan, log, dan metrik.
+fn print_decision(decision: &omnilang_core::runtime::Decision) {
 	println!("--- Decision Results ---");
 	println!("Actions triggered: {:?}", decision.actions);
+	// Tampilkan payload hanya untuk aksi yang membawa parameter/metadata
+	for payload in &decision.payloads {
+		let bare = payload.params.is_empty()
+			&& payload.required_capabilities.is_empty()
+			&& payload.metadata == Default::default();
+		if !bare {
+			println!("  {} => {}", payload.action, serde_json::to_string(payload).unwrap_or_default());
+		}
+	}
+	for truncation in &decision.truncations {
+		println!("Truncated: {}", truncation);
+	}
+	for violation in &decision.violations {
+		println!("Constraint: {}", violation);
+	}
+	for failure in &decision.unauthorized {
+		println!("Unauthorized: {}", failure);
+	}
 	println!("Logs:");
-	for log in decision.logs {
+	for log in &decision.logs {
 		println!("  {}", log);
 	}
 	println!("Metrics: {:?}", decision.metrics);
+}
 
-	// Record for global metrics
-	record_decision(&decision.metrics);
+/// Isi `file_path`: dari `verified` jika tanda tangannya sudah diperiksa, selain itu dari disk.
+fn read_source(file_path: &str, verified: Option<&omnilang_core::compose::Sources>) -> Result<String, String> {
+	match verified.and_then(|sources| sources.get(std::path::Path::new(file_path))) {
+		Some(source) => Ok(source.to_string()),
+		None => fs::read_to_string(file_path).map_err(|e| e.to_string()),
+	}
+}
 
-	0
+/// Resolve `IMPORT:`/`EXTEND:` relatif terhadap file kebijakan. Dengan `verified`,
+/// file komposisi diambil dari isi yang sudah diverifikasi, bukan dibaca ulang.
+fn resolve_composition(
+	policy: omnilang_core::ast::Policy,
+	file_path: &str,
+	verified: Option<&omnilang_core::compose::Sources>,
+) -> Result<omnilang_core::ast::Policy, i32> {
+	if policy.composition.is_empty() {
+		return Ok(policy);
+	}
+	let origin = std::path::Path::new(file_path);
+	let resolved = match verified {
+		Some(sources) => omnilang_core::compose::resolve_policy_from(policy, origin, sources),
+		None => omnilang_core::compose::resolve_policy(policy, origin),
+	};
+	resolved.map_err(|e| {
+		error_println!("Composition Error: {}", e);
+		1
+	})
+}
+
+/// Runtime dengan batas guard dari `--config <runtime.json>`, atau bawaan.
+fn runtime_with_config(config_path: Option<&String>) -> Result<Runtime, i32> {
+	let Some(path) = config_path else {
+		return Ok(Runtime::new());
+	};
+	match RuntimeConfig::from_file(path) {
+		Ok(config) => Ok(Runtime::with_config(config)),
+		Err(e) => {
+			error_println!("Error: {}", e);
+			Err(1)
+		}
+	}
+}
+
+/// Opsi `--cache <entries>` dan `--cache-file <path>` untuk `exec` dan `watch`.
+#[derive(Default)]
+struct CacheOptions {
+	entries: Option<String>,
+	file: Option<String>,
+}
+
+impl CacheOptions {
+	/// Ambil flag cache di `args[i]`; `true` jika flag dan nilainya terpakai.
+	fn parse_flag(&mut self, args: &[String], i: usize) -> bool {
+		if i + 1 >= args.len() {
+			return false;
+		}
+		match args[i].as_str() {
+			"--cache" => self.entries = Some(args[i + 1].clone()),
+			"--cache-file" => self.file = Some(args[i + 1].clone()),
+			_ => return false,
+		}
+		true
+	}
+
+	/// Pasang cache di `runtime` jika salah satu flag diberikan. Entri versi lama
+	/// kebijakan `name` di file cache diinvalidasi.
+	fn enable(&self, runtime: &mut Runtime, name: &str, policy: &omnilang_core::ast::Policy) -> Result<(), i32> {
+		if self.entries.is_none() && self.file.is_none() {
+			return Ok(());
+		}
+		let capacity = match self.entries.as_deref().map(str::parse::<usize>) {
+			None => omnilang_core::cache::DEFAULT_CAPACITY,
+			Some(Ok(n)) if n > 0 => n,
+			Some(_) => {
+				error_println!("Error: --cache expects a positive number of entries");
+				return Err(1);
+			}
+		};
+		let mut cache = omnilang_core::cache::DecisionCache::new(capacity);
+		if let Some(path) = &self.file {
+			cache = match cache.with_store(path) {
+				Ok(c) => c,
+				Err(e) => {
+					error_println!("Error: {}", e);
+					return Err(1);
+				}
+			};
+		}
+		runtime.enable_cache(cache);
+		if let Err(e) = runtime.bind_cached_policy(name, policy) {
+			error_println!("Error: {}", e);
+			return Err(1);
+		}
+		Ok(())
+	}
+}
+
+/// Tambahkan keputusan ke snapshot `--metrics-file`; kegagalan hanya diperingatkan.
+fn persist_metrics(path: Option<&String>, label: &str, decision: &omnilang_core::runtime::Decision, latency: std::time::Duration) {
+	let Some(path) = path else {
+		return;
+	};
+	if let Err(e) = MetricsRegistry::update(path, |registry| registry.record(label, decision, latency)) {
+		eprintln!("Metrics Warning: {}", e);
+	}
+}
+
+/// Jalankan endpoint `/metrics` di `0.0.0.0:<port>`.
+fn serve_metrics(port: &str, source: MetricsSource) -> Result<(), i32> {
+	let Ok(port) = port.parse::<u16>() else {
+		error_println!("Error: --metrics-port expects a port number, got '{}'", port);
+		return Err(1);
+	};
+	match omnilang_core::metrics::spawn_http(&format!("0.0.0.0:{}", port), source) {
+		Ok(addr) => {
+			eprintln!("metrics: serving http://{}/metrics", addr);
+			Ok(())
+		}
+		Err(e) => {
+			error_println!("Error: {}", e);
+			Err(1)
+		}
+	}
+}
+
+/// Kunci HMAC audit dari file `--audit-key`/`--key`; spasi di awal/akhir diabaikan.
+fn read_audit_key(path: Option<&String>) -> Result<Option<String>, i32> {
+	let Some(path) = path else {
+		return Ok(None);
+	};
+	match fs::read_to_string(path) {
+		Ok(key) if !key.trim().is_empty() => Ok(Some(key.trim().to_string())),
+		Ok(_) => {
+			error_println!("Error: Audit key file {} is empty", path);
+			Err(1)
+		}
+		Err(e) => {
+			error_println!("Error reading audit key {}: {}", path, e);
+			Err(1)
+		}
+	}
+}
+
+/// Buka log audit `--audit-log`, dengan kunci HMAC opsional.
+fn open_audit_log(path: &str, key_path: Option<&String>) -> Result<omnilang_core::audit::AuditLog, i32> {
+	let key = read_audit_key(key_path)?;
+	omnilang_core::audit::AuditLog::open(path, key.as_deref()).map_err(|e| {
+		error_println!("Audit Error: {}", e);
+		1
+	})
+}
+
+/// Tolak konteks yang melanggar `SCHEMA:` sebelum kebijakan dieksekusi.
+fn context_matches_schema(runtime: &Runtime, policy: &omnilang_core::ast::Policy) -> bool {
+	match runtime.validate_context(&policy.schema) {
+		Ok(()) => true,
+		Err(violations) => {
+			for violation in violations {
+				error_println!("Schema Error: {}", violation);
+			}
+			false
+		}
+	}
 }
 
 fn handle_compile(args: &[String]) -> i32 {
@@ -198,14 +536,46 @@ fn handle_compile(args: &[String]) -> i32 {
 			return 1;
 		}
 	};
+	let policy = match resolve_composition(policy, &args[0], None) {
+		Ok(p) => p,
+		Err(code) => return code,
+	};
 
-	let mut target = CompileTarget::Ir;
-	if args.len() > 2 && args[1] == "--target" && args[2] == "wasm" {
-		target = CompileTarget::Wasm;
+	let mut target = "ir";
+	let mut out_path = None;
+	let mut key_path = None;
+	let mut i = 1;
+	while i < args.len() {
+		if args[i] == "--target" && i + 1 < args.len() {
+			target = args[i + 1].as_str();
+			i += 2;
+		} else if args[i] == "--output" && i + 1 < args.len() {
+			out_path = Some(args[i + 1].clone());
+			i += 2;
+		} else if args[i] == "--key" && i + 1 < args.len() {
+			key_path = Some(&args[i + 1]);
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	let target = match target {
+		"ir" | "json" => CompileTarget::Ir,
+		"wasm" => CompileTarget::Wasm,
+		"native" => CompileTarget::Native,
+		"bundle" => return write_bundle(&policy, &args[0], out_path, key_path),
+		other => {
+			println!("Error: Unknown target '{}' (expected ir, json, wasm, native or bundle)", other);
+			return 1;
+		}
+	};
+	if key_path.is_some() {
+		println!("Error: --key only applies to --target bundle");
+		return 1;
 	}
 
-    let out_path = "output.bin";
-	match emit(&policy, target, out_path) {
+	let out_path = out_path.unwrap_or_else(|| "output.bin".to_string());
+	match emit(&policy, target, &out_path) {
 		Ok(_) => {
 			println!("Compilation success. Output written to {}", out_path);
 			0
@@ -217,12 +587,243 @@ fn handle_compile(args: &[String]) -> i32 {
 	}
 }
 
+/// `compile --target bundle`: tulis bundle (bawaan `<file>.bundle.json`), ditandatangani jika ada `--key`.
+fn write_bundle(policy: &omnilang_core::ast::Policy, file_path: &str, out_path: Option<String>, key_path: Option<&String>) -> i32 {
+	let mut bundle = match PolicyBundle::build(policy, std::path::Path::new(file_path)) {
+		Ok(b) => b,
+		Err(e) => {
+			println!("Compilation Error: {}", e);
+			return 1;
+		}
+	};
+	if let Some(key_path) = key_path {
+		let key = match load_signing_keys(&[key_path]) {
+			Ok(mut keys) => keys.remove(0),
+			Err(code) => return code,
+		};
+		if let Err(e) = bundle.sign(&key) {
+			println!("Compilation Error: {}", e);
+			return 1;
+		}
+	}
+	let out_path = out_path.unwrap_or_else(|| std::path::Path::new(file_path).with_extension("bundle.json").display().to_string());
+	let written = bundle.to_json().and_then(|json| fs::write(&out_path, json + "\n").map_err(|e| e.to_string()));
+	match written {
+		Ok(()) => {
+			println!("Compilation success. Bundle written to {} (IR sha256 {})", out_path, bundle.ir_sha256);
+			0
+		}
+		Err(e) => {
+			println!("Compilation Error: {}", e);
+			1
+		}
+	}
+}
+
+fn handle_conformance(args: &[String]) -> i32 {
+	let mut paths = Vec::new();
+	let mut options = omnilang_core::conformance::ConformanceOptions::default();
+	let mut format = "text";
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--engines" && i + 1 < args.len() {
+			let engines: Result<Vec<_>, _> = args[i + 1].split(',').map(|e| omnilang_core::conformance::Engine::parse(e.trim())).collect();
+			match engines {
+				Ok(engines) if !engines.is_empty() => options.engines = engines,
+				Ok(_) => {}
+				Err(e) => {
+					println!("Error: {}", e);
+					return 1;
+				}
+			}
+			i += 2;
+		} else if args[i] == "--context" && i + 1 < args.len() {
+			let context = fs::read_to_string(&args[i + 1]).map_err(|e| e.to_string()).and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()));
+			match context {
+				Ok(context) => options.contexts.push((args[i + 1].clone(), context)),
+				Err(e) => {
+					println!("Error: Could not load context {}: {}", args[i + 1], e);
+					return 1;
+				}
+			}
+			i += 2;
+		} else if (args[i] == "--random" || args[i] == "--seed") && i + 1 < args.len() {
+			let Ok(n) = args[i + 1].parse::<u64>() else {
+				println!("Error: {} expects a number, got '{}'", args[i], args[i + 1]);
+				return 1;
+			};
+			if args[i] == "--random" {
+				options.random_contexts = n as usize;
+			} else {
+				options.seed = n;
+			}
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else if args[i].starts_with("--") {
+			i += 1;
+		} else {
+			paths.push(std::path::PathBuf::from(&args[i]));
+			i += 1;
+		}
+	}
+	if format != "text" && format != "json" {
+		println!("Error: Unknown format '{}' (expected text or json)", format);
+		return 1;
+	}
+	if paths.is_empty() {
+		paths.push(std::path::PathBuf::from("examples"));
+	}
+
+	let report = match omnilang_core::conformance::run(&paths, &options) {
+		Ok(r) => r,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	if format == "json" {
+		println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+	} else {
+		print!("{}", omnilang_core::conformance::render_text(&report));
+	}
+	if report.is_clean() { 0 } else { 1 }
+}
+
+fn handle_run_bundle(args: &[String]) -> i32 {
+	if args.is_empty() {
+		println!("Error: Usage: omnilang run-bundle <bundle.json> [--context <context.json>] [--format <text|json>] [--info]");
+		return 1;
+	}
+	let mut context_path = None;
+	let mut format = "text";
+	let mut info = false;
+	let mut metrics_path = None;
+	let mut require_signature = false;
+	let mut key_paths = Vec::new();
+	let mut i = 1;
+	while i < args.len() {
+		if args[i] == "--context" && i + 1 < args.len() {
+			context_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else if args[i] == "--info" {
+			info = true;
+			i += 1;
+		} else if args[i] == "--metrics-file" && i + 1 < args.len() {
+			metrics_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--require-signature" {
+			require_signature = true;
+			i += 1;
+		} else if args[i] == "--key" && i + 1 < args.len() {
+			key_paths.push(&args[i + 1]);
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	if format != "text" && format != "json" {
+		println!("Error: Unknown format '{}' (expected text or json)", format);
+		return 1;
+	}
+	if require_signature && key_paths.is_empty() {
+		println!("Error: --require-signature needs at least one --key <key-file>");
+		return 1;
+	}
+	let keys = match load_signing_keys(&key_paths) {
+		Ok(keys) => keys,
+		Err(code) => return code,
+	};
+
+	let bundle = match PolicyBundle::load(&args[0], require_signature.then_some(keys.as_slice())) {
+		Ok(b) => b,
+		Err(e) => {
+			println!("Bundle Error: {}", e);
+			return 1;
+		}
+	};
+	if info {
+		println!("Bundle: {}", args[0]);
+		println!("  Intent: {}", bundle.intent.as_deref().unwrap_or("-"));
+		println!("  Format: {} v{} (IR v{}), compiler {}", bundle.format, bundle.format_version, bundle.ir_version, bundle.compiler_version);
+		println!("  IR sha256: {}", bundle.ir_sha256);
+		for source in &bundle.sources {
+			println!("  Source: {} {}", source.sha256, source.file);
+		}
+		let fields: Vec<&str> = bundle.schema.iter().map(|f| f.field.as_str()).collect();
+		println!("  Schema: {}", if fields.is_empty() { "-".to_string() } else { fields.join(", ") });
+		match &bundle.signature {
+			Some(signature) => println!("  Signed: key {}{}", signature.key_id, if require_signature { " (verified)" } else { "" }),
+			None => println!("  Signed: no"),
+		}
+		return 0;
+	}
+
+	let context = match context_path {
+		Some(path) => match fs::read_to_string(path)
+			.map_err(|e| e.to_string())
+			.and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()))
+		{
+			Ok(value) => value,
+			Err(e) => {
+				println!("Error reading context: {}", e);
+				return 1;
+			}
+		},
+		None => serde_json::json!({}),
+	};
+	if let Err(violations) = bundle.validate_context(&context) {
+		for violation in violations {
+			println!("Schema Error: {}", violation);
+		}
+		return 1;
+	}
+
+	let now_ms = std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|d| d.as_millis() as u64)
+		.unwrap_or_default();
+	let started = std::time::Instant::now();
+	let decision = bundle.execute(context, &mut omnilang_core::temporal::TemporalState::new(), now_ms);
+	let label = omnilang_core::metrics::policy_label(bundle.intent.as_deref(), &args[0]);
+	persist_metrics(metrics_path, &label, &decision, started.elapsed());
+	if format == "json" {
+		println!("{}", serde_json::to_string_pretty(&decision).unwrap_or_default());
+	} else {
+		print_decision(&decision);
+	}
+	0
+}
+
 fn handle_lint(args: &[String]) -> i32 {
 	if args.is_empty() {
 		println!("Error: No policy file specified.");
 		return 1;
 	}
 
+	let mut context_path = None;
+	let mut format = "text";
+	let mut i = 1;
+	while i < args.len() {
+		if args[i] == "--context" && i + 1 < args.len() {
+			context_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	if !matches!(format, "text" | "json" | "sarif") {
+		println!("Error: Unknown format '{}' (expected text, json or sarif)", format);
+		return 1;
+	}
+
 	let source = match fs::read_to_string(&args[0]) {
 		Ok(s) => s,
 		Err(e) => {
@@ -246,17 +847,72 @@ fn handle_lint(args: &[String]) -> i32 {
 			return 1;
 		}
 	};
+	let policy = match resolve_composition(policy, &args[0], None) {
+		Ok(p) => p,
+		Err(code) => return code,
+	};
+
+	let mut context = None;
+	if let Some(path) = context_path {
+		let parsed = fs::read_to_string(path)
+			.map_err(|e| e.to_string())
+			.and_then(|c| serde_json::from_str::<serde_json::Value>(&c).map_err(|e| e.to_string()));
+		match parsed {
+			Ok(value) => context = Some(value),
+			Err(e) => {
+				println!("Error reading context: {}", e);
+				return 1;
+			}
+		}
+	}
 
 	let linter = Linter::new();
-	let result = linter.lint_policy(&policy, None);
-	let issues = result.findings;
+	let result = linter.lint_policy(&policy, context.as_ref());
+	// Temuan Error membuat lint gagal (exit 1) di semua format
+	let code = if result.has_errors { 1 } else { 0 };
 
-	if issues.is_empty() {
-		println!("No issues found. Policy is clean.");
-	} else {
-		for issue in issues {
-			println!("[{:?}] {}", issue.severity, issue.message);
+	match format {
+		"sarif" => println!("{}", serde_json::to_string_pretty(&result.to_sarif(&args[0])).unwrap_or_default()),
+		"json" => println!("{}", serde_json::to_string_pretty(&result.findings).unwrap_or_default()),
+		_ if result.findings.is_empty() => println!("No issues found. Policy is clean."),
+		_ => {
+			for issue in &result.findings {
+				println!("[{:?}] {}", issue.severity, issue.message);
+				if let Some(witness) = &issue.witness {
+					println!("    witness: {}", witness);
+				}
+			}
+		}
+	}
+	code
+}
+
+fn handle_schema(args: &[String]) -> i32 {
+	if args.is_empty() {
+		println!("Error: No policy file specified.");
+		return 1;
+	}
+	let policy = match omnilang_core::compose::load_policy(&args[0]) {
+		Ok(p) => p,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	if policy.schema.is_empty() {
+		println!("Error: Policy has no SCHEMA section.");
+		return 1;
+	}
+
+	let schema = serde_json::to_string_pretty(&omnilang_core::schema::to_json_schema(&policy)).unwrap_or_default();
+	if args.len() > 2 && args[1] == "--output" {
+		if let Err(e) = fs::write(&args[2], schema + "\n") {
+			println!("Error writing schema: {}", e);
+			return 1;
 		}
+		println!("JSON Schema written to {}", args[2]);
+	} else {
+		println!("{}", schema);
 	}
 	0
 }
@@ -264,20 +920,424 @@ fn handle_lint(args: &[String]) -> i32 {
 use omnilang_core::checker::Checker;
 use omnilang_core::program_evaluator::ProgramEvaluator;
 
-fn handle_test(args: &[String]) -> i32 {
+fn handle_explain(args: &[String]) -> i32 {
 	if args.is_empty() {
-		println!("Error: No test file specified.");
+		println!("Error: No policy file specified.");
 		return 1;
 	}
 
-    let mut has_failure = false;
+	let mut context_path = None;
+	let mut config_path = None;
+	let mut format = "text";
+	let mut i = 1;
+	while i < args.len() {
+		if args[i] == "--context" && i + 1 < args.len() {
+			context_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--config" && i + 1 < args.len() {
+			config_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	if format != "text" && format != "json" {
+		println!("Error: Unknown format '{}' (expected text or json)", format);
+		return 1;
+	}
+
+	let source = match fs::read_to_string(&args[0]) {
+		Ok(s) => s,
+		Err(e) => {
+			println!("Error reading file: {}", e);
+			return 1;
+		}
+	};
+	let tokens = match Lexer::new(&source).tokenize() {
+		Ok(t) => t,
+		Err(e) => {
+			println!("Lexer Error: {}", e);
+			return 1;
+		}
+	};
+	let policy = match Parser::new(tokens).parse_policy() {
+		Ok(p) => p,
+		Err(e) => {
+			println!("Policy Parser Error: {}", e);
+			return 1;
+		}
+	};
+	let policy = match resolve_composition(policy, &args[0], None) {
+		Ok(p) => p,
+		Err(code) => return code,
+	};
+
+	let mut runtime = match runtime_with_config(config_path) {
+		Ok(r) => r,
+		Err(code) => return code,
+	};
+	if let Some(cp) = context_path {
+		if let Err(e) = runtime.load_context_from_file(cp) {
+			println!("Warning: Could not load context: {}", e);
+		} else if !context_matches_schema(&runtime, &policy) {
+			return 1;
+		}
+	}
+	let decision = runtime.execute_policy(&policy);
+
+	if format == "json" {
+		let mut report = serde_json::json!({
+			"actions": decision.actions,
+			"explanations": decision.explanations,
+		});
+		if !decision.truncations.is_empty() {
+			report["truncations"] = serde_json::json!(decision.truncations);
+		}
+		if !decision.violations.is_empty() {
+			report["violations"] = serde_json::json!(decision.violations);
+		}
+		if !decision.unauthorized.is_empty() {
+			report["unauthorized"] = serde_json::json!(decision.unauthorized);
+		}
+		println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+	} else {
+		print!("{}", omnilang_core::explain::render_text(&decision.explanations));
+		for truncation in &decision.truncations {
+			println!("Truncated: {}", truncation);
+		}
+		for violation in &decision.violations {
+			println!("Constraint: {}", violation);
+		}
+		for failure in &decision.unauthorized {
+			println!("Unauthorized: {}", failure);
+		}
+	}
+	0
+}
+
+fn handle_simulate(args: &[String]) -> i32 {
+	if args.len() < 2 {
+		println!("Error: Usage: omnilang simulate <file.omni> <series.jsonl|csv>");
+		return 1;
+	}
+
+	let mut context_path = None;
+	let mut config_path = None;
+	let mut interval_ms = 1_000u64;
+	let mut format = "text";
+	let mut i = 2;
+	while i < args.len() {
+		if args[i] == "--context" && i + 1 < args.len() {
+			context_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--config" && i + 1 < args.len() {
+			config_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--interval" && i + 1 < args.len() {
+			match args[i + 1].parse() {
+				Ok(ms) => interval_ms = ms,
+				Err(_) => {
+					println!("Error: --interval expects milliseconds, got '{}'", args[i + 1]);
+					return 1;
+				}
+			}
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	if format != "text" && format != "json" {
+		println!("Error: Unknown format '{}' (expected text or json)", format);
+		return 1;
+	}
 
-    for file_path in args {
+	let policy = match omnilang_core::compose::load_policy(&args[0]) {
+		Ok(p) => p,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	let config = match config_path.map(|p| RuntimeConfig::from_file(p)).transpose() {
+		Ok(c) => c.unwrap_or_default(),
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	let mut base = Runtime::new();
+	if let Some(cp) = context_path {
+		if let Err(e) = base.load_context_from_file(cp) {
+			println!("Error: Could not load context: {}", e);
+			return 1;
+		}
+	}
+	let ticks = match omnilang_core::simulate::load_series(&args[1], interval_ms) {
+		Ok(t) => t,
+		Err(e) => {
+			println!("Error: {}: {}", args[1], e);
+			return 1;
+		}
+	};
+
+	let simulation = omnilang_core::simulate::simulate(&policy, base.context_snapshot(), &ticks, config);
+	if format == "json" {
+		println!("{}", serde_json::to_string_pretty(&simulation).unwrap_or_default());
+	} else {
+		print!("{}", omnilang_core::simulate::render_text(&simulation));
+	}
+	0
+}
+
+fn handle_watch(args: &[String]) -> i32 {
+	if args.is_empty() {
+		println!("Error: Usage: omnilang watch <file.omni> [--changes-only] [--socket <path>]");
+		return 1;
+	}
+
+	let mut context_path = None;
+	let mut config_path = None;
+	let mut socket_path = None;
+	let mut options = omnilang_core::watch::WatchOptions::default();
+	let mut audit_path = None;
+	let mut audit_key = None;
+	let mut cache = CacheOptions::default();
+	let mut metrics_path = None;
+	let mut metrics_port = None;
+	let mut i = 1;
+	while i < args.len() {
+		if args[i] == "--context" && i + 1 < args.len() {
+			context_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--config" && i + 1 < args.len() {
+			config_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--socket" && i + 1 < args.len() {
+			socket_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--changes-only" {
+			options.changes_only = true;
+			i += 1;
+		} else if args[i] == "--audit-log" && i + 1 < args.len() {
+			audit_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--audit-key" && i + 1 < args.len() {
+			audit_key = Some(&args[i + 1]);
+			i += 2;
+		} else if cache.parse_flag(args, i) {
+			i += 2;
+		} else if args[i] == "--metrics-file" && i + 1 < args.len() {
+			metrics_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--metrics-port" && i + 1 < args.len() {
+			metrics_port = Some(&args[i + 1]);
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+
+	let policy = match omnilang_core::compose::load_policy(&args[0]) {
+		Ok(p) => p,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	let mut runtime = match runtime_with_config(config_path) {
+		Ok(r) => r,
+		Err(code) => return code,
+	};
+	if let Some(cp) = context_path {
+		if let Err(e) = runtime.load_context_from_file(cp) {
+			println!("Error: Could not load context: {}", e);
+			return 1;
+		}
+		if !context_matches_schema(&runtime, &policy) {
+			return 1;
+		}
+	}
+	if let Err(code) = cache.enable(&mut runtime, &args[0], &policy) {
+		return code;
+	}
+
+	let mut watcher = omnilang_core::watch::Watcher::new(&policy, runtime, options);
+	if let Some(path) = audit_path {
+		match open_audit_log(path, audit_key) {
+			Ok(log) => watcher = watcher.with_audit(log),
+			Err(code) => return code,
+		}
+	}
+	let mut metrics = None;
+	let mut snapshot_writer = None;
+	if metrics_path.is_some() || metrics_port.is_some() {
+		let registry = match metrics_path.map(MetricsRegistry::load).transpose() {
+			Ok(registry) => registry.unwrap_or_default(),
+			Err(e) => {
+				println!("Error: {}", e);
+				return 1;
+			}
+		};
+		snapshot_writer = metrics_path.map(|path| std::sync::Arc::new(std::sync::Mutex::new(SnapshotWriter::new(path, registry.clone()))));
+		let shared = std::sync::Arc::new(std::sync::Mutex::new(registry));
+		let label = omnilang_core::metrics::policy_label(policy.intent.as_deref(), &args[0]);
+		watcher = watcher.with_metrics(shared.clone(), label);
+		if let Some(port) = metrics_port {
+			if let Err(code) = serve_metrics(port, MetricsSource::Live(shared.clone())) {
+				return code;
+			}
+		}
+		if let Some(writer) = &snapshot_writer {
+			omnilang_core::metrics::spawn_snapshot_writer(shared.clone(), writer.clone(), std::time::Duration::from_secs(10));
+		}
+		metrics = Some(shared);
+	}
+	let code = match socket_path {
+		Some(path) => watch_socket(&mut watcher, path),
+		None => {
+			let stdin = std::io::stdin();
+			let stdout = std::io::stdout();
+			match watcher.run(stdin.lock(), &mut stdout.lock()) {
+				Ok(()) => 0,
+				// Pembaca output sudah berhenti; bukan kesalahan kebijakan
+				Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => 0,
+				Err(e) => {
+					eprintln!("watch: {}", e);
+					1
+				}
+			}
+		}
+	};
+	if let (Some(writer), Some(shared)) = (&snapshot_writer, &metrics) {
+		let snapshot = shared.lock().unwrap_or_else(|e| e.into_inner()).clone();
+		if let Err(e) = writer.lock().unwrap_or_else(|e| e.into_inner()).flush(&snapshot) {
+			eprintln!("watch: {}", e);
+		}
+	}
+	let stats = &watcher.stats;
+	eprintln!("watch: {} patches, {} decisions, {} errors", stats.patches, stats.emitted, stats.errors);
+	if let Some(cache) = watcher.runtime().cache_stats() {
+		eprintln!(
+			"watch: cache {} hits ({} memory, {} disk), {} misses, {} bypassed, {} evictions",
+			cache.hits, cache.memory_hits, cache.disk_hits, cache.misses, cache.bypassed, cache.evictions
+		);
+	}
+	code
+}
+
+/// Layani satu koneksi per waktu; keputusan ditulis kembali ke koneksi yang mengirim patch.
+#[cfg(unix)]
+fn watch_socket(watcher: &mut omnilang_core::watch::Watcher, path: &str) -> i32 {
+	use std::os::unix::fs::FileTypeExt;
+	use std::os::unix::net::UnixListener;
+
+	// Socket sisa proses sebelumnya boleh diganti, file lain tidak
+	if let Ok(meta) = fs::symlink_metadata(path) {
+		if meta.file_type().is_socket() {
+			let _ = fs::remove_file(path);
+		}
+	}
+	let listener = match UnixListener::bind(path) {
+		Ok(l) => l,
+		Err(e) => {
+			println!("Error: cannot listen on '{}': {}", path, e);
+			return 1;
+		}
+	};
+	eprintln!("watch: listening on {}", path);
+	for stream in listener.incoming() {
+		let result = stream.and_then(|stream| {
+			let reader = std::io::BufReader::new(stream.try_clone()?);
+			let mut writer = stream;
+			watcher.run(reader, &mut writer)
+		});
+		if let Err(e) = result {
+			eprintln!("watch: connection closed: {}", e);
+		}
+	}
+	0
+}
+
+#[cfg(not(unix))]
+fn watch_socket(_watcher: &mut omnilang_core::watch::Watcher, path: &str) -> i32 {
+	println!("Error: --socket '{}' requires Unix domain sockets, which this platform does not support", path);
+	1
+}
+
+fn handle_test(args: &[String]) -> i32 {
+	let mut files = Vec::new();
+	let mut config = RuntimeConfig::default();
+	let mut format = "text";
+	let mut coverage_requested = false;
+	let mut coverage_format = "text";
+	let mut coverage_output: Option<&String> = None;
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--config" && i + 1 < args.len() {
+			match RuntimeConfig::from_file(&args[i + 1]) {
+				Ok(c) => config = c,
+				Err(e) => {
+					println!("Error: {}", e);
+					return 1;
+				}
+			}
+			i += 2;
+		} else if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else if args[i] == "--coverage" {
+			coverage_requested = true;
+			i += 1;
+		} else if args[i] == "--coverage-format" && i + 1 < args.len() {
+			coverage_requested = true;
+			coverage_format = args[i + 1].as_str();
+			i += 2;
+		} else if args[i] == "--coverage-output" && i + 1 < args.len() {
+			coverage_requested = true;
+			coverage_output = Some(&args[i + 1]);
+			i += 2;
+		} else {
+			files.push(&args[i]);
+			i += 1;
+		}
+	}
+	if files.is_empty() {
+		println!("Error: No test file specified.");
+		return 1;
+	}
+	if format != "text" && format != "junit" {
+		println!("Error: Unknown format '{}' (expected text or junit)", format);
+		return 1;
+	}
+	if !matches!(coverage_format, "text" | "json" | "lcov") {
+		println!("Error: Unknown coverage format '{}' (expected text, json or lcov)", coverage_format);
+		return 1;
+	}
+	let junit = format == "junit";
+	if junit && coverage_requested && coverage_output.is_none() {
+		println!("Error: --format junit writes XML to stdout; use --coverage-output for the coverage report");
+		return 1;
+	}
+	let mut coverage: Option<Vec<(String, Coverage)>> = coverage_requested.then(Vec::new);
+
+    let mut suites = Vec::new();
+    let mut has_failure = false;
+
+    for file_path in files {
+        let fail = |message: String| {
+            report(junit, &message);
+            SuiteReport::single(file_path, Err(message))
+        };
         let source = match fs::read_to_string(file_path) {
             Ok(s) => s,
             Err(e) => {
-                println!("Error reading file {}: {}", file_path, e);
-                has_failure = true;
+                suites.push(fail(format!("Error reading file {}: {}", file_path, e)));
                 continue;
             }
         };
@@ -286,19 +1346,23 @@ fn handle_test(args: &[String]) -> i32 {
         let tokens = match lexer.tokenize() {
             Ok(t) => t,
             Err(e) => {
-                println!("Lexer Error in {}: {}", file_path, e);
-                has_failure = true;
+                suites.push(fail(format!("Lexer Error in {}: {}", file_path, e)));
                 continue;
             }
         };
 
+        // Kebijakan deklaratif diuji lewat kasus EXPECT:/TEST: dan file pendamping
+        if is_policy_source(&tokens) {
+            suites.push(run_policy_tests(file_path, tokens, &config, coverage.as_mut(), junit));
+            continue;
+        }
+
         let mut parser = Parser::new(tokens);
         // Tests are treated as Programs (scripts) allowing full language features like modules/functions/assertions
         let program = match parser.parse_program() {
             Ok(p) => p,
             Err(e) => {
-                println!("Parser Error in {} (Evaluator Test Mode): {}", file_path, e);
-                has_failure = true;
+                suites.push(fail(format!("Parser Error in {} (Evaluator Test Mode): {}", file_path, e)));
                 continue;
             }
         };
@@ -306,36 +1370,392 @@ fn handle_test(args: &[String]) -> i32 {
         // Type Check
         let mut checker = Checker::new();
         if let Err(errors) = checker.check_program(&program) {
-            println!("Type Check FAILED: {}", file_path);
+            let mut message = format!("Type Check FAILED: {}", file_path);
             for err in errors {
-                println!("  - {}", err);
+                message.push_str(&format!("\n  - {}", err));
             }
-            has_failure = true;
+            suites.push(fail(message));
             continue;
         }
 
         let mut evaluator = ProgramEvaluator::new();
         match evaluator.evaluate_program(&program) {
             Ok(_) => {
-                println!("Test PASSED: {}", file_path);
+                report(junit, &format!("Test PASSED: {}", file_path));
+                suites.push(SuiteReport::single(file_path, Ok(())));
             }
             Err(e) => {
-                println!("Test FAILED: {}", file_path);
-                println!("  Reason: {}", e);
-                has_failure = true;
+                suites.push(fail(format!("Test FAILED: {}\n  Reason: {}", file_path, e)));
             }
         }
     }
+    if suites.iter().any(|s| !s.passed()) {
+        has_failure = true;
+    }
+
+	if let Some(reports) = &coverage {
+		let rendered = render_coverage(reports, coverage_format);
+		match coverage_output {
+			Some(path) => match fs::write(path, rendered) {
+				Ok(()) => report(junit, &format!("Coverage report written to {}", path)),
+				Err(e) => {
+					report(junit, &format!("Error writing coverage report {}: {}", path, e));
+					has_failure = true;
+				}
+			},
+			None => print!("{}", rendered),
+		}
+	}
+	if junit {
+		print!("{}", omnilang_core::testing::render_junit(&suites));
+	}
 
 	if has_failure { 1 } else { 0 }
 }
 
-fn handle_metrics() -> i32 {
-	println!("Global Policy Metrics:");
-	// In-memory stats for demo
-	println!("Total Evaluated: 12");
-	println!("Success Rate: 100%");
-	println!("Avg Latency: 1.2ms");
+/// Pesan `omnilang test`; dengan `--format junit` ke stderr agar stdout hanya berisi XML.
+fn report(junit: bool, message: &str) {
+	if junit {
+		eprintln!("{}", message);
+	} else {
+		println!("{}", message);
+	}
+}
+
+/// Laporan cakupan per file kebijakan; JSON dikunci dengan path file.
+fn render_coverage(reports: &[(String, Coverage)], format: &str) -> String {
+	match format {
+		"json" => {
+			let by_file: std::collections::BTreeMap<_, _> = reports.iter().map(|(file, c)| (file, c)).collect();
+			serde_json::to_string_pretty(&by_file).unwrap_or_default() + "\n"
+		}
+		"lcov" => reports.iter().map(|(file, c)| coverage::render_lcov(c, file)).collect(),
+		_ => reports
+			.iter()
+			.map(|(file, c)| format!("Coverage report: {}\n{}", file, coverage::render_text(c)))
+			.collect(),
+	}
+}
+
+/// File diawali section kebijakan (`INTENT:`, `RULE:`, ...), bukan modul/fungsi Program.
+fn is_policy_source(tokens: &[omnilang_core::lexer::Token]) -> bool {
+	use omnilang_core::lexer::TokenType;
+	matches!(
+		tokens.first().map(|t| &t.token_type),
+		Some(
+			TokenType::Intent
+				| TokenType::Actor
+				| TokenType::Context
+				| TokenType::Assumption
+				| TokenType::Rule
+				| TokenType::Constraint
+				| TokenType::Units
+				| TokenType::Schema
+				| TokenType::Guard
+				| TokenType::Expect
+				| TokenType::Combine
+				| TokenType::Imports
+				| TokenType::Extend
+				| TokenType::Capability
+		)
+	)
+}
+
+/// Jalankan kasus uji kebijakan satu file; ringkasan teks dicetak kecuali untuk `--format junit`.
+fn run_policy_tests(
+	file_path: &str,
+	tokens: Vec<omnilang_core::lexer::Token>,
+	config: &RuntimeConfig,
+	coverage: Option<&mut Vec<(String, Coverage)>>,
+	junit: bool,
+) -> SuiteReport {
+	let fail = |message: String| {
+		report(junit, &message);
+		SuiteReport::single(file_path, Err(message))
+	};
+	let path = std::path::Path::new(file_path);
+	let policy = match Parser::new(tokens).parse_policy() {
+		Ok(p) => p,
+		Err(e) => return fail(format!("Parser Error in {}: {}", file_path, e)),
+	};
+	let policy = if policy.composition.is_empty() {
+		policy
+	} else {
+		match omnilang_core::compose::resolve_policy(policy, path) {
+			Ok(p) => p,
+			Err(e) => return fail(format!("Composition Error: {}", e)),
+		}
+	};
+	let cases = match omnilang_core::testing::collect_cases(&policy, path) {
+		Ok(cases) => cases,
+		Err(e) => return fail(format!("Error: {}", e)),
+	};
+	if cases.is_empty() {
+		report(
+			junit,
+			&format!(
+				"No test cases in {} (add an EXPECT: section or {})",
+				file_path,
+				omnilang_core::testing::sidecar_path(path).display()
+			),
+		);
+		return SuiteReport { name: file_path.to_string(), ..Default::default() };
+	}
+
+	let base_dir = path.parent().unwrap_or_else(|| std::path::Path::new(""));
+	let results: Vec<_> = match coverage {
+		Some(reports) => {
+			let mut file_coverage = Coverage::new(&policy);
+			let results = cases
+				.iter()
+				.map(|case| omnilang_core::testing::run_case_with_coverage(&policy, case, base_dir, config, &mut file_coverage))
+				.collect();
+			reports.push((file_path.to_string(), file_coverage));
+			results
+		}
+		None => cases
+			.iter()
+			.map(|case| omnilang_core::testing::run_case(&policy, case, base_dir, config))
+			.collect(),
+	};
+	if !junit {
+		println!("Policy tests: {} ({} cases)", file_path, results.len());
+		print!("{}", omnilang_core::testing::render_text(&results));
+	}
+	SuiteReport { name: file_path.to_string(), cases: results, error: None }
+}
+
+fn handle_audit(args: &[String]) -> i32 {
+	if args.len() < 2 || args[0] != "verify" {
+		println!("Error: Usage: omnilang audit verify <audit.jsonl> [--key <key-file>]");
+		return 1;
+	}
+	let mut key_path = None;
+	let mut i = 2;
+	while i < args.len() {
+		if args[i] == "--key" && i + 1 < args.len() {
+			key_path = Some(&args[i + 1]);
+			i += 2;
+		} else {
+			i += 1;
+		}
+	}
+	let key = match read_audit_key(key_path) {
+		Ok(key) => key,
+		Err(code) => return code,
+	};
+
+	let report = match omnilang_core::audit::verify(&args[1], key.as_deref()) {
+		Ok(report) => report,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	if report.is_valid() {
+		println!("Audit log OK: {} records, head {}", report.records, report.head);
+		if key.is_none() {
+			println!("Note: HMAC signatures not checked (pass --key to verify them)");
+		}
+		return 0;
+	}
+	println!("Audit log INVALID: {} ({} records read)", args[1], report.records);
+	for issue in &report.issues {
+		println!("  {}", issue);
+	}
+	1
+}
+
+/// Kunci dari setiap `--key <key-file>`.
+fn load_signing_keys(key_paths: &[&String]) -> Result<Vec<SigningKey>, i32> {
+	key_paths
+		.iter()
+		.map(SigningKey::from_file)
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| {
+			error_println!("Error: {}", e);
+			1
+		})
+}
+
+/// File yang harus ditandatangani untuk menjalankan `file_path`: file itu sendiri,
+/// ditambah file `IMPORT:`/`EXTEND:` jika berupa kebijakan.
+fn signed_sources(file_path: &str) -> Result<Vec<std::path::PathBuf>, String> {
+	let policy = fs::read_to_string(file_path)
+		.ok()
+		.and_then(|source| Lexer::new(&source).tokenize().ok())
+		.is_some_and(|tokens| is_policy_source(&tokens));
+	if policy {
+		omnilang_core::compose::source_files(file_path)
+	} else {
+		Ok(vec![std::path::PathBuf::from(file_path)])
+	}
+}
+
+/// `--require-signature`: tolak file (atau file komposisinya) yang tidak ditandatangani
+/// salah satu kunci `--key`, atau yang berubah setelah ditandatangani. Mengembalikan isi
+/// yang diverifikasi; pemanggil mem-parse isi ini, bukan membaca file lagi.
+fn check_signatures(file_path: &str, key_paths: &[&String]) -> Result<omnilang_core::compose::Sources, i32> {
+	if key_paths.is_empty() {
+		error_println!("Error: --require-signature needs at least one --key <key-file>");
+		return Err(1);
+	}
+	let keys = load_signing_keys(key_paths)?;
+	let read = |path: &std::path::Path| omnilang_core::security::read_verified(path, &keys);
+	let main_path = std::path::Path::new(file_path);
+	let sources = read(main_path).and_then(|source| {
+		let policy = Lexer::new(&source).tokenize().is_ok_and(|tokens| is_policy_source(&tokens));
+		if policy {
+			omnilang_core::compose::read_sources(main_path, source, read)
+		} else {
+			let mut sources = omnilang_core::compose::Sources::default();
+			sources.insert(main_path, source);
+			Ok(sources)
+		}
+	});
+	sources.map_err(|e| {
+		error_println!("Signature Error: {}", e);
+		1
+	})
+}
+
+fn handle_sign(args: &[String]) -> i32 {
+	if args.len() == 2 && args[0] == "--generate-key" {
+		let key = match SigningKey::generate() {
+			Ok(key) => key,
+			Err(e) => {
+				println!("Error: {}", e);
+				return 1;
+			}
+		};
+		if std::path::Path::new(&args[1]).exists() {
+			println!("Error: {} already exists", args[1]);
+			return 1;
+		}
+		if let Err(e) = fs::write(&args[1], format!("{}\n", key.secret())) {
+			println!("Error writing key {}: {}", args[1], e);
+			return 1;
+		}
+		// Kunci adalah rahasia bersama; hanya pemiliknya yang boleh membaca
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			let _ = fs::set_permissions(&args[1], fs::Permissions::from_mode(0o600));
+		}
+		println!("Key {} written to {}", key.id, args[1]);
+		return 0;
+	}
+
+	let mut files = Vec::new();
+	let mut key_path = None;
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--key" && i + 1 < args.len() {
+			key_path = Some(&args[i + 1]);
+			i += 2;
+		} else {
+			files.push(&args[i]);
+			i += 1;
+		}
+	}
+	let (Some(key_path), false) = (key_path, files.is_empty()) else {
+		println!("Error: Usage: omnilang sign <file>... --key <key-file>");
+		return 1;
+	};
+	let key = match load_signing_keys(&[key_path]) {
+		Ok(mut keys) => keys.remove(0),
+		Err(code) => return code,
+	};
+	for file in files {
+		match omnilang_core::security::sign_file(std::path::Path::new(file), &key) {
+			Ok(manifest) => println!("Signed {} (key {}, sha256 {})", file, manifest.key_id, manifest.sha256),
+			Err(e) => {
+				println!("Error: {}", e);
+				return 1;
+			}
+		}
+	}
+	0
+}
+
+fn handle_verify(args: &[String]) -> i32 {
+	let mut files = Vec::new();
+	let mut key_paths = Vec::new();
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--key" && i + 1 < args.len() {
+			key_paths.push(&args[i + 1]);
+			i += 2;
+		} else {
+			files.push(&args[i]);
+			i += 1;
+		}
+	}
+	if files.is_empty() || key_paths.is_empty() {
+		println!("Error: Usage: omnilang verify <file>... --key <key-file> [--key <key-file>]...");
+		return 1;
+	}
+	let keys = match load_signing_keys(&key_paths) {
+		Ok(keys) => keys,
+		Err(code) => return code,
+	};
+
+	let mut failed = false;
+	for file in files {
+		let sources = match signed_sources(file) {
+			Ok(sources) => sources,
+			Err(e) => {
+				println!("FAILED {}: {}", file, e);
+				failed = true;
+				continue;
+			}
+		};
+		for source in sources {
+			match omnilang_core::security::verify_signature(&source, &keys) {
+				Ok(manifest) => println!("OK {} (key {})", source.display(), manifest.key_id),
+				Err(e) => {
+					println!("FAILED {}: {}", source.display(), e);
+					failed = true;
+				}
+			}
+		}
+	}
+	if failed { 1 } else { 0 }
+}
+
+fn handle_metrics(args: &[String]) -> i32 {
+	let mut path = omnilang_core::metrics::DEFAULT_SNAPSHOT;
+	let mut format = "text";
+	let mut i = 0;
+	while i < args.len() {
+		if args[i] == "--format" && i + 1 < args.len() {
+			format = args[i + 1].as_str();
+			i += 2;
+		} else {
+			path = args[i].as_str();
+			i += 1;
+		}
+	}
+	if !std::path::Path::new(path).exists() {
+		println!("Error: no metrics snapshot at {} (record one with exec/watch --metrics-file)", path);
+		return 1;
+	}
+	let registry = match MetricsRegistry::load(path) {
+		Ok(r) => r,
+		Err(e) => {
+			println!("Error: {}", e);
+			return 1;
+		}
+	};
+	match format {
+		"text" => print!("{}", registry.render_text()),
+		"openmetrics" => print!("{}", registry.render_openmetrics()),
+		"json" => println!("{}", serde_json::to_string_pretty(&registry).unwrap_or_default()),
+		other => {
+			println!("Error: Unknown format '{}' (expected text, openmetrics or json)", other);
+			return 1;
+		}
+	}
 	0
 }
 
@@ -370,6 +1790,10 @@ fn handle_serve(args: &[String]) -> i32 {
 	let mut port = 8080;
 	let mut token: Option<String> = None;
 	let mut hui_port: Option<String> = None;
+	let mut require_signature = false;
+	let mut key_paths = Vec::new();
+	let mut metrics_port = None;
+	let mut metrics_path = None;
 
 	let mut i = 1;
 	while i < args.len() {
@@ -382,12 +1806,32 @@ fn handle_serve(args: &[String]) -> i32 {
 		} else if args[i] == "--hui" && i + 1 < args.len() {
 			hui_port = Some(args[i + 1].clone());
 			i += 2;
+		} else if args[i] == "--metrics-port" && i + 1 < args.len() {
+			metrics_port = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--metrics-file" && i + 1 < args.len() {
+			metrics_path = Some(&args[i + 1]);
+			i += 2;
+		} else if args[i] == "--require-signature" {
+			require_signature = true;
+			i += 1;
+		} else if args[i] == "--key" && i + 1 < args.len() {
+			key_paths.push(&args[i + 1]);
+			i += 2;
 		} else {
 			i += 1;
 		}
 	}
+	// Worker mesh hanya menjalankan file yang ditandatangani kunci tepercaya
+	let mut verified = None;
+	if require_signature {
+		match check_signatures(file_path, &key_paths) {
+			Ok(sources) => verified = Some(sources),
+			Err(code) => return code,
+		}
+	}
 
-	let source = match std::fs::read_to_string(file_path) {
+	let source = match read_source(file_path, verified.as_ref()) {
 		Ok(s) => s,
 		Err(e) => {
 			println!("Error reading file: {}", e);
@@ -426,6 +1870,14 @@ fn handle_serve(args: &[String]) -> i32 {
 		println!("Worker initialization side-effects (ignored): {}", e);
 	}
 
+	// Worker tidak mengevaluasi kebijakan; endpoint menyajikan snapshot dari exec/watch di node ini
+	if let Some(port) = metrics_port {
+		let path = metrics_path.map_or(omnilang_core::metrics::DEFAULT_SNAPSHOT, |p| p.as_str());
+		if let Err(code) = serve_metrics(port, MetricsSource::Snapshot(path.into())) {
+			return code;
+		}
+	}
+
 	use std::sync::{Arc, Mutex};
 	let shared_evaluator = Arc::new(Mutex::new(evaluator));
 	omnilang_core::mesh::worker::start_worker(port, shared_evaluator, token);
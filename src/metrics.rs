@@ -1,52 +1,427 @@
-//! Minimal runtime metrics collector and exporter (OpenMetrics text).
+//! Metrik keputusan per kebijakan: jumlah evaluasi, rule dan aksi yang dipicu,
+//! guard hit, serta histogram latensi. Registry bisa disimpan sebagai snapshot
+//! JSON (`--metrics-file`) dan diekspor dalam format teks OpenMetrics lewat
+//! endpoint HTTP `/metrics`.
 
+use crate::runtime::Decision;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use omnilang_core::runtime::DecisionMetrics;
-
-static TOTAL_POLICIES: AtomicU64 = AtomicU64::new(0);
-static TOTAL_RULES: AtomicU64 = AtomicU64::new(0);
-static TOTAL_ACTIONS: AtomicU64 = AtomicU64::new(0);
-static TOTAL_GUARD_HITS: AtomicU64 = AtomicU64::new(0);
-static TOTAL_DURATION_MS: AtomicU64 = AtomicU64::new(0);
-
-/// Record a decision metrics snapshot into global counters.
-pub fn record_decision(metrics: &DecisionMetrics) {
-    TOTAL_POLICIES.fetch_add(1, Ordering::Relaxed);
-    TOTAL_RULES.fetch_add(metrics.rules_evaluated as u64, Ordering::Relaxed);
-    TOTAL_ACTIONS.fetch_add(metrics.actions_triggered as u64, Ordering::Relaxed);
-    TOTAL_GUARD_HITS.fetch_add(metrics.guard_hits as u64, Ordering::Relaxed);
-    // Saturating add to avoid overflow; ms fits in u64 for practical ranges.
-    TOTAL_DURATION_MS.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
-        v.checked_add(metrics.duration_ms as u64).or(Some(u64::MAX))
-    }).ok();
-}
-
-/// Export metrics in OpenMetrics text format.
-#[allow(dead_code)]
-pub fn export_openmetrics() -> String {
-    let ts = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0);
-
-    let mut out = String::new();
-    out.push_str("# TYPE omnilang_policies_total counter\n");
-    out.push_str(&format!("omnilang_policies_total {} {}\n", TOTAL_POLICIES.load(Ordering::Relaxed), ts));
-
-    out.push_str("# TYPE omnilang_rules_total counter\n");
-    out.push_str(&format!("omnilang_rules_total {} {}\n", TOTAL_RULES.load(Ordering::Relaxed), ts));
-
-    out.push_str("# TYPE omnilang_actions_total counter\n");
-    out.push_str(&format!("omnilang_actions_total {} {}\n", TOTAL_ACTIONS.load(Ordering::Relaxed), ts));
-
-    out.push_str("# TYPE omnilang_guard_hits_total counter\n");
-    out.push_str(&format!("omnilang_guard_hits_total {} {}\n", TOTAL_GUARD_HITS.load(Ordering::Relaxed), ts));
-
-    out.push_str("# TYPE omnilang_duration_ms_total counter\n");
-    out.push_str(&format!("omnilang_duration_ms_total {} {}\n", TOTAL_DURATION_MS.load(Ordering::Relaxed), ts));
-
-    out.push_str("# EOF\n");
-    out
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Snapshot bawaan `omnilang metrics`.
+pub const DEFAULT_SNAPSHOT: &str = ".omnilang/metrics.json";
+
+/// Batas atas bucket histogram latensi (detik); bucket terakhir `+Inf`.
+pub const LATENCY_BUCKETS: [f64; 10] = [0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.05, 0.25];
+
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// Jumlah observasi per bucket (tidak kumulatif); satu elemen lebih banyak dari
+    /// [`LATENCY_BUCKETS`] untuk `+Inf`.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum_seconds: f64,
+}
+
+impl LatencyHistogram {
+    pub fn observe(&mut self, latency: Duration) {
+        let seconds = latency.as_secs_f64();
+        self.buckets.resize(LATENCY_BUCKETS.len() + 1, 0);
+        let bucket = LATENCY_BUCKETS.iter().position(|le| seconds <= *le).unwrap_or(LATENCY_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_seconds += seconds;
+    }
+
+    /// Hitungan kumulatif per batas `le`, termasuk `+Inf`.
+    pub fn cumulative(&self) -> Vec<u64> {
+        let mut total = 0;
+        (0..=LATENCY_BUCKETS.len())
+            .map(|i| {
+                total += self.buckets.get(i).copied().unwrap_or(0);
+                total
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyMetrics {
+    pub evaluations: u64,
+    pub rules_evaluated: u64,
+    pub actions_triggered: u64,
+    pub guard_hits: u64,
+    pub violations: u64,
+    pub unauthorized: u64,
+    pub latency: LatencyHistogram,
+    /// Nama aksi -> berapa kali dipicu.
+    #[serde(default)]
+    pub actions: BTreeMap<String, u64>,
+    /// Teks rule -> berapa kali rule itu memicu aksi.
+    #[serde(default)]
+    pub rules: BTreeMap<String, u64>,
+}
+
+/// Metrik semua kebijakan, dikunci dengan label kebijakan (lihat [`policy_label`]).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsRegistry {
+    pub policies: BTreeMap<String, PolicyMetrics>,
+}
+
+/// Pembaca satu counter dari [`PolicyMetrics`].
+type CounterField = fn(&PolicyMetrics) -> u64;
+
+/// Registry yang dibagi antara loop evaluasi dan endpoint HTTP.
+pub type SharedMetrics = Arc<Mutex<MetricsRegistry>>;
+
+/// Label kebijakan untuk metrik: `INTENT:` jika ada, selain itu nama file tanpa ekstensi.
+pub fn policy_label(intent: Option<&str>, path: &str) -> String {
+    match intent {
+        Some(intent) if !intent.trim().is_empty() => intent.trim().to_string(),
+        _ => Path::new(path).file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| path.to_string()),
+    }
+}
+
+impl MetricsRegistry {
+    /// Baca snapshot; file yang belum ada berarti registry kosong.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("cannot read metrics snapshot {}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("invalid metrics snapshot {}: {}", path.display(), e))
+    }
+
+    /// Tulis snapshot lewat file sementara lalu rename, agar pembaca tidak melihat file setengah jadi.
+    /// Nama file sementara unik per penulisan sehingga penulis paralel tidak saling menimpa.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("cannot create dir {}: {}", parent.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| format!("serialize metrics failed: {}", e))?;
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(format!(".{}.{}.tmp", std::process::id(), NEXT_TMP.fetch_add(1, Ordering::Relaxed)));
+        let tmp = PathBuf::from(tmp);
+        fs::write(&tmp, content).map_err(|e| format!("write metrics snapshot {} failed: {}", tmp.display(), e))?;
+        fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            format!("replace metrics snapshot {} failed: {}", path.display(), e)
+        })
+    }
+
+    /// Baca-ubah-tulis snapshot di bawah kunci eksklusif `<path>.lock`, sehingga
+    /// beberapa proses bisa menambah hitungan ke snapshot yang sama tanpa kehilangan data.
+    pub fn update(path: impl AsRef<Path>, apply: impl FnOnce(&mut Self)) -> Result<Self, String> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| format!("cannot create dir {}: {}", parent.display(), e))?;
+        }
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        let lock = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| format!("cannot open {}: {}", lock_path.display(), e))?;
+        // Kunci dilepas saat `lock` di-drop
+        lock.lock().map_err(|e| format!("cannot lock {}: {}", lock_path.display(), e))?;
+        let mut registry = Self::load(path)?;
+        apply(&mut registry);
+        registry.save(path)?;
+        Ok(registry)
+    }
+
+    /// Tambahkan semua hitungan `other` ke registry ini.
+    pub fn merge(&mut self, other: &MetricsRegistry) {
+        for (label, theirs) in &other.policies {
+            let ours = self.policies.entry(label.clone()).or_default();
+            ours.evaluations += theirs.evaluations;
+            ours.rules_evaluated += theirs.rules_evaluated;
+            ours.actions_triggered += theirs.actions_triggered;
+            ours.guard_hits += theirs.guard_hits;
+            ours.violations += theirs.violations;
+            ours.unauthorized += theirs.unauthorized;
+            ours.latency.buckets.resize(LATENCY_BUCKETS.len() + 1, 0);
+            for (bucket, count) in ours.latency.buckets.iter_mut().zip(&theirs.latency.buckets) {
+                *bucket += count;
+            }
+            ours.latency.count += theirs.latency.count;
+            ours.latency.sum_seconds += theirs.latency.sum_seconds;
+            for (action, count) in &theirs.actions {
+                *ours.actions.entry(action.clone()).or_default() += count;
+            }
+            for (rule, count) in &theirs.rules {
+                *ours.rules.entry(rule.clone()).or_default() += count;
+            }
+        }
+    }
+
+    /// Hitungan yang bertambah sejak `base` (registry ini harus kelanjutan dari `base`).
+    pub fn delta_since(&self, base: &MetricsRegistry) -> MetricsRegistry {
+        let empty = PolicyMetrics::default();
+        let policies = self
+            .policies
+            .iter()
+            .filter_map(|(label, now)| {
+                let then = base.policies.get(label).unwrap_or(&empty);
+                if now.evaluations == then.evaluations {
+                    return None;
+                }
+                let since = |now: &BTreeMap<String, u64>, then: &BTreeMap<String, u64>| -> BTreeMap<String, u64> {
+                    now.iter()
+                        .map(|(k, n)| (k.clone(), n.saturating_sub(then.get(k).copied().unwrap_or(0))))
+                        .filter(|(_, n)| *n > 0)
+                        .collect()
+                };
+                let bucket = |h: &LatencyHistogram, i: usize| h.buckets.get(i).copied().unwrap_or(0);
+                let buckets = (0..=LATENCY_BUCKETS.len()).map(|i| bucket(&now.latency, i).saturating_sub(bucket(&then.latency, i))).collect();
+                let delta = PolicyMetrics {
+                    evaluations: now.evaluations.saturating_sub(then.evaluations),
+                    rules_evaluated: now.rules_evaluated.saturating_sub(then.rules_evaluated),
+                    actions_triggered: now.actions_triggered.saturating_sub(then.actions_triggered),
+                    guard_hits: now.guard_hits.saturating_sub(then.guard_hits),
+                    violations: now.violations.saturating_sub(then.violations),
+                    unauthorized: now.unauthorized.saturating_sub(then.unauthorized),
+                    latency: LatencyHistogram {
+                        buckets,
+                        count: now.latency.count.saturating_sub(then.latency.count),
+                        sum_seconds: (now.latency.sum_seconds - then.latency.sum_seconds).max(0.0),
+                    },
+                    actions: since(&now.actions, &then.actions),
+                    rules: since(&now.rules, &then.rules),
+                };
+                Some((label.clone(), delta))
+            })
+            .collect();
+        MetricsRegistry { policies }
+    }
+
+    pub fn record(&mut self, policy: &str, decision: &Decision, latency: Duration) {
+        let metrics = self.policies.entry(policy.to_string()).or_default();
+        metrics.evaluations += 1;
+        metrics.rules_evaluated += decision.metrics.rules_evaluated as u64;
+        metrics.actions_triggered += decision.actions.len() as u64;
+        metrics.guard_hits += decision.metrics.guard_hits as u64;
+        metrics.violations += decision.violations.len() as u64;
+        metrics.unauthorized += decision.unauthorized.len() as u64;
+        metrics.latency.observe(latency);
+        for action in &decision.actions {
+            *metrics.actions.entry(action.clone()).or_default() += 1;
+        }
+        for explanation in &decision.explanations {
+            *metrics.rules.entry(explanation.rule.clone()).or_default() += 1;
+        }
+    }
+
+    /// Ekspor dalam format teks OpenMetrics, diakhiri `# EOF`.
+    pub fn render_openmetrics(&self) -> String {
+        let mut out = String::new();
+        let counters: [(&str, &str, CounterField); 6] = [
+            ("omnilang_evaluations", "Policy evaluations.", |m| m.evaluations),
+            ("omnilang_rules_evaluated", "Rules evaluated across all evaluations.", |m| m.rules_evaluated),
+            ("omnilang_actions_triggered", "Actions in final decisions.", |m| m.actions_triggered),
+            ("omnilang_guard_hits", "Loops stopped by a GUARD limit.", |m| m.guard_hits),
+            ("omnilang_constraint_violations", "CONSTRAINT invariants violated.", |m| m.violations),
+            ("omnilang_unauthorized_actions", "Actions rejected for a missing capability.", |m| m.unauthorized),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# TYPE {} counter\n# HELP {} {}", name, name, help);
+            for (policy, metrics) in &self.policies {
+                let _ = writeln!(out, "{}_total{{policy=\"{}\"}} {}", name, escape(policy), value(metrics));
+            }
+        }
+
+        let _ = writeln!(out, "# TYPE omnilang_action counter\n# HELP omnilang_action Times each action was triggered.");
+        for (policy, metrics) in &self.policies {
+            for (action, count) in &metrics.actions {
+                let _ = writeln!(out, "omnilang_action_total{{policy=\"{}\",action=\"{}\"}} {}", escape(policy), escape(action), count);
+            }
+        }
+        let _ = writeln!(out, "# TYPE omnilang_rule_fired counter\n# HELP omnilang_rule_fired Times each rule triggered an action.");
+        for (policy, metrics) in &self.policies {
+            for (rule, count) in &metrics.rules {
+                let _ = writeln!(out, "omnilang_rule_fired_total{{policy=\"{}\",rule=\"{}\"}} {}", escape(policy), escape(rule), count);
+            }
+        }
+
+        let _ = writeln!(
+            out,
+            "# TYPE omnilang_decision_latency_seconds histogram\n# UNIT omnilang_decision_latency_seconds seconds\n\
+             # HELP omnilang_decision_latency_seconds Time to evaluate a policy."
+        );
+        for (policy, metrics) in &self.policies {
+            let policy = escape(policy);
+            let cumulative = metrics.latency.cumulative();
+            for (i, count) in cumulative.iter().enumerate() {
+                let le = LATENCY_BUCKETS.get(i).map_or("+Inf".to_string(), |le| le.to_string());
+                let _ = writeln!(out, "omnilang_decision_latency_seconds_bucket{{policy=\"{}\",le=\"{}\"}} {}", policy, le, count);
+            }
+            let _ = writeln!(out, "omnilang_decision_latency_seconds_count{{policy=\"{}\"}} {}", policy, metrics.latency.count);
+            let _ = writeln!(out, "omnilang_decision_latency_seconds_sum{{policy=\"{}\"}} {}", policy, metrics.latency.sum_seconds);
+        }
+        out.push_str("# EOF\n");
+        out
+    }
+
+    /// Ringkasan untuk `omnilang metrics`.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let total: u64 = self.policies.values().map(|m| m.evaluations).sum();
+        let _ = writeln!(out, "Policy Metrics: {} evaluations across {} policies", total, self.policies.len());
+        for (policy, metrics) in &self.policies {
+            let latency = &metrics.latency;
+            let avg_ms = if latency.count == 0 { 0.0 } else { latency.sum_seconds * 1000.0 / latency.count as f64 };
+            let _ = writeln!(out, "\n{}", policy);
+            let _ = writeln!(out, "  Evaluations: {}", metrics.evaluations);
+            let _ = writeln!(out, "  Rules evaluated: {}", metrics.rules_evaluated);
+            let _ = writeln!(out, "  Actions triggered: {}", metrics.actions_triggered);
+            let _ = writeln!(out, "  Guard hits: {}", metrics.guard_hits);
+            let _ = writeln!(out, "  Constraint violations: {}, unauthorized: {}", metrics.violations, metrics.unauthorized);
+            let _ = writeln!(out, "  Latency: avg {:.3}ms, p50 <= {}, p99 <= {}", avg_ms, quantile(latency, 0.5), quantile(latency, 0.99));
+            for (action, count) in &metrics.actions {
+                let _ = writeln!(out, "  Action {}: {}", action, count);
+            }
+            for (rule, count) in &metrics.rules {
+                let _ = writeln!(out, "  Rule fired {}x: {}", count, rule);
+            }
+        }
+        out
+    }
+}
+
+/// Batas bucket terkecil yang memuat kuantil `q`, mis. `"1ms"`.
+fn quantile(latency: &LatencyHistogram, q: f64) -> String {
+    if latency.count == 0 {
+        return "-".to_string();
+    }
+    let target = (latency.count as f64 * q).ceil() as u64;
+    let index = latency.cumulative().iter().position(|c| *c >= target).unwrap_or(LATENCY_BUCKETS.len());
+    match LATENCY_BUCKETS.get(index) {
+        Some(le) => format!("{}ms", le * 1000.0),
+        None => format!("> {}ms", LATENCY_BUCKETS[LATENCY_BUCKETS.len() - 1] * 1000.0),
+    }
+}
+
+/// Escape nilai label OpenMetrics.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Sumber data endpoint `/metrics`.
+#[derive(Clone)]
+pub enum MetricsSource {
+    /// Registry yang diisi proses ini (`watch`).
+    Live(SharedMetrics),
+    /// Snapshot yang dibaca ulang setiap scrape, diisi proses lain (`serve`).
+    Snapshot(PathBuf),
+}
+
+impl MetricsSource {
+    fn render(&self) -> Result<String, String> {
+        match self {
+            MetricsSource::Live(metrics) => Ok(metrics.lock().unwrap_or_else(|e| e.into_inner()).render_openmetrics()),
+            MetricsSource::Snapshot(path) => Ok(MetricsRegistry::load(path)?.render_openmetrics()),
+        }
+    }
+}
+
+/// Layani `GET /metrics` di `addr` pada thread terpisah; mengembalikan alamat yang dipakai
+/// (berguna untuk port 0).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_http(addr: &str, source: MetricsSource) -> Result<std::net::SocketAddr, String> {
+    let listener = std::net::TcpListener::bind(addr).map_err(|e| format!("cannot listen on {}: {}", addr, e))?;
+    let local = listener.local_addr().map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = respond(stream, &source);
+        }
+    });
+    Ok(local)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn respond(stream: std::net::TcpStream, source: &MetricsSource) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    // Header diabaikan, tetapi dibaca habis sebelum membalas
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim_end().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => match source.render() {
+            Ok(body) => ("200 OK", CONTENT_TYPE, body),
+            Err(e) => ("500 Internal Server Error", "text/plain; charset=utf-8", format!("{}\n", e)),
+        },
+        (_, "/metrics") => ("405 Method Not Allowed", "text/plain; charset=utf-8", "method not allowed\n".to_string()),
+        _ => ("404 Not Found", "text/plain; charset=utf-8", "not found\n".to_string()),
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Penulis snapshot untuk proses yang berjalan lama: hanya hitungan baru sejak
+/// flush terakhir yang digabung ke snapshot, jadi hitungan proses lain tetap utuh.
+#[derive(Debug, Clone)]
+pub struct SnapshotWriter {
+    path: PathBuf,
+    flushed: MetricsRegistry,
+}
+
+impl SnapshotWriter {
+    /// `base` adalah isi registry live yang sudah ada di snapshot (biasanya hasil `load`).
+    pub fn new(path: impl Into<PathBuf>, base: MetricsRegistry) -> Self {
+        SnapshotWriter { path: path.into(), flushed: base }
+    }
+
+    /// Gabungkan pertambahan `current` sejak flush terakhir ke snapshot.
+    pub fn flush(&mut self, current: &MetricsRegistry) -> Result<(), String> {
+        let delta = current.delta_since(&self.flushed);
+        if delta.policies.is_empty() {
+            return Ok(());
+        }
+        MetricsRegistry::update(&self.path, |registry| registry.merge(&delta))?;
+        self.flushed = current.clone();
+        Ok(())
+    }
+}
+
+/// Flush registry lewat `writer` setiap `interval` pada thread terpisah.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_snapshot_writer(metrics: SharedMetrics, writer: Arc<Mutex<SnapshotWriter>>, interval: Duration) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        let snapshot = metrics.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Err(e) = writer.lock().unwrap_or_else(|e| e.into_inner()).flush(&snapshot) {
+            eprintln!("metrics: {}", e);
+        }
+    });
 }
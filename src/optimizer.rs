@@ -0,0 +1,312 @@
+//! Optimasi `PolicyIR` sebelum dijalankan `ir_interpreter` atau dikompilasi ke native/WASM.
+//!
+//! - Kondisi tanpa field konteks dan tanpa operator temporal dilipat menjadi konstanta.
+//! - Rule yang kondisinya selalu salah (tanpa ELSE), `WHILE false`, arm setelah
+//!   wildcard, dan rule setelah rule yang selalu aktif pada `FIRST_MATCH` dihapus.
+//! - Rule berurutan yang diawali term `AND` yang sama diangkat ke [`HoistedIR`].
+//! - Rantai rule `Field == literal` menjadi [`DispatchIR`] dan arm `MATCH` dengan
+//!   pola literal mendapat [`JumpTableIR`].
+//!
+//! Aksi, payload, dan urutan aksi tetap sama dengan IR asli. Log dan
+//! `rules_evaluated` hanya mencatat rule yang benar-benar dievaluasi, dan
+//! penjelasan memakai kondisi yang sudah dilipat.
+
+use crate::ast::{CombiningAlgorithm, CompareOp, Condition, MatchPattern, Operand, PathSegment};
+use crate::evaluator::{eval_condition, literal_key};
+use crate::ir::{DispatchIR, HoistedIR, JumpTableIR, PolicyIR, RuleIR, StandardRuleIR};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Panjang minimum rantai `Field == literal` yang dijadikan [`DispatchIR`].
+const MIN_DISPATCH_RULES: usize = 3;
+/// Panjang minimum rule dengan term pertama yang sama untuk [`HoistedIR`].
+const MIN_HOISTED_RULES: usize = 2;
+
+/// Ringkasan perubahan yang dibuat [`optimize`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    /// Kondisi rule/`WHILE` yang berubah karena pelipatan konstanta.
+    pub folded_conditions: usize,
+    pub removed_rules: usize,
+    pub removed_arms: usize,
+    pub hoisted_blocks: usize,
+    pub dispatch_tables: usize,
+    pub match_tables: usize,
+}
+
+impl OptimizeReport {
+    pub fn is_empty(&self) -> bool {
+        *self == OptimizeReport::default()
+    }
+}
+
+/// Optimasi `ir` di tempat dan bangun ulang `flat_rules` dari hasilnya.
+pub fn optimize(ir: &mut PolicyIR) -> OptimizeReport {
+    let mut report = OptimizeReport::default();
+    let first_match = ir.combining == CombiningAlgorithm::FirstMatch;
+    ir.rules = optimize_rules(std::mem::take(&mut ir.rules), first_match, &mut report);
+    ir.flat_rules = Vec::new();
+    flatten(&ir.rules, &mut ir.flat_rules);
+    report
+}
+
+/// `top_first_match`: daftar ini adalah rule tingkat atas kebijakan `FIRST_MATCH`, yang
+/// dihentikan setelah rule pertama yang aktif, jadi rule di dalamnya tidak boleh digabung.
+fn optimize_rules(rules: Vec<RuleIR>, top_first_match: bool, report: &mut OptimizeReport) -> Vec<RuleIR> {
+    let mut out = Vec::new();
+    let mut rules = rules.into_iter();
+    for rule in rules.by_ref() {
+        let rule = optimize_rule(rule, report);
+        let always_fires = match &rule {
+            // Kondisi konstan yang tersisa selalu memicu THEN atau ELSE
+            RuleIR::Standard(r) => match r.condition {
+                Condition::Const { value } => value || r.else_action.is_some(),
+                _ => false,
+            },
+            RuleIR::Match(m) => m.arms.last().is_some_and(|arm| arm.pattern == MatchPattern::Wildcard),
+            _ => false,
+        };
+        out.push(rule);
+        if top_first_match && always_fires {
+            break;
+        }
+    }
+    report.removed_rules += rules.count();
+    if top_first_match {
+        out
+    } else {
+        group_rules(out, report)
+    }
+}
+
+fn optimize_rule(rule: RuleIR, report: &mut OptimizeReport) -> RuleIR {
+    match rule {
+        RuleIR::Standard(mut r) => {
+            // Rule yang selalu salah tetap disimpan: runtime menghitungnya di `rules_evaluated`
+            r.condition = fold_counted(r.condition, report);
+            RuleIR::Standard(r)
+        }
+        RuleIR::For(mut f) => {
+            f.body = optimize_rules(f.body, false, report);
+            RuleIR::For(f)
+        }
+        RuleIR::While(mut w) => {
+            w.condition = fold_counted(w.condition, report);
+            if w.condition == (Condition::Const { value: false }) {
+                // Loop tetap dihitung sekali, tetapi body-nya tidak pernah dijalankan
+                report.removed_rules += std::mem::take(&mut w.body).len();
+                return RuleIR::While(w);
+            }
+            w.body = optimize_rules(w.body, false, report);
+            RuleIR::While(w)
+        }
+        RuleIR::Match(mut m) => {
+            if let Some(wildcard) = m.arms.iter().position(|arm| arm.pattern == MatchPattern::Wildcard) {
+                report.removed_arms += m.arms.len() - wildcard - 1;
+                m.arms.truncate(wildcard + 1);
+            }
+            m.table = match_table(&m.scrutinee, &m.arms);
+            if m.table.is_some() {
+                report.match_tables += 1;
+            }
+            RuleIR::Match(m)
+        }
+        // Sudah dioptimasi; optimize() pada IR yang sama tidak mengubah apa pun
+        other => other,
+    }
+}
+
+fn fold_counted(condition: Condition, report: &mut OptimizeReport) -> Condition {
+    let folded = fold(condition.clone());
+    if folded != condition {
+        report.folded_conditions += 1;
+    }
+    folded
+}
+
+/// Lipat sub-kondisi konstan. Kondisi temporal tidak disentuh karena setiap
+/// term-nya harus dievaluasi agar statusnya maju.
+fn fold(condition: Condition) -> Condition {
+    if condition.is_temporal() {
+        return condition;
+    }
+    match condition {
+        Condition::Not { inner } => match fold(*inner) {
+            Condition::Const { value } => Condition::Const { value: !value },
+            inner => Condition::Not { inner: Box::new(inner) },
+        },
+        Condition::And { terms } => fold_terms(terms, true),
+        Condition::Or { terms } => fold_terms(terms, false),
+        Condition::Const { .. } => condition,
+        atom if !reads_context(&atom) => Condition::Const { value: eval_condition(&atom, &Value::Null) },
+        atom => atom,
+    }
+}
+
+/// `AND` (`identity` true) atau `OR` (`identity` false): term identitas dibuang,
+/// term penyerap membuat seluruh kondisi konstan.
+fn fold_terms(terms: Vec<Condition>, identity: bool) -> Condition {
+    let mut kept = Vec::new();
+    for term in terms {
+        match fold(term) {
+            Condition::Const { value } if value == identity => {}
+            Condition::Const { value } => return Condition::Const { value },
+            term => kept.push(term),
+        }
+    }
+    match kept.len() {
+        0 => Condition::Const { value: identity },
+        1 => kept.remove(0),
+        _ if identity => Condition::And { terms: kept },
+        _ => Condition::Or { terms: kept },
+    }
+}
+
+fn reads_context(condition: &Condition) -> bool {
+    match condition {
+        Condition::Const { .. } => false,
+        Condition::Compare { left, right, .. } => operand_reads_context(left) || operand_reads_context(right),
+        Condition::In { value, set } => operand_reads_context(value) || operand_reads_context(set),
+        Condition::Truthy { value } => operand_reads_context(value),
+        Condition::Not { inner } => reads_context(inner),
+        Condition::And { terms } | Condition::Or { terms } => terms.iter().any(reads_context),
+        Condition::Sustained { .. } | Condition::Becomes { .. } | Condition::Latch { .. } => true,
+    }
+}
+
+fn operand_reads_context(operand: &Operand) -> bool {
+    match operand {
+        Operand::Number { .. } | Operand::Bool { .. } | Operand::Str { .. } => false,
+        Operand::List { items } => items.iter().any(operand_reads_context),
+        Operand::Arith { left, right, .. } => operand_reads_context(left) || operand_reads_context(right),
+        Operand::Path { .. } | Operand::Count { .. } => true,
+    }
+}
+
+/// Gabungkan rantai rule menjadi [`DispatchIR`] atau [`HoistedIR`] jika cukup panjang.
+fn group_rules(rules: Vec<RuleIR>, report: &mut OptimizeReport) -> Vec<RuleIR> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < rules.len() {
+        let dispatch = run_length(&rules[i..], |r| dispatch_case(r).map(|(field, _, _)| field));
+        if dispatch >= MIN_DISPATCH_RULES {
+            out.push(build_dispatch(&rules[i..i + dispatch]));
+            report.dispatch_tables += 1;
+            i += dispatch;
+            continue;
+        }
+        let hoisted = run_length(&rules[i..], |r| leading_term(r).cloned());
+        if hoisted >= MIN_HOISTED_RULES {
+            let guard = match &rules[i] {
+                RuleIR::Standard(r) => leading_term(r).cloned(),
+                _ => None,
+            };
+            let guard = guard.unwrap_or(Condition::Const { value: true });
+            out.push(RuleIR::Hoisted(HoistedIR { guard, body: rules[i..i + hoisted].to_vec() }));
+            report.hoisted_blocks += 1;
+            i += hoisted;
+            continue;
+        }
+        out.push(rules[i].clone());
+        i += 1;
+    }
+    out
+}
+
+/// Jumlah rule berurutan dari awal `rules` yang menghasilkan kunci yang sama.
+fn run_length<K: PartialEq>(rules: &[RuleIR], key: impl Fn(&StandardRuleIR) -> Option<K>) -> usize {
+    let key_of = |rule: &RuleIR| match rule {
+        RuleIR::Standard(r) if r.else_action.is_none() && !r.condition.is_temporal() => key(r),
+        _ => None,
+    };
+    let Some(first) = rules.first().and_then(key_of) else {
+        return 0;
+    };
+    rules.iter().take_while(|rule| key_of(rule).as_ref() == Some(&first)).count()
+}
+
+/// Term pertama kondisi `AND`.
+fn leading_term(rule: &StandardRuleIR) -> Option<&Condition> {
+    match &rule.condition {
+        Condition::And { terms } => terms.first(),
+        _ => None,
+    }
+}
+
+/// `Field == literal` (boleh sebagai term pertama `AND`): field, kunci literal, dan
+/// nama simbolik literal itu jika ada.
+fn dispatch_case(rule: &StandardRuleIR) -> Option<(Operand, String, Option<String>)> {
+    let compare = match &rule.condition {
+        Condition::And { terms } => terms.first()?,
+        condition => condition,
+    };
+    let Condition::Compare { left: left @ Operand::Path { .. }, op: CompareOp::Eq, right } = compare else {
+        return None;
+    };
+    Some((left.clone(), literal_key(right)?, symbol(right)))
+}
+
+fn build_dispatch(rules: &[RuleIR]) -> RuleIR {
+    let mut table = JumpTableIR::default();
+    let mut field = None;
+    for (index, rule) in rules.iter().enumerate() {
+        let RuleIR::Standard(r) = rule else { continue };
+        let Some((operand, key, symbol)) = dispatch_case(r) else { continue };
+        field.get_or_insert(operand);
+        table.cases.entry(key).or_default().push(index);
+        table.symbols.extend(symbol.filter(|s| !table.symbols.contains(s)));
+    }
+    RuleIR::Dispatch(DispatchIR {
+        field: field.unwrap_or(Operand::Bool { value: false }),
+        table,
+        rules: rules.to_vec(),
+    })
+}
+
+/// Jump table arm `MATCH` jika scrutinee tidak temporal dan semua pola adalah literal berkunci.
+fn match_table(scrutinee: &Operand, arms: &[crate::ir::PolicyMatchArmIR]) -> Option<JumpTableIR> {
+    if scrutinee.is_temporal() || arms.is_empty() {
+        return None;
+    }
+    let mut table = JumpTableIR::default();
+    for (index, arm) in arms.iter().enumerate() {
+        match &arm.pattern {
+            MatchPattern::Wildcard => table.default = Some(index),
+            MatchPattern::Value { value } => {
+                // Hanya arm pertama per kunci yang bisa terpilih
+                table.cases.entry(literal_key(value)?).or_insert_with(|| vec![index]);
+                table.symbols.extend(symbol(value).filter(|s| !table.symbols.contains(s)));
+            }
+        }
+    }
+    Some(table)
+}
+
+fn symbol(operand: &Operand) -> Option<String> {
+    match operand {
+        Operand::Path { path, .. } => match path.segments.as_slice() {
+            [PathSegment::Key(name)] => Some(name.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Seperti `flat_rules` dari [`crate::ir::build_policy_ir`]: loop tanpa badan diikuti isinya.
+fn flatten(rules: &[RuleIR], out: &mut Vec<RuleIR>) {
+    for rule in rules {
+        match rule {
+            RuleIR::For(f) => {
+                out.push(RuleIR::For(crate::ir::ForLoopIR { body: Vec::new(), ..f.clone() }));
+                flatten(&f.body, out);
+            }
+            RuleIR::While(w) => {
+                out.push(RuleIR::While(crate::ir::WhileLoopIR { body: Vec::new(), ..w.clone() }));
+                flatten(&w.body, out);
+            }
+            RuleIR::Hoisted(h) => flatten(&h.body, out),
+            RuleIR::Dispatch(d) => flatten(&d.rules, out),
+            RuleIR::Standard(_) | RuleIR::Match(_) => out.push(rule.clone()),
+        }
+    }
+}
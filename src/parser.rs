@@ -1,3 +1,4 @@
+use crate::action_abi::ActionCapability;
 use crate::ast::*;
 use crate::lexer::{Token, TokenType};
 
@@ -6,6 +7,20 @@ pub struct Parser {
     pos: usize,
 }
 
+/// Parse a standalone condition such as `Temperature > 50 AND Mode IN [1, 2]`.
+pub fn parse_condition(source: &str) -> Result<Condition, String> {
+    let tokens = crate::lexer::Lexer::new(source).tokenize()?;
+    let mut parser = Parser::new(tokens);
+    let condition = parser.parse_condition()?;
+    if !parser.is_at_end() {
+        return Err(parser.parse_error(&format!(
+            "Unexpected token '{}' after condition",
+            parser.peek().lexeme
+        )));
+    }
+    Ok(condition)
+}
+
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         Parser { tokens, pos: 0 }
@@ -38,6 +53,13 @@ impl Parser {
         let mut impacts = Vec::new();
         let mut traces = Vec::new();
         let mut reviews = Vec::new();
+        let mut units = Vec::new();
+        let mut schema = Vec::new();
+        let mut capabilities = Vec::new();
+        let mut guard = GuardDecl::default();
+        let mut tests = Vec::new();
+        let mut combining = CombiningAlgorithm::default();
+        let mut composition = Composition::default();
 
         while !self.is_at_end() {
             let token = self.peek().clone();
@@ -66,7 +88,7 @@ impl Parser {
                 TokenType::Rule => {
                     self.advance();
                     self.consume(TokenType::Colon, "Expected ':' after RULE")?;
-                    rules.extend(self.parse_rules()?);
+                    rules.extend(self.parse_rules(&mut composition.overrides)?);
                 }
                 TokenType::Constraint => {
                     self.advance();
@@ -88,6 +110,54 @@ impl Parser {
                     self.consume(TokenType::Colon, "Expected ':' after REVIEW")?;
                     reviews.extend(self.parse_reviews()?);
                 }
+                TokenType::Units => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after UNITS")?;
+                    units.extend(self.parse_units()?);
+                }
+                TokenType::Schema => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after SCHEMA")?;
+                    schema.extend(self.parse_schema()?);
+                }
+                TokenType::Capability => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after CAPABILITY")?;
+                    capabilities.extend(self.parse_capabilities()?);
+                }
+                TokenType::Guard => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after GUARD")?;
+                    self.parse_guard(&mut guard)?;
+                }
+                TokenType::Expect => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after EXPECT")?;
+                    tests.extend(self.parse_expect()?);
+                }
+                TokenType::Combine => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after COMBINE")?;
+                    let name = self.parse_line_lexemes();
+                    combining = CombiningAlgorithm::parse(&name).map_err(|e| self.parse_error(&e))?;
+                }
+                TokenType::Imports => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after IMPORT")?;
+                    composition.imports.extend(self.parse_policy_refs("IMPORT")?);
+                }
+                TokenType::Extend => {
+                    self.advance();
+                    self.consume(TokenType::Colon, "Expected ':' after EXTEND")?;
+                    if composition.extends.is_some() {
+                        return Err(self.parse_error("A policy can EXTEND only one base policy"));
+                    }
+                    let mut refs = self.parse_policy_refs("EXTEND")?;
+                    if refs.len() != 1 {
+                        return Err(self.parse_error("EXTEND expects exactly one base policy"));
+                    }
+                    composition.extends = refs.pop();
+                }
                 TokenType::Eof => break,
                 _ => {
                     return Err(format!("Unexpected token {:?} at line {}", token.token_type, token.line));
@@ -95,7 +165,7 @@ impl Parser {
             }
         }
 
-        Ok(Policy {
+        let mut policy = Policy {
             intent,
             actors,
             context,
@@ -105,12 +175,43 @@ impl Parser {
             impacts,
             traces,
             reviews,
-        })
+            units,
+            schema,
+            capabilities,
+            guard,
+            tests,
+            combining,
+            composition,
+        };
+        crate::schema::register_units(&mut policy)?;
+        let mut test_names = std::collections::HashSet::new();
+        for test in &policy.tests {
+            if !test_names.insert(test.name.as_str()) {
+                return Err(format!("Duplicate test case '{}'", test.name));
+            }
+        }
+        let mut names = std::collections::HashSet::new();
+        for name in policy.rules.iter().filter_map(Rule::name) {
+            if !names.insert(name) {
+                return Err(format!("Duplicate rule name '{}'", name));
+            }
+        }
+        crate::units::apply_declared_units(&mut policy);
+        crate::units::check_policy_units(&policy)?;
+        Ok(policy)
     }
 
     fn parse_text_line(&mut self) -> Result<String, String> {
+        self.parse_text_until(false)
+    }
+
+    fn parse_text_until(&mut self, stop_at_else: bool) -> Result<String, String> {
+        self.parse_text_stopping_at(|p| stop_at_else && p.check(TokenType::Else))
+    }
+
+    fn parse_text_stopping_at(&mut self, stop: impl Fn(&Self) -> bool) -> Result<String, String> {
         let mut content = String::new();
-        while !self.is_at_end() && !self.is_section_header(self.peek()) {
+        while !(self.is_at_end() || self.is_section_header(self.peek()) || stop(self)) {
             let t = self.advance();
             let chunk = match &t.token_type {
                 TokenType::Ident(s) | TokenType::String(s) => s.clone(),
@@ -143,10 +244,17 @@ impl Parser {
             };
 
             self.consume(TokenType::Colon, "Expected ':'")?;
-            let role = self.parse_text_line()?;
+            let role = self.parse_text_stopping_at(Self::at_can_clause)?;
+            let capabilities = if self.at_can_clause() {
+                self.advance(); // CAN
+                Some(self.parse_capability_list("CAN")?)
+            } else {
+                None
+            };
             actors.push(Actor {
                 role,
                 primary: is_primary,
+                capabilities,
             });
         }
         Ok(actors)
@@ -187,39 +295,13 @@ impl Parser {
         Ok(list)
     }
 
-    fn parse_rules(&mut self) -> Result<Vec<Rule>, String> {
+    fn parse_rules(&mut self, overrides: &mut Vec<String>) -> Result<Vec<Rule>, String> {
         let mut rules = Vec::new();
         while self.match_token(TokenType::Minus) {
-            if self.check(TokenType::If) {
-                self.advance();
-                let mut condition = String::new();
-                while !self.check(TokenType::Then) && !self.is_at_end() {
-                    let chunk = self.parse_text_chunk()?;
-                    let is_punct = matches!(chunk.as_str(), "." | "[" | "]" | "(" | ")" | ",");
-                    if !condition.is_empty() && !is_punct {
-                        condition.push(' ');
-                    }
-                    condition.push_str(&chunk);
-                }
-
-                // Normalize spacing so evaluator sees clean tokens
-                condition = condition
-                    .replace(" .", ".")
-                    .replace(". ", ".")
-                    .replace("[ ", "[")
-                    .replace(" ]", "]")
-                    .replace("IN[", "IN [")
-                    .replace("in[", "in [");
-
-                if self.match_token(TokenType::Then) {
-                    let action = self.parse_text_line()?;
-                    rules.push(Rule::Standard(StandardRule {
-                        condition: condition.trim().to_string(),
-                        action,
-                    }));
-                    } else {
-                        return Err("Unexpected end of rule: missing THEN".to_string());
-                }
+            if self.is_rule_label() {
+                rules.push(self.parse_named_rule(overrides)?);
+            } else if self.check(TokenType::If) || self.is_rule_modifier() {
+                rules.push(self.parse_if_rule()?);
             } else if self.check(TokenType::Match) || matches!(self.peek().token_type, TokenType::Ident(ref s) if s.eq_ignore_ascii_case("match")) {
                 rules.push(self.parse_match_rule()?);
             } else if self.check(TokenType::For) {
@@ -233,42 +315,230 @@ impl Parser {
         }
         Ok(rules)
     }
-    fn parse_match_rule(&mut self) -> Result<Rule, String> {
-        self.advance(); // MATCH
-        let mut scrutinee = String::new();
-        while !self.check(TokenType::LBrace) && !self.is_at_end() {
-            let chunk = self.parse_text_chunk()?;
-            let is_punct = matches!(chunk.as_str(), "." | "[" | "]" | "(" | ")" | ",");
-            if !scrutinee.is_empty() && !is_punct {
-                scrutinee.push(' ');
+
+    /// `name:` atau `OVERRIDE name:` di awal rule.
+    fn is_rule_label(&self) -> bool {
+        let labelled = |offset: usize| {
+            matches!(self.peek_ahead(offset).token_type, TokenType::Ident(_))
+                && self.check_ahead(offset + 1, TokenType::Colon)
+        };
+        labelled(0) || (self.is_override_label() && labelled(1))
+    }
+
+    fn is_override_label(&self) -> bool {
+        matches!(self.peek().token_type, TokenType::Ident(ref s) if s.eq_ignore_ascii_case("override"))
+            && self.check_ahead(2, TokenType::Colon)
+    }
+
+    /// `- [OVERRIDE] name: [DENY] [PRIORITY n] IF ...`
+    fn parse_named_rule(&mut self, overrides: &mut Vec<String>) -> Result<Rule, String> {
+        let is_override = self.is_override_label() && self.match_word("override");
+        let name = self.advance().lexeme.clone();
+        self.advance(); // :
+        if !(self.check(TokenType::If) || self.is_rule_modifier()) {
+            return Err(self.parse_error(&format!("Only IF rules can be named (rule '{}')", name)));
+        }
+        let mut rule = self.parse_standard_rule()?;
+        if is_override {
+            overrides.push(name.clone());
+        }
+        rule.name = Some(name);
+        Ok(Rule::Standard(rule))
+    }
+
+    fn is_rule_modifier(&self) -> bool {
+        matches!(self.peek().token_type, TokenType::Ident(ref s)
+            if s.eq_ignore_ascii_case("deny") || s.eq_ignore_ascii_case("priority") || s.eq_ignore_ascii_case("by"))
+    }
+
+    /// `- [DENY] [PRIORITY n] [BY Role] IF cond THEN action [ELSE action]`
+    fn parse_if_rule(&mut self) -> Result<Rule, String> {
+        self.parse_standard_rule().map(Rule::Standard)
+    }
+
+    fn parse_standard_rule(&mut self) -> Result<StandardRule, String> {
+        let line = self.previous().line; // baris '-' pembuka rule
+        let mut effect = RuleEffect::Permit;
+        let mut priority = 0;
+        let mut actor = None;
+        loop {
+            if self.match_word("deny") {
+                effect = RuleEffect::Deny;
+            } else if self.match_word("priority") {
+                priority = self.parse_priority()?;
+            } else if self.match_word("by") {
+                if actor.is_some() {
+                    return Err(self.parse_error("A rule can name only one BY actor"));
+                }
+                actor = match &self.peek().token_type {
+                    TokenType::Ident(role) | TokenType::String(role) => Some(role.clone()),
+                    _ => return Err(self.parse_error(&format!("Expected actor role after BY, got '{}'", self.peek().lexeme))),
+                };
+                self.advance();
+            } else {
+                break;
             }
-            scrutinee.push_str(&chunk);
         }
-        
-        // Normalize
-        scrutinee = scrutinee.replace(" .", ".").replace(". ", ".");
+        self.consume(TokenType::If, "Expected IF after rule modifiers")?;
+        let condition = self.parse_condition()?;
+        // Bentuk dua baris: `- IF cond` lalu `- THEN aksi` sebagai item berikutnya
+        if self.check(TokenType::Minus) && self.check_ahead(1, TokenType::Then) {
+            self.advance();
+        }
+        self.consume(TokenType::Then, "Unexpected end of rule: missing THEN")?;
+        let action = self.parse_action(true, "THEN")?;
+        let else_action = if self.match_token(TokenType::Else) {
+            Some(self.parse_action(false, "ELSE")?)
+        } else {
+            None
+        };
+        Ok(StandardRule { name: None, condition, action, else_action, priority, effect, actor, line })
+    }
+
+    /// Aksi setelah THEN/ELSE/`=>`: `Name(key: operand, ...)` atau teks bebas.
+    fn parse_action(&mut self, stop_at_else: bool, after: &str) -> Result<ActionCall, String> {
+        let is_call = matches!(self.peek().token_type, TokenType::Ident(_))
+            && self.peek_ahead(1).token_type == TokenType::LParen
+            && self.peek_ahead(1).line == self.peek().line;
+        if !is_call {
+            let text = self.parse_text_until(stop_at_else)?;
+            if text.is_empty() {
+                return Err(self.parse_error(&format!("Missing action after {}", after)));
+            }
+            return Ok(ActionCall::named(text));
+        }
+
+        let name = self.advance().lexeme.clone();
+        self.advance(); // (
+        let mut params: Vec<ActionParam> = Vec::new();
+        if !self.check(TokenType::RParen) {
+            loop {
+                let key = match Self::path_segment_name(self.peek()) {
+                    Some(key) => key,
+                    None => return Err(self.parse_error(&format!("Expected parameter name in action '{}'", name))),
+                };
+                if params.iter().any(|p| p.name == key) {
+                    return Err(self.parse_error(&format!("Duplicate parameter '{}' in action '{}'", key, name)));
+                }
+                self.advance();
+                self.consume(TokenType::Colon, "Expected ':' after action parameter name")?;
+                let value = self.parse_operand()?;
+                if key == "requires" {
+                    self.check_capability_list(&value)?;
+                }
+                params.push(ActionParam { name: key, value });
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RParen, "Expected ')' after action parameters")?;
+        Ok(ActionCall { name, params })
+    }
+
+    fn check_capability_list(&self, value: &Operand) -> Result<(), String> {
+        let items = match value {
+            Operand::List { items } => items.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        for item in items {
+            let known = matches!(item, Operand::Path { path, .. }
+                if crate::action_abi::ActionCapability::from_name(&path.to_string()).is_some());
+            if !known {
+                return Err(self.parse_error(&format!(
+                    "Unknown capability '{}' in requires (expected Read, Write, Network, FileSystem or Execute)",
+                    item
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `CAN [Read, Network]` di akhir baris actor; harus huruf besar agar peran
+    /// seperti "Robot that can weld" tetap teks bebas.
+    fn at_can_clause(&self) -> bool {
+        self.peek().lexeme == "CAN"
+            && self.check_ahead(1, TokenType::LBracket)
+            && self.peek_ahead(1).line == self.peek().line
+    }
 
+    /// `[Read, Write, Network, FileSystem, Execute]`; `[]` berarti tanpa capability.
+    fn parse_capability_list(&mut self, after: &str) -> Result<Vec<ActionCapability>, String> {
+        self.consume(TokenType::LBracket, &format!("Expected '[' after {}", after))?;
+        let mut list = Vec::new();
+        while !self.check(TokenType::RBracket) && !self.is_at_end() {
+            let name = self.peek().lexeme.clone();
+            let capability = ActionCapability::from_name(&name).ok_or_else(|| {
+                self.parse_error(&format!(
+                    "Unknown capability '{}' in {} (expected Read, Write, Network, FileSystem or Execute)",
+                    name, after
+                ))
+            })?;
+            self.advance();
+            if !list.contains(&capability) {
+                list.push(capability);
+            }
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RBracket, &format!("Expected ']' after {} capability list", after))?;
+        Ok(list)
+    }
+
+    /// `- Action: [Network, FileSystem]`
+    fn parse_capabilities(&mut self) -> Result<Vec<ActionRequirement>, String> {
+        let mut list: Vec<ActionRequirement> = Vec::new();
+        while self.match_token(TokenType::Minus) {
+            let action = match &self.peek().token_type {
+                TokenType::Ident(name) | TokenType::String(name) => name.clone(),
+                _ => return Err(self.parse_error("Expected action name in CAPABILITY")),
+            };
+            self.advance();
+            if list.iter().any(|r| r.action == action) {
+                return Err(self.parse_error(&format!("Duplicate CAPABILITY entry for action '{}'", action)));
+            }
+            self.consume(TokenType::Colon, "Expected ':' after action name in CAPABILITY")?;
+            let requires = self.parse_capability_list("CAPABILITY")?;
+            list.push(ActionRequirement { action, requires });
+        }
+        Ok(list)
+    }
+
+    fn parse_priority(&mut self) -> Result<i64, String> {
+        let negative = self.match_token(TokenType::Minus);
+        let token = self.peek().clone();
+        match token.token_type {
+            TokenType::Number(n) if n.fract() == 0.0 && token.lexeme.chars().all(|c| c.is_ascii_digit()) => {
+                self.advance();
+                Ok(if negative { -(n as i64) } else { n as i64 })
+            }
+            _ => Err(self.parse_error(&format!("Expected integer after PRIORITY, got '{}'", token.lexeme))),
+        }
+    }
+
+    fn parse_match_rule(&mut self) -> Result<Rule, String> {
+        let line = self.previous().line; // baris '-' pembuka rule
+        self.advance(); // MATCH
+        let scrutinee = self.parse_operand()?;
         self.consume(TokenType::LBrace, "Expected '{' after MATCH expression")?;
 
         let mut arms = Vec::new();
         while !self.check(TokenType::RBrace) && !self.is_at_end() {
             self.consume(TokenType::Minus, "Expected '-' before match arm")?;
-            let pattern = self.parse_text_chunk_until_arrow()?;
+            let pattern = if matches!(self.peek().token_type, TokenType::Ident(ref s) if s == "_") {
+                self.advance();
+                MatchPattern::Wildcard
+            } else {
+                MatchPattern::Value { value: self.parse_operand()? }
+            };
             self.consume(TokenType::Arrow, "Expected '=>' after pattern")?;
-            let action = self.parse_text_line()?;
+            let action = self.parse_action(false, "=>")?;
             arms.push(PolicyMatchArm { pattern, action });
         }
 
         self.consume(TokenType::RBrace, "Expected '}' after match arms")?;
-        Ok(Rule::Match(PolicyMatchRule { scrutinee, arms }))
-    }
-
-    fn parse_text_chunk_until_arrow(&mut self) -> Result<String, String> {
-        let mut parts = Vec::new();
-        while !self.check(TokenType::Arrow) && !self.is_at_end() && !self.is_section_header(self.peek()) {
-            parts.push(self.advance().lexeme.clone());
-        }
-        Ok(parts.join(" ").trim().to_string())
+        Ok(Rule::Match(PolicyMatchRule { scrutinee, arms, line }))
     }
 
     fn parse_struct_init(&mut self, name: String) -> Result<Expr, String> {
@@ -299,114 +569,392 @@ impl Parser {
 
 
     fn parse_for_rule(&mut self) -> Result<Rule, String> {
+        let line = self.previous().line; // baris '-' pembuka rule
         self.advance(); // FOR
         let iterator = self.consume_ident("Expected iterator name after FOR")?;
         self.consume(TokenType::In, "Expected IN after iterator")?;
         let collection = self.consume_ident("Expected collection name after IN")?;
 
         self.consume(TokenType::LBrace, "Expected '{' to start FOR body")?;
-        let mut body = Vec::new();
-        while !self.check(TokenType::RBrace) && !self.is_at_end() {
-            if self.match_token(TokenType::Minus) {
-                if self.check(TokenType::If) {
-                    self.advance();
-                    let mut condition = String::new();
-                    while !self.check(TokenType::Then) && !self.is_at_end() {
-                        let chunk = self.parse_text_chunk()?;
-                        let is_punct = matches!(chunk.as_str(), "." | "[" | "]" | "(" | ")" | ",");
-                        if !condition.is_empty() && !is_punct {
-                            condition.push(' ');
-                        }
-                        condition.push_str(&chunk);
-                    }
-                    condition = condition
-                        .replace(" .", ".")
-                        .replace(". ", ".")
-                        .replace("[ ", "[")
-                        .replace(" ]", "]")
-                        .replace("IN[", "IN [")
-                        .replace("in[", "in [");
-                    if self.match_token(TokenType::Then) {
-                        let action = self.parse_text_line()?;
-                        body.push(Rule::Standard(StandardRule {
-                            condition: condition.trim().to_string(),
-                            action,
-                        }));
-                    }
-                } else if self.check(TokenType::For) {
-                    body.push(self.parse_for_rule()?);
-                } else if self.check(TokenType::While) {
-                    body.push(self.parse_while_rule()?);
-                } else {
-                    return Err("Unexpected token in FOR body".to_string());
-                }
-            } else {
-                return Err("Expected '-' to start rule inside FOR".to_string());
-            }
-        }
+        let body = self.parse_loop_body("FOR")?;
         self.consume(TokenType::RBrace, "Expected '}' to end FOR body")?;
 
         Ok(Rule::For(ForLoop {
             iterator,
             collection,
             body,
+            line,
         }))
     }
 
     fn parse_while_rule(&mut self) -> Result<Rule, String> {
+        let line = self.previous().line; // baris '-' pembuka rule
         self.advance(); // WHILE
-        let mut condition = String::new();
-        while !self.check(TokenType::LBrace) && !self.is_at_end() {
-            let chunk = self.parse_text_chunk()?;
-            let is_punct = matches!(chunk.as_str(), "." | "[" | "]" | "(" | ")" | ",");
-            if !condition.is_empty() && !is_punct {
-                condition.push(' ');
-            }
-            condition.push_str(&chunk);
-        }
-        condition = condition
-            .replace(" .", ".")
-            .replace(". ", ".")
-            .replace("[ ", "[")
-            .replace(" ]", "]")
-            .replace("IN[", "IN [")
-            .replace("in[", "in [");
+        let condition = self.parse_condition()?;
 
         self.consume(TokenType::LBrace, "Expected '{' to start WHILE body")?;
+        let body = self.parse_loop_body("WHILE")?;
+        self.consume(TokenType::RBrace, "Expected '}' to end WHILE body")?;
+
+        Ok(Rule::While(WhileLoop { condition, body, line }))
+    }
+
+    fn parse_loop_body(&mut self, kind: &str) -> Result<Vec<Rule>, String> {
         let mut body = Vec::new();
         while !self.check(TokenType::RBrace) && !self.is_at_end() {
             if self.match_token(TokenType::Minus) {
-                if self.check(TokenType::If) {
-                    self.advance();
-                    let mut cond = String::new();
-                    while !self.check(TokenType::Then) && !self.is_at_end() {
-                        cond.push_str(&self.parse_text_chunk()?);
-                        cond.push(' ');
-                    }
-                    if self.match_token(TokenType::Then) {
-                        let action = self.parse_text_line()?;
-                        body.push(Rule::Standard(StandardRule {
-                            condition: cond.trim().to_string(),
-                            action,
-                        }));
-                    }
+                if self.check(TokenType::If) || self.is_rule_modifier() {
+                    body.push(self.parse_if_rule()?);
                 } else if self.check(TokenType::For) {
                     body.push(self.parse_for_rule()?);
                 } else if self.check(TokenType::While) {
                     body.push(self.parse_while_rule()?);
                 } else {
-                    return Err("Unexpected token in WHILE body".to_string());
+                    return Err(format!("Unexpected token in {} body", kind));
                 }
             } else {
-                return Err("Expected '-' to start rule inside WHILE".to_string());
+                return Err(format!("Expected '-' to start rule inside {}", kind));
             }
         }
-        self.consume(TokenType::RBrace, "Expected '}' to end WHILE body")?;
+        Ok(body)
+    }
 
-        Ok(Rule::While(WhileLoop {
-            condition: condition.trim().to_string(),
-            body,
-        }))
+    // Condition grammar (tightest last):
+    //   or      := and (OR and)*
+    //   and     := not (AND not)*
+    //   not     := NOT not | BECOMES not | ('(' or ')' | compare) (FOR duration)* (UNTIL not)?
+    //   compare := operand ((< > <= >= == = !=) operand | IN operand)?
+    //   operand := term ((+ -) term)*,  term := unary ((* /) unary)*
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        let first = self.parse_condition_and()?;
+        let mut terms = vec![first];
+        while self.match_word("OR") || self.match_token(TokenType::Or) {
+            terms.push(self.parse_condition_and()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Condition::Or { terms } })
+    }
+
+    fn parse_condition_and(&mut self) -> Result<Condition, String> {
+        let first = self.parse_condition_not()?;
+        let mut terms = vec![first];
+        while self.match_word("AND") || self.match_token(TokenType::And) {
+            terms.push(self.parse_condition_not()?);
+        }
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Condition::And { terms } })
+    }
+
+    fn parse_condition_not(&mut self) -> Result<Condition, String> {
+        if self.match_word("NOT") || self.match_token(TokenType::Bang) {
+            let inner = self.parse_condition_not()?;
+            return Ok(Condition::Not { inner: Box::new(inner) });
+        }
+        if self.match_word("BECOMES") {
+            let inner = self.parse_condition_not()?;
+            return Ok(Condition::Becomes { inner: Box::new(inner) });
+        }
+        if self.check(TokenType::LParen) {
+            // `(` may open a grouped condition or an arithmetic operand such as `(a + b) > 3`
+            let saved = self.pos;
+            self.advance();
+            if let Ok(inner) = self.parse_condition() {
+                if self.match_token(TokenType::RParen) && !self.is_comparison_start() {
+                    return self.parse_temporal_postfix(inner);
+                }
+            }
+            self.pos = saved;
+        }
+        let cond = self.parse_comparison_condition()?;
+        self.parse_temporal_postfix(cond)
+    }
+
+    /// Operator temporal postfix: `cond FOR 5s` dan `set UNTIL reset`.
+    fn parse_temporal_postfix(&mut self, mut cond: Condition) -> Result<Condition, String> {
+        loop {
+            if self.check(TokenType::For) && matches!(self.peek_ahead(1).token_type, TokenType::Number(_)) {
+                self.advance();
+                let duration = self.parse_duration("FOR")?;
+                cond = Condition::Sustained { inner: Box::new(cond), duration };
+            } else if self.match_word("UNTIL") {
+                let reset = self.parse_condition_not()?;
+                return Ok(Condition::Latch { set: Box::new(cond), reset: Box::new(reset) });
+            } else {
+                return Ok(cond);
+            }
+        }
+    }
+
+    /// Durasi literal (`5s`, `500ms`, `2min`); angka tanpa satuan berarti detik.
+    fn parse_duration(&mut self, after: &str) -> Result<Operand, String> {
+        if !matches!(self.peek().token_type, TokenType::Number(_)) {
+            return Err(self.parse_error(&format!("Expected duration after {}", after)));
+        }
+        let duration = self.parse_operand_primary()?;
+        match crate::units::static_dimension(&duration)? {
+            None | Some(crate::units::Dimension::Time) => Ok(duration),
+            Some(other) => Err(self.parse_error(&format!(
+                "{} expects a duration, got {} '{}'",
+                after, other, duration
+            ))),
+        }
+    }
+
+    fn parse_comparison_condition(&mut self) -> Result<Condition, String> {
+        let left = self.parse_operand()?;
+        if self.match_token(TokenType::In) {
+            let set = self.parse_operand()?;
+            return Ok(Condition::In { value: left, set });
+        }
+        let op = match self.peek().token_type {
+            TokenType::Lt => CompareOp::Lt,
+            TokenType::Gt => CompareOp::Gt,
+            TokenType::Lte => CompareOp::Lte,
+            TokenType::Gte => CompareOp::Gte,
+            TokenType::Eq | TokenType::Assign => CompareOp::Eq,
+            TokenType::Neq => CompareOp::Neq,
+            _ => {
+                return Ok(match left {
+                    Operand::Bool { value } => Condition::Const { value },
+                    other => Condition::Truthy { value: other },
+                });
+            }
+        };
+        self.advance();
+        let right = self.parse_operand()?;
+        Ok(Condition::Compare { left, op, right })
+    }
+
+    /// Apakah token berikutnya melanjutkan operand/perbandingan. Token di baris lain
+    /// (mis. `-` pembuka item berikutnya) tidak pernah melanjutkan ekspresi.
+    fn is_comparison_start(&self) -> bool {
+        self.peek().line == self.previous().line
+            && matches!(
+                self.peek().token_type,
+                TokenType::Lt
+                    | TokenType::Gt
+                    | TokenType::Lte
+                    | TokenType::Gte
+                    | TokenType::Eq
+                    | TokenType::Assign
+                    | TokenType::Neq
+                    | TokenType::In
+                    | TokenType::Plus
+                    | TokenType::Minus
+                    | TokenType::Mul
+                    | TokenType::Div
+            )
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        let mut left = self.parse_operand_term()?;
+        loop {
+            let op = match self.peek().token_type {
+                TokenType::Plus => ArithOp::Add,
+                // A '-' at the start of a line begins the next rule, not a subtraction
+                TokenType::Minus if self.peek().line == self.previous().line => ArithOp::Sub,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_operand_term()?;
+            left = Operand::Arith { left: Box::new(left), op, right: Box::new(right) };
+        }
+        Ok(left)
+    }
+
+    fn parse_operand_term(&mut self) -> Result<Operand, String> {
+        let mut left = self.parse_operand_unary()?;
+        loop {
+            let op = match self.peek().token_type {
+                TokenType::Mul => ArithOp::Mul,
+                TokenType::Div => ArithOp::Div,
+                _ => break,
+            };
+            self.advance();
+            let right = self.parse_operand_unary()?;
+            left = Operand::Arith { left: Box::new(left), op, right: Box::new(right) };
+        }
+        Ok(left)
+    }
+
+    fn parse_operand_unary(&mut self) -> Result<Operand, String> {
+        if self.match_token(TokenType::Minus) {
+            return Ok(match self.parse_operand_unary()? {
+                Operand::Number { value, unit } => Operand::Number { value: -value, unit },
+                other => Operand::Arith {
+                    left: Box::new(Operand::Number { value: 0.0, unit: None }),
+                    op: ArithOp::Sub,
+                    right: Box::new(other),
+                },
+            });
+        }
+        self.parse_operand_primary()
+    }
+
+    fn parse_operand_primary(&mut self) -> Result<Operand, String> {
+        if self.is_at_end() {
+            return Err(self.parse_error("Unexpected end of condition"));
+        }
+        let token = self.peek().clone();
+        match &token.token_type {
+            TokenType::Number(n) => {
+                self.advance();
+                let (value, suffix) = crate::units::split_number(&token.lexeme).unwrap_or((*n, ""));
+                Ok(Operand::Number { value, unit: self.parse_unit_suffix(suffix) })
+            }
+            TokenType::String(s) => {
+                self.advance();
+                Ok(Operand::Str { value: s.clone() })
+            }
+            TokenType::True => {
+                self.advance();
+                Ok(Operand::Bool { value: true })
+            }
+            TokenType::False => {
+                self.advance();
+                Ok(Operand::Bool { value: false })
+            }
+            TokenType::LBracket => {
+                self.advance();
+                let mut items = Vec::new();
+                if !self.check(TokenType::RBracket) {
+                    loop {
+                        items.push(self.parse_operand()?);
+                        if !self.match_token(TokenType::Comma) {
+                            break;
+                        }
+                    }
+                }
+                self.consume(TokenType::RBracket, "Expected ']' to close list")?;
+                Ok(Operand::List { items })
+            }
+            TokenType::LParen => {
+                self.advance();
+                let inner = self.parse_operand()?;
+                self.consume(TokenType::RParen, "Expected ')' after expression")?;
+                Ok(inner)
+            }
+            TokenType::Ident(word)
+                if word.eq_ignore_ascii_case("COUNT") && self.check_ahead(1, TokenType::LParen) =>
+            {
+                self.advance();
+                self.advance();
+                let condition = self.parse_condition()?;
+                self.consume(TokenType::RParen, "Expected ')' after COUNT condition")?;
+                if !self.match_word("WITHIN") {
+                    return Err(self.parse_error("Expected WITHIN <duration> after COUNT(...)"));
+                }
+                let window = self.parse_duration("WITHIN")?;
+                Ok(Operand::Count { condition: Box::new(condition), window: Box::new(window) })
+            }
+            _ if token.lexeme.eq_ignore_ascii_case("match") => Err(self.parse_error(
+                "MATCH is not an expression inside a condition; write it as a `- MATCH field { ... }` rule",
+            )),
+            _ if self.is_function_call() => Err(self.parse_error(&format!(
+                "Function calls are not supported in conditions ('{}'); compute the value into a context field",
+                token.lexeme
+            ))),
+            _ => match Self::path_segment_name(&token) {
+                Some(_) => Ok(Operand::Path { path: self.parse_field_path()?, unit: None }),
+                None => Err(self.parse_error(&format!(
+                    "Unexpected token '{}' in condition",
+                    token.lexeme
+                ))),
+            },
+        }
+    }
+
+    /// `name(` atau `a::b` di posisi operand: sintaks ekspresi program, bukan field konteks.
+    fn is_function_call(&self) -> bool {
+        let next = self.peek_ahead(1);
+        Self::path_segment_name(self.peek()).is_some()
+            && next.line == self.peek().line
+            && (next.token_type == TokenType::LParen
+                || (next.token_type == TokenType::Colon && self.check_ahead(2, TokenType::Colon)))
+    }
+
+    /// Satuan setelah angka: sufiks lexeme (`30kmh`), `%` terpisah, atau
+    /// satuan majemuk `km/h` yang di-tokenize sebagai `km` `/` `h`.
+    fn parse_unit_suffix(&mut self, suffix: &str) -> Option<String> {
+        if suffix.is_empty() {
+            return self.match_token(TokenType::Percent).then(|| "%".to_string());
+        }
+        if self.check(TokenType::Div) {
+            if let TokenType::Ident(denom) = &self.peek_ahead(1).token_type {
+                let compound = format!("{}/{}", suffix, denom);
+                if let Some(def) = crate::units::lookup(&compound) {
+                    self.advance();
+                    self.advance();
+                    return Some(def.symbol.to_string());
+                }
+            }
+        }
+        Some(crate::units::lookup(suffix).map(|d| d.symbol).unwrap_or(suffix).to_string())
+    }
+
+    fn parse_field_path(&mut self) -> Result<FieldPath, String> {
+        let mut segments = Vec::new();
+        let first = self.advance().clone();
+        segments.push(PathSegment::Key(
+            Self::path_segment_name(&first).unwrap_or_default(),
+        ));
+        loop {
+            if self.check(TokenType::Dot) {
+                self.advance();
+                let seg = self.advance().clone();
+                match Self::path_segment_name(&seg) {
+                    Some(name) => segments.push(PathSegment::Key(name)),
+                    None => return Err(self.parse_error("Expected field name after '.'")),
+                }
+            } else if self.check(TokenType::LBracket) {
+                self.advance();
+                let idx = match self.advance().token_type {
+                    TokenType::Number(n) if n >= 0.0 && n.fract() == 0.0 => n as usize,
+                    _ => return Err(self.parse_error("Expected array index inside '[]'")),
+                };
+                self.consume(TokenType::RBracket, "Expected ']' after array index")?;
+                segments.push(PathSegment::Index(idx));
+            } else {
+                break;
+            }
+        }
+        Ok(FieldPath { segments })
+    }
+
+    /// Field names may collide with soft keywords (e.g. `Risk`, `Domain`); those are accepted as identifiers.
+    fn path_segment_name(token: &Token) -> Option<String> {
+        match &token.token_type {
+            TokenType::Ident(s) => {
+                let reserved = ["AND", "OR", "NOT", "BECOMES", "UNTIL", "WITHIN"].iter().any(|w| s.eq_ignore_ascii_case(w));
+                if reserved || s == "_" {
+                    None
+                } else {
+                    Some(s.clone())
+                }
+            }
+            TokenType::Primary
+            | TokenType::Secondary
+            | TokenType::Domain
+            | TokenType::Lokasi
+            | TokenType::Fase
+            | TokenType::Legal
+            | TokenType::Ethical
+            | TokenType::Technical
+            | TokenType::Benefit
+            | TokenType::Risk
+            | TokenType::TradeOff
+            | TokenType::Moral
+            | TokenType::Regulation
+            | TokenType::Evidence => Some(token.lexeme.clone()),
+            _ => None,
+        }
+    }
+
+    fn match_word(&mut self, word: &str) -> bool {
+        if matches!(self.peek().token_type, TokenType::Ident(ref s) if s.eq_ignore_ascii_case(word)) {
+            self.advance();
+            true
+        } else {
+            false
+        }
     }
 
     fn parse_constraints(&mut self) -> Result<Vec<Constraint>, String> {
@@ -426,15 +974,76 @@ impl Parser {
                 self.consume(TokenType::Colon, ":")?;
             }
 
-            let desc = self.parse_text_line()?;
+            let invariant = if self.is_invariant_start() { Some(self.parse_invariant()?) } else { None };
+            let desc = match &invariant {
+                Some(invariant) => invariant.to_string(),
+                None => self.parse_text_line()?,
+            };
             list.push(Constraint {
                 kind: kind.to_string(),
                 description: desc,
+                invariant,
             });
         }
         Ok(list)
     }
 
+    /// `NEVER cond` atau `MAX n OF [...]`; constraint lain tetap berupa teks bebas.
+    /// Kata kunci harus huruf besar agar kalimat seperti "Never share data" tetap teks.
+    fn is_invariant_start(&self) -> bool {
+        match &self.peek().token_type {
+            TokenType::Ident(s) if s == "NEVER" => true,
+            TokenType::Ident(s) if s == "MAX" => {
+                matches!(self.peek_ahead(1).token_type, TokenType::Number(_))
+            }
+            _ => false,
+        }
+    }
+
+    /// `NEVER cond [ELSE action]` atau `MAX n OF [A, B] [ELSE action]`
+    fn parse_invariant(&mut self) -> Result<Invariant, String> {
+        let line = self.peek().line;
+        let check = if self.match_word("never") {
+            InvariantCheck::Never { condition: self.parse_condition()? }
+        } else {
+            self.advance(); // MAX
+            let token = self.advance().clone();
+            let limit = match token.token_type {
+                TokenType::Number(n) if n >= 0.0 && n.fract() == 0.0 && token.lexeme.parse::<usize>().is_ok() => n as usize,
+                _ => return Err(self.parse_error(&format!("MAX expects a whole number, got '{}'", token.lexeme))),
+            };
+            if !self.match_word("of") {
+                return Err(self.parse_error("Expected OF after MAX n"));
+            }
+            self.consume(TokenType::LBracket, "Expected '[' after MAX n OF")?;
+            let mut actions = Vec::new();
+            while !self.check(TokenType::RBracket) && !self.is_at_end() {
+                match &self.peek().token_type {
+                    TokenType::Ident(name) | TokenType::String(name) => actions.push(name.clone()),
+                    _ => return Err(self.parse_error(&format!("Expected action name in MAX list, got '{}'", self.peek().lexeme))),
+                }
+                self.advance();
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RBracket, "Expected ']' after MAX action list")?;
+            if actions.is_empty() {
+                return Err(self.parse_error("MAX n OF expects at least one action"));
+            }
+            InvariantCheck::AtMost { limit, actions }
+        };
+        let fallback = if self.match_token(TokenType::Else) {
+            Some(self.parse_action(false, "ELSE")?)
+        } else {
+            None
+        };
+        if !self.is_at_end() && self.peek().line == line && !self.is_section_header(self.peek()) {
+            return Err(self.parse_error(&format!("Unexpected '{}' after constraint invariant", self.peek().lexeme)));
+        }
+        Ok(Invariant { check, fallback })
+    }
+
     fn parse_impacts(&mut self) -> Result<Vec<Impact>, String> {
         let mut list = Vec::new();
         while self.match_token(TokenType::Minus) {
@@ -513,37 +1122,255 @@ impl Parser {
         Ok(list)
     }
 
-    // Helper functions
-    fn parse_text_chunk(&mut self) -> Result<String, String> {
-        let t = self.advance();
-        match &t.token_type {
-            TokenType::Ident(s) | TokenType::String(s) => Ok(s.clone()),
-            TokenType::Number(n) => Ok(n.to_string()),
-            TokenType::Gt => Ok(">".to_string()),
-            TokenType::Lt => Ok("<".to_string()),
-            TokenType::Eq => Ok("==".to_string()),
-            TokenType::Neq => Ok("!=".to_string()),
-            TokenType::Gte => Ok(">=".to_string()),
-            TokenType::Lte => Ok("<=".to_string()),
-            TokenType::Assign => Ok("=".to_string()),
-            TokenType::Plus => Ok("+".to_string()),
-            TokenType::Minus => Ok("-".to_string()),
-            TokenType::Mul => Ok("*".to_string()),
-            TokenType::Div => Ok("/".to_string()),
-            TokenType::Percent => Ok("%".to_string()),
-            TokenType::In => Ok("IN".to_string()),
-            TokenType::LParen => Ok("(".to_string()),
-            TokenType::RParen => Ok(")".to_string()),
-            TokenType::Dot => Ok(".".to_string()),
-            TokenType::Comma => Ok(",".to_string()),
-            TokenType::LBracket => Ok("[".to_string()),
-            TokenType::RBracket => Ok("]".to_string()),
-            TokenType::Colon => Ok(":".to_string()),
-            TokenType::True => Ok("True".to_string()),
-            TokenType::False => Ok("False".to_string()),
-            _ => Ok(t.lexeme.clone()),
+    fn parse_units(&mut self) -> Result<Vec<UnitDecl>, String> {
+        let mut list = Vec::new();
+        while self.match_token(TokenType::Minus) {
+            if Self::path_segment_name(self.peek()).is_none() {
+                return Err(self.parse_error("Expected field name in UNITS"));
+            }
+            let field = self.parse_field_path()?.to_string();
+            self.consume(TokenType::Colon, "Expected ':' after field name in UNITS")?;
+            let unit = self.parse_line_lexemes();
+            if crate::units::lookup(&unit).is_none() {
+                return Err(self.parse_error(&format!("Unknown unit '{}' for field '{}'", unit, field)));
+            }
+            list.push(UnitDecl { field, unit });
+        }
+        Ok(list)
+    }
+
+    /// `- Field: type [unit] [[min, max]] [required|optional]`; field wajib secara default.
+    fn parse_schema(&mut self) -> Result<Vec<SchemaField>, String> {
+        let mut list = Vec::new();
+        while self.match_token(TokenType::Minus) {
+            if Self::path_segment_name(self.peek()).is_none() {
+                return Err(self.parse_error("Expected field name in SCHEMA"));
+            }
+            let field = self.parse_field_path()?.to_string();
+            self.consume(TokenType::Colon, "Expected ':' after field name in SCHEMA")?;
+            let line = self.previous().line;
+            if self.peek().line != line || self.is_at_end() {
+                return Err(self.parse_error(&format!("Expected a type for field '{}' in SCHEMA", field)));
+            }
+            let ty = FieldType::parse(&self.advance().lexeme.clone()).map_err(|e| self.parse_error(&e))?;
+
+            let mut unit = String::new();
+            while self.peek().line == line
+                && !self.is_at_end()
+                && !self.check(TokenType::LBracket)
+                && !self.is_presence_word()
+            {
+                unit.push_str(&self.advance().lexeme.clone());
+            }
+            let unit = if unit.is_empty() {
+                None
+            } else if !ty.is_numeric() {
+                return Err(self.parse_error(&format!("Only number fields can have a unit (field '{}')", field)));
+            } else if crate::units::lookup(&unit).is_none() {
+                return Err(self.parse_error(&format!("Unknown unit '{}' for field '{}'", unit, field)));
+            } else {
+                Some(unit)
+            };
+
+            let mut range = None;
+            if self.peek().line == line && self.match_token(TokenType::LBracket) {
+                if !ty.is_numeric() {
+                    return Err(self.parse_error(&format!("Only number fields can have a range (field '{}')", field)));
+                }
+                let min = self.parse_schema_bound()?;
+                self.consume(TokenType::Comma, "Expected ',' between SCHEMA range bounds")?;
+                let max = self.parse_schema_bound()?;
+                self.consume(TokenType::RBracket, "Expected ']' after SCHEMA range")?;
+                if min > max {
+                    return Err(self.parse_error(&format!("Empty range [{}, {}] for field '{}'", min, max, field)));
+                }
+                range = Some((min, max));
+            }
+
+            let mut required = true;
+            if self.peek().line == line && self.is_presence_word() {
+                required = self.advance().lexeme.eq_ignore_ascii_case("required");
+            }
+            if self.peek().line == line && !self.is_at_end() {
+                let unexpected = self.peek().lexeme.clone();
+                return Err(self.parse_error(&format!("Unexpected '{}' in SCHEMA entry for '{}'", unexpected, field)));
+            }
+            list.push(SchemaField { field, ty, unit, range, required });
+        }
+        Ok(list)
+    }
+
+    fn is_presence_word(&self) -> bool {
+        matches!(self.peek().token_type, TokenType::Ident(ref s)
+            if s.eq_ignore_ascii_case("required") || s.eq_ignore_ascii_case("optional"))
+    }
+
+    /// Batas range ditulis dalam satuan field, tanpa sufiks.
+    fn parse_schema_bound(&mut self) -> Result<f64, String> {
+        let negative = self.match_token(TokenType::Minus);
+        let token = self.advance().clone();
+        match token.token_type {
+            TokenType::Number(n) if token.lexeme.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) => {
+                Ok(if negative { -n } else { n })
+            }
+            TokenType::Number(_) => Err(format!(
+                "[Line {}, Col {}] SCHEMA range bounds are written in the field's unit, without a suffix",
+                token.line, token.column
+            )),
+            _ => Err(format!("[Line {}, Col {}] Expected a number in SCHEMA range", token.line, token.column)),
+        }
+    }
+
+    /// `- MaxIterations: 500` dan `- MaxTime: 2s` (angka tanpa satuan berarti detik).
+    fn parse_guard(&mut self, guard: &mut GuardDecl) -> Result<(), String> {
+        while self.match_token(TokenType::Minus) {
+            let key = self.advance().lexeme.clone();
+            self.consume(TokenType::Colon, "Expected ':' after GUARD key")?;
+            let raw = self.parse_line_lexemes();
+            match key.to_ascii_lowercase().as_str() {
+                "maxiterations" => {
+                    let n = raw
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| self.parse_error(&format!("MaxIterations expects a positive integer, got '{}'", raw)))?;
+                    guard.max_iterations = Some(n);
+                }
+                "maxtime" => {
+                    let ms = crate::units::parse_quantity(&raw)
+                        .filter(|q| q.dimension.is_none() || q.dimension == Some(crate::units::Dimension::Time))
+                        .map(|q| (q.value * 1000.0).round())
+                        .filter(|ms| *ms >= 1.0)
+                        .ok_or_else(|| self.parse_error(&format!("MaxTime expects a duration, got '{}'", raw)))?;
+                    guard.max_time_ms = Some(ms as u128);
+                }
+                _ => {
+                    return Err(self.parse_error(&format!(
+                        "Unknown GUARD key '{}' (expected MaxIterations or MaxTime)",
+                        key
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `- nama: {konteks JSON} => Aksi, ... [NOT Aksi, ...] [GUARD true|false]`.
+    /// Konteks juga boleh berupa path file JSON (string). `ONLY` sebelum daftar aksi
+    /// menuntut aksi yang muncul persis sama; `NONE` berarti tidak ada aksi sama sekali.
+    fn parse_expect(&mut self) -> Result<Vec<PolicyTest>, String> {
+        let mut tests = Vec::new();
+        while self.match_token(TokenType::Minus) {
+            let name = match &self.peek().token_type {
+                TokenType::Ident(s) | TokenType::String(s) => s.clone(),
+                _ => return Err(self.parse_error("Expected test case name")),
+            };
+            self.advance();
+            self.consume(TokenType::Colon, "Expected ':' after test case name")?;
+            let mut test = PolicyTest { name, ..PolicyTest::default() };
+            match &self.peek().token_type {
+                TokenType::LBrace => test.context = Some(self.parse_inline_json()?),
+                TokenType::String(path) => {
+                    test.context_file = Some(path.clone());
+                    self.advance();
+                }
+                _ => return Err(self.parse_error("Expected inline {...} context or context file path")),
+            }
+            self.consume(TokenType::Arrow, "Expected '=>' after test context")?;
+
+            let mut absent = false;
+            while !self.is_section_header(self.peek()) {
+                let token = self.advance().clone();
+                match &token.token_type {
+                    TokenType::Comma => {}
+                    TokenType::Ident(word) if word.eq_ignore_ascii_case("ONLY") || word.eq_ignore_ascii_case("NONE") => {
+                        test.exact = true;
+                    }
+                    TokenType::Ident(word) if word.eq_ignore_ascii_case("NOT") => absent = true,
+                    TokenType::Ident(word) if word.eq_ignore_ascii_case("GUARD") => {
+                        test.guard = Some(match self.advance().token_type {
+                            TokenType::True => true,
+                            TokenType::False => false,
+                            _ => return Err(self.parse_error("GUARD in a test case expects true or false")),
+                        });
+                    }
+                    TokenType::Ident(action) | TokenType::String(action) if absent => test.absent.push(action.clone()),
+                    TokenType::Ident(action) | TokenType::String(action) => test.expect.push(action.clone()),
+                    _ => {
+                        return Err(format!(
+                            "[Line {}, Col {}] Unexpected '{}' in test case '{}'",
+                            token.line, token.column, token.lexeme, test.name
+                        ))
+                    }
+                }
+            }
+            tests.push(test);
+        }
+        Ok(tests)
+    }
+
+    /// Baca objek JSON yang ditulis langsung di kebijakan. Kata tanpa kutip dibaca
+    /// sebagai string, dan angka bersatuan (`12bar`) sebagai string kuantitas.
+    fn parse_inline_json(&mut self) -> Result<serde_json::Value, String> {
+        let mut text = String::new();
+        let mut depth = 0usize;
+        loop {
+            if self.is_at_end() {
+                return Err(self.parse_error("Unterminated inline test context"));
+            }
+            let token = self.advance().clone();
+            match &token.token_type {
+                TokenType::LBrace | TokenType::LBracket => {
+                    depth += 1;
+                    text.push_str(&token.lexeme);
+                }
+                TokenType::RBrace | TokenType::RBracket => {
+                    depth -= 1;
+                    text.push_str(&token.lexeme);
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                TokenType::Comma | TokenType::Colon | TokenType::Minus => text.push_str(&token.lexeme),
+                TokenType::String(s) => text.push_str(&serde_json::Value::String(s.clone()).to_string()),
+                TokenType::Number(_) if token.lexeme.parse::<f64>().is_ok() => text.push_str(&token.lexeme),
+                TokenType::True => text.push_str("true"),
+                TokenType::False => text.push_str("false"),
+                TokenType::Ident(word) if word == "null" => text.push_str("null"),
+                _ => text.push_str(&serde_json::Value::String(token.lexeme.clone()).to_string()),
+            }
         }
+        serde_json::from_str(&text).map_err(|e| self.parse_error(&format!("Invalid inline test context: {}", e)))
     }
+
+    /// Rujukan kebijakan lain: satu di baris yang sama (`EXTEND: base.omni`) atau
+    /// daftar `- path` per baris. Path boleh ditulis sebagai string.
+    fn parse_policy_refs(&mut self, section: &str) -> Result<Vec<String>, String> {
+        let mut refs = Vec::new();
+        if !self.check(TokenType::Minus) && self.peek().line == self.previous().line {
+            refs.push(self.parse_line_lexemes());
+        }
+        while self.match_token(TokenType::Minus) {
+            refs.push(self.parse_line_lexemes());
+        }
+        if refs.is_empty() || refs.iter().any(|r| r.is_empty()) {
+            return Err(self.parse_error(&format!("Expected policy path in {}", section)));
+        }
+        Ok(refs)
+    }
+
+    /// Gabungkan lexeme sampai akhir baris tanpa spasi, untuk nilai seperti
+    /// `km/h` atau `deny-overrides`.
+    fn parse_line_lexemes(&mut self) -> String {
+        let line = self.peek().line;
+        let mut text = String::new();
+        while !self.is_at_end() && self.peek().line == line {
+            text.push_str(&self.advance().lexeme.clone());
+        }
+        text
+    }
+
+    // Helper functions
     fn is_section_header(&self, t: &Token) -> bool {
         matches!(
             t.token_type,
@@ -556,6 +1383,14 @@ impl Parser {
                 | TokenType::Impact
                 | TokenType::Trace
                 | TokenType::Review
+                | TokenType::Units
+                | TokenType::Schema
+                | TokenType::Guard
+                | TokenType::Expect
+                | TokenType::Combine
+                | TokenType::Imports
+                | TokenType::Extend
+                | TokenType::Capability
                 | TokenType::Minus
                 | TokenType::LBrace
                 | TokenType::RBrace
@@ -1,32 +1,109 @@
-use crate::action_abi::ActionResult;
-use crate::ast::Policy;
-use crate::evaluator::evaluate_condition;
+use crate::action_abi::{ActionCapability, ActionPayload, ActionResult};
+use crate::cache::{CacheStats, DecisionCache};
+use crate::ast::{CombiningAlgorithm, Condition, GuardDecl, Policy, RuleEffect, SchemaField};
+use crate::coverage::{Coverage, RuleCoverage};
+use crate::evaluator::{build_action_payload_in, eval_condition_in, explain_condition_in, explain_match_in};
+use crate::explain::{rule_text, temporal_scope, Branch, Explanation, LoopFrame};
+use crate::ir::{GuardMeta, GuardSource};
 use crate::error::OmniError;
 use crate::observability::format_log;
 use crate::omniroutine::{OmniRoutine, RoutineTask};
+use crate::schema::SchemaViolation;
+use crate::temporal::TemporalState;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
+use std::fmt;
 use std::fs;
-use std::time::Instant;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-// Safety guards for loop execution
-const MAX_LOOP_ITERATIONS: usize = 50; // Lowered for better demo flow
-const MAX_LOOP_TIME_MS: u128 = 1_000; // 1 second max per loop
+/// Batas guard loop untuk runtime dan IR. Section `GUARD:` kebijakan boleh
+/// mengganti batas bawaan, tetapi tidak melebihi batas tertinggi di sini.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// Batas iterasi per loop untuk kebijakan tanpa `GUARD:`.
+    pub max_loop_iterations: usize,
+    /// Batas waktu per loop (ms) untuk kebijakan tanpa `GUARD:`.
+    pub max_loop_time_ms: u128,
+    /// Batas iterasi tertinggi yang boleh diminta `GUARD:`.
+    pub max_guard_iterations: usize,
+    /// Batas waktu tertinggi (ms) yang boleh diminta `GUARD:`.
+    pub max_guard_time_ms: u128,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            max_loop_iterations: 50, // Lowered for better demo flow
+            max_loop_time_ms: 1_000,
+            max_guard_iterations: 10_000,
+            max_guard_time_ms: 10_000,
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Baca konfigurasi dari file JSON; field yang tidak ada memakai nilai bawaan.
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("cannot read runtime config: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("invalid runtime config: {}", e))
+    }
+
+    /// Batas efektif untuk kebijakan dengan section `GUARD:` `decl`.
+    pub fn guard_for(&self, decl: &GuardDecl) -> GuardMeta {
+        let iterations = decl.max_iterations.unwrap_or(self.max_loop_iterations);
+        let time_ms = decl.max_time_ms.unwrap_or(self.max_loop_time_ms);
+        let max_iterations = iterations.min(self.max_guard_iterations.max(self.max_loop_iterations));
+        let max_time_ms = time_ms.min(self.max_guard_time_ms.max(self.max_loop_time_ms));
+        let source = if decl.is_empty() {
+            GuardSource::Runtime
+        } else if max_iterations < iterations || max_time_ms < time_ms {
+            GuardSource::Clamped
+        } else {
+            GuardSource::Policy
+        };
+        GuardMeta { max_iterations, max_time_ms, source }
+    }
+}
 
 pub struct Runtime {
     context_data: Value,
+    /// Status operator temporal; bertahan antar `execute_policy` dan `update_data`.
+    temporal: Mutex<TemporalState>,
+    config: RuntimeConfig,
+    /// Cache keputusan opsional; hanya dipakai untuk kebijakan tanpa operator temporal.
+    cache: Option<Mutex<DecisionCache>>,
+}
+
+/// Status yang diteruskan ke setiap rule selama satu eksekusi kebijakan.
+struct ExecState<'a> {
+    trace_step: usize,
+    start: Instant,
+    temporal: &'a mut TemporalState,
+    /// Loop yang sedang berjalan; menentukan scope node temporal dan dicatat di penjelasan.
+    loops: Vec<LoopFrame>,
+    guard: GuardMeta,
+    /// Pencatat cakupan jika diminta, dan posisi rule yang sedang dievaluasi.
+    coverage: Option<&'a mut Coverage>,
+    path: Vec<usize>,
+}
+
+impl ExecState<'_> {
+    fn covered_rule(&mut self) -> Option<&mut RuleCoverage> {
+        self.coverage.as_deref_mut()?.rule_at(&self.path)
+    }
 }
 
-fn resolve_loop_elements(data: &Value, collection_path: &str) -> Vec<Value> {
+pub(crate) fn resolve_loop_elements(data: &Value, collection_path: &str) -> Vec<Value> {
     if let Some(arr) = resolve_array_path(data, collection_path) {
-        let capped: Vec<Value> = arr.into_iter().take(MAX_LOOP_ITERATIONS).collect();
-        return capped;
+        return arr;
     }
     // fallback to 3 null iterations to preserve legacy behavior
     vec![Value::Null, Value::Null, Value::Null]
 }
 
-fn resolve_array_path(root: &Value, path: &str) -> Option<Vec<Value>> {
+pub(crate) fn resolve_array_path(root: &Value, path: &str) -> Option<Vec<Value>> {
     let mut current = root;
     for segment in path.split('.') {
         if segment.is_empty() {
@@ -56,13 +133,181 @@ fn resolve_array_path(root: &Value, path: &str) -> Option<Vec<Value>> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Decision {
     pub actions: Vec<String>,
+    /// Payload lengkap untuk setiap entri `actions`, dengan urutan yang sama.
+    #[serde(default)]
+    pub payloads: Vec<ActionPayload>,
+    /// Alasan setiap entri `actions` dipicu, dengan urutan yang sama.
+    #[serde(default)]
+    pub explanations: Vec<Explanation>,
     pub logs: Vec<String>,
     pub guard_triggered: bool,
+    /// Batas guard yang berlaku selama eksekusi ini.
+    #[serde(default)]
+    pub guard: GuardMeta,
+    /// Koleksi FOR yang berhenti diproses karena guard; elemen sisanya tidak dievaluasi.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub truncations: Vec<Truncation>,
+    /// Invarian `CONSTRAINT:` yang dilanggar; aksinya sudah diveto atau diganti.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<ConstraintViolation>,
+    /// Aksi yang ditolak karena actor-nya tidak memiliki capability yang dibutuhkan.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unauthorized: Vec<UnauthorizedAction>,
     pub metrics: DecisionMetrics,
     pub traces: Vec<TraceEvent>,
+    #[serde(skip)]
+    pub(crate) fired: Vec<RuleFiring>,
+}
+
+/// Koleksi FOR yang tidak diproses sampai habis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Truncation {
+    pub collection: String,
+    /// Jumlah elemen koleksi.
+    pub total: usize,
+    /// Jumlah elemen yang sempat dievaluasi.
+    pub processed: usize,
+    pub limit: GuardLimit,
+}
+
+/// Invarian `CONSTRAINT:` yang dilanggar aksi hasil combining.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintViolation {
+    /// Jenis constraint: `Legal`, `Ethical`, atau `Technical`.
+    pub kind: String,
+    pub constraint: String,
+    /// Aksi yang dikeluarkan dari keputusan.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vetoed: Vec<String>,
+    /// Aksi pengganti dari `ELSE` invarian.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+impl fmt::Display for ConstraintViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} violated", self.kind, self.constraint)?;
+        if !self.vetoed.is_empty() {
+            write!(f, "; vetoed {}", self.vetoed.join(", "))?;
+        }
+        if let Some(replacement) = &self.replacement {
+            write!(f, "; replaced with {}", replacement)?;
+        }
+        Ok(())
+    }
+}
+
+/// Aksi yang ditolak otorisasi capability actor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnauthorizedAction {
+    pub action: String,
+    /// Actor dari `BY Role`, atau actor primary untuk rule tanpa `BY`.
+    pub actor: String,
+    /// Capability yang dibutuhkan aksi tetapi tidak ada di `CAN [...]` actor.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<ActionCapability>,
+    /// Actor tidak dideklarasikan di `ACTOR:`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub undeclared: bool,
+}
+
+impl fmt::Display for UnauthorizedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.undeclared {
+            return write!(f, "rejected {}; actor '{}' is not declared in ACTOR", self.action, self.actor);
+        }
+        let missing: Vec<String> = self.missing.iter().map(|c| c.to_string()).collect();
+        write!(f, "rejected {}; actor '{}' lacks {}", self.action, self.actor, missing.join(", "))
+    }
+}
+
+/// Batas guard yang menghentikan loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardLimit {
+    Iterations,
+    Time,
+}
+
+impl fmt::Display for Truncation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let limit = match self.limit {
+            GuardLimit::Iterations => "iteration limit",
+            GuardLimit::Time => "time limit",
+        };
+        write!(
+            f,
+            "FOR over '{}' stopped at {} of {} elements ({})",
+            self.collection, self.processed, self.total, limit
+        )
+    }
+}
+
+/// Aksi yang dipicu satu rule, sebelum digabung oleh combining algorithm.
+#[derive(Debug, Clone)]
+pub(crate) struct RuleFiring {
+    pub payload: ActionPayload,
+    /// Prioritas dan efek rule ikut tercatat di sini.
+    pub explanation: Explanation,
 }
 
 impl Decision {
+    pub(crate) fn new(guard: GuardMeta) -> Self {
+        Decision {
+            actions: Vec::new(),
+            payloads: Vec::new(),
+            explanations: Vec::new(),
+            logs: Vec::new(),
+            guard_triggered: false,
+            guard,
+            truncations: Vec::new(),
+            violations: Vec::new(),
+            unauthorized: Vec::new(),
+            metrics: DecisionMetrics::default(),
+            traces: Vec::new(),
+            fired: Vec::new(),
+        }
+    }
+
+    /// Catat loop FOR yang dihentikan guard sebelum koleksinya habis.
+    pub(crate) fn truncate(&mut self, collection: &str, total: usize, processed: usize, limit: GuardLimit) {
+        let truncation = Truncation { collection: collection.to_string(), total, processed, limit };
+        self.log(format!("Truncated: {}", truncation));
+        self.truncations.push(truncation);
+    }
+
+    /// Catat invarian yang dilanggar setelah aksinya diveto atau diganti.
+    pub(crate) fn violate(&mut self, violation: ConstraintViolation) {
+        self.log(format!("Constraint: {}", violation));
+        self.violations.push(violation);
+    }
+
+    /// Tolak entri keputusan ke-`index` yang tidak diotorisasi.
+    pub(crate) fn reject(&mut self, index: usize, failure: UnauthorizedAction) {
+        self.actions.remove(index);
+        self.payloads.remove(index);
+        self.explanations.remove(index);
+        self.log(format!("Unauthorized: {}", failure));
+        self.unauthorized.push(failure);
+    }
+
+    /// Keluarkan aksi dari keputusan beserta payload dan penjelasannya.
+    pub(crate) fn veto(&mut self, action: &str) {
+        self.actions.retain(|a| a != action);
+        self.payloads.retain(|p| p.action != action);
+        self.explanations.retain(|e| e.action != action);
+    }
+
+    /// Tambahkan aksi di luar rule (pengganti invarian) jika belum ada di keputusan.
+    pub(crate) fn push_action(&mut self, payload: ActionPayload, explanation: Explanation) {
+        if self.actions.contains(&payload.action) {
+            return;
+        }
+        self.actions.push(payload.action.clone());
+        self.payloads.push(payload);
+        self.explanations.push(explanation);
+    }
+
     pub fn add_action(&mut self, action: String) {
         if !self.actions.contains(&action) {
             self.actions.push(action);
@@ -72,6 +317,45 @@ impl Decision {
     pub fn log(&mut self, msg: String) {
         self.logs.push(crate::observability::format_log(&msg));
     }
+
+    pub(crate) fn fire(&mut self, payload: ActionPayload, explanation: Explanation) {
+        self.fired.push(RuleFiring { payload, explanation });
+    }
+
+    pub(crate) fn has_fired(&self, action: &str) -> bool {
+        self.fired.iter().any(|f| f.payload.action == action)
+    }
+
+    /// Isi `actions` dari aksi yang dipicu sesuai combining algorithm kebijakan.
+    pub(crate) fn apply_combining(&mut self, algorithm: CombiningAlgorithm) {
+        let keep: Vec<bool> = match algorithm {
+            CombiningAlgorithm::AllMatches => vec![true; self.fired.len()],
+            CombiningAlgorithm::FirstMatch => (0..self.fired.len()).map(|i| i == 0).collect(),
+            CombiningAlgorithm::DenyOverrides => {
+                let any_deny = self.fired.iter().any(|f| f.explanation.effect == RuleEffect::Deny);
+                self.fired
+                    .iter()
+                    .map(|f| !any_deny || f.explanation.effect == RuleEffect::Deny)
+                    .collect()
+            }
+            CombiningAlgorithm::PriorityOrdered => {
+                let top = self.fired.iter().map(|f| f.explanation.priority).max().unwrap_or(0);
+                self.fired.iter().map(|f| f.explanation.priority == top).collect()
+            }
+        };
+
+        let fired = std::mem::take(&mut self.fired);
+        for (firing, kept) in fired.iter().zip(keep) {
+            if kept {
+                self.actions.push(firing.payload.action.clone());
+                self.payloads.push(firing.payload.clone());
+                self.explanations.push(firing.explanation.clone());
+            } else {
+                self.log(format!("Combining ({}): suppressed {}", algorithm, firing.payload.action));
+            }
+        }
+        self.fired = fired;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -99,11 +383,54 @@ impl Default for Runtime {
 
 impl Runtime {
     pub fn new() -> Self {
+        Self::with_config(RuntimeConfig::default())
+    }
+
+    pub fn with_config(config: RuntimeConfig) -> Self {
         Runtime {
             context_data: Value::Object(serde_json::Map::new()),
+            temporal: Mutex::new(TemporalState::new()),
+            config,
+            cache: None,
+        }
+    }
+
+    /// Pasang cache keputusan untuk [`Runtime::execute_policy`] dan [`Runtime::execute_policy_at`].
+    pub fn enable_cache(&mut self, cache: DecisionCache) {
+        self.cache = Some(Mutex::new(cache));
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        Some(self.cache.as_ref()?.lock().unwrap_or_else(|e| e.into_inner()).stats())
+    }
+
+    /// Buang semua keputusan ter-cache untuk `policy` (dengan konfigurasi runtime ini).
+    pub fn invalidate_cached(&self, policy: &Policy) -> Result<usize, String> {
+        match &self.cache {
+            Some(cache) => {
+                let key = crate::cache::policy_key(policy, &self.config);
+                cache.lock().unwrap_or_else(|e| e.into_inner()).invalidate_policy(&key)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Ikat `name` (mis. path file kebijakan) ke versi `policy` saat ini; entri
+    /// ter-cache dari versi sebelumnya diinvalidasi.
+    pub fn bind_cached_policy(&self, name: &str, policy: &Policy) -> Result<usize, String> {
+        match &self.cache {
+            Some(cache) => {
+                let key = crate::cache::policy_key(policy, &self.config);
+                cache.lock().unwrap_or_else(|e| e.into_inner()).bind_policy(name, &key)
+            }
+            None => Ok(0),
         }
     }
 
+    pub fn config(&self) -> &RuntimeConfig {
+        &self.config
+    }
+
     /// Memuat konteks dari file JSON (map key -> number)
     pub fn load_context_from_file(&mut self, path: &str) -> Result<(), String> {
         let content = fs::read_to_string(path).map_err(|e| format!("cannot read context file: {}", e))?;
@@ -116,6 +443,16 @@ impl Runtime {
         Ok(())
     }
 
+    /// Validasi konteks saat ini terhadap `SCHEMA:` kebijakan sebelum dieksekusi.
+    pub fn validate_context(&self, schema: &[SchemaField]) -> Result<(), Vec<SchemaViolation>> {
+        let violations = crate::schema::validate(schema, &self.context_data);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
     pub fn context_data_as_string(&self) -> String {
         serde_json::to_string(&self.context_data).unwrap_or_else(|_| "{}".to_string())
     }
@@ -135,37 +472,103 @@ impl Runtime {
     /// Mengeksekusi Kebijakan terhadap data saat ini
     /// Mengembalikan daftar aksi yang harus dilakukan (Triggered Actions)
     pub fn execute_policy(&self, policy: &Policy) -> Decision {
-        let start = Instant::now();
-        let mut ctx = self.context_data.clone();
-        let mut trace_step: usize = 0;
-        let mut decision = Decision {
-            actions: Vec::new(),
-            logs: Vec::new(),
-            guard_triggered: false,
-            metrics: DecisionMetrics::default(),
-            traces: Vec::new(),
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.execute_policy_at(policy, now_ms)
+    }
+
+    /// Seperti [`Runtime::execute_policy`] dengan waktu evaluasi eksplisit (ms),
+    /// untuk simulasi dan replay operator temporal.
+    pub fn execute_policy_at(&self, policy: &Policy, now_ms: u64) -> Decision {
+        let Some(cache) = &self.cache else {
+            return self.run_policy(policy, now_ms, None);
         };
+        // Keputusan kebijakan temporal bergantung pada riwayat, bukan hanya konteks
+        if !crate::cache::is_cacheable(policy) {
+            cache.lock().unwrap_or_else(|e| e.into_inner()).record_bypass();
+            return self.run_policy(policy, now_ms, None);
+        }
+        let policy_key = crate::cache::policy_key(policy, &self.config);
+        let context_hash = crate::cache::context_hash(&self.context_data);
+        if let Some((mut decision, tier)) = cache.lock().unwrap_or_else(|e| e.into_inner()).get(&policy_key, &context_hash) {
+            decision.log(format!("CACHE hit ({})", tier));
+            return decision;
+        }
+        let mut decision = self.run_policy(policy, now_ms, None);
+        if let Err(e) = cache.lock().unwrap_or_else(|e| e.into_inner()).put(&policy_key, &context_hash, &decision) {
+            decision.log(format!("CACHE write failed: {}", e));
+        }
+        decision
+    }
+
+    /// Seperti [`Runtime::execute_policy`], sekaligus menambah hitungan rule, cabang,
+    /// badan loop, dan sub-kondisi yang dijalankan ke `coverage`.
+    pub fn execute_policy_with_coverage(&self, policy: &Policy, coverage: &mut Coverage) -> Decision {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        coverage.runs += 1;
+        self.run_policy(policy, now_ms, Some(coverage))
+    }
+
+    fn run_policy(&self, policy: &Policy, now_ms: u64, coverage: Option<&mut Coverage>) -> Decision {
+        let mut temporal = self.temporal.lock().unwrap_or_else(|e| e.into_inner());
+        temporal.begin(now_ms);
+        let guard = self.config.guard_for(&policy.guard);
+        let mut state = ExecState {
+            trace_step: 0,
+            start: Instant::now(),
+            temporal: &mut temporal,
+            loops: Vec::new(),
+            guard,
+            coverage,
+            path: Vec::new(),
+        };
+        let mut ctx = self.context_data.clone();
+        let mut decision = Decision::new(guard);
+        if guard.source == GuardSource::Clamped {
+            decision.log(format!(
+                "GUARD capped by runtime config: {} iterations, {} ms per loop",
+                guard.max_iterations, guard.max_time_ms
+            ));
+        }
 
         decision
             .logs
             .push(format_log(&format!("Context Data: {}", ctx)));
         Self::push_trace(
             &mut decision.traces,
-            &mut trace_step,
+            &mut state.trace_step,
             "start",
             "Context loaded".to_string(),
             &ctx,
-            start.elapsed().as_millis(),
+            state.start.elapsed().as_millis(),
         );
 
-        for rule in &policy.rules {
-            self.execute_rule(rule, &mut ctx, &mut decision, &mut trace_step, &start);
+        for (index, rule) in policy.rules.iter().enumerate() {
+            state.path.push(index);
+            self.execute_rule(rule, &mut ctx, &mut decision, &mut state);
+            state.path.pop();
+            if policy.combining == CombiningAlgorithm::FirstMatch && !decision.fired.is_empty() {
+                decision.log("first-match: stopping after first matching rule".to_string());
+                break;
+            }
         }
+        decision.apply_combining(policy.combining);
+        let actors = || policy.actors.iter().map(|a| (a.role.as_str(), a.primary, a.capabilities.as_deref()));
+        crate::authorization::authorize(actors(), &policy.capabilities, &mut decision);
+        let invariants = policy.constraints.iter().filter_map(|c| Some((c.kind.as_str(), c.invariant.as_ref()?)));
+        crate::invariant::enforce(invariants, &ctx, state.temporal, &mut decision);
+        // Aksi pengganti invarian dijalankan actor primary
+        crate::authorization::authorize(actors(), &policy.capabilities, &mut decision);
 
-        decision.metrics.duration_ms = start.elapsed().as_millis();
+        decision.metrics.duration_ms = state.start.elapsed().as_millis();
         Self::push_trace(
             &mut decision.traces,
-            &mut trace_step,
+            &mut state.trace_step,
             "end",
             "Policy execution finished".to_string(),
             &ctx,
@@ -174,29 +577,50 @@ impl Runtime {
         decision
     }
 
+    /// Lupakan riwayat operator temporal (FOR/BECOMES/UNTIL/COUNT).
+    pub fn reset_temporal_state(&self) {
+        self.temporal.lock().unwrap_or_else(|e| e.into_inner()).reset();
+    }
+
     fn execute_rule(
         &self,
         rule: &crate::ast::Rule,
         data: &mut Value,
         decision: &mut Decision,
-        trace_step: &mut usize,
-        start: &Instant,
+        state: &mut ExecState,
     ) {
         match rule {
             crate::ast::Rule::Standard(r) => {
                 decision.metrics.rules_evaluated += 1;
-                let is_triggered = evaluate_condition(&r.condition, data);
+                let scope = temporal_scope(&state.loops);
+                let trace = explain_condition_in(&r.condition, data, state.temporal, &scope);
+                let is_triggered = trace.result;
+                if let Some(covered) = state.covered_rule() {
+                    covered.evaluated += 1;
+                    covered.record_condition(&r.condition, &trace);
+                    covered.hit_branch(if is_triggered { 0 } else { 1 });
+                }
+                let explain = |action: &crate::ast::ActionCall, branch: Branch, loops: &[LoopFrame]| Explanation {
+                    action: action.name.clone(),
+                    rule: rule_text(&r.condition, &r.action, r.else_action.as_ref()),
+                    branch,
+                    priority: r.priority,
+                    effect: r.effect,
+                    condition: trace,
+                    loops: loops.to_vec(),
+                    actor: r.actor.clone(),
+                };
 
                 decision
                     .logs
                     .push(format_log(&format!("Checking Rule: IF {} ...", r.condition)));
                 Self::push_trace(
                     &mut decision.traces,
-                    trace_step,
+                    &mut state.trace_step,
                     "rule",
                     format!("IF {}", r.condition),
                     data,
-                    start.elapsed().as_millis(),
+                    state.start.elapsed().as_millis(),
                 );
 
                 if is_triggered {
@@ -204,77 +628,99 @@ impl Runtime {
                     decision
                         .logs
                         .push(format_log(&format!("MATCH -> THEN {}", r.action)));
-                    decision.actions.push(r.action.clone());
+                    let payload = build_action_payload_in(&r.action, data, state.temporal, &scope);
+                    decision.fire(payload, explain(&r.action, Branch::Then, &state.loops));
                     Self::push_trace(
                         &mut decision.traces,
-                        trace_step,
+                        &mut state.trace_step,
                         "action",
                         format!("Trigger action: {}", r.action),
                         data,
-                        start.elapsed().as_millis(),
+                        state.start.elapsed().as_millis(),
+                    );
+                } else if let Some(else_action) = &r.else_action {
+                    decision.metrics.actions_triggered += 1;
+                    decision
+                        .logs
+                        .push(format_log(&format!("No match -> ELSE {}", else_action)));
+                    let payload = build_action_payload_in(else_action, data, state.temporal, &scope);
+                    decision.fire(payload, explain(else_action, Branch::Else, &state.loops));
+                    Self::push_trace(
+                        &mut decision.traces,
+                        &mut state.trace_step,
+                        "action",
+                        format!("Trigger ELSE action: {}", else_action),
+                        data,
+                        state.start.elapsed().as_millis(),
                     );
                 } else {
                     decision.logs.push(format_log("No match"));
                     Self::push_trace(
                         &mut decision.traces,
-                        trace_step,
+                        &mut state.trace_step,
                         "rule",
                         "Condition not met".to_string(),
                         data,
-                        start.elapsed().as_millis(),
+                        state.start.elapsed().as_millis(),
                     );
                 }
             }
             crate::ast::Rule::For(loop_data) => {
                 decision.metrics.rules_evaluated += 1;
+                if let Some(covered) = state.covered_rule() {
+                    covered.evaluated += 1;
+                }
                 decision.logs.push(format_log(&format!(
                     "[LOOP] FOR {} IN {}",
                     loop_data.iterator, loop_data.collection
                 )));
                 Self::push_trace(
                     &mut decision.traces,
-                    trace_step,
+                    &mut state.trace_step,
                     "loop",
                     format!("FOR {} IN {}", loop_data.iterator, loop_data.collection),
                     data,
-                    start.elapsed().as_millis(),
+                    state.start.elapsed().as_millis(),
                 );
                 let start_time = Instant::now();
                 let mut iter_count = 0usize;
                 let elements = resolve_loop_elements(data, &loop_data.collection);
+                let total = elements.len();
                 for element in elements {
                     iter_count += 1;
-                    if iter_count > MAX_LOOP_ITERATIONS {
+                    if iter_count > state.guard.max_iterations {
                         decision.guard_triggered = true;
                         decision.metrics.guard_hits += 1;
                         decision.logs.push(format_log(&format!(
                             "Guard hit: loop iteration limit exceeded ({})",
-                            MAX_LOOP_ITERATIONS
+                            state.guard.max_iterations
                         )));
+                        decision.truncate(&loop_data.collection, total, iter_count - 1, GuardLimit::Iterations);
                         Self::push_trace(
                             &mut decision.traces,
-                            trace_step,
+                            &mut state.trace_step,
                             "guard",
                             "Loop iteration limit hit".to_string(),
                             data,
-                            start.elapsed().as_millis(),
+                            state.start.elapsed().as_millis(),
                         );
                         break;
                     }
-                    if start_time.elapsed().as_millis() > MAX_LOOP_TIME_MS {
+                    if start_time.elapsed().as_millis() > state.guard.max_time_ms {
                         decision.guard_triggered = true;
                         decision.metrics.guard_hits += 1;
                         decision.logs.push(format_log(&format!(
                             "Guard hit: loop time exceeded ({} ms)",
-                            MAX_LOOP_TIME_MS
+                            state.guard.max_time_ms
                         )));
+                        decision.truncate(&loop_data.collection, total, iter_count - 1, GuardLimit::Time);
                         Self::push_trace(
                             &mut decision.traces,
-                            trace_step,
+                            &mut state.trace_step,
                             "guard",
                             "Loop time limit hit".to_string(),
                             data,
-                            start.elapsed().as_millis(),
+                            state.start.elapsed().as_millis(),
                         );
                         break;
                     }
@@ -288,67 +734,78 @@ impl Runtime {
                         .push(format_log(&format!("Iteration {} for {}", iter_count, loop_data.iterator)));
                     Self::push_trace(
                         &mut decision.traces,
-                        trace_step,
+                        &mut state.trace_step,
                         "loop-iter",
                         format!("Iter {} set {}", iter_count, loop_data.iterator),
                         data,
-                        start.elapsed().as_millis(),
+                        state.start.elapsed().as_millis(),
                     );
-                    for sub_rule in &loop_data.body {
-                        self.execute_rule(sub_rule, data, decision, trace_step, start);
+                    state.loops.push(LoopFrame::for_each(
+                        &loop_data.iterator,
+                        &loop_data.collection,
+                        iter_count - 1,
+                        &element,
+                    ));
+                    if let Some(covered) = state.covered_rule() {
+                        covered.hit_branch(0);
                     }
+                    self.execute_body(&loop_data.body, data, decision, state);
+                    state.loops.pop();
                 }
             }
             crate::ast::Rule::While(loop_data) => {
                 decision.metrics.rules_evaluated += 1;
+                if let Some(covered) = state.covered_rule() {
+                    covered.evaluated += 1;
+                }
                 decision
                     .logs
                     .push(format_log(&format!("[LOOP] WHILE {}", loop_data.condition)));
                 Self::push_trace(
                     &mut decision.traces,
-                    trace_step,
+                    &mut state.trace_step,
                     "loop",
                     format!("WHILE {}", loop_data.condition),
                     data,
-                    start.elapsed().as_millis(),
+                    state.start.elapsed().as_millis(),
                 );
                 let start_time = Instant::now();
                 let mut iter_count = 0usize;
                 
                 // Guarded while loop: continue while condition holds but respect limits
-                while evaluate_condition(&loop_data.condition, data) {
+                while Self::while_condition(&loop_data.condition, data, state) {
                     iter_count += 1;
-                    if iter_count > MAX_LOOP_ITERATIONS {
+                    if iter_count > state.guard.max_iterations {
                         decision.guard_triggered = true;
                         decision.metrics.guard_hits += 1;
                         decision.logs.push(format_log(&format!(
                             "Guard hit: WHILE iteration limit exceeded ({})",
-                            MAX_LOOP_ITERATIONS
+                            state.guard.max_iterations
                         )));
                         Self::push_trace(
                             &mut decision.traces,
-                            trace_step,
+                            &mut state.trace_step,
                             "guard",
                             "WHILE iteration limit hit".to_string(),
                             data,
-                            start.elapsed().as_millis(),
+                            state.start.elapsed().as_millis(),
                         );
                         break;
                     }
-                    if start_time.elapsed().as_millis() > MAX_LOOP_TIME_MS {
+                    if start_time.elapsed().as_millis() > state.guard.max_time_ms {
                         decision.guard_triggered = true;
                         decision.metrics.guard_hits += 1;
                         decision.logs.push(format_log(&format!(
                             "Guard hit: WHILE time exceeded ({} ms)",
-                            MAX_LOOP_TIME_MS
+                            state.guard.max_time_ms
                         )));
                         Self::push_trace(
                             &mut decision.traces,
-                            trace_step,
+                            &mut state.trace_step,
                             "guard",
                             "WHILE time limit hit".to_string(),
                             data,
-                            start.elapsed().as_millis(),
+                            state.start.elapsed().as_millis(),
                         );
                         break;
                     }
@@ -357,21 +814,48 @@ impl Runtime {
                         .push(format_log(&format!("Condition met. Iteration {}", iter_count)));
                     Self::push_trace(
                         &mut decision.traces,
-                        trace_step,
+                        &mut state.trace_step,
                         "loop-iter",
                         format!("WHILE iter {}", iter_count),
                         data,
-                        start.elapsed().as_millis(),
+                        state.start.elapsed().as_millis(),
                     );
-                    for sub_rule in &loop_data.body {
-                        self.execute_rule(sub_rule, data, decision, trace_step, start);
+                    state.loops.push(LoopFrame::while_loop(&loop_data.condition, iter_count - 1));
+                    if let Some(covered) = state.covered_rule() {
+                        covered.hit_branch(0);
                     }
+                    self.execute_body(&loop_data.body, data, decision, state);
+                    state.loops.pop();
                 }
             }
             crate::ast::Rule::Match(match_rule) => {
-                self.execute_match_rule(match_rule, data, decision, trace_step, start);
+                self.execute_match_rule(match_rule, data, decision, state);
+            }
+        }
+    }
+
+    fn execute_body(&self, body: &[crate::ast::Rule], data: &mut Value, decision: &mut Decision, state: &mut ExecState) {
+        for (index, sub_rule) in body.iter().enumerate() {
+            state.path.push(index);
+            self.execute_rule(sub_rule, data, decision, state);
+            state.path.pop();
+        }
+    }
+
+    /// Kondisi WHILE; dengan pencatat cakupan, sub-kondisi dan keluarnya loop ikut dicatat.
+    fn while_condition(condition: &Condition, data: &Value, state: &mut ExecState) -> bool {
+        let scope = temporal_scope(&state.loops);
+        if state.coverage.is_none() {
+            return eval_condition_in(condition, data, state.temporal, &scope);
+        }
+        let trace = explain_condition_in(condition, data, state.temporal, &scope);
+        if let Some(covered) = state.covered_rule() {
+            covered.record_condition(condition, &trace);
+            if !trace.result {
+                covered.hit_branch(1);
             }
         }
+        trace.result
     }
 
     fn execute_match_rule(
@@ -379,35 +863,44 @@ impl Runtime {
         rule: &crate::ast::PolicyMatchRule,
         data: &mut Value,
         decision: &mut Decision,
-        trace_step: &mut usize,
-        start: &Instant,
+        state: &mut ExecState,
     ) {
         decision.metrics.rules_evaluated += 1;
         decision.logs.push(format_log(&format!("[MATCH] {}", rule.scrutinee)));
         
         Self::push_trace(
             &mut decision.traces,
-            trace_step,
+            &mut state.trace_step,
             "match",
             format!("MATCH {}", rule.scrutinee),
             data,
-            start.elapsed().as_millis(),
+            state.start.elapsed().as_millis(),
         );
 
-        for arm in &rule.arms {
-            // Check if scrutinee == pattern
-            let condition = format!("{} == {}", rule.scrutinee, arm.pattern);
-            if evaluate_condition(&condition, data) {
+        if let Some(covered) = state.covered_rule() {
+            covered.evaluated += 1;
+        }
+        let scope = temporal_scope(&state.loops);
+        for (index, arm) in rule.arms.iter().enumerate() {
+            let trace = explain_match_in(&rule.scrutinee, &arm.pattern, data, state.temporal, &scope);
+            if trace.result {
+                if let Some(covered) = state.covered_rule() {
+                    covered.hit_branch(index);
+                }
                 decision.logs.push(format_log(&format!("-> Match arm: {} => {}", arm.pattern, arm.action)));
-                decision.add_action(arm.action.clone());
+                if !decision.has_fired(&arm.action.name) {
+                    let payload = build_action_payload_in(&arm.action, data, state.temporal, &scope);
+                    let explanation = Explanation::match_arm(&rule.scrutinee, &arm.pattern, &arm.action, trace, &state.loops);
+                    decision.fire(payload, explanation);
+                }
                 
                 Self::push_trace(
                     &mut decision.traces,
-                    trace_step,
+                    &mut state.trace_step,
                     "match-hit",
                     format!("Arm matched: {}", arm.pattern),
                     data,
-                    start.elapsed().as_millis(),
+                    state.start.elapsed().as_millis(),
                 );
                 return; // Match first arm only
             }
@@ -416,11 +909,11 @@ impl Runtime {
         decision.logs.push(format_log("-> No match found"));
         Self::push_trace(
             &mut decision.traces,
-            trace_step,
+            &mut state.trace_step,
             "match-miss",
             "No arm matched".to_string(),
             data,
-            start.elapsed().as_millis(),
+            state.start.elapsed().as_millis(),
         );
     }
 
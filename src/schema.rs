@@ -0,0 +1,304 @@
+//! Skema konteks dari section `SCHEMA:`: validasi konteks sebelum eksekusi
+//! dan ekspor sebagai JSON Schema untuk produsen data di hulu.
+//!
+//! Skema bersifat ketat di tingkat atas: field konteks yang tidak dideklarasikan
+//! dilaporkan, karena salah ketik nama sensor sebaliknya hanya membuat kondisi
+//! bernilai salah.
+
+use crate::ast::{FieldType, Policy, SchemaField, UnitDecl};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Satu pelanggaran skema pada konteks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    pub field: String,
+    #[serde(flatten)]
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ViolationKind {
+    Missing {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        suggestion: Option<String>,
+    },
+    WrongType { expected: String, found: String },
+    OutOfRange { value: f64, min: f64, max: f64 },
+    Undeclared {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        suggestion: Option<String>,
+    },
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ViolationKind::Missing { suggestion } => {
+                write!(f, "missing required field '{}'", self.field)?;
+                match suggestion {
+                    Some(s) => write!(f, " (context has '{}')", s),
+                    None => Ok(()),
+                }
+            }
+            ViolationKind::WrongType { expected, found } => {
+                write!(f, "field '{}' expected {}, got {}", self.field, expected, found)
+            }
+            ViolationKind::OutOfRange { value, min, max } => {
+                write!(f, "field '{}' = {} is outside [{}, {}]", self.field, value, min, max)
+            }
+            ViolationKind::Undeclared { suggestion } => {
+                write!(f, "field '{}' is not declared in SCHEMA", self.field)?;
+                match suggestion {
+                    Some(s) => write!(f, " (did you mean '{}'?)", s),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Validasi konteks terhadap skema. Daftar kosong berarti konteks valid;
+/// kebijakan tanpa `SCHEMA:` menerima konteks apa pun.
+pub fn validate(schema: &[SchemaField], context: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    if schema.is_empty() {
+        return violations;
+    }
+    let Some(object) = context.as_object() else {
+        violations.push(SchemaViolation {
+            field: "context".to_string(),
+            kind: ViolationKind::WrongType { expected: "object".to_string(), found: type_name(context).to_string() },
+        });
+        return violations;
+    };
+
+    let undeclared: Vec<&String> = object
+        .keys()
+        .filter(|key| !schema.iter().any(|f| root(&f.field) == key.as_str()))
+        .collect();
+
+    for decl in schema {
+        let Some(value) = lookup(context, &decl.field) else {
+            if decl.required {
+                let suggestion = if decl.field.contains('.') {
+                    None
+                } else {
+                    closest(&decl.field, undeclared.iter().map(|s| s.as_str()))
+                };
+                violations.push(SchemaViolation { field: decl.field.clone(), kind: ViolationKind::Missing { suggestion } });
+            }
+            continue;
+        };
+        if !matches_type(decl.ty, value) {
+            violations.push(SchemaViolation {
+                field: decl.field.clone(),
+                kind: ViolationKind::WrongType { expected: decl.ty.to_string(), found: type_name(value).to_string() },
+            });
+            continue;
+        }
+        if let (Some((min, max)), Some(n)) = (decl.range, value.as_f64()) {
+            if n < min || n > max {
+                violations.push(SchemaViolation {
+                    field: decl.field.clone(),
+                    kind: ViolationKind::OutOfRange { value: n, min, max },
+                });
+            }
+        }
+    }
+
+    for key in undeclared {
+        let suggestion = closest(key, schema.iter().map(|f| root(&f.field)));
+        violations.push(SchemaViolation { field: key.clone(), kind: ViolationKind::Undeclared { suggestion } });
+    }
+    violations
+}
+
+/// Ekspor skema sebagai JSON Schema (draft 2020-12).
+pub fn to_json_schema(policy: &Policy) -> Value {
+    let mut root_node = object_node();
+    for decl in &policy.schema {
+        let segments: Vec<&str> = decl.field.split('.').collect();
+        let mut node = &mut root_node;
+        for (i, segment) in segments.iter().enumerate() {
+            if decl.required {
+                mark_required(node, segment);
+            }
+            let properties = node["properties"].as_object_mut().expect("object node");
+            let child = properties.entry(segment.to_string()).or_insert_with(object_node);
+            if i + 1 == segments.len() {
+                apply_field(child, decl);
+            }
+            node = child;
+        }
+    }
+    prune(&mut root_node);
+
+    let mut schema = Map::new();
+    schema.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+    if let Some(intent) = &policy.intent {
+        schema.insert("title".to_string(), json!(intent));
+    }
+    if let Value::Object(body) = root_node {
+        schema.extend(body);
+    }
+    schema.insert("additionalProperties".to_string(), json!(false));
+    Value::Object(schema)
+}
+
+/// Daftarkan satuan dari `SCHEMA:` sebagai [`UnitDecl`] dan tolak field yang dideklarasikan dua kali.
+pub(crate) fn register_units(policy: &mut Policy) -> Result<(), String> {
+    for (i, decl) in policy.schema.iter().enumerate() {
+        if policy.schema[..i].iter().any(|other| other.field == decl.field) {
+            return Err(format!("Duplicate SCHEMA field '{}'", decl.field));
+        }
+        let Some(unit) = &decl.unit else { continue };
+        match policy.units.iter().find(|u| u.field == decl.field) {
+            Some(existing) if &existing.unit != unit => {
+                return Err(format!("Conflicting units for '{}': {} and {}", decl.field, existing.unit, unit));
+            }
+            Some(_) => {}
+            None => policy.units.push(UnitDecl { field: decl.field.clone(), unit: unit.clone() }),
+        }
+    }
+    Ok(())
+}
+
+/// Gabungkan skema dari beberapa kebijakan; deklarasi identik boleh berulang.
+pub(crate) fn merge(decls: Vec<SchemaField>) -> Result<Vec<SchemaField>, String> {
+    let mut merged: Vec<SchemaField> = Vec::new();
+    for decl in decls {
+        match merged.iter().find(|d| d.field == decl.field) {
+            Some(existing) if *existing != decl => {
+                return Err(format!("Conflicting SCHEMA declarations for '{}'", decl.field));
+            }
+            Some(_) => {}
+            None => merged.push(decl),
+        }
+    }
+    Ok(merged)
+}
+
+/// Nama terdekat (jarak edit <= 2) untuk saran salah ketik.
+pub fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    candidates
+        .map(|candidate| (edit_distance(&name.to_lowercase(), &candidate.to_lowercase()), candidate))
+        .filter(|(distance, candidate)| *distance <= 2 && *distance < candidate.len())
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { prev } else { prev + 1 };
+            prev = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(prev + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn root(field: &str) -> &str {
+    field.split('.').next().unwrap_or(field)
+}
+
+fn lookup<'a>(context: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(context, |value, key| value.get(key))
+        .filter(|value| !value.is_null())
+}
+
+fn matches_type(ty: FieldType, value: &Value) -> bool {
+    match ty {
+        FieldType::Number => value.is_number(),
+        FieldType::Integer => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        FieldType::String => value.is_string(),
+        FieldType::Bool => value.is_boolean(),
+        FieldType::Array => value.is_array(),
+        FieldType::Object => value.is_object(),
+        FieldType::Any => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn object_node() -> Value {
+    json!({ "type": "object", "properties": {}, "required": [] })
+}
+
+fn mark_required(node: &mut Value, key: &str) {
+    let required = node["required"].as_array_mut().expect("object node");
+    if !required.iter().any(|r| r == key) {
+        required.push(json!(key));
+    }
+}
+
+fn apply_field(node: &mut Value, decl: &SchemaField) {
+    let map = node.as_object_mut().expect("object node");
+    match decl.ty {
+        // Field bertipe object boleh punya anak yang dideklarasikan terpisah
+        FieldType::Object => {}
+        FieldType::Any => {
+            map.remove("type");
+        }
+        FieldType::Bool => {
+            map.insert("type".to_string(), json!("boolean"));
+        }
+        ty => {
+            map.insert("type".to_string(), json!(ty.name()));
+        }
+    }
+    if let Some(unit) = &decl.unit {
+        map.insert("x-unit".to_string(), json!(unit));
+    }
+    if let Some((min, max)) = decl.range {
+        map.insert("minimum".to_string(), bound(min));
+        map.insert("maximum".to_string(), bound(max));
+    }
+}
+
+fn bound(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        json!(n as i64)
+    } else {
+        json!(n)
+    }
+}
+
+/// Buang `properties`/`required` kosong dan keduanya dari node non-object.
+fn prune(node: &mut Value) {
+    let Some(map) = node.as_object_mut() else { return };
+    let is_object = map.get("type") == Some(&json!("object"));
+    if let Some(Value::Object(properties)) = map.get_mut("properties") {
+        for child in properties.values_mut() {
+            prune(child);
+        }
+    }
+    for key in ["properties", "required"] {
+        let empty = match map.get(key) {
+            Some(Value::Object(m)) => m.is_empty(),
+            Some(Value::Array(a)) => a.is_empty(),
+            _ => false,
+        };
+        if empty || (!is_object && key == "required") {
+            map.remove(key);
+        }
+    }
+}
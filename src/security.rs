@@ -1,6 +1,17 @@
+//! Integritas dan tanda tangan file kebijakan/Program.
+//!
+//! Tanda tangan disimpan terpisah di `<file>.sig` sebagai [`SignatureManifest`]:
+//! id kunci, SHA-256 isi file, dan HMAC-SHA256 atas nama file dan hash tersebut.
+//! Kunci adalah rahasia bersama; id-nya adalah 16 karakter pertama SHA-256 kunci.
+
+use crate::stdlib::{hash_sha256, hmac_sha256, random_hex};
+use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Algoritma tanda tangan yang didukung.
+pub const SIGNATURE_ALGORITHM: &str = "hmac-sha256";
 
 /// Memvalidasi integritas file berdasarkan checksum SHA-256.
 pub fn verify_integrity(file_path: &str, expected_hash: &str) -> Result<bool, String> {
@@ -21,10 +32,148 @@ pub fn generate_checksum(content: &str) -> String {
     format!("{:x}", result)
 }
 
-/// Contoh penggunaan dalam sistem kapabilitas: 
-/// Hanya script dengan hash yang terdaftar yang boleh mengakses resource sensitif.
-pub fn is_trusted_script(file_path: &str) -> bool {
-    // Di masa depan, ini akan memeriksa database tanda tangan digital.
-    // Untuk saat ini, fungsi ini adalah placeholder untuk arsitektur Zero-Trust.
-    Path::new(file_path).exists()
+/// Hanya script dengan tanda tangan sah dari salah satu `keys` yang dipercaya.
+pub fn is_trusted_script(file_path: &str, keys: &[SigningKey]) -> bool {
+    verify_signature(Path::new(file_path), keys).is_ok()
+}
+
+/// Kunci penandatangan (rahasia bersama) beserta id-nya.
+#[derive(Clone)]
+pub struct SigningKey {
+    pub id: String,
+    secret: String,
+}
+
+impl SigningKey {
+    pub fn new(secret: &str) -> Self {
+        let secret = secret.trim().to_string();
+        SigningKey { id: hash_sha256(&secret)[..16].to_string(), secret }
+    }
+
+    /// Baca kunci dari file; spasi di awal/akhir diabaikan.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let secret = fs::read_to_string(path).map_err(|e| format!("cannot read key '{}': {}", path.display(), e))?;
+        if secret.trim().is_empty() {
+            return Err(format!("key file '{}' is empty", path.display()));
+        }
+        Ok(SigningKey::new(&secret))
+    }
+
+    /// Kunci acak baru (32 byte, hex).
+    pub fn generate() -> Result<Self, String> {
+        random_hex(32).map(|secret| SigningKey::new(&secret)).map_err(|e| e.to_string())
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    /// HMAC-SHA256 (hex) atas `message`.
+    pub fn mac(&self, message: &str) -> String {
+        hmac_sha256(&self.secret, message)
+    }
+
+    fn sign(&self, file_name: &str, sha256: &str) -> String {
+        self.mac(&format!("omnilang-signature-v1:{}:{}", file_name, sha256))
+    }
+}
+
+/// Periksa HMAC `signature` atas `message` dengan kunci `key_id` dari `keys`.
+pub fn verify_mac(keys: &[SigningKey], key_id: &str, message: &str, signature: &str) -> Result<(), String> {
+    let key = keys
+        .iter()
+        .find(|k| k.id == key_id)
+        .ok_or_else(|| format!("signed with unknown key '{}'", key_id))?;
+    if !constant_time_eq(key.mac(message).as_bytes(), signature.as_bytes()) {
+        return Err("invalid signature".to_string());
+    }
+    Ok(())
+}
+
+/// Isi file `<file>.sig`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureManifest {
+    pub algorithm: String,
+    pub key_id: String,
+    /// Nama file yang ditandatangani (tanpa direktori); file yang diganti nama harus ditandatangani ulang.
+    pub file: String,
+    pub sha256: String,
+    pub signature: String,
+}
+
+/// Path manifest tanda tangan: `boiler.omni` -> `boiler.omni.sig`.
+pub fn signature_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.as_os_str().to_owned();
+    name.push(".sig");
+    PathBuf::from(name)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+/// Tandatangani file dan tulis manifest-nya ke `<file>.sig`.
+pub fn sign_file(path: &Path, key: &SigningKey) -> Result<SignatureManifest, String> {
+    let content = fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    let sha256 = format!("{:x}", Sha256::digest(&content));
+    let name = file_name(path);
+    let manifest = SignatureManifest {
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        key_id: key.id.clone(),
+        signature: key.sign(&name, &sha256),
+        file: name,
+        sha256,
+    };
+    let sig_path = signature_path(path);
+    let text = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(&sig_path, text + "\n").map_err(|e| format!("cannot write '{}': {}", sig_path.display(), e))?;
+    Ok(manifest)
+}
+
+/// Periksa `<file>.sig`: kunci dikenal, nama file cocok, isi file tidak berubah, dan
+/// tanda tangan sah. Mengembalikan manifest jika semuanya cocok.
+pub fn verify_signature(path: &Path, keys: &[SigningKey]) -> Result<SignatureManifest, String> {
+    let content = fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    verify_content(path, &content, keys)
+}
+
+/// Baca file sekali, verifikasi tanda tangannya, dan kembalikan isi yang diverifikasi.
+/// Pemanggil harus mem-parse teks ini, bukan membaca file lagi, agar file yang
+/// diganti setelah verifikasi tidak ikut dijalankan.
+pub fn read_verified(path: &Path, keys: &[SigningKey]) -> Result<String, String> {
+    let content = fs::read(path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    verify_content(path, &content, keys)?;
+    String::from_utf8(content).map_err(|e| format!("'{}' is not valid UTF-8: {}", path.display(), e))
+}
+
+fn verify_content(path: &Path, content: &[u8], keys: &[SigningKey]) -> Result<SignatureManifest, String> {
+    let sig_path = signature_path(path);
+    if !sig_path.exists() {
+        return Err(format!("'{}' is not signed (missing {})", path.display(), sig_path.display()));
+    }
+    let text = fs::read_to_string(&sig_path).map_err(|e| format!("cannot read '{}': {}", sig_path.display(), e))?;
+    let manifest: SignatureManifest =
+        serde_json::from_str(&text).map_err(|e| format!("invalid signature file '{}': {}", sig_path.display(), e))?;
+    if manifest.algorithm != SIGNATURE_ALGORITHM {
+        return Err(format!("unsupported signature algorithm '{}'", manifest.algorithm));
+    }
+    let key = keys
+        .iter()
+        .find(|k| k.id == manifest.key_id)
+        .ok_or_else(|| format!("'{}' is signed with unknown key '{}'", path.display(), manifest.key_id))?;
+    if manifest.file != file_name(path) {
+        return Err(format!("signature is for '{}', not '{}'", manifest.file, file_name(path)));
+    }
+    if format!("{:x}", Sha256::digest(content)) != manifest.sha256 {
+        return Err(format!("'{}' was modified after signing (SHA-256 mismatch)", path.display()));
+    }
+    if !constant_time_eq(key.sign(&manifest.file, &manifest.sha256).as_bytes(), manifest.signature.as_bytes()) {
+        return Err(format!("invalid signature for '{}'", path.display()));
+    }
+    Ok(manifest)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
@@ -0,0 +1,353 @@
+//! Replay deret waktu konteks terhadap kebijakan (`omnilang simulate`).
+//!
+//! Setiap baris JSONL/CSV adalah satu *tick*: field-nya diterapkan sebagai
+//! pembaruan konteks, lalu kebijakan dieksekusi pada waktu tick tersebut sehingga
+//! operator temporal melihat riwayat yang sama seperti saat produksi.
+
+use crate::ast::Policy;
+use crate::runtime::{Runtime, RuntimeConfig};
+use crate::schema::SchemaViolation;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+/// Kolom/field waktu opsional: milidetik atau string RFC 3339.
+pub const TIMESTAMP_FIELD: &str = "timestamp";
+
+/// Satu baris deret waktu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tick {
+    pub time_ms: u64,
+    /// Pembaruan konteks; kunci bertitik (`Boiler.Temperature`) menulis field bersarang.
+    pub updates: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Simulation {
+    pub ticks: Vec<TickResult>,
+    pub transitions: Vec<Transition>,
+    pub summary: Summary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickResult {
+    pub index: usize,
+    pub time_ms: u64,
+    pub actions: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub guard_triggered: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<SchemaViolation>,
+}
+
+/// Aksi mulai aktif (`start`) atau berhenti aktif (`stop`) pada suatu tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub tick: usize,
+    pub time_ms: u64,
+    pub action: String,
+    pub kind: TransitionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionKind {
+    Start,
+    Stop,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Summary {
+    pub ticks: usize,
+    pub duration_ms: u64,
+    pub guard_hits: usize,
+    /// Jumlah tick dengan konteks yang melanggar `SCHEMA:`.
+    pub invalid_ticks: usize,
+    pub actions: BTreeMap<String, ActionStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActionStats {
+    /// Berapa kali aksi berubah dari tidak aktif menjadi aktif.
+    pub activations: usize,
+    pub active_ticks: usize,
+    /// Lama aktif, dihitung dari tick mulai sampai tick berhenti (atau tick terakhir).
+    pub active_ms: u64,
+    pub first_start_ms: u64,
+}
+
+/// Baca deret waktu dari file; format ditentukan dari ekstensi (`.csv`, selain itu JSONL).
+pub fn load_series(path: &str, interval_ms: u64) -> Result<Vec<Tick>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("cannot read time series: {}", e))?;
+    if path.to_ascii_lowercase().ends_with(".csv") {
+        parse_csv(&text, interval_ms)
+    } else {
+        parse_jsonl(&text, interval_ms)
+    }
+}
+
+/// Satu objek JSON per baris; baris kosong dilewati. Tanpa `timestamp`, tick ke-n
+/// berada pada `n * interval_ms`.
+pub fn parse_jsonl(text: &str, interval_ms: u64) -> Result<Vec<Tick>, String> {
+    let mut ticks = Vec::new();
+    for (line_no, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: Value = serde_json::from_str(line).map_err(|e| format!("line {}: invalid JSON: {}", line_no + 1, e))?;
+        let Value::Object(updates) = value else {
+            return Err(format!("line {}: expected a JSON object", line_no + 1));
+        };
+        let tick = make_tick(updates, &ticks, interval_ms).map_err(|e| format!("line {}: {}", line_no + 1, e))?;
+        ticks.push(tick);
+    }
+    Ok(ticks)
+}
+
+/// CSV dengan baris header. Sel kosong tidak memperbarui field; angka, `true`/`false`,
+/// dan angka bersatuan (`"12bar"`) tetap dibaca apa adanya oleh evaluator.
+pub fn parse_csv(text: &str, interval_ms: u64) -> Result<Vec<Tick>, String> {
+    let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let columns = split_csv_line(header).map_err(|e| format!("line 1: {}", e))?;
+    let mut ticks = Vec::new();
+    for (line_no, line) in lines {
+        let cells = split_csv_line(line).map_err(|e| format!("line {}: {}", line_no + 1, e))?;
+        if cells.len() != columns.len() {
+            return Err(format!(
+                "line {}: expected {} columns, got {}",
+                line_no + 1,
+                columns.len(),
+                cells.len()
+            ));
+        }
+        let updates: Map<String, Value> = columns
+            .iter()
+            .zip(cells)
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(column, cell)| (column.clone(), csv_value(&cell)))
+            .collect();
+        let tick = make_tick(updates, &ticks, interval_ms).map_err(|e| format!("line {}: {}", line_no + 1, e))?;
+        ticks.push(tick);
+    }
+    Ok(ticks)
+}
+
+/// Jalankan kebijakan pada setiap tick, dimulai dari konteks `base`.
+pub fn simulate(policy: &Policy, base: Value, ticks: &[Tick], config: RuntimeConfig) -> Simulation {
+    let mut runtime = Runtime::with_config(config);
+    if let Value::Object(fields) = base {
+        for (key, value) in fields {
+            runtime.update_data(&key, value);
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut transitions = Vec::new();
+    let mut summary = Summary::default();
+    let mut active: BTreeMap<String, u64> = BTreeMap::new();
+
+    for (index, tick) in ticks.iter().enumerate() {
+        for (key, value) in &tick.updates {
+            apply_update(&mut runtime, key, value.clone());
+        }
+        let violations = crate::schema::validate(&policy.schema, &runtime.context_snapshot());
+        let decision = runtime.execute_policy_at(policy, tick.time_ms);
+
+        let now: BTreeSet<String> = decision.actions.iter().cloned().collect();
+        for (action, since) in std::mem::take(&mut active) {
+            if now.contains(&action) {
+                active.insert(action, since);
+            } else {
+                let stats = summary.actions.entry(action.clone()).or_default();
+                stats.active_ms += tick.time_ms.saturating_sub(since);
+                transitions.push(Transition { tick: index, time_ms: tick.time_ms, action, kind: TransitionKind::Stop });
+            }
+        }
+        for action in &now {
+            let stats = summary.actions.entry(action.clone()).or_default();
+            stats.active_ticks += 1;
+            if !active.contains_key(action) {
+                if stats.activations == 0 {
+                    stats.first_start_ms = tick.time_ms;
+                }
+                stats.activations += 1;
+                active.insert(action.clone(), tick.time_ms);
+                transitions.push(Transition {
+                    tick: index,
+                    time_ms: tick.time_ms,
+                    action: action.clone(),
+                    kind: TransitionKind::Start,
+                });
+            }
+        }
+
+        summary.guard_hits += decision.metrics.guard_hits;
+        if !violations.is_empty() {
+            summary.invalid_ticks += 1;
+        }
+        results.push(TickResult {
+            index,
+            time_ms: tick.time_ms,
+            actions: decision.actions,
+            guard_triggered: decision.guard_triggered,
+            violations,
+        });
+    }
+
+    let end_ms = ticks.last().map(|t| t.time_ms).unwrap_or_default();
+    for (action, since) in active {
+        if let Some(stats) = summary.actions.get_mut(&action) {
+            stats.active_ms += end_ms.saturating_sub(since);
+        }
+    }
+    summary.ticks = ticks.len();
+    summary.duration_ms = end_ms.saturating_sub(ticks.first().map(|t| t.time_ms).unwrap_or_default());
+    Simulation { ticks: results, transitions, summary }
+}
+
+/// Ringkasan teks untuk CLI: transisi per tick lalu statistik per aksi.
+/// Waktu ditulis relatif terhadap tick pertama.
+pub fn render_text(simulation: &Simulation) -> String {
+    let mut out = String::new();
+    let origin = simulation.ticks.first().map(|t| t.time_ms).unwrap_or_default();
+    let _ = writeln!(out, "--- Timeline ---");
+    if simulation.transitions.is_empty() {
+        let _ = writeln!(out, "No action transitions.");
+    }
+    for t in &simulation.transitions {
+        let sign = match t.kind {
+            TransitionKind::Start => '+',
+            TransitionKind::Stop => '-',
+        };
+        let _ = writeln!(out, "[tick {:>4}] +{}ms {}{}", t.tick, t.time_ms.saturating_sub(origin), sign, t.action);
+    }
+    for tick in simulation.ticks.iter().filter(|t| !t.violations.is_empty()) {
+        for violation in &tick.violations {
+            let _ = writeln!(out, "[tick {:>4}] schema: {}", tick.index, violation);
+        }
+    }
+
+    let summary = &simulation.summary;
+    let _ = writeln!(out, "--- Summary ---");
+    let _ = writeln!(
+        out,
+        "ticks: {}, duration: {}ms, guard hits: {}, invalid ticks: {}",
+        summary.ticks, summary.duration_ms, summary.guard_hits, summary.invalid_ticks
+    );
+    for (action, stats) in &summary.actions {
+        let _ = writeln!(
+            out,
+            "  {}: {} activation(s), active {} tick(s) / {}ms, first at +{}ms",
+            action,
+            stats.activations,
+            stats.active_ticks,
+            stats.active_ms,
+            stats.first_start_ms.saturating_sub(origin)
+        );
+    }
+    out
+}
+
+/// Tick berikutnya setelah `previous`; waktu tidak boleh mundur.
+fn make_tick(mut updates: Map<String, Value>, previous: &[Tick], interval_ms: u64) -> Result<Tick, String> {
+    let time_ms = take_timestamp(&mut updates)?.unwrap_or(previous.len() as u64 * interval_ms);
+    if let Some(last) = previous.last().filter(|last| time_ms < last.time_ms) {
+        return Err(format!("tick at {}ms is earlier than the previous tick at {}ms", time_ms, last.time_ms));
+    }
+    Ok(Tick { time_ms, updates })
+}
+
+/// Ambil field `timestamp` dari pembaruan: milidetik atau string RFC 3339.
+pub(crate) fn take_timestamp(updates: &mut Map<String, Value>) -> Result<Option<u64>, String> {
+    let time_ms = match updates.remove(TIMESTAMP_FIELD) {
+        None => return Ok(None),
+        Some(Value::Number(n)) => n
+            .as_u64()
+            .or_else(|| n.as_f64().filter(|f| *f >= 0.0).map(|f| f as u64))
+            .ok_or_else(|| format!("invalid {} '{}'", TIMESTAMP_FIELD, n))?,
+        Some(Value::String(s)) => match s.parse::<u64>() {
+            Ok(ms) => ms,
+            Err(_) => {
+                let at = crate::stdlib::parse_iso8601(&s).map_err(|_| format!("invalid {} '{}'", TIMESTAMP_FIELD, s))?;
+                u64::try_from(at.unix_timestamp_nanos() / 1_000_000)
+                    .map_err(|_| format!("{} '{}' is before 1970", TIMESTAMP_FIELD, s))?
+            }
+        },
+        Some(other) => return Err(format!("invalid {} '{}'", TIMESTAMP_FIELD, other)),
+    };
+    Ok(Some(time_ms))
+}
+
+/// Terapkan satu pembaruan; `a.b.c` menulis ke objek bersarang di bawah `a`.
+fn apply_update(runtime: &mut Runtime, key: &str, value: Value) {
+    let mut context = runtime.context_snapshot();
+    let root = set_path(&mut context, key, value);
+    let updated = context.get(root).cloned().unwrap_or(Value::Null);
+    runtime.update_data(root, updated);
+}
+
+/// Tulis `value` ke `context` pada kunci bertitik `key`; mengembalikan field akarnya.
+pub(crate) fn set_path<'k>(context: &mut Value, key: &'k str, value: Value) -> &'k str {
+    let mut segments = key.split('.');
+    let root = segments.next().unwrap_or(key);
+    let Some(fields) = context.as_object_mut() else {
+        return root;
+    };
+    let mut node = fields.entry(root.to_string()).or_insert(Value::Null);
+    for segment in segments {
+        if !node.is_object() {
+            *node = Value::Object(Map::new());
+        }
+        node = node
+            .as_object_mut()
+            .expect("object node")
+            .entry(segment.to_string())
+            .or_insert(Value::Null);
+    }
+    *node = value;
+    root
+}
+
+fn csv_value(cell: &str) -> Value {
+    match cell {
+        "true" | "TRUE" | "True" => Value::Bool(true),
+        "false" | "FALSE" | "False" => Value::Bool(false),
+        _ => cell
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| cell.parse::<f64>().map(Value::from))
+            .unwrap_or_else(|_| Value::String(cell.to_string())),
+    }
+}
+
+/// Pisahkan satu baris CSV; mendukung sel ber-kutip dengan `""` sebagai kutip literal.
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if cell.trim().is_empty() => {
+                cell.clear();
+                quoted = true;
+            }
+            (',', false) => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    cells.push(cell.trim().to_string());
+    Ok(cells)
+}
@@ -0,0 +1,100 @@
+//! Status antar evaluasi untuk operator temporal: `cond FOR 5s`, `BECOMES cond`,
+//! `set UNTIL reset`, dan `COUNT(cond) WITHIN 1m`.
+//!
+//! Setiap node disimpan dengan kunci `scope + teks kondisi`; satu evaluasi kebijakan
+//! adalah satu *tick* (lihat [`TemporalState::begin`]). Node yang sama yang dievaluasi
+//! lebih dari sekali dalam satu tick (mis. kondisi WHILE) memakai hasil yang sudah ada,
+//! sehingga status hanya maju sekali per evaluasi.
+
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Default)]
+pub struct TemporalState {
+    tick: u64,
+    now_ms: u64,
+    nodes: HashMap<String, TemporalNode>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TemporalNode {
+    /// Tick terakhir node ini dievaluasi beserta hasilnya.
+    seen: Option<u64>,
+    last: f64,
+    flag: bool,
+    since: Option<u64>,
+    hits: VecDeque<u64>,
+}
+
+impl TemporalState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mulai evaluasi baru pada waktu `now_ms`. Jam yang mundur diabaikan.
+    pub fn begin(&mut self, now_ms: u64) {
+        self.tick += 1;
+        self.now_ms = self.now_ms.max(now_ms);
+    }
+
+    pub fn now_ms(&self) -> u64 {
+        self.now_ms
+    }
+
+    /// Jumlah evaluasi sejak dibuat atau di-reset.
+    pub fn ticks(&self) -> u64 {
+        self.tick
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn step(&mut self, key: &str, update: impl FnOnce(&mut TemporalNode, u64) -> f64) -> f64 {
+        let (tick, now) = (self.tick, self.now_ms);
+        let node = self.nodes.entry(key.to_string()).or_default();
+        if node.seen != Some(tick) {
+            node.last = update(node, now);
+            node.seen = Some(tick);
+        }
+        node.last
+    }
+
+    /// `FOR`: benar jika `active` sudah benar terus-menerus selama `duration_ms`.
+    pub(crate) fn sustained(&mut self, key: &str, active: bool, duration_ms: u64) -> bool {
+        self.step(key, |node, now| {
+            node.since = if active { Some(node.since.unwrap_or(now)) } else { None };
+            let held = node.since.is_some_and(|since| now - since >= duration_ms);
+            f64::from(u8::from(held))
+        }) != 0.0
+    }
+
+    /// `BECOMES`: tepi naik; evaluasi pertama dianggap berawal dari salah.
+    pub(crate) fn rising_edge(&mut self, key: &str, active: bool) -> bool {
+        self.step(key, |node, _| {
+            let rising = active && !node.flag;
+            node.flag = active;
+            f64::from(u8::from(rising))
+        }) != 0.0
+    }
+
+    /// `UNTIL`: terkunci saat `set` benar dan lepas saat `reset` benar.
+    pub(crate) fn latch(&mut self, key: &str, set: bool, reset: bool) -> bool {
+        self.step(key, |node, _| {
+            node.flag = !reset && (node.flag || set);
+            f64::from(u8::from(node.flag))
+        }) != 0.0
+    }
+
+    /// `COUNT ... WITHIN`: jumlah evaluasi dengan `hit` benar dalam `window_ms` terakhir.
+    pub(crate) fn count(&mut self, key: &str, hit: bool, window_ms: u64) -> usize {
+        self.step(key, |node, now| {
+            if hit {
+                node.hits.push_back(now);
+            }
+            while node.hits.front().is_some_and(|&t| now - t >= window_ms) {
+                node.hits.pop_front();
+            }
+            node.hits.len() as f64
+        }) as usize
+    }
+}
@@ -0,0 +1,281 @@
+//! Kasus uji kebijakan (`omnilang test`): konteks dijalankan lewat
+//! [`Runtime::execute_policy`] lalu aksinya dibandingkan dengan harapan.
+//!
+//! Kasus berasal dari section `EXPECT:`/`TEST:` dan dari file pendamping
+//! `<kebijakan>.tests.json` berisi array [`PolicyTest`].
+
+use crate::ast::{Policy, PolicyTest};
+use crate::coverage::Coverage;
+use crate::runtime::{Runtime, RuntimeConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Akhiran file pendamping: `boiler.omni` -> `boiler.tests.json`.
+pub const SIDECAR_SUFFIX: &str = ".tests.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseResult {
+    pub name: String,
+    pub actions: Vec<String>,
+    pub guard_triggered: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Hasil satu file uji untuk laporan JUnit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SuiteReport {
+    /// Path file kebijakan atau skrip Program.
+    pub name: String,
+    pub cases: Vec<CaseResult>,
+    /// File yang tidak bisa dijalankan (baca, parse, komposisi) atau skrip Program yang gagal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SuiteReport {
+    /// File dengan satu hasil: skrip Program yang lulus, atau file yang gagal dijalankan.
+    pub fn single(name: &str, outcome: Result<(), String>) -> Self {
+        match outcome {
+            Ok(()) => SuiteReport {
+                name: name.to_string(),
+                cases: vec![CaseResult { name: name.to_string(), actions: Vec::new(), guard_triggered: false, mismatches: Vec::new() }],
+                error: None,
+            },
+            Err(error) => SuiteReport { name: name.to_string(), cases: Vec::new(), error: Some(error) },
+        }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| !c.passed()).count()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.failures() == 0
+    }
+}
+
+/// Perbedaan antara hasil eksekusi dan harapan kasus uji.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mismatch {
+    Missing { action: String },
+    Unexpected { action: String },
+    Guard { expected: bool, actual: bool },
+    /// Konteks tidak bisa dimuat atau melanggar `SCHEMA:`.
+    Context { message: String },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::Missing { action } => write!(f, "- {} (expected, not triggered)", action),
+            Mismatch::Unexpected { action } => write!(f, "+ {} (triggered, not expected)", action),
+            Mismatch::Guard { expected, actual } => {
+                write!(f, "guard_triggered: expected {}, got {}", expected, actual)
+            }
+            Mismatch::Context { message } => write!(f, "context: {}", message),
+        }
+    }
+}
+
+/// Path file pendamping untuk sebuah file kebijakan.
+pub fn sidecar_path(policy_path: &Path) -> PathBuf {
+    let stem = policy_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    policy_path.with_file_name(format!("{}{}", stem, SIDECAR_SUFFIX))
+}
+
+/// Baca file pendamping jika ada; tanpa file berarti tanpa kasus.
+pub fn load_sidecar(policy_path: &Path) -> Result<Vec<PolicyTest>, String> {
+    let path = sidecar_path(policy_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("invalid test file '{}': {}", path.display(), e))
+}
+
+/// Semua kasus untuk kebijakan: `EXPECT:` lebih dulu, lalu file pendamping.
+pub fn collect_cases(policy: &Policy, policy_path: &Path) -> Result<Vec<PolicyTest>, String> {
+    let mut cases = policy.tests.clone();
+    for case in load_sidecar(policy_path)? {
+        if cases.iter().any(|c| c.name == case.name) {
+            return Err(format!("Duplicate test case '{}'", case.name));
+        }
+        cases.push(case);
+    }
+    Ok(cases)
+}
+
+/// Jalankan satu kasus pada runtime baru. `base_dir` dipakai untuk `context_file`.
+pub fn run_case(policy: &Policy, case: &PolicyTest, base_dir: &Path, config: &RuntimeConfig) -> CaseResult {
+    execute_case(policy, case, base_dir, config, None)
+}
+
+/// Seperti [`run_case`], sekaligus mencatat cakupan rule ke `coverage`.
+/// Kasus yang konteksnya gagal dimuat tidak dihitung sebagai eksekusi.
+pub fn run_case_with_coverage(
+    policy: &Policy,
+    case: &PolicyTest,
+    base_dir: &Path,
+    config: &RuntimeConfig,
+    coverage: &mut Coverage,
+) -> CaseResult {
+    execute_case(policy, case, base_dir, config, Some(coverage))
+}
+
+fn execute_case(
+    policy: &Policy,
+    case: &PolicyTest,
+    base_dir: &Path,
+    config: &RuntimeConfig,
+    coverage: Option<&mut Coverage>,
+) -> CaseResult {
+    let mut result = CaseResult { name: case.name.clone(), actions: Vec::new(), guard_triggered: false, mismatches: Vec::new() };
+    let context = match load_context(case, base_dir) {
+        Ok(context) => context,
+        Err(message) => {
+            result.mismatches.push(Mismatch::Context { message });
+            return result;
+        }
+    };
+    for violation in crate::schema::validate(&policy.schema, &context) {
+        result.mismatches.push(Mismatch::Context { message: violation.to_string() });
+    }
+
+    let mut runtime = Runtime::with_config(config.clone());
+    if let Value::Object(fields) = context {
+        for (key, value) in fields {
+            runtime.update_data(&key, value);
+        }
+    }
+    let decision = match coverage {
+        Some(coverage) => runtime.execute_policy_with_coverage(policy, coverage),
+        None => runtime.execute_policy(policy),
+    };
+
+    for action in &case.expect {
+        if !decision.actions.contains(action) {
+            result.mismatches.push(Mismatch::Missing { action: action.clone() });
+        }
+    }
+    for action in &decision.actions {
+        if case.absent.contains(action) || (case.exact && !case.expect.contains(action)) {
+            result.mismatches.push(Mismatch::Unexpected { action: action.clone() });
+        }
+    }
+    if let Some(expected) = case.guard {
+        if expected != decision.guard_triggered {
+            result.mismatches.push(Mismatch::Guard { expected, actual: decision.guard_triggered });
+        }
+    }
+    result.actions = decision.actions;
+    result.guard_triggered = decision.guard_triggered;
+    result
+}
+
+/// Laporan teks per kasus (PASS/FAIL dengan diff aksi) dan ringkasan.
+pub fn render_text(results: &[CaseResult]) -> String {
+    let mut out = String::new();
+    for result in results {
+        if result.passed() {
+            let _ = writeln!(out, "  PASS {}", result.name);
+            continue;
+        }
+        let _ = writeln!(out, "  FAIL {}", result.name);
+        let _ = writeln!(out, "       actions: [{}]", result.actions.join(", "));
+        for mismatch in &result.mismatches {
+            let _ = writeln!(out, "       {}", mismatch);
+        }
+    }
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    let _ = writeln!(out, "Result: {} passed, {} failed", results.len() - failed, failed);
+    out
+}
+
+/// Laporan JUnit XML: satu `<testsuite>` per file dan satu `<testcase>` per kasus.
+/// Kasus gagal mendapat `<failure>` berisi diff aksi; file yang tidak bisa dijalankan
+/// dilaporkan sebagai satu `<testcase>` dengan `<error>`.
+pub fn render_junit(suites: &[SuiteReport]) -> String {
+    let tests: usize = suites.iter().map(|s| s.cases.len() + usize::from(s.error.is_some())).sum();
+    let failures: usize = suites.iter().map(SuiteReport::failures).sum();
+    let errors = suites.iter().filter(|s| s.error.is_some()).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<testsuites name=\"omnilang\" tests=\"{}\" failures=\"{}\" errors=\"{}\">", tests, failures, errors);
+    for suite in suites {
+        let name = xml_escape(&suite.name);
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+            name,
+            suite.cases.len() + usize::from(suite.error.is_some()),
+            suite.failures(),
+            usize::from(suite.error.is_some())
+        );
+        for case in &suite.cases {
+            let case_name = xml_escape(&case.name);
+            if case.passed() {
+                let _ = writeln!(out, "    <testcase name=\"{}\" classname=\"{}\"/>", case_name, name);
+                continue;
+            }
+            let mismatches: Vec<String> = case.mismatches.iter().map(|m| m.to_string()).collect();
+            let _ = writeln!(out, "    <testcase name=\"{}\" classname=\"{}\">", case_name, name);
+            let _ = writeln!(
+                out,
+                "      <failure message=\"{}\">actions: [{}]\n{}</failure>",
+                xml_escape(&mismatches.join("; ")),
+                xml_escape(&case.actions.join(", ")),
+                xml_escape(&mismatches.join("\n"))
+            );
+            let _ = writeln!(out, "    </testcase>");
+        }
+        if let Some(error) = &suite.error {
+            let _ = writeln!(out, "    <testcase name=\"{}\" classname=\"{}\">", name, name);
+            let _ = writeln!(out, "      <error message=\"{}\"/>", xml_escape(error));
+            let _ = writeln!(out, "    </testcase>");
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn load_context(case: &PolicyTest, base_dir: &Path) -> Result<Value, String> {
+    let context = match (&case.context, &case.context_file) {
+        (Some(_), Some(_)) => return Err("both 'context' and 'context_file' are set".to_string()),
+        (Some(context), None) => context.clone(),
+        (None, Some(file)) => {
+            let path = base_dir.join(file);
+            let text = std::fs::read_to_string(&path).map_err(|e| format!("cannot read '{}': {}", path.display(), e))?;
+            serde_json::from_str(&text).map_err(|e| format!("invalid JSON in '{}': {}", path.display(), e))?
+        }
+        (None, None) => Value::Object(Default::default()),
+    };
+    if !context.is_object() {
+        return Err("expected a JSON object".to_string());
+    }
+    Ok(context)
+}
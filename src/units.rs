@@ -0,0 +1,396 @@
+//! Registry satuan fisik untuk kondisi kebijakan.
+//!
+//! Setiap satuan punya dimensi dan konversi linear ke satuan dasar dimensinya
+//! (`base = value * scale + offset`). Satuan dasar: `m`, `s`, `km/h`, `C`, `V`,
+//! `A`, `bar`, dan rasio (1.0 = 100%). Angka tanpa satuan dibaca dalam satuan
+//! dasar dimensi lawannya, sehingga konteks lama tetap berperilaku sama.
+
+use crate::ast::{ActionCall, ArithOp, Condition, InvariantCheck, MatchPattern, Operand, Policy, Rule};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Length,
+    Time,
+    Speed,
+    Temperature,
+    Voltage,
+    Current,
+    Pressure,
+    Ratio,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dimension::Length => "length",
+            Dimension::Time => "time",
+            Dimension::Speed => "speed",
+            Dimension::Temperature => "temperature",
+            Dimension::Voltage => "voltage",
+            Dimension::Current => "current",
+            Dimension::Pressure => "pressure",
+            Dimension::Ratio => "ratio",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitDef {
+    pub symbol: &'static str,
+    pub dimension: Dimension,
+    pub scale: f64,
+    pub offset: f64,
+}
+
+const fn unit(symbol: &'static str, dimension: Dimension, scale: f64) -> UnitDef {
+    UnitDef { symbol, dimension, scale, offset: 0.0 }
+}
+
+const UNITS: &[UnitDef] = &[
+    unit("mm", Dimension::Length, 0.001),
+    unit("cm", Dimension::Length, 0.01),
+    unit("m", Dimension::Length, 1.0),
+    unit("km", Dimension::Length, 1000.0),
+    unit("ms", Dimension::Time, 0.001),
+    unit("s", Dimension::Time, 1.0),
+    unit("min", Dimension::Time, 60.0),
+    unit("h", Dimension::Time, 3600.0),
+    unit("d", Dimension::Time, 86400.0),
+    unit("km/h", Dimension::Speed, 1.0),
+    unit("m/s", Dimension::Speed, 3.6),
+    unit("mph", Dimension::Speed, 1.609344),
+    unit("C", Dimension::Temperature, 1.0),
+    UnitDef { symbol: "F", dimension: Dimension::Temperature, scale: 5.0 / 9.0, offset: -32.0 * 5.0 / 9.0 },
+    UnitDef { symbol: "K", dimension: Dimension::Temperature, scale: 1.0, offset: -273.15 },
+    unit("mV", Dimension::Voltage, 0.001),
+    unit("V", Dimension::Voltage, 1.0),
+    unit("kV", Dimension::Voltage, 1000.0),
+    unit("mA", Dimension::Current, 0.001),
+    unit("A", Dimension::Current, 1.0),
+    unit("Pa", Dimension::Pressure, 0.00001),
+    unit("kPa", Dimension::Pressure, 0.01),
+    unit("mbar", Dimension::Pressure, 0.001),
+    unit("bar", Dimension::Pressure, 1.0),
+    unit("psi", Dimension::Pressure, 0.0689475729),
+    unit("%", Dimension::Ratio, 0.01),
+];
+
+// Ejaan alternatif -> simbol kanonik
+const ALIASES: &[(&str, &str)] = &[
+    ("kmh", "km/h"),
+    ("kph", "km/h"),
+    ("mps", "m/s"),
+    ("pct", "%"),
+    ("sec", "s"),
+    ("hr", "h"),
+    ("c", "C"),
+    ("v", "V"),
+    ("a", "A"),
+];
+
+/// Cari definisi satuan (simbol kanonik atau alias).
+pub fn lookup(symbol: &str) -> Option<&'static UnitDef> {
+    let canonical = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == symbol)
+        .map(|(_, target)| *target)
+        .unwrap_or(symbol);
+    UNITS.iter().find(|u| u.symbol == canonical)
+}
+
+/// Nilai numerik yang sudah dinormalisasi ke satuan dasar dimensinya.
+/// `dimension == None` berarti angka tanpa satuan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub dimension: Option<Dimension>,
+}
+
+impl Quantity {
+    pub fn bare(value: f64) -> Self {
+        Quantity { value, dimension: None }
+    }
+
+    /// Bangun kuantitas dari angka dan simbol satuan. Simbol yang tidak dikenal
+    /// diperlakukan sebagai label (angka tanpa dimensi).
+    pub fn from_unit(value: f64, symbol: Option<&str>) -> Self {
+        match symbol.and_then(lookup) {
+            Some(def) => Quantity {
+                value: value * def.scale + def.offset,
+                dimension: Some(def.dimension),
+            },
+            None => Quantity::bare(value),
+        }
+    }
+
+    /// Dua kuantitas bisa dibandingkan jika dimensinya sama atau salah satunya tanpa satuan.
+    pub fn compatible(&self, other: &Quantity) -> bool {
+        match (self.dimension, other.dimension) {
+            (Some(l), Some(r)) => l == r,
+            _ => true,
+        }
+    }
+}
+
+/// Pisahkan awalan numerik dari sufiks satuan, mis. `"250cm"` -> `(250.0, "cm")`.
+pub fn split_number(raw: &str) -> Option<(f64, &str)> {
+    let raw = raw.trim();
+    let bytes = raw.as_bytes();
+    let mut end = 0;
+    if end < bytes.len() && (bytes[end] == b'-' || bytes[end] == b'+') {
+        end += 1;
+    }
+    let digits_start = end;
+    while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.') {
+        end += 1;
+    }
+    if end == digits_start {
+        return None;
+    }
+    // Eksponen hanya jika diikuti digit, agar "5e" tidak menelan sufiks
+    if end < bytes.len() && (bytes[end] == b'e' || bytes[end] == b'E') {
+        let mut exp = end + 1;
+        if exp < bytes.len() && (bytes[exp] == b'-' || bytes[exp] == b'+') {
+            exp += 1;
+        }
+        if exp < bytes.len() && bytes[exp].is_ascii_digit() {
+            while exp < bytes.len() && bytes[exp].is_ascii_digit() {
+                exp += 1;
+            }
+            end = exp;
+        }
+    }
+    let value = raw[..end].parse::<f64>().ok()?;
+    Some((value, raw[end..].trim()))
+}
+
+/// Baca string konteks seperti `"1km"`, `"25%"`, atau `"42"` sebagai kuantitas.
+/// Sufiks yang bukan satuan terdaftar (mis. `"A1"`, `"3 apples"`) bukan angka.
+pub fn parse_quantity(raw: &str) -> Option<Quantity> {
+    let (value, suffix) = split_number(raw)?;
+    if suffix.is_empty() {
+        return Some(Quantity::bare(value));
+    }
+    lookup(suffix).map(|_| Quantity::from_unit(value, Some(suffix)))
+}
+
+/// Pastikan semua perbandingan di RULE membandingkan dimensi yang sama,
+/// mis. `Distance < 5s` ditolak saat parse. Dipanggil setelah [`apply_declared_units`].
+pub fn check_policy_units(policy: &Policy) -> Result<(), String> {
+    for decl in &policy.units {
+        if lookup(&decl.unit).is_none() {
+            return Err(format!("Unknown unit '{}' declared for field '{}'", decl.unit, decl.field));
+        }
+    }
+    policy.rules.iter().try_for_each(check_rule)?;
+    for invariant in policy.constraints.iter().filter_map(|c| c.invariant.as_ref()) {
+        if let InvariantCheck::Never { condition } = &invariant.check {
+            check_condition(condition)?;
+        }
+        invariant.fallback.iter().try_for_each(check_action)?;
+    }
+    Ok(())
+}
+
+fn check_rule(rule: &Rule) -> Result<(), String> {
+    match rule {
+        Rule::Standard(r) => {
+            check_condition(&r.condition)?;
+            check_action(&r.action)?;
+            r.else_action.iter().try_for_each(check_action)
+        }
+        Rule::While(w) => {
+            check_condition(&w.condition)?;
+            w.body.iter().try_for_each(check_rule)
+        }
+        Rule::For(f) => f.body.iter().try_for_each(check_rule),
+        Rule::Match(m) => {
+            for arm in &m.arms {
+                if let MatchPattern::Value { value } = &arm.pattern {
+                    check_pair(&m.scrutinee, value, &format!("MATCH {} => {}", m.scrutinee, value))?;
+                }
+                check_action(&arm.action)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn check_action(action: &ActionCall) -> Result<(), String> {
+    for param in &action.params {
+        let dimension = static_dimension(&param.value)?;
+        if let Some(d) = dimension.filter(|d| param.name == "timeout" && *d != Dimension::Time) {
+            return Err(format!(
+                "Unit mismatch in '{}': timeout must be a duration, got {}",
+                action, d
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_condition(cond: &Condition) -> Result<(), String> {
+    match cond {
+        Condition::Const { .. } => Ok(()),
+        Condition::Compare { left, right, .. } => check_pair(left, right, &cond.to_string()),
+        Condition::In { value, set } => match set {
+            Operand::List { items } => items
+                .iter()
+                .try_for_each(|item| check_pair(value, item, &cond.to_string())),
+            _ => check_pair(value, set, &cond.to_string()),
+        },
+        Condition::Truthy { value } => static_dimension(value).map(|_| ()),
+        Condition::Not { inner } => check_condition(inner),
+        Condition::And { terms } | Condition::Or { terms } => terms.iter().try_for_each(check_condition),
+        Condition::Sustained { inner, .. } | Condition::Becomes { inner } => check_condition(inner),
+        Condition::Latch { set, reset } => {
+            check_condition(set)?;
+            check_condition(reset)
+        }
+    }
+}
+
+fn check_pair(left: &Operand, right: &Operand, source: &str) -> Result<(), String> {
+    match (static_dimension(left)?, static_dimension(right)?) {
+        (Some(l), Some(r)) if l != r => Err(format!(
+            "Unit mismatch in '{}': cannot compare {} with {}",
+            source, l, r
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Dimensi operand yang bisa diketahui tanpa konteks (literal bersatuan atau
+/// field yang sudah diberi satuan dari `UNITS:`).
+pub fn static_dimension(operand: &Operand) -> Result<Option<Dimension>, String> {
+    match operand {
+        Operand::Number { unit, .. } | Operand::Path { unit, .. } => {
+            Ok(unit.as_deref().and_then(lookup).map(|u| u.dimension))
+        }
+        Operand::Arith { left, op, right } => {
+            let l = static_dimension(left)?;
+            let r = static_dimension(right)?;
+            match op {
+                ArithOp::Add | ArithOp::Sub => match (l, r) {
+                    (Some(a), Some(b)) if a != b => Err(format!(
+                        "Unit mismatch in '{}': cannot combine {} with {}",
+                        operand, a, b
+                    )),
+                    (a, b) => Ok(a.or(b)),
+                },
+                // Skala dengan angka tanpa satuan mempertahankan dimensi
+                ArithOp::Mul => Ok(match (l, r) {
+                    (Some(d), None) | (None, Some(d)) => Some(d),
+                    _ => None,
+                }),
+                ArithOp::Div => Ok(match (l, r) {
+                    (Some(d), None) => Some(d),
+                    _ => None,
+                }),
+            }
+        }
+        // Hasil COUNT adalah jumlah tanpa satuan
+        Operand::Count { condition, .. } => check_condition(condition).map(|_| None),
+        _ => Ok(None),
+    }
+}
+
+/// Tempelkan satuan deklarasi `UNITS:` ke setiap referensi field di RULE agar
+/// evaluator dan IR tidak perlu melihat deklarasi lagi.
+pub fn apply_declared_units(policy: &mut Policy) {
+    if policy.units.is_empty() {
+        return;
+    }
+    let declared: HashMap<String, String> = policy
+        .units
+        .iter()
+        .map(|d| (d.field.clone(), d.unit.clone()))
+        .collect();
+    for rule in &mut policy.rules {
+        annotate_rule(rule, &declared);
+    }
+    for invariant in policy.constraints.iter_mut().filter_map(|c| c.invariant.as_mut()) {
+        if let InvariantCheck::Never { condition } = &mut invariant.check {
+            annotate_condition(condition, &declared);
+        }
+        if let Some(fallback) = &mut invariant.fallback {
+            annotate_action(fallback, &declared);
+        }
+    }
+}
+
+fn annotate_rule(rule: &mut Rule, declared: &HashMap<String, String>) {
+    match rule {
+        Rule::Standard(r) => {
+            annotate_condition(&mut r.condition, declared);
+            annotate_action(&mut r.action, declared);
+            if let Some(else_action) = &mut r.else_action {
+                annotate_action(else_action, declared);
+            }
+        }
+        Rule::While(w) => {
+            annotate_condition(&mut w.condition, declared);
+            w.body.iter_mut().for_each(|r| annotate_rule(r, declared));
+        }
+        Rule::For(f) => f.body.iter_mut().for_each(|r| annotate_rule(r, declared)),
+        Rule::Match(m) => {
+            annotate_operand(&mut m.scrutinee, declared);
+            for arm in &mut m.arms {
+                if let MatchPattern::Value { value } = &mut arm.pattern {
+                    annotate_operand(value, declared);
+                }
+                annotate_action(&mut arm.action, declared);
+            }
+        }
+    }
+}
+
+fn annotate_action(action: &mut ActionCall, declared: &HashMap<String, String>) {
+    for param in &mut action.params {
+        annotate_operand(&mut param.value, declared);
+    }
+}
+
+fn annotate_condition(cond: &mut Condition, declared: &HashMap<String, String>) {
+    match cond {
+        Condition::Const { .. } => {}
+        Condition::Compare { left, right, .. } => {
+            annotate_operand(left, declared);
+            annotate_operand(right, declared);
+        }
+        Condition::In { value, set } => {
+            annotate_operand(value, declared);
+            annotate_operand(set, declared);
+        }
+        Condition::Truthy { value } => annotate_operand(value, declared),
+        Condition::Not { inner } => annotate_condition(inner, declared),
+        Condition::And { terms } | Condition::Or { terms } => {
+            terms.iter_mut().for_each(|t| annotate_condition(t, declared))
+        }
+        Condition::Sustained { inner, .. } | Condition::Becomes { inner } => annotate_condition(inner, declared),
+        Condition::Latch { set, reset } => {
+            annotate_condition(set, declared);
+            annotate_condition(reset, declared);
+        }
+    }
+}
+
+fn annotate_operand(operand: &mut Operand, declared: &HashMap<String, String>) {
+    match operand {
+        Operand::Path { path, unit } if unit.is_none() => {
+            *unit = declared.get(&path.to_string()).cloned();
+        }
+        Operand::List { items } => items.iter_mut().for_each(|i| annotate_operand(i, declared)),
+        Operand::Arith { left, right, .. } => {
+            annotate_operand(left, declared);
+            annotate_operand(right, declared);
+        }
+        Operand::Count { condition, .. } => annotate_condition(condition, declared),
+        _ => {}
+    }
+}
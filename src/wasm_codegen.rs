@@ -0,0 +1,1008 @@
+//! Backend WASM: menurunkan `PolicyIR` langsung ke modul WebAssembly (lewat teks
+//! WAT dan crate `wat`), tanpa toolchain Rust atau `cargo build`.
+//!
+//! Modul mengevaluasi kondisi, loop beserta guard-nya, dan `MATCH` sendiri. Konteks
+//! JSON tetap milik host dan dibaca lewat *handle* nilai. Host juga membangun
+//! payload dan penjelasan untuk setiap aksi yang dipicu modul; [`WasmHost`] adalah
+//! implementasi host yang tidak bergantung pada engine WASM tertentu.
+//!
+//! ABI versi [`ABI_VERSION`], semua import dari modul `"omnilang"`. Teks (`ptr`, `len`)
+//! adalah UTF-8 di `memory` modul; handle bernilai negatif berarti tidak ada.
+//!
+//! | Import | Tipe | Arti |
+//! |---|---|---|
+//! | `lookup(ptr, len) -> h` | `(i32 i32) -> i32` | Nilai dot-path konteks, mis. `pump.rpm` |
+//! | `kind(h) -> k` | `(i32) -> i32` | 0 tidak ada/null/objek/array, 1 angka (termasuk teks bersatuan seperti `"250cm"`), 2 boolean, 3 teks |
+//! | `number(h) -> v` | `(i32) -> f64` | Angka dalam satuan dasar; boolean menjadi 1/0 |
+//! | `dimension(h) -> d` | `(i32) -> i32` | Kode dimensi angka, 0 tanpa satuan (lihat [`dimension_code`]) |
+//! | `text_eq(a, b) -> r` | `(i32 i32) -> i32` | 1 jika dua teks sama |
+//! | `text_len(h) -> n` | `(i32) -> i32` | Panjang teks |
+//! | `intern(ptr, len) -> h` | `(i32 i32) -> i32` | Handle untuk teks literal |
+//! | `collection(ptr, len) -> h` | `(i32 i32) -> i32` | Elemen koleksi `FOR` sebagai array |
+//! | `length(h) -> n` | `(i32) -> i32` | Panjang array, -1 jika bukan array |
+//! | `element(h, i) -> h` | `(i32 i32) -> i32` | Elemen ke-`i` array |
+//! | `iteration(loop, i, h)` | `(i32 i32 i32)` | Mulai iterasi `i`; untuk `FOR`, `h` diikat ke variabel iterasi |
+//! | `leave(loop)` | `(i32)` | Iterasi selesai |
+//! | `fire(entry)` | `(i32)` | Aksi `entry` dipicu (lihat [`WasmEntry`]) |
+//! | `guard(loop, limit, total, processed)` | `(i32 i32 i32 i32)` | Guard loop terpicu; `limit` 0 iterasi, 1 waktu; `total` -1 untuk `WHILE` |
+//! | `now_ms() -> t` | `() -> f64` | Jam monotonik dalam milidetik |
+//!
+//! Export: `memory`, `evaluate() -> i32` (jumlah aksi yang dipicu), global
+//! `rules_evaluated` dan `abi_version`. Custom section `omnilang.ir` berisi IR JSON
+//! sumber modul, sehingga host bisa membangun tabel entry/loop tanpa file lain.
+//!
+//! Operator temporal (`FOR 5s`, `BECOMES`, `UNTIL`, `COUNT`) butuh status antar
+//! evaluasi dan belum didukung; kompilasi kebijakan seperti itu gagal.
+
+use crate::ast::{ActionCall, ArithOp, CompareOp, Condition, MatchPattern, Operand};
+use crate::evaluator::{build_action_payload_in, explain_condition_in, explain_match_in, resolve_field};
+use crate::explain::{rule_text, temporal_scope, Branch, Explanation, LoopFrame};
+use crate::ir::{GuardMeta, PolicyIR, RuleIR, StandardRuleIR};
+use crate::runtime::{resolve_loop_elements, Decision, GuardLimit};
+use crate::temporal::TemporalState;
+use crate::units::{parse_quantity, Dimension, Quantity};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::Instant;
+
+/// Versi ABI import/export modul.
+pub const ABI_VERSION: i32 = 1;
+/// Nama custom section berisi IR JSON.
+pub const IR_SECTION: &str = "omnilang.ir";
+
+/// Aksi yang bisa dipicu modul lewat `fire(entry)`; indeks entry sesuai urutan di sini.
+#[derive(Debug, Clone)]
+pub enum WasmEntry {
+    Rule { rule: StandardRuleIR, branch: Branch },
+    Arm { scrutinee: Operand, pattern: MatchPattern, action: ActionCall },
+}
+
+/// Loop yang dilaporkan modul lewat `iteration`, `leave`, dan `guard`.
+#[derive(Debug, Clone)]
+pub enum WasmLoop {
+    For { iterator: String, collection: String },
+    While { condition: Condition },
+}
+
+/// Hasil penurunan IR: teks WAT beserta tabel entry dan loop-nya.
+#[derive(Debug, Clone)]
+pub struct WasmModule {
+    pub wat: String,
+    pub entries: Vec<WasmEntry>,
+    pub loops: Vec<WasmLoop>,
+}
+
+/// Kode dimensi untuk import `dimension`: 0 tanpa satuan, lalu 1-8 sesuai urutan [`Dimension`].
+pub fn dimension_code(dimension: Option<Dimension>) -> i32 {
+    match dimension {
+        None => 0,
+        Some(Dimension::Length) => 1,
+        Some(Dimension::Time) => 2,
+        Some(Dimension::Speed) => 3,
+        Some(Dimension::Temperature) => 4,
+        Some(Dimension::Voltage) => 5,
+        Some(Dimension::Current) => 6,
+        Some(Dimension::Pressure) => 7,
+        Some(Dimension::Ratio) => 8,
+    }
+}
+
+/// Kompilasi IR menjadi biner WASM.
+pub fn compile_wasm(ir: &PolicyIR) -> Result<Vec<u8>, String> {
+    let module = lower(ir)?;
+    let mut bytes = wat::parse_str(&module.wat).map_err(|e| format!("wasm assembly failed: {}", e))?;
+    let ir_json = serde_json::to_string(ir).map_err(|e| format!("serialize IR failed: {}", e))?;
+    append_custom_section(&mut bytes, IR_SECTION, ir_json.as_bytes());
+    Ok(bytes)
+}
+
+/// Turunkan IR ke teks WAT.
+pub fn lower(ir: &PolicyIR) -> Result<WasmModule, String> {
+    let mut lowering = Lowering::default();
+    let first_match = ir.combining == crate::ast::CombiningAlgorithm::FirstMatch;
+    for rule in &ir.rules {
+        lowering.rule(rule)?;
+        if first_match {
+            lowering.code.push_str("(if (global.get $fired) (then (return (global.get $fired))))\n");
+        }
+    }
+    Ok(WasmModule { wat: lowering.finish(), entries: lowering.entries, loops: lowering.loops })
+}
+
+const PRELUDE: &str = r#"  (import "omnilang" "lookup" (func $lookup (param i32 i32) (result i32)))
+  (import "omnilang" "kind" (func $kind (param i32) (result i32)))
+  (import "omnilang" "number" (func $number (param i32) (result f64)))
+  (import "omnilang" "dimension" (func $dimension (param i32) (result i32)))
+  (import "omnilang" "text_eq" (func $text_eq (param i32 i32) (result i32)))
+  (import "omnilang" "text_len" (func $text_len (param i32) (result i32)))
+  (import "omnilang" "intern" (func $intern (param i32 i32) (result i32)))
+  (import "omnilang" "collection" (func $collection (param i32 i32) (result i32)))
+  (import "omnilang" "length" (func $length (param i32) (result i32)))
+  (import "omnilang" "element" (func $element (param i32 i32) (result i32)))
+  (import "omnilang" "iteration" (func $iteration (param i32 i32 i32)))
+  (import "omnilang" "leave" (func $leave (param i32)))
+  (import "omnilang" "fire" (func $fire (param i32)))
+  (import "omnilang" "guard" (func $guard (param i32 i32 i32 i32)))
+  (import "omnilang" "now_ms" (func $now_ms (result f64)))
+  (global $rules (mut i32) (i32.const 0))
+  (global $fired (mut i32) (i32.const 0))
+  (global (export "abi_version") i32 (i32.const 1))
+  (export "rules_evaluated" (global $rules))
+  (func $emit (param $entry i32)
+    (call $fire (local.get $entry))
+    (global.set $fired (i32.add (global.get $fired) (i32.const 1))))
+  ;; Sama dengan compare_atoms di evaluator. op: 0 <, 1 >, 2 <=, 3 >=, 4 ==, 5 !=
+  (func $compare (param $op i32) (param $lk i32) (param $lv f64) (param $la i32)
+                 (param $rk i32) (param $rv f64) (param $ra i32) (result i32)
+    (local $eq i32)
+    (if (i32.and (i32.eq (local.get $lk) (i32.const 1)) (i32.eq (local.get $rk) (i32.const 1)))
+      (then
+        (if (i32.and (i32.and (i32.ne (local.get $la) (i32.const 0)) (i32.ne (local.get $ra) (i32.const 0)))
+                     (i32.ne (local.get $la) (local.get $ra)))
+          (then (return (i32.const 0))))
+        (local.set $eq (f64.le (f64.abs (f64.sub (local.get $lv) (local.get $rv)))
+          (f64.mul (f64.const 1e-9)
+            (f64.max (f64.max (f64.abs (local.get $lv)) (f64.abs (local.get $rv))) (f64.const 1)))))
+        (if (i32.eq (local.get $op) (i32.const 0))
+          (then (return (i32.and (f64.lt (local.get $lv) (local.get $rv)) (i32.eqz (local.get $eq))))))
+        (if (i32.eq (local.get $op) (i32.const 1))
+          (then (return (i32.and (f64.gt (local.get $lv) (local.get $rv)) (i32.eqz (local.get $eq))))))
+        (if (i32.eq (local.get $op) (i32.const 2))
+          (then (return (i32.or (f64.lt (local.get $lv) (local.get $rv)) (local.get $eq)))))
+        (if (i32.eq (local.get $op) (i32.const 3))
+          (then (return (i32.or (f64.gt (local.get $lv) (local.get $rv)) (local.get $eq)))))
+        (if (i32.eq (local.get $op) (i32.const 4)) (then (return (local.get $eq))))
+        (return (i32.eqz (local.get $eq)))))
+    (if (i32.and (i32.eq (local.get $lk) (i32.const 2)) (i32.eq (local.get $rk) (i32.const 2)))
+      (then (local.set $eq (f64.eq (local.get $lv) (local.get $rv))))
+      (else
+        (if (i32.and (i32.eq (local.get $lk) (i32.const 3)) (i32.eq (local.get $rk) (i32.const 3)))
+          (then (local.set $eq (call $text_eq (local.get $la) (local.get $ra))))
+          (else (return (i32.const 0))))))
+    (if (i32.eq (local.get $op) (i32.const 4)) (then (return (local.get $eq))))
+    (if (i32.eq (local.get $op) (i32.const 5)) (then (return (i32.eqz (local.get $eq)))))
+    (i32.const 0))
+"#;
+
+/// Atom hasil operand di tiga local: jenis (seperti import `kind`), angka, dan
+/// dimensi (angka) atau handle (teks).
+#[derive(Clone, Copy)]
+struct AtomLocals {
+    kind: usize,
+    value: usize,
+    aux: usize,
+}
+
+#[derive(Default)]
+struct Lowering {
+    code: String,
+    /// Tipe setiap local `evaluate`, diberi nama `$l<indeks>`.
+    locals: Vec<&'static str>,
+    data: Vec<u8>,
+    strings: HashMap<String, (usize, usize)>,
+    labels: usize,
+    entries: Vec<WasmEntry>,
+    loops: Vec<WasmLoop>,
+}
+
+impl Lowering {
+    fn finish(&self) -> String {
+        let mut wat = String::from("(module\n");
+        wat.push_str(PRELUDE);
+        let pages = self.data.len() / 65536 + 1;
+        let _ = writeln!(wat, "  (memory (export \"memory\") {})", pages);
+        if !self.data.is_empty() {
+            let bytes: String = self.data.iter().map(|b| format!("\\{:02x}", b)).collect();
+            let _ = writeln!(wat, "  (data (i32.const 0) \"{}\")", bytes);
+        }
+        wat.push_str("  (func (export \"evaluate\") (result i32)\n");
+        for (index, ty) in self.locals.iter().enumerate() {
+            let _ = writeln!(wat, "    (local $l{} {})", index, ty);
+        }
+        wat.push_str("    (global.set $rules (i32.const 0))\n    (global.set $fired (i32.const 0))\n");
+        wat.push_str(&self.code);
+        wat.push_str("    (global.get $fired))\n)\n");
+        wat
+    }
+
+    fn local(&mut self, ty: &'static str) -> usize {
+        self.locals.push(ty);
+        self.locals.len() - 1
+    }
+
+    fn label(&mut self) -> String {
+        self.labels += 1;
+        format!("$b{}", self.labels)
+    }
+
+    /// Letakkan teks di data segment (sekali per teks) dan kembalikan `(ptr, len)`.
+    fn string(&mut self, text: &str) -> (usize, usize) {
+        if let Some(&slot) = self.strings.get(text) {
+            return slot;
+        }
+        let slot = (self.data.len(), text.len());
+        self.data.extend_from_slice(text.as_bytes());
+        self.strings.insert(text.to_string(), slot);
+        slot
+    }
+
+    fn emit(&mut self, line: impl AsRef<str>) {
+        self.code.push_str(line.as_ref());
+        self.code.push('\n');
+    }
+
+    fn count_rule(&mut self) {
+        self.emit("(global.set $rules (i32.add (global.get $rules) (i32.const 1)))");
+    }
+
+    fn entry(&mut self, entry: WasmEntry) -> usize {
+        self.entries.push(entry);
+        self.entries.len() - 1
+    }
+
+    fn rule(&mut self, rule: &RuleIR) -> Result<(), String> {
+        match rule {
+            RuleIR::Standard(r) => {
+                self.count_rule();
+                let result = self.condition(&r.condition)?;
+                let then_entry = self.entry(WasmEntry::Rule { rule: r.clone(), branch: Branch::Then });
+                let else_branch = match &r.else_action {
+                    Some(_) => {
+                        let else_entry = self.entry(WasmEntry::Rule { rule: r.clone(), branch: Branch::Else });
+                        format!(" (else (call $emit (i32.const {})))", else_entry)
+                    }
+                    None => String::new(),
+                };
+                self.emit(format!(
+                    "(if (local.get $l{}) (then (call $emit (i32.const {}))){})",
+                    result, then_entry, else_branch
+                ));
+            }
+            RuleIR::For(f) => {
+                self.count_rule();
+                let loop_id = self.loops.len();
+                self.loops.push(WasmLoop::For { iterator: f.iterator.clone(), collection: f.collection.clone() });
+                let (ptr, len) = self.string(&f.collection);
+                let (items, total, index, start) =
+                    (self.local("i32"), self.local("i32"), self.local("i32"), self.local("f64"));
+                let (exit, next) = (self.label(), self.label());
+                self.emit(format!("(local.set $l{} (call $collection (i32.const {}) (i32.const {})))", items, ptr, len));
+                self.emit(format!("(local.set $l{} (call $length (local.get $l{})))", total, items));
+                self.emit(format!("(local.set $l{} (i32.const 0))", index));
+                self.emit(format!("(local.set $l{} (call $now_ms))", start));
+                self.emit(format!("(block {} (loop {}", exit, next));
+                self.emit(format!("(br_if {} (i32.ge_s (local.get $l{}) (local.get $l{})))", exit, index, total));
+                self.guard_checks(loop_id, &f.guard, index, start, &format!("(local.get $l{})", total), &exit);
+                self.emit(format!(
+                    "(call $iteration (i32.const {}) (local.get $l{}) (call $element (local.get $l{}) (local.get $l{})))",
+                    loop_id, index, items, index
+                ));
+                for sub in &f.body {
+                    self.rule(sub)?;
+                }
+                self.emit(format!("(call $leave (i32.const {}))", loop_id));
+                self.emit(format!("(local.set $l{} (i32.add (local.get $l{}) (i32.const 1)))", index, index));
+                self.emit(format!("(br {})))", next));
+            }
+            RuleIR::While(w) => {
+                self.count_rule();
+                let loop_id = self.loops.len();
+                self.loops.push(WasmLoop::While { condition: w.condition.clone() });
+                let (index, start) = (self.local("i32"), self.local("f64"));
+                let (exit, next) = (self.label(), self.label());
+                self.emit(format!("(local.set $l{} (i32.const 0))", index));
+                self.emit(format!("(local.set $l{} (call $now_ms))", start));
+                self.emit(format!("(block {} (loop {}", exit, next));
+                let result = self.condition(&w.condition)?;
+                self.emit(format!("(br_if {} (i32.eqz (local.get $l{})))", exit, result));
+                self.guard_checks(loop_id, &w.guard, index, start, "(i32.const -1)", &exit);
+                self.emit(format!("(call $iteration (i32.const {}) (local.get $l{}) (i32.const -1))", loop_id, index));
+                for sub in &w.body {
+                    self.rule(sub)?;
+                }
+                self.emit(format!("(call $leave (i32.const {}))", loop_id));
+                self.emit(format!("(local.set $l{} (i32.add (local.get $l{}) (i32.const 1)))", index, index));
+                self.emit(format!("(br {})))", next));
+            }
+            RuleIR::Match(m) => {
+                self.count_rule();
+                reject_temporal_operand(&m.scrutinee)?;
+                let done = self.label();
+                self.emit(format!("(block {}", done));
+                let scrutinee = self.operand(&m.scrutinee, false)?;
+                for arm in &m.arms {
+                    let entry = self.entry(WasmEntry::Arm {
+                        scrutinee: m.scrutinee.clone(),
+                        pattern: arm.pattern.clone(),
+                        action: arm.action.clone(),
+                    });
+                    match &arm.pattern {
+                        MatchPattern::Wildcard => {
+                            self.emit(format!("(call $emit (i32.const {})) (br {})", entry, done));
+                        }
+                        MatchPattern::Value { value } => {
+                            reject_temporal_operand(value)?;
+                            let skip = self.label();
+                            self.emit(format!("(block {}", skip));
+                            self.emit(format!("(br_if {} (i32.eqz (local.get $l{})))", skip, scrutinee.kind));
+                            let pattern = self.operand(value, true)?;
+                            self.emit(format!(
+                                "(if {} (then (call $emit (i32.const {})) (br {}))))",
+                                compare_call(CompareOp::Eq, scrutinee, pattern),
+                                entry,
+                                done
+                            ));
+                        }
+                    }
+                }
+                self.emit(")");
+            }
+            RuleIR::Hoisted(block) => {
+                let result = self.condition(&block.guard)?;
+                self.emit(format!("(if (local.get $l{}) (then", result));
+                for sub in &block.body {
+                    self.rule(sub)?;
+                }
+                // Rule yang dilewati tetap dihitung, seperti di runtime
+                self.emit(format!(") (else (global.set $rules (i32.add (global.get $rules) (i32.const {})))))", block.body.len()));
+            }
+            // Tabel dispatch dievaluasi linear; hasilnya sama karena rule di dalamnya tanpa ELSE
+            RuleIR::Dispatch(dispatch) => {
+                for sub in &dispatch.rules {
+                    self.rule(sub)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Guard iterasi lalu waktu, seperti `ir_interpreter`: iterasi ke-`index + 1`
+    /// yang melewati batas menghentikan loop.
+    fn guard_checks(&mut self, loop_id: usize, guard: &GuardMeta, index: usize, start: usize, total: &str, exit: &str) {
+        let max_iterations = guard.max_iterations.min(i32::MAX as usize);
+        self.emit(format!(
+            "(if (i32.gt_u (i32.add (local.get $l{}) (i32.const 1)) (i32.const {})) (then (call $guard (i32.const {}) (i32.const 0) {} (local.get $l{})) (br {})))",
+            index, max_iterations, loop_id, total, index, exit
+        ));
+        self.emit(format!(
+            "(if (f64.gt (f64.sub (call $now_ms) (local.get $l{})) (f64.const {})) (then (call $guard (i32.const {}) (i32.const 1) {} (local.get $l{})) (br {})))",
+            start,
+            f64_literal(guard.max_time_ms as f64),
+            loop_id,
+            total,
+            index,
+            exit
+        ));
+    }
+
+    /// Tulis kode evaluasi kondisi; hasilnya (0/1) ada di local yang dikembalikan.
+    fn condition(&mut self, condition: &Condition) -> Result<usize, String> {
+        if condition.is_temporal() {
+            return Err(format!("wasm backend does not support temporal condition '{}'", condition));
+        }
+        let result = self.local("i32");
+        match condition {
+            Condition::Const { value } => self.emit(format!("(local.set $l{} (i32.const {}))", result, *value as i32)),
+            Condition::Compare { left, op, right } => {
+                let done = self.label();
+                self.emit(format!("(local.set $l{} (i32.const 0))", result));
+                self.emit(format!("(block {}", done));
+                let left = self.operand(left, false)?;
+                self.emit(format!("(br_if {} (i32.eqz (local.get $l{})))", done, left.kind));
+                let right = self.operand(right, true)?;
+                self.emit(format!("(local.set $l{} {}))", result, compare_call(*op, left, right)));
+            }
+            Condition::In { value, set } => {
+                let done = self.label();
+                self.emit(format!("(local.set $l{} (i32.const 0))", result));
+                self.emit(format!("(block {}", done));
+                let left = self.operand(value, false)?;
+                self.emit(format!("(br_if {} (i32.eqz (local.get $l{})))", done, left.kind));
+                match set {
+                    Operand::List { items } => {
+                        for item in items {
+                            let item = self.operand(item, true)?;
+                            self.emit(format!("(local.set $l{} {})", result, compare_call(CompareOp::Eq, left, item)));
+                            self.emit(format!("(br_if {} (local.get $l{}))", done, result));
+                        }
+                    }
+                    Operand::Path { path, .. } => self.in_array(path, set, left, result, &done)?,
+                    other => {
+                        let item = self.operand(other, true)?;
+                        self.emit(format!("(local.set $l{} {})", result, compare_call(CompareOp::Eq, left, item)));
+                    }
+                }
+                self.emit(")");
+            }
+            Condition::Truthy { value } => {
+                let atom = self.operand(value, false)?;
+                self.emit(format!(
+                    "(local.set $l{r} (if (result i32) (i32.eq (local.get $l{k}) (i32.const 3)) \
+                     (then (i32.gt_s (call $text_len (local.get $l{a})) (i32.const 0))) \
+                     (else (i32.and (i32.ne (local.get $l{k}) (i32.const 0)) (f64.ne (local.get $l{v}) (f64.const 0))))))",
+                    r = result,
+                    k = atom.kind,
+                    v = atom.value,
+                    a = atom.aux
+                ));
+            }
+            Condition::Not { inner } => {
+                let inner = self.condition(inner)?;
+                self.emit(format!("(local.set $l{} (i32.eqz (local.get $l{})))", result, inner));
+            }
+            Condition::And { terms } | Condition::Or { terms } => {
+                let is_and = matches!(condition, Condition::And { .. });
+                let done = self.label();
+                self.emit(format!("(local.set $l{} (i32.const {}))", result, is_and as i32));
+                self.emit(format!("(block {}", done));
+                for term in terms {
+                    let term = self.condition(term)?;
+                    self.emit(format!("(local.set $l{} (local.get $l{}))", result, term));
+                    let test = if is_and { format!("(i32.eqz (local.get $l{}))", result) } else { format!("(local.get $l{})", result) };
+                    self.emit(format!("(br_if {} {})", done, test));
+                }
+                self.emit(")");
+            }
+            Condition::Sustained { .. } | Condition::Becomes { .. } | Condition::Latch { .. } => unreachable!(),
+        }
+        Ok(result)
+    }
+
+    /// `value IN Path`: elemen array dibandingkan apa adanya; selain array, `Path`
+    /// dibandingkan sebagai referensi biasa.
+    fn in_array(&mut self, path: &crate::ast::FieldPath, set: &Operand, left: AtomLocals, result: usize, done: &str) -> Result<(), String> {
+        let (ptr, len) = self.string(&path.to_string());
+        let (array, total, index, element) = (self.local("i32"), self.local("i32"), self.local("i32"), self.local("i32"));
+        let item = AtomLocals { kind: self.local("i32"), value: self.local("f64"), aux: self.local("i32") };
+        let (not_array, next) = (self.label(), self.label());
+        self.emit(format!("(local.set $l{} (call $lookup (i32.const {}) (i32.const {})))", array, ptr, len));
+        self.emit(format!("(local.set $l{} (if (result i32) (i32.ge_s (local.get $l{a}) (i32.const 0)) (then (call $length (local.get $l{a}))) (else (i32.const -1))))", total, a = array));
+        self.emit(format!("(block {}", not_array));
+        self.emit(format!("(br_if {} (i32.lt_s (local.get $l{}) (i32.const 0)))", not_array, total));
+        self.emit(format!("(local.set $l{} (i32.const 0))", index));
+        self.emit(format!("(loop {}", next));
+        self.emit(format!("(br_if {} (i32.ge_s (local.get $l{}) (local.get $l{})))", done, index, total));
+        self.emit(format!("(local.set $l{} (call $element (local.get $l{}) (local.get $l{})))", element, array, index));
+        self.load_handle(element, item, None);
+        self.emit(format!("(local.set $l{} {})", result, compare_call(CompareOp::Eq, left, item)));
+        self.emit(format!("(br_if {} (local.get $l{}))", done, result));
+        self.emit(format!("(local.set $l{} (i32.add (local.get $l{}) (i32.const 1)))", index, index));
+        self.emit(format!("(br {})))", next));
+        let reference = self.operand(set, true)?;
+        self.emit(format!("(local.set $l{} {})", result, compare_call(CompareOp::Eq, left, reference)));
+        Ok(())
+    }
+
+    /// Isi atom dari handle konteks; `unit` adalah satuan deklarasi `UNITS:` field.
+    fn load_handle(&mut self, handle: usize, atom: AtomLocals, unit: Option<&str>) {
+        self.emit(format!("(local.set $l{} (i32.const 0))", atom.kind));
+        self.emit(format!("(local.set $l{} (f64.const 0))", atom.value));
+        self.emit(format!("(local.set $l{} (i32.const 0))", atom.aux));
+        let mut number = format!(
+            "(local.set $l{v} (call $number (local.get $l{h}))) (local.set $l{a} (call $dimension (local.get $l{h})))",
+            v = atom.value,
+            a = atom.aux,
+            h = handle
+        );
+        // Angka konteks tanpa satuan memakai satuan deklarasi (skala dan offset)
+        let declared = Quantity::from_unit(0.0, unit);
+        if declared.dimension.is_some() {
+            let scale = Quantity::from_unit(1.0, unit).value - declared.value;
+            let _ = write!(
+                number,
+                " (if (i32.eqz (local.get $l{a})) (then (local.set $l{v} (f64.add (f64.mul (local.get $l{v}) (f64.const {s})) (f64.const {o}))) (local.set $l{a} (i32.const {d}))))",
+                a = atom.aux,
+                v = atom.value,
+                s = f64_literal(scale),
+                o = f64_literal(declared.value),
+                d = dimension_code(declared.dimension)
+            );
+        }
+        self.emit(format!(
+            "(if (i32.ge_s (local.get $l{h}) (i32.const 0)) (then (local.set $l{k} (call $kind (local.get $l{h}))) \
+             (if (i32.eq (local.get $l{k}) (i32.const 1)) (then {number})) \
+             (if (i32.eq (local.get $l{k}) (i32.const 2)) (then (local.set $l{v} (call $number (local.get $l{h}))))) \
+             (if (i32.eq (local.get $l{k}) (i32.const 3)) (then (local.set $l{a} (local.get $l{h}))))))",
+            h = handle,
+            k = atom.kind,
+            v = atom.value,
+            a = atom.aux,
+            number = number
+        ));
+    }
+
+    /// Tulis kode evaluasi operand. `reference` mengikuti sisi kanan perbandingan:
+    /// nama yang tidak ada di konteks menjadi teks simbolik.
+    fn operand(&mut self, operand: &Operand, reference: bool) -> Result<AtomLocals, String> {
+        reject_temporal_operand(operand)?;
+        let atom = AtomLocals { kind: self.local("i32"), value: self.local("f64"), aux: self.local("i32") };
+        let set = |kind: i32, value: f64, aux: &str| {
+            format!(
+                "(local.set $l{} (i32.const {})) (local.set $l{} (f64.const {})) (local.set $l{} {})",
+                atom.kind,
+                kind,
+                atom.value,
+                f64_literal(value),
+                atom.aux,
+                aux
+            )
+        };
+        match operand {
+            Operand::Number { value, unit } => {
+                let q = Quantity::from_unit(*value, unit.as_deref());
+                let code = set(1, q.value, &format!("(i32.const {})", dimension_code(q.dimension)));
+                self.emit(code);
+            }
+            Operand::Bool { value } => {
+                let code = set(2, *value as i32 as f64, "(i32.const 0)");
+                self.emit(code);
+            }
+            Operand::Str { value } => {
+                let code = match parse_quantity(value) {
+                    Some(q) => set(1, q.value, &format!("(i32.const {})", dimension_code(q.dimension))),
+                    None => {
+                        let (ptr, len) = self.string(value);
+                        set(3, 0.0, &format!("(call $intern (i32.const {}) (i32.const {}))", ptr, len))
+                    }
+                };
+                self.emit(code);
+            }
+            Operand::Path { path, unit } => {
+                let (ptr, len) = self.string(&path.to_string());
+                let handle = self.local("i32");
+                self.emit(format!("(local.set $l{} (call $lookup (i32.const {}) (i32.const {})))", handle, ptr, len));
+                self.load_handle(handle, atom, unit.as_deref());
+                if reference {
+                    self.emit(format!(
+                        "(if (i32.eqz (local.get $l{k})) (then (local.set $l{k} (i32.const 3)) (local.set $l{a} (call $intern (i32.const {p}) (i32.const {n})))))",
+                        k = atom.kind,
+                        a = atom.aux,
+                        p = ptr,
+                        n = len
+                    ));
+                }
+            }
+            Operand::List { .. } => {
+                let code = set(0, 0.0, "(i32.const 0)");
+                self.emit(code);
+            }
+            Operand::Arith { left, op, right } => {
+                let (l, r) = (self.operand(left, false)?, self.operand(right, false)?);
+                let code = set(0, 0.0, "(i32.const 0)");
+                self.emit(code);
+                let (value, aux) = match op {
+                    ArithOp::Add | ArithOp::Sub => {
+                        let instr = if *op == ArithOp::Add { "f64.add" } else { "f64.sub" };
+                        (
+                            format!("({} (local.get $l{}) (local.get $l{}))", instr, l.value, r.value),
+                            format!("(select (local.get $l{la}) (local.get $l{ra}) (i32.ne (local.get $l{la}) (i32.const 0)))", la = l.aux, ra = r.aux),
+                        )
+                    }
+                    ArithOp::Mul => (
+                        format!("(f64.mul (local.get $l{}) (local.get $l{}))", l.value, r.value),
+                        format!(
+                            "(if (result i32) (i32.eqz (local.get $l{la})) (then (local.get $l{ra})) (else (select (local.get $l{la}) (i32.const 0) (i32.eqz (local.get $l{ra})))))",
+                            la = l.aux,
+                            ra = r.aux
+                        ),
+                    ),
+                    ArithOp::Div => (
+                        format!("(f64.div (local.get $l{}) (local.get $l{}))", l.value, r.value),
+                        format!("(select (local.get $l{la}) (i32.const 0) (i32.eqz (local.get $l{ra})))", la = l.aux, ra = r.aux),
+                    ),
+                };
+                // Penjumlahan/pengurangan dimensi berbeda tidak menghasilkan nilai
+                let compatible = match op {
+                    ArithOp::Add | ArithOp::Sub => format!(
+                        "(i32.or (i32.or (i32.eqz (local.get $l{la})) (i32.eqz (local.get $l{ra}))) (i32.eq (local.get $l{la}) (local.get $l{ra})))",
+                        la = l.aux,
+                        ra = r.aux
+                    ),
+                    _ => "(i32.const 1)".to_string(),
+                };
+                self.emit(format!(
+                    "(if (i32.and (i32.and (i32.eq (local.get $l{lk}) (i32.const 1)) (i32.eq (local.get $l{rk}) (i32.const 1))) {c}) \
+                     (then (local.set $l{k} (i32.const 1)) (local.set $l{v} {value}) (local.set $l{a} {aux})))",
+                    lk = l.kind,
+                    rk = r.kind,
+                    c = compatible,
+                    k = atom.kind,
+                    v = atom.value,
+                    a = atom.aux,
+                    value = value,
+                    aux = aux
+                ));
+            }
+            Operand::Count { .. } => unreachable!(),
+        }
+        Ok(atom)
+    }
+}
+
+fn reject_temporal_operand(operand: &Operand) -> Result<(), String> {
+    if operand.is_temporal() {
+        return Err(format!("wasm backend does not support temporal operand '{}'", operand));
+    }
+    Ok(())
+}
+
+fn compare_call(op: CompareOp, left: AtomLocals, right: AtomLocals) -> String {
+    let code = match op {
+        CompareOp::Lt => 0,
+        CompareOp::Gt => 1,
+        CompareOp::Lte => 2,
+        CompareOp::Gte => 3,
+        CompareOp::Eq => 4,
+        CompareOp::Neq => 5,
+    };
+    format!(
+        "(call $compare (i32.const {}) (local.get $l{}) (local.get $l{}) (local.get $l{}) (local.get $l{}) (local.get $l{}) (local.get $l{}))",
+        code, left.kind, left.value, left.aux, right.kind, right.value, right.aux
+    )
+}
+
+fn f64_literal(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+fn append_custom_section(bytes: &mut Vec<u8>, name: &str, payload: &[u8]) {
+    let mut body = Vec::new();
+    write_leb128(&mut body, name.len());
+    body.extend_from_slice(name.as_bytes());
+    body.extend_from_slice(payload);
+    bytes.push(0);
+    write_leb128(bytes, body.len());
+    bytes.extend_from_slice(&body);
+}
+
+fn write_leb128(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn read_leb128(bytes: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut value = 0usize;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Isi custom section `name` dari biner WASM.
+pub fn custom_section<'a>(bytes: &'a [u8], name: &str) -> Option<&'a [u8]> {
+    if bytes.get(..4)? != b"\0asm" {
+        return None;
+    }
+    let mut pos = 8;
+    while pos < bytes.len() {
+        let id = bytes[pos];
+        pos += 1;
+        let size = read_leb128(bytes, &mut pos)?;
+        let section = bytes.get(pos..pos + size)?;
+        pos += size;
+        if id == 0 {
+            let mut inner = 0;
+            let name_len = read_leb128(section, &mut inner)?;
+            if section.get(inner..inner + name_len)? == name.as_bytes() {
+                return section.get(inner + name_len..);
+            }
+        }
+    }
+    None
+}
+
+/// Host ABI untuk modul hasil [`compile_wasm`], tanpa engine WASM. Engine memanggil
+/// method dengan nama yang sama untuk setiap import (membaca teks dari `memory`
+/// modul), memanggil [`WasmHost::begin`] sebelum `evaluate`, dan
+/// [`WasmHost::finish`] sesudahnya.
+pub struct WasmHost {
+    ir: PolicyIR,
+    entries: Vec<WasmEntry>,
+    loops: Vec<WasmLoop>,
+    context: Value,
+    handles: Vec<Value>,
+    frames: Vec<LoopFrame>,
+    decision: Decision,
+    temporal: TemporalState,
+    started: Instant,
+}
+
+impl WasmHost {
+    /// Host untuk modul `bytes`; tabel entry dan loop dibangun ulang dari custom section IR.
+    pub fn from_module(bytes: &[u8]) -> Result<Self, String> {
+        let section = custom_section(bytes, IR_SECTION).ok_or_else(|| format!("module has no '{}' section", IR_SECTION))?;
+        let ir: PolicyIR = serde_json::from_slice(section).map_err(|e| format!("invalid '{}' section: {}", IR_SECTION, e))?;
+        Self::new(ir)
+    }
+
+    pub fn new(ir: PolicyIR) -> Result<Self, String> {
+        let module = lower(&ir)?;
+        let decision = Decision::new(ir.guard);
+        Ok(WasmHost {
+            ir,
+            entries: module.entries,
+            loops: module.loops,
+            context: Value::Null,
+            handles: Vec::new(),
+            frames: Vec::new(),
+            decision,
+            temporal: TemporalState::new(),
+            started: Instant::now(),
+        })
+    }
+
+    /// Mulai evaluasi baru dengan `context`.
+    pub fn begin(&mut self, context: Value) {
+        self.context = context;
+        self.handles.clear();
+        self.frames.clear();
+        self.decision = Decision::new(self.ir.guard);
+        self.temporal = TemporalState::new();
+        self.temporal.begin(0);
+        self.started = Instant::now();
+    }
+
+    /// Selesaikan evaluasi seperti `ir_interpreter`: combining, otorisasi, dan invarian.
+    pub fn finish(&mut self, rules_evaluated: usize) -> Decision {
+        let mut decision = std::mem::replace(&mut self.decision, Decision::new(self.ir.guard));
+        decision.metrics.rules_evaluated = rules_evaluated;
+        crate::ir_interpreter::finish_decision(&self.ir, &self.context, &mut self.temporal, &mut decision);
+        decision.metrics.duration_ms = self.started.elapsed().as_millis();
+        decision
+    }
+
+    fn handle(&mut self, value: Value) -> i32 {
+        self.handles.push(value);
+        self.handles.len() as i32 - 1
+    }
+
+    fn value(&self, handle: i32) -> Option<&Value> {
+        usize::try_from(handle).ok().and_then(|h| self.handles.get(h))
+    }
+
+    /// Angka atau teks bersatuan di handle, seperti atom angka di evaluator.
+    fn quantity(&self, handle: i32) -> Option<Quantity> {
+        match self.value(handle)? {
+            Value::Number(n) => n.as_f64().map(Quantity::bare),
+            Value::String(s) => parse_quantity(s),
+            _ => None,
+        }
+    }
+
+    pub fn lookup(&mut self, path: &str) -> i32 {
+        let found = crate::ast::FieldPath::parse(path)
+            .ok()
+            .and_then(|path| resolve_field(&self.context, &path).cloned());
+        match found {
+            Some(value) => self.handle(value),
+            None => -1,
+        }
+    }
+
+    pub fn kind(&self, handle: i32) -> i32 {
+        if self.quantity(handle).is_some() {
+            return 1;
+        }
+        match self.value(handle) {
+            Some(Value::Bool(_)) => 2,
+            Some(Value::String(_)) => 3,
+            _ => 0,
+        }
+    }
+
+    pub fn number(&self, handle: i32) -> f64 {
+        match (self.quantity(handle), self.value(handle)) {
+            (Some(q), _) => q.value,
+            (None, Some(Value::Bool(b))) => *b as i32 as f64,
+            _ => 0.0,
+        }
+    }
+
+    pub fn dimension(&self, handle: i32) -> i32 {
+        dimension_code(self.quantity(handle).and_then(|q| q.dimension))
+    }
+
+    pub fn text_eq(&self, a: i32, b: i32) -> i32 {
+        match (self.value(a), self.value(b)) {
+            (Some(Value::String(a)), Some(Value::String(b))) => (a == b) as i32,
+            _ => 0,
+        }
+    }
+
+    pub fn text_len(&self, handle: i32) -> i32 {
+        match self.value(handle) {
+            Some(Value::String(s)) => s.len().min(i32::MAX as usize) as i32,
+            _ => 0,
+        }
+    }
+
+    pub fn intern(&mut self, text: &str) -> i32 {
+        self.handle(Value::String(text.to_string()))
+    }
+
+    pub fn collection(&mut self, path: &str) -> i32 {
+        let elements = resolve_loop_elements(&self.context, path);
+        self.handle(Value::Array(elements))
+    }
+
+    pub fn length(&self, handle: i32) -> i32 {
+        match self.value(handle) {
+            Some(Value::Array(items)) => items.len().min(i32::MAX as usize) as i32,
+            _ => -1,
+        }
+    }
+
+    pub fn element(&mut self, handle: i32, index: i32) -> i32 {
+        let item = usize::try_from(index).ok().and_then(|i| match self.value(handle) {
+            Some(Value::Array(items)) => items.get(i).cloned(),
+            _ => None,
+        });
+        match item {
+            Some(value) => self.handle(value),
+            None => -1,
+        }
+    }
+
+    pub fn iteration(&mut self, loop_id: i32, index: i32, element: i32) {
+        let index = index.max(0) as usize;
+        let frame = match self.loops.get(loop_id as usize) {
+            Some(WasmLoop::For { iterator, collection }) => {
+                let value = self.value(element).cloned().unwrap_or(Value::Null);
+                if let Some(obj) = self.context.as_object_mut() {
+                    obj.insert(iterator.clone(), value.clone());
+                }
+                LoopFrame::for_each(iterator, collection, index, &value)
+            }
+            Some(WasmLoop::While { condition }) => LoopFrame::while_loop(condition, index),
+            None => return,
+        };
+        self.frames.push(frame);
+    }
+
+    pub fn leave(&mut self, _loop_id: i32) {
+        self.frames.pop();
+    }
+
+    /// Bangun payload dan penjelasan aksi dari konteks saat ini.
+    pub fn fire(&mut self, entry: i32) {
+        let Some(entry) = self.entries.get(entry as usize).cloned() else { return };
+        let scope = temporal_scope(&self.frames);
+        let (action, explanation) = match &entry {
+            WasmEntry::Rule { rule, branch } => {
+                let condition = explain_condition_in(&rule.condition, &self.context, &mut self.temporal, &scope);
+                let action = match branch {
+                    Branch::Else => rule.else_action.as_ref().unwrap_or(&rule.action),
+                    _ => &rule.action,
+                };
+                let explanation = Explanation {
+                    action: action.name.clone(),
+                    rule: rule_text(&rule.condition, &rule.action, rule.else_action.as_ref()),
+                    branch: *branch,
+                    priority: rule.priority,
+                    effect: rule.effect,
+                    condition,
+                    loops: self.frames.clone(),
+                    actor: rule.actor.clone(),
+                };
+                (action, explanation)
+            }
+            WasmEntry::Arm { scrutinee, pattern, action } => {
+                let trace = explain_match_in(scrutinee, pattern, &self.context, &mut self.temporal, &scope);
+                (action, Explanation::match_arm(scrutinee, pattern, action, trace, &self.frames))
+            }
+        };
+        let payload = build_action_payload_in(action, &self.context, &mut self.temporal, &scope);
+        self.decision.fire(payload, explanation);
+        self.decision.metrics.actions_triggered += 1;
+    }
+
+    pub fn guard(&mut self, loop_id: i32, limit: i32, total: i32, processed: i32) {
+        let limit = if limit == 0 { GuardLimit::Iterations } else { GuardLimit::Time };
+        self.decision.guard_triggered = true;
+        self.decision.metrics.guard_hits += 1;
+        if let Some(WasmLoop::For { collection, .. }) = self.loops.get(loop_id as usize) {
+            let collection = collection.clone();
+            self.decision.logs.push(format!("Guard hit: loop {:?} limit exceeded", limit));
+            self.decision.truncate(&collection, total.max(0) as usize, processed.max(0) as usize, limit);
+        } else {
+            self.decision.logs.push(format!("Guard hit: WHILE {:?} limit exceeded", limit));
+        }
+    }
+
+    pub fn now_ms(&self) -> f64 {
+        self.started.elapsed().as_millis() as f64
+    }
+}
+
+
+/// Jalankan modul hasil [`compile_wasm`] dengan interpreter wasmi dan [`WasmHost`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn execute_wasm(bytes: &[u8], context: Value) -> Result<Decision, String> {
+    use wasmi::{Caller, Engine, Extern, Linker, Module, Store, Val};
+
+    fn text(caller: &Caller<'_, WasmHost>, ptr: i32, len: i32) -> String {
+        let memory = caller.get_export("memory").and_then(Extern::into_memory);
+        let bytes = memory.and_then(|m| m.data(caller).get(ptr as usize..ptr as usize + len as usize).map(<[u8]>::to_vec));
+        String::from_utf8(bytes.unwrap_or_default()).unwrap_or_default()
+    }
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, bytes).map_err(|e| format!("invalid wasm module: {}", e))?;
+    let mut host = WasmHost::from_module(bytes)?;
+    host.begin(context);
+    let mut store = Store::new(&engine, host);
+    let mut linker = <Linker<WasmHost>>::new(&engine);
+    let ns = "omnilang";
+    let link = |e: wasmi::errors::LinkerError| format!("wasm link failed: {}", e);
+    linker
+        .func_wrap(ns, "lookup", |mut c: Caller<'_, WasmHost>, p: i32, l: i32| {
+            let path = text(&c, p, l);
+            c.data_mut().lookup(&path)
+        })
+        .map_err(link)?;
+    linker.func_wrap(ns, "kind", |c: Caller<'_, WasmHost>, h: i32| c.data().kind(h)).map_err(link)?;
+    linker.func_wrap(ns, "number", |c: Caller<'_, WasmHost>, h: i32| c.data().number(h)).map_err(link)?;
+    linker.func_wrap(ns, "dimension", |c: Caller<'_, WasmHost>, h: i32| c.data().dimension(h)).map_err(link)?;
+    linker.func_wrap(ns, "text_eq", |c: Caller<'_, WasmHost>, a: i32, b: i32| c.data().text_eq(a, b)).map_err(link)?;
+    linker.func_wrap(ns, "text_len", |c: Caller<'_, WasmHost>, h: i32| c.data().text_len(h)).map_err(link)?;
+    linker
+        .func_wrap(ns, "intern", |mut c: Caller<'_, WasmHost>, p: i32, l: i32| {
+            let value = text(&c, p, l);
+            c.data_mut().intern(&value)
+        })
+        .map_err(link)?;
+    linker
+        .func_wrap(ns, "collection", |mut c: Caller<'_, WasmHost>, p: i32, l: i32| {
+            let path = text(&c, p, l);
+            c.data_mut().collection(&path)
+        })
+        .map_err(link)?;
+    linker.func_wrap(ns, "length", |c: Caller<'_, WasmHost>, h: i32| c.data().length(h)).map_err(link)?;
+    linker
+        .func_wrap(ns, "element", |mut c: Caller<'_, WasmHost>, h: i32, i: i32| c.data_mut().element(h, i))
+        .map_err(link)?;
+    linker
+        .func_wrap(ns, "iteration", |mut c: Caller<'_, WasmHost>, id: i32, i: i32, h: i32| c.data_mut().iteration(id, i, h))
+        .map_err(link)?;
+    linker.func_wrap(ns, "leave", |mut c: Caller<'_, WasmHost>, id: i32| c.data_mut().leave(id)).map_err(link)?;
+    linker.func_wrap(ns, "fire", |mut c: Caller<'_, WasmHost>, e: i32| c.data_mut().fire(e)).map_err(link)?;
+    linker
+        .func_wrap(ns, "guard", |mut c: Caller<'_, WasmHost>, id: i32, limit: i32, total: i32, done: i32| {
+            c.data_mut().guard(id, limit, total, done)
+        })
+        .map_err(link)?;
+    linker.func_wrap(ns, "now_ms", |c: Caller<'_, WasmHost>| c.data().now_ms()).map_err(link)?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| format!("wasm instantiation failed: {}", e))?;
+    let evaluate = instance
+        .get_typed_func::<(), i32>(&store, "evaluate")
+        .map_err(|e| format!("module has no evaluate export: {}", e))?;
+    evaluate.call(&mut store, ()).map_err(|e| format!("wasm evaluate trapped: {}", e))?;
+    let rules = match instance.get_global(&store, "rules_evaluated").map(|g| g.get(&store)) {
+        Some(Val::I32(n)) => n.max(0) as usize,
+        _ => return Err("module has no rules_evaluated export".to_string()),
+    };
+    Ok(store.data_mut().finish(rules))
+}
@@ -0,0 +1,153 @@
+//! Evaluasi berkelanjutan (`omnilang watch`). Kebijakan dan `Runtime` tetap di
+//! memori; setiap baris JSON pada input adalah patch konteks, dan setiap patch
+//! menghasilkan satu baris JSON `Decision` pada output.
+//!
+//! Patch memakai aturan yang sama dengan `simulate`: kunci bertitik menulis field
+//! bersarang dan `timestamp` (ms atau RFC 3339) menentukan waktu evaluasi. Tanpa
+//! `timestamp`, waktu evaluasi adalah jam sistem.
+
+use crate::ast::Policy;
+use crate::audit::AuditLog;
+use crate::metrics::SharedMetrics;
+use crate::runtime::{Decision, Runtime};
+use crate::simulate::{set_path, take_timestamp};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WatchOptions {
+    /// Tulis keputusan hanya jika aksi, payload, pelanggaran, atau penolakannya berubah.
+    pub changes_only: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchStats {
+    /// Patch yang diterapkan dan dievaluasi.
+    pub patches: usize,
+    /// Keputusan yang ditulis ke output.
+    pub emitted: usize,
+    /// Baris yang ditolak (JSON tidak valid atau melanggar `SCHEMA:`).
+    pub errors: usize,
+}
+
+pub struct Watcher<'p> {
+    policy: &'p Policy,
+    runtime: Runtime,
+    options: WatchOptions,
+    /// Ringkasan keputusan terakhir yang ditulis, untuk `changes_only`.
+    last: Option<Value>,
+    /// Log audit dan hash kebijakan; setiap keputusan dicatat, juga yang tidak ditulis.
+    audit: Option<(AuditLog, String)>,
+    /// Registry metrik dan label kebijakan; setiap keputusan dicatat.
+    metrics: Option<(SharedMetrics, String)>,
+    pub stats: WatchStats,
+}
+
+impl<'p> Watcher<'p> {
+    /// `runtime` membawa konteks awal dan konfigurasi guard.
+    pub fn new(policy: &'p Policy, runtime: Runtime, options: WatchOptions) -> Self {
+        Watcher { policy, runtime, options, last: None, audit: None, metrics: None, stats: WatchStats::default() }
+    }
+
+    /// Catat setiap keputusan ke log audit berantai hash.
+    pub fn with_audit(mut self, log: AuditLog) -> Self {
+        self.audit = Some((log, crate::audit::policy_hash(self.policy)));
+        self
+    }
+
+    /// Catat setiap keputusan ke `metrics` dengan label kebijakan `label`.
+    pub fn with_metrics(mut self, metrics: SharedMetrics, label: impl Into<String>) -> Self {
+        self.metrics = Some((metrics, label.into()));
+        self
+    }
+
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Terapkan satu baris patch lalu evaluasi kebijakan. `Ok(None)` untuk baris kosong
+    /// atau, dengan `changes_only`, keputusan yang sama dengan keputusan terakhir.
+    /// Patch yang ditolak tidak mengubah konteks.
+    pub fn apply_line(&mut self, line: &str) -> Result<Option<Decision>, String> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let value: Value = serde_json::from_str(line).map_err(|e| format!("invalid JSON: {}", e))?;
+        let Value::Object(mut updates) = value else {
+            return Err("expected a JSON object".to_string());
+        };
+        let now_ms = match take_timestamp(&mut updates)? {
+            Some(ms) => ms,
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default(),
+        };
+
+        let mut context = self.runtime.context_snapshot();
+        let mut roots = Vec::new();
+        for (key, value) in updates {
+            let root = set_path(&mut context, &key, value).to_string();
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        let violations = crate::schema::validate(&self.policy.schema, &context);
+        if !violations.is_empty() {
+            let messages: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            return Err(format!("schema: {}", messages.join("; ")));
+        }
+        for root in roots {
+            let value = context.get(&root).cloned().unwrap_or(Value::Null);
+            self.runtime.update_data(&root, value);
+        }
+
+        self.stats.patches += 1;
+        let started = Instant::now();
+        let decision = self.runtime.execute_policy_at(self.policy, now_ms);
+        if let Some((metrics, label)) = &self.metrics {
+            metrics.lock().unwrap_or_else(|e| e.into_inner()).record(label, &decision, started.elapsed());
+        }
+        if let Some((log, policy_hash)) = &mut self.audit {
+            log.append(policy_hash, &context, &decision, now_ms).map_err(|e| format!("audit: {}", e))?;
+        }
+        let summary = json!({
+            "actions": decision.actions,
+            "payloads": decision.payloads,
+            "violations": decision.violations,
+            "unauthorized": decision.unauthorized,
+        });
+        if self.options.changes_only && self.last.as_ref() == Some(&summary) {
+            return Ok(None);
+        }
+        self.last = Some(summary);
+        Ok(Some(decision))
+    }
+
+    /// Baca patch dari `input` sampai EOF dan tulis satu baris JSON per keputusan.
+    /// Baris yang ditolak ditulis sebagai `{"error": ..., "line": n}` tanpa menghentikan
+    /// loop. Setiap panggilan mulai dari keputusan terakhir kosong, jadi klien baru
+    /// selalu menerima keputusan pertamanya.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        self.last = None;
+        for (line_no, line) in input.lines().enumerate() {
+            let line = line?;
+            let record = match self.apply_line(&line) {
+                Ok(Some(decision)) => {
+                    self.stats.emitted += 1;
+                    serde_json::to_string(&decision).map_err(io::Error::other)?
+                }
+                Ok(None) => continue,
+                Err(e) => {
+                    self.stats.errors += 1;
+                    json!({ "error": e, "line": line_no + 1 }).to_string()
+                }
+            };
+            writeln!(output, "{}", record)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
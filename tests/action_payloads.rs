@@ -0,0 +1,86 @@
+use omnilang_core::action_abi::ActionCapability;
+use omnilang_core::ast::Rule;
+use omnilang_core::{ir::build_policy_ir, ir_interpreter::execute_ir, lexer::Lexer, parser::Parser, runtime::Runtime};
+use serde_json::json;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+const VEHICLE: &str = r#"
+INTENT: Vehicle safety
+UNITS:
+- vehicle.speed: m/s
+RULE:
+- IF ObstacleDistance < 5m THEN Brake(force: 0.8, timeout: 200ms, speed: vehicle.speed, mode: Eco) ELSE Cruise
+- FOR worker IN Workers {
+    - IF worker.zone == "red" THEN Notify(id: worker.id, channels: ["sms", "radio"], requires: [Network], retry: 3)
+}
+"#;
+
+#[test]
+fn action_calls_are_parsed_with_named_params() {
+    let policy = parse_policy(VEHICLE).expect("parse failed");
+    let Rule::Standard(rule) = &policy.rules[0] else {
+        panic!("expected standard rule");
+    };
+    assert_eq!(rule.action.name, "Brake");
+    assert_eq!(rule.action.params.len(), 4);
+    assert_eq!(rule.action.to_string(), "Brake(force: 0.8, timeout: 200ms, speed: vehicle.speed, mode: Eco)");
+    assert_eq!(rule.else_action.as_ref().unwrap().to_string(), "Cruise");
+
+    let ir = serde_json::to_value(build_policy_ir(&policy)).unwrap();
+    assert_eq!(ir["rules"][0]["action"]["name"], "Brake");
+    assert_eq!(ir["rules"][0]["action"]["params"][1]["name"], "timeout");
+    assert!(ir["rules"][0]["else_action"].get("params").is_none());
+}
+
+#[test]
+fn runtime_and_ir_build_identical_payloads() {
+    let ctx = json!({
+        "ObstacleDistance": 3,
+        "vehicle": { "speed": 10 },
+        "Workers": [{ "id": "w-1", "zone": "red" }, { "id": "w-2", "zone": "green" }]
+    });
+    let policy = parse_policy(VEHICLE).expect("parse failed");
+    let mut rt = Runtime::new();
+    for (key, value) in ctx.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    let decision = rt.execute_policy(&policy);
+    let ir_decision = execute_ir(&build_policy_ir(&policy), ctx);
+
+    assert_eq!(decision.actions, vec!["Brake", "Notify"]);
+    assert_eq!(decision.payloads, ir_decision.payloads);
+
+    let brake = &decision.payloads[0];
+    assert_eq!(brake.params["force"], json!(0.8));
+    // 10 m/s dalam satuan dasar km/h
+    assert_eq!(brake.params["speed"], json!(36));
+    assert_eq!(brake.params["mode"], json!("Eco"));
+    assert!(!brake.params.contains_key("timeout"));
+    assert_eq!(brake.metadata.timeout_ms, Some(200));
+
+    let notify = &decision.payloads[1];
+    assert_eq!(notify.params["id"], json!("w-1"));
+    assert_eq!(notify.params["channels"], json!(["sms", "radio"]));
+    assert_eq!(notify.required_capabilities, vec![ActionCapability::Network]);
+    assert_eq!(notify.metadata.retry, Some(3));
+}
+
+#[test]
+fn malformed_action_params_fail_at_parse_time() {
+    let cases = [
+        ("- IF A > 1 THEN Brake(force 0.8)", "Expected ':'"),
+        ("- IF A > 1 THEN Brake(force: 1, force: 2)", "Duplicate parameter 'force'"),
+        ("- IF A > 1 THEN Upload(requires: [Teleport])", "Unknown capability 'Teleport'"),
+        ("- IF A > 1 THEN Brake(timeout: 5m)", "timeout must be a duration"),
+    ];
+    for (rule, expected) in cases {
+        let err = parse_policy(&format!("INTENT: x\nRULE:\n{}\n", rule)).unwrap_err();
+        assert!(err.contains(expected), "{}: {}", rule, err);
+    }
+}
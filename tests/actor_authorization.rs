@@ -0,0 +1,176 @@
+use omnilang_core::action_abi::ActionCapability;
+use omnilang_core::authorization::check_policy;
+use omnilang_core::compose::load_policy;
+use omnilang_core::ir::build_policy_ir;
+use omnilang_core::ir_interpreter::execute_ir;
+use omnilang_core::runtime::{Decision, Runtime};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn run(policy: &omnilang_core::ast::Policy, context: &Value) -> Decision {
+    let mut rt = Runtime::new();
+    for (key, value) in context.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    rt.execute_policy(policy)
+}
+
+const COOLING: &str = "INTENT: Cooling\nACTOR:\n\
+                       - Primary: Cooling Controller CAN [Execute, Write]\n\
+                       - Secondary: NocOperator CAN [Read, Network]\n\
+                       CAPABILITY:\n\
+                       - NotifyOnCall: [Network]\n\
+                       - WriteIncidentLog: [FileSystem]\n\
+                       - IncreaseCooling: [Execute]\n\
+                       RULE:\n\
+                       - IF Temperature > 30 THEN IncreaseCooling(requires: [Write])\n\
+                       - BY NocOperator IF Temperature > 35 THEN NotifyOnCall(channel: \"pager\")\n\
+                       - IF Temperature > 40 THEN WriteIncidentLog\n\
+                       - BY NocOperator IF Humidity > 70 THEN IncreaseCooling ELSE Observe\n";
+
+#[test]
+fn actors_capabilities_and_rule_scopes_parse_into_ast_and_ir() {
+    let policy = parse_policy(COOLING).expect("parse failed");
+    assert_eq!(policy.actors[0].role, "Cooling Controller");
+    assert_eq!(policy.actors[0].capabilities, Some(vec![ActionCapability::Execute, ActionCapability::Write]));
+    assert_eq!(policy.capabilities[1].action, "WriteIncidentLog");
+    assert_eq!(policy.capabilities[1].requires, vec![ActionCapability::FileSystem]);
+    let scoped: Vec<Option<&str>> = policy
+        .rules
+        .iter()
+        .map(|r| match r {
+            omnilang_core::ast::Rule::Standard(s) => s.actor.as_deref(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(scoped, vec![None, Some("NocOperator"), None, Some("NocOperator")]);
+
+    // `can` huruf kecil tetap bagian dari nama peran; actor tanpa CAN tidak dibatasi
+    let prose = parse_policy("INTENT: x\nACTOR:\n- Primary: Robot that can weld\nRULE:\n- IF A THEN B\n").unwrap();
+    assert_eq!(prose.actors[0].role, "Robot that can weld");
+    assert!(prose.actors[0].capabilities.is_none());
+
+    let ir = serde_json::to_value(build_policy_ir(&policy)).unwrap();
+    assert_eq!(ir["actors"][1]["capabilities"], json!([{ "type": "read" }, { "type": "network" }]));
+    assert_eq!(ir["capabilities"][0]["action"], "NotifyOnCall");
+    assert_eq!(ir["rules"][1]["actor"], "NocOperator");
+    assert!(ir["rules"][0].get("actor").is_none());
+    let plain = serde_json::to_value(build_policy_ir(&prose)).unwrap();
+    assert!(plain.get("capabilities").is_none() && plain["actors"][0].get("capabilities").is_none());
+
+    let err = parse_policy("INTENT: x\nACTOR:\n- Primary: Bot CAN [Teleport]\n").unwrap_err();
+    assert!(err.contains("Unknown capability 'Teleport' in CAN"), "{}", err);
+    let err = parse_policy("INTENT: x\nCAPABILITY:\n- Upload: [Network]\n- Upload: [Read]\n").unwrap_err();
+    assert!(err.contains("Duplicate CAPABILITY entry for action 'Upload'"), "{}", err);
+    let err = parse_policy("INTENT: x\nRULE:\n- BY A BY B IF X THEN Y\n").unwrap_err();
+    assert!(err.contains("only one BY actor"), "{}", err);
+    let err = parse_policy("INTENT: x\nRULE:\n- BY 3 IF X THEN Y\n").unwrap_err();
+    assert!(err.contains("Expected actor role after BY"), "{}", err);
+
+    // `Capability:` di tengah baris hanyalah teks item, bukan header seksi
+    let prose = parse_policy("INTENT: x\nASSUMPTION:\n- Capability: granted per shift\nRULE:\n- IF A THEN B\n").unwrap();
+    assert_eq!(prose.assumptions.len(), 1);
+    assert!(prose.capabilities.is_empty());
+}
+
+#[test]
+fn runtime_and_ir_reject_the_same_unauthorized_actions() {
+    let policy = parse_policy(COOLING).expect("parse failed");
+    let context = json!({ "Temperature": 42, "Humidity": 80 });
+    let decision = run(&policy, &context);
+    // IncreaseCooling dari actor primary tetap keluar; yang dari NocOperator ditolak
+    assert_eq!(decision.actions, vec!["IncreaseCooling", "NotifyOnCall"]);
+    assert_eq!(decision.payloads[0].required_capabilities, vec![ActionCapability::Write, ActionCapability::Execute]);
+    assert_eq!(decision.payloads[1].required_capabilities, vec![ActionCapability::Network]);
+    assert_eq!(decision.payloads[1].params["channel"], json!("pager"));
+    assert_eq!(decision.explanations[1].actor.as_deref(), Some("NocOperator"));
+    assert!(decision.explanations[0].actor.is_none());
+
+    assert_eq!(decision.unauthorized.len(), 2);
+    assert_eq!(
+        decision.unauthorized[0].to_string(),
+        "rejected WriteIncidentLog; actor 'Cooling Controller' lacks FileSystem"
+    );
+    assert_eq!(decision.unauthorized[1].actor, "NocOperator");
+    assert_eq!(decision.unauthorized[1].missing, vec![ActionCapability::Execute]);
+    assert!(decision.logs.iter().any(|l| l.contains("Unauthorized: rejected WriteIncidentLog")));
+
+    let ir_decision = execute_ir(&build_policy_ir(&policy), context.clone());
+    assert_eq!(ir_decision.actions, decision.actions);
+    assert_eq!(ir_decision.unauthorized, decision.unauthorized);
+
+    // ELSE ikut dijalankan actor rule; Observe tidak butuh capability
+    let calm = run(&policy, &json!({ "Temperature": 20, "Humidity": 40 }));
+    assert_eq!(calm.actions, vec!["Observe"]);
+    assert!(serde_json::to_value(&calm).unwrap().get("unauthorized").is_none());
+
+    // Actor BY yang tidak dideklarasikan selalu ditolak; tanpa ACTOR tidak ada pemeriksaan
+    let typo = parse_policy("INTENT: x\nACTOR:\n- Primary: Ops\nRULE:\n- BY Opz IF A THEN Restart\n- IF A THEN Upload(requires: Network)\n").unwrap();
+    let decision = run(&typo, &json!({ "A": true }));
+    assert_eq!(decision.actions, vec!["Upload"]);
+    assert!(decision.unauthorized[0].undeclared);
+    let open = parse_policy("INTENT: x\nCAPABILITY:\n- Upload: [Network]\nRULE:\n- IF A THEN Upload\n").unwrap();
+    assert_eq!(run(&open, &json!({ "A": true })).actions, vec!["Upload"]);
+}
+
+#[test]
+fn static_check_invariants_and_composition_respect_authorization() {
+    let policy = parse_policy(COOLING).unwrap();
+    let failures = check_policy(&policy);
+    let found: Vec<(usize, &str, &str)> = failures.iter().map(|(l, f)| (*l, f.action.as_str(), f.actor.as_str())).collect();
+    assert_eq!(found, vec![(12, "WriteIncidentLog", "Cooling Controller"), (13, "IncreaseCooling", "NocOperator")]);
+
+    // Aksi yang ditolak tidak memicu NEVER; aksi pengganti dijalankan actor primary
+    let guarded = parse_policy(
+        "INTENT: Press\nACTOR:\n- Primary: PressControl CAN [Execute]\n- Secondary: Vendor CAN [Read]\n\
+         RULE:\n- BY Vendor IF Jam THEN Unlock(requires: [Execute])\n- IF Jam THEN StopPress\n- IF Door THEN Siren(requires: Network)\n\
+         CONSTRAINT:\n- Ethical: NEVER (StopPress AND Unlock) ELSE Lockout\n- Technical: NEVER (StopPress AND Door) ELSE Alarm(requires: Network)\n",
+    )
+    .unwrap();
+    let decision = run(&guarded, &json!({ "Jam": true, "Door": true }));
+    assert!(decision.actions.is_empty(), "{:?}", decision.actions);
+    assert_eq!(decision.violations.len(), 1);
+    let rejected: Vec<&str> = decision.unauthorized.iter().map(|f| f.action.as_str()).collect();
+    assert_eq!(rejected, vec!["Unlock", "Siren", "Alarm"]);
+    assert_eq!(execute_ir(&build_policy_ir(&guarded), json!({ "Jam": true, "Door": true })).unauthorized, decision.unauthorized);
+
+    let dir = std::env::temp_dir().join(format!("omni_authz_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("base.omni"), "INTENT: base\nCAPABILITY:\n- Upload: [Network]\nRULE:\n- IF A THEN Upload\n").unwrap();
+    std::fs::write(dir.join("site.omni"), "INTENT: site\nEXTEND: base.omni\nCAPABILITY:\n- Upload: [Network]\n- Archive: [FileSystem]\nRULE:\n- IF B THEN Archive\n").unwrap();
+    std::fs::write(dir.join("clash.omni"), "INTENT: clash\nEXTEND: base.omni\nCAPABILITY:\n- Upload: [FileSystem]\n").unwrap();
+    let site = load_policy(dir.join("site.omni")).unwrap();
+    let actions: Vec<&str> = site.capabilities.iter().map(|r| r.action.as_str()).collect();
+    assert_eq!(actions, vec!["Upload", "Archive"]);
+    let err = load_policy(dir.join("clash.omni")).unwrap_err();
+    assert!(err.contains("Conflicting CAPABILITY entries for action 'Upload'"), "{}", err);
+}
+
+#[test]
+fn by_rules_inside_loops_are_authorized_for_their_actor() {
+    let policy = parse_policy(
+        "INTENT: Racks\nACTOR:\n- Primary: Cooling Controller CAN [Execute]\n- Secondary: NocOperator CAN [Network]\nRULE:\n\
+         - FOR rack IN Racks {\n    - BY NocOperator IF rack.temp > 35 THEN NotifyOnCall(rack: rack.id, requires: [Network])\n    \
+         - BY NocOperator IF rack.temp > 40 THEN IncreaseCooling(requires: [Execute])\n}\n",
+    )
+    .expect("parse failed");
+    let context = json!({ "Racks": [{ "id": "r1", "temp": 38 }, { "id": "r2", "temp": 45 }] });
+    let decision = run(&policy, &context);
+    // Aksi yang dipicu di dalam loop dijalankan actor BY, bukan actor primary
+    assert_eq!(decision.actions, vec!["NotifyOnCall", "NotifyOnCall"]);
+    assert_eq!(decision.payloads[1].params["rack"], json!("r2"));
+    assert!(decision.explanations.iter().all(|e| e.actor.as_deref() == Some("NocOperator")));
+    assert_eq!(decision.unauthorized.len(), 1);
+    assert_eq!(decision.unauthorized[0].to_string(), "rejected IncreaseCooling; actor 'NocOperator' lacks Execute");
+    assert_eq!(execute_ir(&build_policy_ir(&policy), context).unauthorized, decision.unauthorized);
+
+    let found: Vec<(usize, String)> = check_policy(&policy).into_iter().map(|(l, f)| (l, f.action)).collect();
+    assert_eq!(found, vec![(8, "IncreaseCooling".to_string())]);
+}
@@ -1,6 +1,5 @@
 use omnilang_core::{emitter::{emit, CompileTarget}, lexer::Lexer, parser::Parser};
 use std::fs;
-use std::process::Command;
 
 fn parse_policy(path: &str) -> omnilang_core::ast::Policy {
     let src = fs::read_to_string(path).expect("policy missing");
@@ -10,31 +9,12 @@ fn parse_policy(path: &str) -> omnilang_core::ast::Policy {
     parser.parse_policy().expect("parse failed")
 }
 
-fn has_wasm_target() -> bool {
-    if let Ok(output) = Command::new("rustup")
-        .arg("target")
-        .arg("list")
-        .arg("--installed")
-        .output()
-    {
-        if let Ok(list) = String::from_utf8(output.stdout) {
-            return list.lines().any(|l| l.trim() == "wasm32-wasi");
-        }
-    }
-    false
-}
-
 #[test]
 fn wasm_codegen_produces_binary() {
-    if !has_wasm_target() {
-        eprintln!("skip wasm smoke test: wasm32-wasi target not installed");
-        return;
-    }
-
     let policy = parse_policy("examples/demo.omni");
     let out_path = "target/test_wasm_runner.wasm";
     emit(&policy, CompileTarget::Wasm, out_path).expect("emit wasm failed");
-    let meta = fs::metadata(out_path).expect("output missing");
-    assert!(meta.len() > 0, "wasm binary should be non-empty");
+    let bytes = fs::read(out_path).expect("output missing");
+    assert_eq!(&bytes[..4], b"\0asm", "output should be a wasm module");
     fs::remove_file(out_path).ok();
 }
@@ -0,0 +1,117 @@
+use omnilang_core::ast::{CompareOp, Condition, MatchPattern, Operand, Rule};
+use omnilang_core::evaluator::eval_condition;
+use omnilang_core::lexer::Lexer;
+use omnilang_core::parser::{parse_condition, Parser};
+use serde_json::json;
+
+fn parse_policy(src: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(src);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+#[test]
+fn rule_conditions_are_parsed_into_ast() {
+    let policy = parse_policy(
+        r#"
+INTENT: Test
+RULE:
+- IF device.flags[0] == "hot" AND NOT (Mode IN [1, 2] OR Speed > Limit * 0.8) THEN Escalate
+"#,
+    )
+    .expect("parse failed");
+
+    let Rule::Standard(rule) = &policy.rules[0] else {
+        panic!("expected standard rule");
+    };
+    let Condition::And { terms } = &rule.condition else {
+        panic!("expected AND, got {:?}", rule.condition);
+    };
+    assert_eq!(terms.len(), 2);
+    match &terms[0] {
+        Condition::Compare { left: Operand::Path { path, .. }, op: CompareOp::Eq, right: Operand::Str { value } } => {
+            assert_eq!(path.to_string(), "device.flags[0]");
+            assert_eq!(value, "hot");
+        }
+        other => panic!("unexpected first term {:?}", other),
+    }
+    assert!(matches!(&terms[1], Condition::Not { inner } if matches!(inner.as_ref(), Condition::Or { .. })));
+    assert_eq!(
+        rule.condition.to_string(),
+        r#"device.flags[0] == "hot" AND NOT (Mode IN [1, 2] OR Speed > Limit * 0.8)"#
+    );
+}
+
+#[test]
+fn malformed_conditions_fail_at_parse_time() {
+    let err = parse_policy(
+        r#"
+INTENT: Test
+RULE:
+- IF Temperature > THEN CoolDown
+"#,
+    )
+    .unwrap_err();
+    assert!(err.contains("Line 4"), "error should carry a location: {}", err);
+
+    assert!(parse_condition("Temperature >> 5").is_err());
+    assert!(parse_condition("(Temperature > 5").is_err());
+
+    // Ekspresi program tidak lagi diterima sebagai kondisi
+    let err = parse_condition("reduce(Readings, |acc, x| acc + x, 0) == 12").unwrap_err();
+    assert!(err.contains("Function calls are not supported in conditions ('reduce')"), "{}", err);
+    let err = parse_condition("std::time::now_unix_millis() > 5").unwrap_err();
+    assert!(err.contains("Function calls are not supported in conditions ('std')"), "{}", err);
+    let err = parse_condition("match Status { \"Ready\" => true, _ => false }").unwrap_err();
+    assert!(err.contains("MATCH is not an expression inside a condition"), "{}", err);
+}
+
+#[test]
+fn then_may_follow_on_the_next_item() {
+    let two_line = parse_policy("INTENT: Test\nRULE:\n- IF Temperature > 90 OR Pressure > 2.5\n- THEN EmergencyShutdown\n- IF A THEN B\n")
+        .expect("parse failed");
+    let one_line = parse_policy("INTENT: Test\nRULE:\n- IF Temperature > 90 OR Pressure > 2.5 THEN EmergencyShutdown\n").unwrap();
+    assert_eq!(two_line.rules.len(), 2);
+    let (Rule::Standard(a), Rule::Standard(b)) = (&two_line.rules[0], &one_line.rules[0]) else {
+        panic!("expected standard rules");
+    };
+    assert_eq!(a.condition.to_string(), b.condition.to_string());
+    assert_eq!(a.action, b.action);
+}
+
+#[test]
+fn grouping_arithmetic_and_bare_flags_evaluate() {
+    let ctx = json!({ "A": 1, "B": 5, "C": 10, "Active": true, "Fleet": 25, "Drones": 21 });
+    assert!(eval_condition(&parse_condition("(A > 2 OR B > 2) AND C == 10").unwrap(), &ctx));
+    assert!(!eval_condition(&parse_condition("A > 2 OR B > 2 AND C == 11").unwrap(), &ctx));
+    assert!(eval_condition(&parse_condition("Drones > Fleet * 0.8").unwrap(), &ctx));
+    assert!(eval_condition(&parse_condition("Active").unwrap(), &ctx));
+    assert!(eval_condition(&parse_condition("NOT Missing").unwrap(), &ctx));
+    assert!(eval_condition(&parse_condition("B > -1").unwrap(), &ctx));
+}
+
+#[test]
+fn match_wildcard_arm_is_parsed_and_matches() {
+    let policy = parse_policy(
+        r#"
+INTENT: Test
+RULE:
+- MATCH reactor.status {
+    - "Critical" => EmergencyShutdown
+    - _ => MonitorNormal
+}
+"#,
+    )
+    .expect("parse failed");
+
+    let Rule::Match(m) = &policy.rules[0] else {
+        panic!("expected match rule");
+    };
+    assert_eq!(m.arms[1].pattern, MatchPattern::Wildcard);
+
+    let mut rt = omnilang_core::runtime::Runtime::new();
+    rt.update_data("reactor", json!({ "status": "Idle" }));
+    let decision = rt.execute_policy(&policy);
+    assert_eq!(decision.actions, vec!["MonitorNormal".to_string()]);
+}
@@ -0,0 +1,124 @@
+use omnilang_core::conformance::{
+    check_policy, generate_contexts, render_text, run, ConformanceOptions, ConformanceReport, Divergence, Engine, Outcome,
+    PROGRAM_FILE,
+};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+const PLANT: &str = "INTENT: Plant\nRULE:\n\
+                     - IF Temperature > 90 AND Mode == \"Auto\" THEN Vent\n\
+                     - IF Gap < 30cm THEN Stop\n\
+                     - FOR pump IN Pumps {\n    - IF pump.rpm > 3000 AND pump.active THEN Throttle(id: pump.id)\n}\n\
+                     - MATCH Status {\n    - \"Ready\" => Start\n    - _ => Wait\n}\n";
+
+fn actions(policy: &omnilang_core::ast::Policy, context: &Value) -> Vec<String> {
+    let mut runtime = Runtime::new();
+    for (key, value) in context.as_object().unwrap() {
+        runtime.update_data(key, value.clone());
+    }
+    runtime.execute_policy(policy).actions
+}
+
+#[test]
+fn random_contexts_are_reproducible_and_reach_both_sides_of_each_rule() {
+    let policy = parse_policy(PLANT);
+    let contexts = generate_contexts(&policy, 60, 7);
+    assert_eq!(contexts, generate_contexts(&policy, 60, 7));
+    assert_ne!(contexts, generate_contexts(&policy, 60, 8));
+
+    let temperatures: Vec<&Value> = contexts.iter().filter_map(|c| c.get("Temperature")).collect();
+    for boundary in [json!(90.0), json!(89.0), json!(91.0)] {
+        assert!(temperatures.contains(&&boundary), "{:?}", temperatures);
+    }
+    assert!(contexts.iter().any(|c| c["Gap"] == json!("29cm")));
+    let pumps: Vec<&Value> = contexts.iter().filter_map(|c| c.pointer("/Pumps/0")).collect();
+    assert!(pumps.iter().any(|p| p.get("rpm").is_some() && p.get("active").is_some()), "{:?}", pumps);
+
+    let fired: Vec<Vec<String>> = contexts.iter().map(|c| actions(&policy, c)).collect();
+    for action in ["Vent", "Stop", "Throttle", "Start", "Wait"] {
+        assert!(fired.iter().any(|a| a.iter().any(|x| x == action)), "{} never fired", action);
+        assert!(fired.iter().any(|a| !a.iter().any(|x| x == action)), "{} always fired", action);
+    }
+}
+
+#[test]
+fn examples_agree_across_engines() {
+    let options = ConformanceOptions {
+        contexts: vec![("context.json".to_string(), serde_json::from_str(&std::fs::read_to_string("examples/context.json").unwrap()).unwrap())],
+        ..Default::default()
+    };
+    let report = run(&[PathBuf::from("examples")], &options).unwrap();
+    assert!(report.is_clean(), "{}", render_text(&report));
+    assert!(report.policies.len() > 10);
+    assert!(report.skipped_files.iter().any(|f| f.reason == PROGRAM_FILE));
+    // Kebijakan temporal tidak bisa dikompilasi ke WASM, jadi engine itu dilewati
+    let factory = report.policies.iter().find(|p| p.policy.ends_with("factory_safety.omni")).unwrap();
+    assert_eq!(factory.engines, vec![Engine::Runtime, Engine::Ir, Engine::IrOpt]);
+    assert_eq!(factory.skipped.iter().map(|s| s.engine).collect::<Vec<_>>(), vec![Engine::Wasm, Engine::WasmOpt]);
+    let demo = report.policies.iter().find(|p| p.policy.ends_with("loop_demo.omni")).unwrap();
+    assert_eq!(demo.engines, Engine::DEFAULT.to_vec());
+    assert_eq!(demo.contexts, 22);
+
+    let policy = parse_policy(PLANT);
+    let options = ConformanceOptions { engines: vec![Engine::Wasm, Engine::Runtime], random_contexts: 50, ..Default::default() };
+    let (report, divergences) = check_policy(&policy, "plant", &options);
+    assert_eq!(report.contexts, 51);
+    assert!(divergences.is_empty(), "{:?}", divergences);
+
+    // Optimizer menghasilkan node hoisted, dispatch, dan rule `false`; jumlah rule tetap sama
+    let rewritten = parse_policy(
+        "INTENT: Line
+RULE:
+- IF 1 > 2 THEN Never
+         - IF Online AND Speed > 5 THEN Warn
+- IF Online AND Speed > 9 THEN Stop
+         - IF Mode == \"Idle\" THEN Park
+- IF Mode == \"Run\" THEN Drive
+- IF Mode == \"Hold\" THEN Brake
+",
+    );
+    let options = ConformanceOptions { engines: Engine::DEFAULT.to_vec(), random_contexts: 50, ..Default::default() };
+    let (report, divergences) = check_policy(&rewritten, "line", &options);
+    assert_eq!(report.engines, vec![Engine::Runtime, Engine::Ir, Engine::IrOpt, Engine::Wasm, Engine::WasmOpt]);
+    assert!(divergences.is_empty(), "{:?}", divergences);
+}
+
+#[test]
+fn divergences_name_the_engines_and_differing_fields() {
+    assert_eq!(Engine::parse("wasm"), Ok(Engine::Wasm));
+    assert_eq!(Engine::parse("ir-opt"), Ok(Engine::IrOpt));
+    assert_eq!(Engine::WasmOpt.to_string(), "wasm-opt");
+    assert!(Engine::parse("jvm").unwrap_err().contains("unknown engine 'jvm'"));
+
+    let expected = Outcome { actions: vec!["Vent".to_string()], guard_triggered: false, rules_evaluated: 3 };
+    let divergence = Divergence {
+        policy: "plant.omni".to_string(),
+        context: "random #4".to_string(),
+        input: json!({ "Temperature": 91 }),
+        reference: Engine::Runtime,
+        engine: Engine::Ir,
+        expected: expected.clone(),
+        actual: Some(Outcome { rules_evaluated: 4, ..expected.clone() }),
+        error: None,
+    };
+    assert_eq!(divergence.fields(), vec!["rules_evaluated"]);
+    let failed = Divergence { engine: Engine::Wasm, actual: None, error: Some("trap".to_string()), ..divergence.clone() };
+    assert_eq!(failed.fields(), vec!["error"]);
+
+    let report = ConformanceReport { seed: 3, divergences: vec![divergence, failed], ..Default::default() };
+    assert!(!report.is_clean());
+    let text = render_text(&report);
+    assert!(text.contains("DIVERGENCE plant.omni [random #4]: ir differs from runtime in rules_evaluated"), "{}", text);
+    assert!(text.contains("    ir: actions [\"Vent\"], guard_triggered false, rules_evaluated 4"), "{}", text);
+    assert!(text.contains("    wasm: error: trap"), "{}", text);
+    assert!(text.ends_with("Result: 2 divergences\n"));
+}
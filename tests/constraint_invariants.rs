@@ -0,0 +1,165 @@
+use omnilang_core::analysis::{analyze, FindingKind};
+use omnilang_core::ast::{Invariant, InvariantCheck};
+use omnilang_core::explain::Branch;
+use omnilang_core::ir::build_policy_ir;
+use omnilang_core::ir_interpreter::execute_ir;
+use omnilang_core::runtime::{Decision, Runtime, RuntimeConfig};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn run(policy: &omnilang_core::ast::Policy, context: &Value) -> Decision {
+    let mut rt = Runtime::new();
+    for (key, value) in context.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    rt.execute_policy(policy)
+}
+
+const FORKLIFT: &str = "INTENT: Forklift\nUNITS:\n- Speed: km/h\nRULE:\n\
+                        - IF Route == \"clear\" THEN Accelerate\n\
+                        - IF Load > 500 THEN OpenClamp\n\
+                        - IF Pallet == \"arrived\" THEN CloseClamp\n\
+                        - IF Speed > 20km/h THEN Honk\n\
+                        CONSTRAINT:\n\
+                        - Ethical: NEVER (Accelerate AND HumanNearby) ELSE Brake(force: 0.8)\n\
+                        - Technical: MAX 1 OF [OpenClamp, CloseClamp]\n\
+                        - Technical: NEVER Honk AND Speed > 30km/h\n\
+                        - Legal: Operator wajib bersertifikat\n";
+
+#[test]
+fn invariants_parse_with_fallbacks_and_reject_malformed_input() {
+    let policy = parse_policy(FORKLIFT).expect("parse failed");
+    assert_eq!(policy.constraints.len(), 4);
+
+    let ethical = policy.constraints[0].invariant.as_ref().unwrap();
+    assert!(matches!(ethical.check, InvariantCheck::Never { .. }));
+    assert_eq!(ethical.fallback.as_ref().unwrap().name, "Brake");
+    assert_eq!(policy.constraints[0].description, "NEVER (Accelerate AND HumanNearby) ELSE Brake(force: 0.8)");
+
+    let clamp = policy.constraints[1].invariant.as_ref().unwrap();
+    assert_eq!(
+        clamp,
+        &Invariant {
+            check: InvariantCheck::AtMost { limit: 1, actions: vec!["OpenClamp".to_string(), "CloseClamp".to_string()] },
+            fallback: None,
+        }
+    );
+    assert!(policy.constraints[3].invariant.is_none());
+    assert_eq!(policy.constraints[3].description, "Operator wajib bersertifikat");
+    let prose = parse_policy("INTENT: x\nRULE:\n- IF A THEN B\nCONSTRAINT:\n- Ethical: Never share personal data\n").unwrap();
+    assert!(prose.constraints[0].invariant.is_none());
+    assert_eq!(prose.constraints[0].description, "Never share personal data");
+
+    let ir = serde_json::to_value(build_policy_ir(&policy)).unwrap();
+    assert_eq!(ir["constraints"][1]["invariant"]["check"]["kind"], "at_most");
+    assert!(ir["constraints"][3].get("invariant").is_none());
+
+    let base = "INTENT: x\nRULE:\n- IF A THEN B\nCONSTRAINT:\n";
+    let err = parse_policy(&format!("{}- Technical: MAX 1.5 OF [A, B]\n", base)).unwrap_err();
+    assert!(err.contains("MAX expects a whole number"), "{}", err);
+    let err = parse_policy(&format!("{}- Technical: MAX 1 [A, B]\n", base)).unwrap_err();
+    assert!(err.contains("Expected OF after MAX n"), "{}", err);
+    let err = parse_policy(&format!("{}- Technical: MAX 1 OF []\n", base)).unwrap_err();
+    assert!(err.contains("at least one action"), "{}", err);
+    let err = parse_policy(&format!("{}- Ethical: NEVER (A AND B) always\n", base)).unwrap_err();
+    assert!(err.contains("Unexpected 'always' after constraint invariant"), "{}", err);
+    let err = parse_policy(&format!("{}- Ethical: NEVER B AND Speed > 3s\nUNITS:\n- Speed: km/h\n", base)).unwrap_err();
+    assert!(err.contains("Unit mismatch"), "{}", err);
+}
+
+#[test]
+fn runtime_and_ir_veto_and_replace_the_same_actions() {
+    let policy = parse_policy(FORKLIFT).expect("parse failed");
+    let context = json!({ "Route": "clear", "HumanNearby": true, "Load": 600, "Pallet": "arrived", "Speed": 25 });
+    let decision = run(&policy, &context);
+    assert_eq!(decision.actions, vec!["OpenClamp", "Honk", "Brake"]);
+    assert_eq!(decision.payloads.len(), 3);
+    assert_eq!(decision.payloads[2].params["force"], json!(0.8));
+    let brake = &decision.explanations[2];
+    assert_eq!(brake.branch, Branch::Constraint);
+    assert!(brake.rule.starts_with("CONSTRAINT Ethical: NEVER (Accelerate AND HumanNearby)"));
+
+    assert_eq!(decision.violations.len(), 2);
+    assert_eq!(decision.violations[0].vetoed, vec!["Accelerate"]);
+    assert_eq!(decision.violations[0].replacement.as_deref(), Some("Brake"));
+    assert_eq!(
+        decision.violations[1].to_string(),
+        "Technical: MAX 1 OF [OpenClamp, CloseClamp] violated; vetoed CloseClamp"
+    );
+
+    let ir_decision = execute_ir(&build_policy_ir(&policy), context.clone());
+    assert_eq!(ir_decision.actions, decision.actions);
+    assert_eq!(ir_decision.violations, decision.violations);
+
+    // Field konteks dibandingkan apa adanya; aksi yang tidak dipicu bernilai false
+    let fast = run(&policy, &json!({ "Speed": 35, "HumanNearby": true }));
+    assert!(fast.actions.is_empty());
+    assert_eq!(fast.violations[0].kind, "Technical");
+    assert_eq!(fast.violations[0].vetoed, vec!["Honk"]);
+    let calm = run(&policy, &json!({ "Route": "clear", "Speed": 25 }));
+    assert_eq!(calm.actions, vec!["Accelerate", "Honk"]);
+    assert!(calm.violations.is_empty());
+    assert!(serde_json::to_value(&calm).unwrap().get("violations").is_none());
+}
+
+#[test]
+fn fallbacks_feed_later_invariants_and_lint_reports_forbidden_pairs() {
+    let policy = parse_policy(
+        "INTENT: Press\nRULE:\n\
+         - IF Jam THEN StartPress\n- IF Jam THEN StopPress\n- IF Door == \"open\" THEN Unlock\n\
+         CONSTRAINT:\n\
+         - Technical: MAX 1 OF [StartPress, StopPress] ELSE ResetPress\n\
+         - Ethical: NEVER (ResetPress AND Unlock) ELSE Lockout\n",
+    )
+    .expect("parse failed");
+    let mut rt = Runtime::with_config(RuntimeConfig::default());
+    rt.update_data("Jam", json!(true));
+    rt.update_data("Door", json!("open"));
+    let decision = rt.execute_policy(&policy);
+    // ResetPress dari invarian pertama ikut diveto invarian kedua bersama Unlock
+    assert_eq!(decision.actions, vec!["StartPress", "Lockout"]);
+    assert_eq!(decision.violations[1].vetoed, vec!["Unlock", "ResetPress"]);
+
+    let findings = analyze(&policy);
+    let contradictions: Vec<_> = findings.iter().filter(|f| f.kind == FindingKind::Contradiction).collect();
+    assert_eq!(contradictions.len(), 1);
+    assert!(contradictions[0].message.contains("forbidden by a CONSTRAINT invariant"), "{}", contradictions[0].message);
+
+    let forbidden = parse_policy(
+        "INTENT: Line\nRULE:\n- IF Speed > 5 THEN Accelerate\n- IF Human THEN Greet\n\
+         CONSTRAINT:\n- Ethical: NEVER (Accelerate AND Greet)\n",
+    )
+    .unwrap();
+    let findings = analyze(&forbidden);
+    let pair = findings.iter().find(|f| f.kind == FindingKind::Contradiction).expect("forbidden pair");
+    let witness = pair.witness.clone().unwrap();
+    // Semua aksi yang disebut NEVER diveto, bukan hanya salah satunya
+    let decision = run(&forbidden, &witness);
+    assert!(decision.actions.is_empty());
+    assert_eq!(decision.violations[0].vetoed, vec!["Accelerate", "Greet"]);
+}
+
+#[test]
+fn grouped_never_without_else_ends_at_its_own_line() {
+    let policy = parse_policy(
+        "INTENT: Forklift\nRULE:\n- IF Route == \"clear\" THEN Accelerate\n- IF Load > 500 THEN OpenClamp\n\
+         - IF Pallet == \"arrived\" THEN CloseClamp\nCONSTRAINT:\n\
+         - Ethical: NEVER (Accelerate AND HumanNearby)\n\
+         - Technical: MAX 1 OF [OpenClamp, CloseClamp]\n",
+    )
+    .expect("parse failed");
+    assert_eq!(policy.constraints.len(), 2);
+    assert!(policy.constraints.iter().all(|c| c.invariant.as_ref().is_some_and(|i| i.fallback.is_none())));
+    assert_eq!(policy.constraints[0].description, "NEVER (Accelerate AND HumanNearby)");
+
+    let decision = run(&policy, &json!({ "Route": "clear", "HumanNearby": true, "Load": 600, "Pallet": "arrived" }));
+    assert!(!decision.actions.iter().any(|a| a == "Accelerate"), "{:?}", decision.actions);
+    assert_eq!(decision.violations.len(), 2);
+}
@@ -0,0 +1,117 @@
+use omnilang_core::ast::FieldType;
+use omnilang_core::schema::{to_json_schema, validate, ViolationKind};
+use omnilang_core::{lexer::Lexer, parser::Parser, runtime::Runtime};
+use serde_json::json;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+const BOILER: &str = r#"
+INTENT: Boiler supervision
+SCHEMA:
+- Temperature: number C [-40, 400]
+- Pressure: number bar [0, 40] required
+- Valve: string
+- Alarms: integer optional
+- Boiler.Serial: string optional
+RULE:
+- IF Temperature > 250C AND Valve == "closed" THEN OpenValve
+- IF Pressure > 3000kPa THEN ReleaseValve
+"#;
+
+#[test]
+fn schema_section_parses_and_declares_units() {
+    let policy = parse_policy(BOILER).expect("parse failed");
+    assert_eq!(policy.schema.len(), 5);
+    let temperature = &policy.schema[0];
+    assert_eq!((temperature.ty, temperature.unit.as_deref()), (FieldType::Number, Some("C")));
+    assert_eq!(temperature.range, Some((-40.0, 400.0)));
+    assert!(temperature.required && !policy.schema[3].required);
+    // Satuan dari SCHEMA berlaku seperti UNITS: 35 bar = 3500 kPa
+    let units: Vec<_> = policy.units.iter().map(|u| (u.field.as_str(), u.unit.as_str())).collect();
+    assert_eq!(units, vec![("Temperature", "C"), ("Pressure", "bar")]);
+
+    let mut rt = Runtime::new();
+    for (key, value) in [("Temperature", json!(20)), ("Pressure", json!(35)), ("Valve", json!("open"))] {
+        rt.update_data(key, value);
+    }
+    assert!(rt.validate_context(&policy.schema).is_ok());
+    assert_eq!(rt.execute_policy(&policy).actions, vec!["ReleaseValve"]);
+
+    for (source, error) in [
+        ("SCHEMA:\n- Valve: string bar\n", "Only number fields can have a unit"),
+        ("SCHEMA:\n- Valve: text [0, 1]\n", "Only number fields can have a range"),
+        ("SCHEMA:\n- Level: number [5, 1]\n", "Empty range"),
+        ("SCHEMA:\n- Level: number [0C, 1C]\n", "without a suffix"),
+        ("SCHEMA:\n- Level: decimal\n", "Unknown field type 'decimal'"),
+        ("SCHEMA:\n- Level: number\n- Level: integer\n", "Duplicate SCHEMA field 'Level'"),
+        ("UNITS:\n- Level: m\nSCHEMA:\n- Level: number cm\n", "Conflicting units for 'Level'"),
+    ] {
+        let err = parse_policy(&format!("INTENT: x\n{}RULE:\n- IF A > 1 THEN B\n", source)).unwrap_err();
+        assert!(err.contains(error), "{} => {}", source, err);
+    }
+
+    // `Schema:` di tengah baris hanyalah teks item, bukan header seksi
+    let prose = parse_policy("INTENT: x\nASSUMPTION:\n- Schema: documented elsewhere\nRULE:\n- IF A > 1 THEN B\n").unwrap();
+    assert_eq!(prose.assumptions.len(), 1);
+    assert!(prose.schema.is_empty());
+}
+
+#[test]
+fn context_violations_are_structured_and_suggest_typos() {
+    let policy = parse_policy(BOILER).expect("parse failed");
+    let context = json!({
+        "Temprature": 262,
+        "Pressure": 55,
+        "Valve": 1,
+        "Alarms": 2.5,
+        "Boiler": { "Serial": "B-7" }
+    });
+    let violations = validate(&policy.schema, &context);
+    let kinds: Vec<(&str, &ViolationKind)> = violations.iter().map(|v| (v.field.as_str(), &v.kind)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("Temperature", &ViolationKind::Missing { suggestion: Some("Temprature".into()) }),
+            ("Pressure", &ViolationKind::OutOfRange { value: 55.0, min: 0.0, max: 40.0 }),
+            ("Valve", &ViolationKind::WrongType { expected: "string".into(), found: "number".into() }),
+            ("Alarms", &ViolationKind::WrongType { expected: "integer".into(), found: "number".into() }),
+            ("Temprature", &ViolationKind::Undeclared { suggestion: Some("Temperature".into()) }),
+        ]
+    );
+    assert_eq!(violations[4].to_string(), "field 'Temprature' is not declared in SCHEMA (did you mean 'Temperature'?)");
+    assert_eq!(
+        serde_json::to_value(&violations[1]).unwrap(),
+        json!({ "field": "Pressure", "kind": "out_of_range", "value": 55.0, "min": 0.0, "max": 40.0 })
+    );
+
+    let mut rt = Runtime::new();
+    rt.update_data("Pressure", json!(10));
+    let errors = rt.validate_context(&policy.schema).unwrap_err();
+    let missing: Vec<_> = errors.iter().map(|v| v.field.as_str()).collect();
+    assert_eq!(missing, vec!["Temperature", "Valve"]);
+    assert!(validate(&[], &context).is_empty(), "no SCHEMA accepts any context");
+}
+
+#[test]
+fn schema_exports_as_json_schema() {
+    let policy = parse_policy(BOILER).expect("parse failed");
+    let schema = to_json_schema(&policy);
+    assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+    assert_eq!(schema["title"], "Boiler supervision");
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(schema["required"], json!(["Temperature", "Pressure", "Valve"]));
+    assert_eq!(
+        schema["properties"]["Temperature"],
+        json!({ "type": "number", "x-unit": "C", "minimum": -40, "maximum": 400 })
+    );
+    assert_eq!(schema["properties"]["Alarms"], json!({ "type": "integer" }));
+    assert_eq!(
+        schema["properties"]["Boiler"],
+        json!({ "type": "object", "properties": { "Serial": { "type": "string" } } })
+    );
+}
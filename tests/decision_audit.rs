@@ -0,0 +1,158 @@
+use omnilang_core::audit::{head_path, policy_hash, verify, AuditLog, GENESIS_HASH};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::watch::{WatchOptions, Watcher};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+use std::io::Cursor;
+use std::path::PathBuf;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+fn temp_log(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omni_audit_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("decisions.jsonl")
+}
+
+const WARD: &str = "INTENT: Ward\nRULE:\n- IF OxygenLevel < 90 THEN AlertNurse\n";
+
+/// Tulis `n` keputusan dengan konteks OxygenLevel berbeda.
+fn write_decisions(path: &PathBuf, key: Option<&str>, n: u64) {
+    let policy = parse_policy(WARD);
+    let hash = policy_hash(&policy);
+    let mut log = AuditLog::open(path, key).unwrap();
+    for i in 0..n {
+        let mut rt = Runtime::new();
+        rt.update_data("OxygenLevel", json!(85 + i * 5));
+        let context = rt.context_snapshot();
+        let decision = rt.execute_policy(&policy);
+        log.append(&hash, &context, &decision, 1_700_000_000_000 + i).unwrap();
+    }
+}
+
+fn lines(path: &PathBuf) -> Vec<String> {
+    std::fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn records_chain_hashes_and_reopened_logs_continue_the_chain() {
+    let path = temp_log("chain");
+    write_decisions(&path, Some("s3cret"), 2);
+    write_decisions(&path, Some("s3cret"), 1);
+
+    let records: Vec<Value> = lines(&path).iter().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0]["seq"], 0);
+    assert_eq!(records[0]["prev_hash"], GENESIS_HASH);
+    assert_eq!(records[1]["prev_hash"], records[0]["hash"]);
+    assert_eq!(records[2]["seq"], 2);
+    assert_eq!(records[2]["prev_hash"], records[1]["hash"]);
+    assert_eq!(records[0]["decision"]["actions"], json!(["AlertNurse"]));
+    assert_eq!(records[1]["decision"]["actions"], json!([]));
+    assert_eq!(records[0]["policy_hash"], records[2]["policy_hash"]);
+    assert_ne!(records[0]["context_hash"], records[1]["context_hash"]);
+    assert!(records[0]["hmac"].is_string());
+
+    let report = verify(&path, Some("s3cret")).unwrap();
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.records, 3);
+    assert_eq!(report.head, records[2]["hash"].as_str().unwrap());
+    let wrong = verify(&path, Some("guess")).unwrap();
+    let messages: Vec<String> = wrong.issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(messages.len(), 4, "{:?}", messages);
+    assert_eq!(messages[0], "line 1: HMAC mismatch");
+    assert_eq!(messages[3], "head: HMAC mismatch");
+}
+
+#[test]
+fn verify_detects_modified_deleted_and_truncated_records() {
+    let path = temp_log("tamper");
+    write_decisions(&path, None, 4);
+    let original = lines(&path);
+
+    let mut modified = original.clone();
+    modified[1] = modified[1].replace("\"actions\":[]", "\"actions\":[\"AlertNurse\"]");
+    std::fs::write(&path, modified.join("\n") + "\n").unwrap();
+    let issues: Vec<String> = verify(&path, None).unwrap().issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues, vec!["line 2: record was modified (hash mismatch)"]);
+
+    let mut deleted = original.clone();
+    deleted.remove(2);
+    std::fs::write(&path, deleted.join("\n") + "\n").unwrap();
+    let issues: Vec<String> = verify(&path, None).unwrap().issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues[0], "line 3: expected seq 2, found 3");
+    assert_eq!(issues[1], "line 3: prev_hash does not match the previous record");
+    assert!(issues[2].starts_with("head: expected 4 records"), "{:?}", issues);
+
+    // Pemotongan ekor menjaga rantai tetap utuh, jadi hanya file head yang menangkapnya
+    std::fs::write(&path, original[..3].join("\n") + "\n").unwrap();
+    let report = verify(&path, None).unwrap();
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].line, 0);
+    assert!(AuditLog::open(&path, None).err().unwrap().contains("does not match its head file"));
+
+    std::fs::remove_file(head_path(&path)).unwrap();
+    let report = verify(&path, None).unwrap();
+    assert!(report.issues[0].message.starts_with("missing "), "{:?}", report.issues);
+}
+
+#[test]
+fn watch_records_every_evaluated_patch() {
+    let path = temp_log("watch");
+    let policy = parse_policy(WARD);
+    let log = AuditLog::open(&path, None).unwrap();
+    let mut watcher = Watcher::new(&policy, Runtime::new(), WatchOptions { changes_only: true }).with_audit(log);
+    let input = "{\"OxygenLevel\": 85}\n{\"OxygenLevel\": 80}\nnot json\n{\"OxygenLevel\": 97}\n";
+    let mut output = Vec::new();
+    watcher.run(Cursor::new(input), &mut output).unwrap();
+    assert_eq!(String::from_utf8(output).unwrap().lines().count(), 3);
+
+    // Keputusan yang tidak ditulis karena --changes-only tetap tercatat
+    let records: Vec<Value> = lines(&path).iter().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[1]["decision"]["actions"], json!(["AlertNurse"]));
+    assert_eq!(records[0]["policy_hash"], policy_hash(&policy));
+    assert_eq!(records[2]["context_hash"], omnilang_core::audit::canonical_hash(&json!({ "OxygenLevel": 97 })));
+    assert!(verify(&path, None).unwrap().is_valid());
+}
+
+#[test]
+fn concurrent_writers_keep_one_valid_chain() {
+    let path = temp_log("concurrent");
+    let policy = parse_policy(WARD);
+    let hash = policy_hash(&policy);
+    // Setiap writer membuka log-nya sendiri sebelum yang lain menulis, seperti proses `exec` paralel
+    let logs: Vec<AuditLog> = (0..4).map(|_| AuditLog::open(&path, Some("k")).unwrap()).collect();
+    let handles: Vec<_> = logs
+        .into_iter()
+        .enumerate()
+        .map(|(w, mut log)| {
+            let policy = policy.clone();
+            let hash = hash.clone();
+            std::thread::spawn(move || {
+                for i in 0..10u64 {
+                    let mut rt = Runtime::new();
+                    rt.update_data("OxygenLevel", json!(80 + w as u64 * 10 + i));
+                    let context = rt.context_snapshot();
+                    let decision = rt.execute_policy(&policy);
+                    log.append(&hash, &context, &decision, 1_700_000_000_000 + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let report = verify(&path, Some("k")).unwrap();
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.records, 40);
+    let seqs: Vec<u64> = lines(&path).iter().map(|l| serde_json::from_str::<Value>(l).unwrap()["seq"].as_u64().unwrap()).collect();
+    assert_eq!(seqs, (0..40).collect::<Vec<_>>());
+}
@@ -0,0 +1,112 @@
+use omnilang_core::cache::{context_hash, is_cacheable, policy_key, CacheTier, DecisionCache};
+use omnilang_core::runtime::{Decision, Runtime, RuntimeConfig};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::json;
+use std::path::PathBuf;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+fn decision(action: &str) -> Decision {
+    let mut decision = Runtime::new().execute_policy(&parse_policy("INTENT: X\nRULE:\n- IF true THEN Noop\n"));
+    decision.actions = vec![action.to_string()];
+    decision
+}
+
+fn temp_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("omnilang_cache_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn memory_tier_evicts_least_recently_used_and_counts_hits() {
+    let mut cache = DecisionCache::new(2);
+    cache.put("p", "a", &decision("A")).unwrap();
+    cache.put("p", "b", &decision("B")).unwrap();
+    // `a` dipakai lagi, jadi `b` yang paling lama tidak dipakai
+    assert_eq!(cache.get("p", "a").map(|(d, t)| (d.actions, t)), Some((vec!["A".to_string()], CacheTier::Memory)));
+    cache.put("p", "c", &decision("C")).unwrap();
+    assert!(cache.get("p", "b").is_none());
+    assert!(cache.get("p", "a").is_some());
+    assert!(cache.get("q", "a").is_none());
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.memory_hits, stats.misses, stats.evictions, stats.entries), (2, 2, 2, 1, 2));
+    assert_eq!(stats.hit_rate(), 0.5);
+
+    assert_eq!(cache.invalidate_policy("p").unwrap(), 2);
+    assert!(cache.get("p", "c").is_none());
+    assert_eq!(cache.stats().invalidations, 2);
+}
+
+#[test]
+fn persistent_tier_survives_reopen_and_drops_invalidated_policies() {
+    let path = temp_file("store.jsonl");
+    {
+        let mut cache = DecisionCache::new(8).with_store(&path).unwrap();
+        cache.bind_policy("plant.omni", "v1").unwrap();
+        cache.put("v1", "ctx1", &decision("Vent")).unwrap();
+        cache.put("v1", "ctx2", &decision("Stop")).unwrap();
+        cache.put("other", "ctx1", &decision("Keep")).unwrap();
+    }
+    // Setiap put hanya menambah satu baris
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 4);
+
+    let mut cache = DecisionCache::new(8).with_store(&path).unwrap();
+    let (hit, tier) = cache.get("v1", "ctx2").unwrap();
+    assert_eq!((hit.actions, tier), (vec!["Stop".to_string()], CacheTier::Disk));
+    assert_eq!(cache.get("v1", "ctx2").unwrap().1, CacheTier::Memory);
+
+    // Kebijakan berubah: entri versi lama diinvalidasi, binding yang sama tidak menghapus apa pun
+    assert_eq!(cache.bind_policy("plant.omni", "v2").unwrap(), 2);
+    assert_eq!(cache.bind_policy("plant.omni", "v2").unwrap(), 0);
+    assert!(cache.get("v1", "ctx1").is_none());
+    drop(cache);
+
+    let mut cache = DecisionCache::new(8).with_store(&path).unwrap();
+    assert!(cache.get("v1", "ctx1").is_none());
+    assert_eq!(cache.get("other", "ctx1").unwrap().0.actions, vec!["Keep"]);
+    assert_eq!(cache.stats().entries, 1);
+    // Baris mati dibuang saat file dibuka: satu entri dan satu binding tersisa
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn runtime_serves_repeated_contexts_from_cache_but_not_temporal_policies() {
+    let policy = parse_policy("INTENT: Plant\nRULE:\n- IF Temperature > 90 THEN Vent(level: Temperature)\n");
+    let mut runtime = Runtime::new();
+    runtime.enable_cache(DecisionCache::new(16));
+    runtime.update_data("Temperature", json!(95));
+    let first = runtime.execute_policy(&policy);
+    let second = runtime.execute_policy(&policy);
+    assert_eq!(second.actions, first.actions);
+    assert_eq!(second.payloads, first.payloads);
+    assert!(second.logs.last().unwrap().ends_with("CACHE hit (memory)"));
+
+    runtime.update_data("Temperature", json!(80));
+    assert!(runtime.execute_policy(&policy).actions.is_empty());
+    let changed = parse_policy("INTENT: Plant\nRULE:\n- IF Temperature > 70 THEN Vent(level: Temperature)\n");
+    assert_eq!(runtime.execute_policy(&changed).actions, vec!["Vent"]);
+    let stats = runtime.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries), (1, 3, 3));
+    assert_eq!(runtime.invalidate_cached(&policy).unwrap(), 2);
+
+    // Batas guard runtime ikut menentukan kunci kebijakan
+    let capped = RuntimeConfig { max_loop_iterations: 1, ..RuntimeConfig::default() };
+    assert_ne!(policy_key(&policy, &RuntimeConfig::default()), policy_key(&policy, &capped));
+    assert_eq!(context_hash(&json!({ "a": 1, "b": 2 })), context_hash(&json!({ "b": 2, "a": 1 })));
+
+    let temporal = parse_policy("INTENT: Door\nRULE:\n- IF BECOMES Door == \"open\" THEN Log\n- IF Guard THEN Alarm(n: COUNT(Fault == true) WITHIN 1min)\n");
+    assert!(!is_cacheable(&temporal));
+    assert!(is_cacheable(&policy));
+    runtime.update_data("Door", json!("open"));
+    assert_eq!(runtime.execute_policy(&temporal).actions, vec!["Log"]);
+    assert!(runtime.execute_policy(&temporal).actions.is_empty());
+    assert_eq!(runtime.cache_stats().unwrap().bypassed, 2);
+}
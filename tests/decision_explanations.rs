@@ -0,0 +1,94 @@
+use omnilang_core::explain::{render_text, Branch};
+use omnilang_core::{ir::build_policy_ir, ir_interpreter::execute_ir, lexer::Lexer, parser::Parser, runtime::Runtime};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+const PLANT: &str = r#"
+INTENT: Plant supervision
+RULE:
+- IF Temperature > 80C AND (Mode == "auto" OR Override) THEN CoolDown
+- IF Door == "open" THEN SoundAlarm ELSE LockPanel
+- FOR worker IN Workers {
+    - IF worker.zone == "red" THEN Notify(id: worker.id)
+}
+"#;
+
+fn decide(ctx: Value) -> omnilang_core::runtime::Decision {
+    let policy = parse_policy(PLANT).expect("parse failed");
+    let mut rt = Runtime::new();
+    for (key, value) in ctx.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    let decision = rt.execute_policy(&policy);
+    let ir_decision = execute_ir(&build_policy_ir(&policy), ctx);
+    assert_eq!(decision.explanations, ir_decision.explanations, "runtime and IR interpreter disagree");
+    decision
+}
+
+#[test]
+fn explanation_tree_records_sub_conditions_and_values() {
+    let decision = decide(json!({
+        "Temperature": 85, "Mode": "manual", "Override": true, "Door": "closed", "Workers": []
+    }));
+    assert_eq!(decision.actions, vec!["CoolDown", "LockPanel"]);
+    assert_eq!(decision.explanations.len(), decision.actions.len());
+
+    let cool = &decision.explanations[0];
+    assert_eq!(cool.branch, Branch::Then);
+    assert_eq!(cool.rule, "IF Temperature > 80C AND (Mode == \"auto\" OR Override) THEN CoolDown");
+    assert!(cool.condition.result);
+    let [temp, either] = cool.condition.children.as_slice() else {
+        panic!("expected two AND terms: {:?}", cool.condition);
+    };
+    assert_eq!(temp.values["Temperature"], json!(85));
+    let [mode, overridden] = either.children.as_slice() else {
+        panic!("expected two OR terms: {:?}", either);
+    };
+    assert_eq!((mode.result, &mode.values["Mode"]), (false, &json!("manual")));
+    assert_eq!((overridden.result, &overridden.values["Override"]), (true, &json!(true)));
+
+    let lock = &decision.explanations[1];
+    assert_eq!(lock.branch, Branch::Else);
+    assert!(!lock.condition.result);
+    assert_eq!(lock.condition.values["Door"], json!("closed"));
+}
+
+#[test]
+fn short_circuited_terms_and_symbols_are_not_reported() {
+    let decision = decide(json!({ "Temperature": 85, "Mode": "auto", "Door": "open", "Workers": [] }));
+    let cool = &decision.explanations[0];
+    // OR berhenti di term pertama yang benar; `Override` tidak pernah dibaca
+    assert_eq!(cool.condition.children[1].children.len(), 1);
+    let alarm = &decision.explanations[1];
+    assert_eq!(alarm.branch, Branch::Then);
+    assert_eq!(alarm.condition.values.keys().collect::<Vec<_>>(), vec!["Door"]);
+}
+
+#[test]
+fn loop_iterations_are_attached_and_rendered() {
+    let decision = decide(json!({
+        "Temperature": 20, "Door": "open",
+        "Workers": [{ "id": "w-1", "zone": "green" }, { "id": "w-2", "zone": "red" }]
+    }));
+    assert_eq!(decision.actions, vec!["SoundAlarm", "Notify"]);
+    let notify = &decision.explanations[1];
+    assert_eq!(notify.loops.len(), 1);
+    assert_eq!(notify.loops[0].header, "FOR worker IN Workers");
+    assert_eq!(notify.loops[0].index, 1);
+    assert_eq!(notify.loops[0].value, Some(json!({ "id": "w-2", "zone": "red" })));
+
+    let text = render_text(&decision.explanations);
+    assert!(text.contains("2. Notify"), "{}", text);
+    assert!(text.contains("loop:   FOR worker IN Workers [iteration 1]"), "{}", text);
+    assert!(text.contains("[true ] worker.zone == \"red\"  (worker.zone = \"red\")"), "{}", text);
+
+    let json = serde_json::to_value(&decision).unwrap();
+    assert_eq!(json["explanations"][1]["branch"], "then");
+    assert!(json["explanations"][0].get("loops").is_none());
+}
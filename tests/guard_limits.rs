@@ -0,0 +1,103 @@
+use omnilang_core::ir::{build_policy_ir, build_policy_ir_with_config, GuardSource, RuleIR};
+use omnilang_core::ir_interpreter::execute_ir;
+use omnilang_core::runtime::{GuardLimit, Runtime, RuntimeConfig, Truncation};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn fleet(size: usize) -> Value {
+    let vehicles: Vec<Value> = (0..size).map(|i| json!({ "id": i, "battery": if i % 40 == 39 { 5 } else { 80 } })).collect();
+    json!({ "Vehicles": vehicles })
+}
+
+const FLEET: &str = r#"
+INTENT: Fleet telemetry
+RULE:
+- FOR vehicle IN Vehicles {
+    - IF vehicle.battery < 15 THEN ScheduleCharge(id: vehicle.id)
+}
+"#;
+
+#[test]
+fn default_limits_report_truncation_instead_of_dropping_silently() {
+    let policy = parse_policy(FLEET).expect("parse failed");
+    let mut rt = Runtime::new();
+    rt.update_data("Vehicles", fleet(120)["Vehicles"].clone());
+    let decision = rt.execute_policy(&policy);
+
+    assert_eq!(decision.guard.max_iterations, 50);
+    assert_eq!(decision.guard.source, GuardSource::Runtime);
+    assert!(decision.guard_triggered);
+    // Hanya kendaraan 39 yang masuk dalam 50 iterasi pertama
+    assert_eq!(decision.payloads.len(), 1);
+    assert_eq!(
+        decision.truncations,
+        vec![Truncation { collection: "Vehicles".into(), total: 120, processed: 50, limit: GuardLimit::Iterations }]
+    );
+    assert_eq!(decision.truncations[0].to_string(), "FOR over 'Vehicles' stopped at 50 of 120 elements (iteration limit)");
+
+    let ir_decision = execute_ir(&build_policy_ir(&policy), fleet(120));
+    assert_eq!(ir_decision.truncations, decision.truncations);
+}
+
+#[test]
+fn guard_section_raises_limits_and_flows_into_ir() {
+    let source = FLEET.replace("RULE:", "GUARD:\n- MaxIterations: 500\n- MaxTime: 1500ms\nRULE:");
+    let policy = parse_policy(&source).expect("parse failed");
+    assert_eq!((policy.guard.max_iterations, policy.guard.max_time_ms), (Some(500), Some(1500)));
+
+    let ir = build_policy_ir(&policy);
+    assert_eq!((ir.guard.max_iterations, ir.guard.max_time_ms, ir.guard.source), (500, 1500, GuardSource::Policy));
+    let RuleIR::For(loop_ir) = &ir.rules[0] else { panic!("expected FOR rule") };
+    assert_eq!(loop_ir.guard, ir.guard);
+    let ir_json = serde_json::to_value(&ir).unwrap();
+    assert_eq!(ir_json["guard"], json!({ "max_iterations": 500, "max_time_ms": 1500, "source": "policy" }));
+    assert!(serde_json::to_value(build_policy_ir(&parse_policy(FLEET).unwrap())).unwrap().get("guard").is_none());
+
+    let decision = execute_ir(&ir, fleet(120));
+    assert!(!decision.guard_triggered && decision.truncations.is_empty());
+    assert_eq!(decision.payloads.len(), 3);
+
+    for (guard, error) in [
+        ("- MaxIterations: 0\n", "MaxIterations expects a positive integer"),
+        ("- MaxTime: 5m\n", "MaxTime expects a duration"),
+        ("- MaxDepth: 3\n", "Unknown GUARD key 'MaxDepth'"),
+    ] {
+        let err = parse_policy(&format!("INTENT: x\nGUARD:\n{}RULE:\n- IF A > 1 THEN B\n", guard)).unwrap_err();
+        assert!(err.contains(error), "{} => {}", guard, err);
+    }
+
+    // `Guard:` di tengah baris hanyalah teks item, bukan header seksi
+    let prose = parse_policy("INTENT: x\nASSUMPTION:\n- Guard: rails are installed\nRULE:\n- IF A > 1 THEN B\n").unwrap();
+    assert_eq!(prose.assumptions.len(), 1);
+    assert!(prose.guard.max_iterations.is_none());
+}
+
+#[test]
+fn runtime_config_sets_defaults_and_caps_policy_requests() {
+    let config = RuntimeConfig { max_loop_iterations: 200, max_guard_iterations: 300, ..RuntimeConfig::default() };
+    let plain = parse_policy(FLEET).unwrap();
+    let greedy = parse_policy(&FLEET.replace("RULE:", "GUARD:\n- MaxIterations: 1000\nRULE:")).unwrap();
+
+    let mut rt = Runtime::with_config(config.clone());
+    rt.update_data("Vehicles", fleet(250)["Vehicles"].clone());
+    let decision = rt.execute_policy(&plain);
+    assert_eq!(decision.guard.max_iterations, 200);
+    assert_eq!(decision.truncations[0].processed, 200);
+
+    let decision = rt.execute_policy(&greedy);
+    assert_eq!((decision.guard.max_iterations, decision.guard.source), (300, GuardSource::Clamped));
+    assert!(decision.truncations.is_empty());
+    assert!(decision.logs.iter().any(|l| l.contains("GUARD capped by runtime config")));
+
+    let ir = build_policy_ir_with_config(&greedy, &config);
+    assert_eq!(ir.guard, decision.guard);
+    let parsed: RuntimeConfig = serde_json::from_str(r#"{ "max_loop_iterations": 75 }"#).unwrap();
+    assert_eq!((parsed.max_loop_iterations, parsed.max_loop_time_ms), (75, 1000));
+}
@@ -0,0 +1,153 @@
+use omnilang_core::ir::{build_policy_ir, PolicyIR, RuleIR};
+use omnilang_core::ir_interpreter::execute_ir;
+use omnilang_core::optimizer::{optimize, OptimizeReport};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+fn optimized(source: &str) -> (PolicyIR, PolicyIR, OptimizeReport) {
+    let plain = build_policy_ir(&parse_policy(source));
+    let mut ir = plain.clone();
+    let report = optimize(&mut ir);
+    (plain, ir, report)
+}
+
+/// IR teroptimasi harus memicu aksi, payload, dan penjelasan yang sama.
+fn assert_same_decisions(plain: &PolicyIR, ir: &PolicyIR, contexts: &[Value]) {
+    for ctx in contexts {
+        let expected = execute_ir(plain, ctx.clone());
+        let actual = execute_ir(ir, ctx.clone());
+        assert_eq!(actual.actions, expected.actions, "context {}", ctx);
+        assert_eq!(actual.payloads, expected.payloads, "context {}", ctx);
+        assert_eq!(actual.explanations, expected.explanations, "context {}", ctx);
+        assert!(actual.metrics.rules_evaluated <= expected.metrics.rules_evaluated);
+    }
+}
+
+#[test]
+fn constant_conditions_fold_and_unreachable_rules_are_removed() {
+    let source = "INTENT: Boiler\nRULE:\n\
+                  - IF 1 > 2 THEN Never\n\
+                  - IF Temperature > 90 AND 2 > 1 THEN Vent\n\
+                  - IF 5cm < 1m OR Temperature > 200 THEN Monitor\n\
+                  - IF 1 > 2 THEN Never ELSE Idle\n\
+                  - MATCH Mode {\n    - \"Auto\" => Run\n    - _ => Hold\n    - \"Manual\" => Wait\n}\n";
+    let (plain, ir, report) = optimized(source);
+    assert_eq!(report.folded_conditions, 4);
+    assert_eq!(report.removed_arms, 1);
+    // Rule yang selalu salah tetap ada agar jumlah rule yang dievaluasi sama dengan runtime
+    assert_eq!(report.removed_rules, 0);
+    assert_eq!(ir.rules.len(), 5);
+    assert_eq!(ir.flat_rules.len(), 5);
+    let RuleIR::Standard(never) = &ir.rules[0] else { panic!("expected a standard rule") };
+    assert_eq!(never.condition.to_string(), "false");
+    let RuleIR::Standard(vent) = &ir.rules[1] else { panic!("expected a standard rule") };
+    assert_eq!(vent.condition.to_string(), "Temperature > 90");
+    let RuleIR::Standard(monitor) = &ir.rules[2] else { panic!("expected a standard rule") };
+    assert_eq!(monitor.condition.to_string(), "true");
+
+    let decision = execute_ir(&ir, json!({ "Temperature": 95, "Mode": "Auto" }));
+    assert_eq!(decision.actions, vec!["Vent", "Monitor", "Idle", "Run"]);
+    let expected = execute_ir(&plain, json!({ "Temperature": 95, "Mode": "Auto" }));
+    assert_eq!(expected.actions, decision.actions);
+    assert_eq!(expected.metrics.rules_evaluated, decision.metrics.rules_evaluated);
+
+    // FIRST_MATCH berhenti pada rule yang selalu aktif, jadi rule sesudahnya tidak terjangkau
+    let (_, first, report) = optimized("INTENT: Boiler\nCOMBINE: first-match\nRULE:\n\
+                                        - IF Temperature > 90 THEN Vent\n- IF 1 < 2 THEN Monitor\n- IF Pressure > 5 THEN Release\n");
+    assert_eq!(report.removed_rules, 1);
+    assert_eq!(first.rules.len(), 2);
+    // IR yang sudah dioptimasi tidak berubah lagi
+    let mut again = first.clone();
+    assert!(optimize(&mut again).is_empty());
+}
+
+#[test]
+fn equality_chains_and_literal_match_arms_compile_to_jump_tables() {
+    let source = "INTENT: Line\nRULE:\n\
+                  - IF Mode == \"Idle\" THEN Park\n\
+                  - IF Mode == \"Run\" THEN Drive(speed: Speed)\n\
+                  - IF Mode == \"Run\" AND Speed > 5 THEN Warn\n\
+                  - IF Mode == Stop THEN Brake\n\
+                  - IF Speed > 10 THEN Limit\n\
+                  - MATCH State {\n    - \"Ready\" => Start\n    - Halted => Reset\n    - true => Flag\n    - _ => Wait\n}\n";
+    let (plain, ir, report) = optimized(source);
+    assert_eq!((report.dispatch_tables, report.match_tables), (1, 1));
+    let RuleIR::Dispatch(dispatch) = &ir.rules[0] else { panic!("expected a dispatch table") };
+    assert_eq!(dispatch.rules.len(), 4);
+    assert_eq!(dispatch.table.cases["s:Run"], vec![1, 2]);
+    assert_eq!(dispatch.table.symbols, vec!["Stop".to_string()]);
+    let RuleIR::Match(matcher) = &ir.rules[2] else { panic!("expected a MATCH rule") };
+    let table = matcher.table.as_ref().unwrap();
+    assert_eq!(table.cases.len(), 3);
+    assert_eq!(table.default, Some(3));
+
+    let contexts = [
+        json!({ "Mode": "Run", "Speed": 12, "State": "Ready" }),
+        json!({ "Mode": "Stop", "Speed": 0, "State": "Halted" }),
+        json!({ "Mode": "Idle", "State": true }),
+        json!({ "Mode": 3, "State": 7 }),
+        json!({ "Speed": 4 }),
+        // Nama simbolik yang ada di konteks membuat evaluasi kembali linear
+        json!({ "Mode": "running", "Stop": "running", "Halted": "x", "State": "x" }),
+    ];
+    assert_same_decisions(&plain, &ir, &contexts);
+    let decision = execute_ir(&ir, contexts[0].clone());
+    assert_eq!(decision.actions, vec!["Drive", "Warn", "Limit", "Start"]);
+    // Rule dispatch yang tidak terpilih tetap dihitung, sama seperti runtime
+    assert_eq!(decision.metrics.rules_evaluated, execute_ir(&plain, contexts[0].clone()).metrics.rules_evaluated);
+    assert_eq!(decision.metrics.rules_evaluated, 6);
+    assert_eq!(execute_ir(&ir, contexts[5].clone()).actions, vec!["Brake", "Reset"]);
+}
+
+#[test]
+fn shared_leading_terms_are_hoisted_and_examples_keep_their_decisions() {
+    let source = "INTENT: Plant\nRULE:\n\
+                  - IF Online AND Temperature > 90 THEN Cool\n\
+                  - IF Online AND Temperature > 95 AND Pressure > 5 THEN Vent\n\
+                  - IF Online AND Temperature > 90 FOR 5s THEN Alarm\n\
+                  - FOR pump IN Pumps {\n    - IF pump.active AND pump.rpm > 3000 THEN Throttle(id: pump.id)\n    - IF pump.active AND pump.rpm < 100 THEN Restart(id: pump.id)\n}\n";
+    let (plain, ir, report) = optimized(source);
+    assert_eq!(report.hoisted_blocks, 2);
+    let RuleIR::Hoisted(block) = &ir.rules[0] else { panic!("expected a hoisted block") };
+    assert_eq!(block.guard.to_string(), "Online");
+    assert_eq!(block.body.len(), 2);
+    // Kondisi temporal tidak ikut diangkat
+    assert!(matches!(ir.rules[1], RuleIR::Standard(_)));
+
+    let pumps = json!([{ "id": 1, "active": true, "rpm": 3500 }, { "id": 2, "active": false, "rpm": 50 }]);
+    let contexts = [
+        json!({ "Online": true, "Temperature": 97, "Pressure": 6, "Pumps": pumps }),
+        json!({ "Online": false, "Temperature": 97, "Pressure": 6, "Pumps": [] }),
+    ];
+    assert_same_decisions(&plain, &ir, &contexts);
+    let skipped = execute_ir(&ir, contexts[1].clone());
+    assert!(skipped.logs.contains(&"Skipped 2 rules: Online is false".to_string()), "{:?}", skipped.logs);
+    assert_eq!(skipped.metrics.rules_evaluated, execute_ir(&plain, contexts[1].clone()).metrics.rules_evaluated);
+
+    let mut checked = 0;
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap_or_default();
+        if path.extension().is_none_or(|e| e != "omni") || !source.contains("RULE:") || source.contains("IMPORT:") || source.contains("EXTEND:") {
+            continue;
+        }
+        let mut lexer = Lexer::new(&source);
+        let Ok(policy) = lexer.tokenize().and_then(|tokens| Parser::new(tokens).parse_policy()) else { continue };
+        let plain = build_policy_ir(&policy);
+        let mut ir = plain.clone();
+        optimize(&mut ir);
+        for context in ["context.json", "context_boiler.json", "context_fleet.json", "context_smartcity.json"] {
+            let ctx: Value = serde_json::from_str(&std::fs::read_to_string(format!("examples/{}", context)).unwrap()).unwrap();
+            assert_eq!(execute_ir(&ir, ctx.clone()).actions, execute_ir(&plain, ctx).actions, "{}", path.display());
+        }
+        checked += 1;
+    }
+    assert!(checked > 10);
+}
@@ -1,25 +1,34 @@
-use omnilang_core::ast::{self, Actor, Constraint, Impact, Policy, Review, Rule, StandardRule, Trace};
+use omnilang_core::ast::{self, ActionCall, Actor, CombiningAlgorithm, Constraint, Impact, Policy, Review, Rule, RuleEffect, StandardRule, Trace};
 use omnilang_core::ir::build_policy_ir;
+use omnilang_core::parser::parse_condition;
 
 #[test]
 fn build_ir_includes_flat_rules_and_guards() {
     let policy = Policy {
         intent: Some("Test".to_string()),
-        actors: vec![Actor { role: "Pilot".into(), primary: true }],
+        actors: vec![Actor { role: "Pilot".into(), primary: true, capabilities: None }],
         context: None,
         assumptions: vec![],
         rules: vec![
-            Rule::Standard(StandardRule { condition: "A > 1".into(), action: "Log".into() }),
+            Rule::Standard(StandardRule { name: None, condition: parse_condition("A > 1").unwrap(), action: ActionCall::named("Log"), else_action: None, priority: 0, effect: RuleEffect::Permit, actor: None, line: 0 }),
             Rule::For(ast::ForLoop {
                 iterator: "item".into(),
                 collection: "items".into(),
-                body: vec![Rule::Standard(StandardRule { condition: "x == 1".into(), action: "Act".into() })],
+                body: vec![Rule::Standard(StandardRule { name: None, condition: parse_condition("x == 1").unwrap(), action: ActionCall::named("Act"), else_action: None, priority: 0, effect: RuleEffect::Permit, actor: None, line: 0 })],
+                line: 0,
             }),
         ],
-        constraints: vec![Constraint { kind: "Technical".into(), description: "None".into() }],
+        constraints: vec![Constraint { kind: "Technical".into(), description: "None".into(), invariant: None }],
         impacts: vec![Impact { kind: "Benefit".into(), description: "Ok".into() }],
         traces: vec![Trace { kind: "Evidence".into(), link: "link".into() }],
         reviews: vec![Review { interval: "weekly".into(), criteria: "consistency".into() }],
+        units: vec![],
+        schema: vec![],
+        capabilities: vec![],
+        guard: Default::default(),
+        tests: Vec::new(),
+        combining: CombiningAlgorithm::AllMatches,
+        composition: Default::default(),
     };
 
     let ir = build_policy_ir(&policy);
@@ -55,6 +64,13 @@ fn build_ir_handles_empty_policy() {
         impacts: vec![],
         traces: vec![],
         reviews: vec![],
+        units: vec![],
+        schema: vec![],
+        capabilities: vec![],
+        guard: Default::default(),
+        tests: Vec::new(),
+        combining: CombiningAlgorithm::AllMatches,
+        composition: Default::default(),
     };
 
     let ir = build_policy_ir(&policy);
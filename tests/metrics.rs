@@ -0,0 +1,154 @@
+use omnilang_core::metrics::{
+    policy_label, spawn_http, MetricsRegistry, MetricsSource, SnapshotWriter, CONTENT_TYPE, LATENCY_BUCKETS,
+};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::watch::{WatchOptions, Watcher};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::json;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+const PLANT: &str = "INTENT: Plant \"A\"\nRULE:\n- IF Temperature > 90 THEN Vent\n- IF Temperature > 95 THEN Alarm\n";
+
+fn get(addr: std::net::SocketAddr, request: &str) -> String {
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn decisions_feed_per_policy_rule_and_action_counters() {
+    let policy = parse_policy(PLANT);
+    let mut runtime = Runtime::new();
+    let mut registry = MetricsRegistry::default();
+    for (temperature, latency_us) in [(92, 80), (99, 700), (50, 400_000)] {
+        runtime.update_data("Temperature", json!(temperature));
+        registry.record("Plant \"A\"", &runtime.execute_policy(&policy), Duration::from_micros(latency_us));
+    }
+
+    let plant = &registry.policies["Plant \"A\""];
+    assert_eq!((plant.evaluations, plant.rules_evaluated, plant.actions_triggered), (3, 6, 3));
+    assert_eq!(plant.actions["Vent"], 2);
+    assert_eq!(plant.rules["IF Temperature > 95 THEN Alarm"], 1);
+    assert_eq!(plant.latency.cumulative(), vec![0, 1, 1, 1, 2, 2, 2, 2, 2, 2, 3]);
+    assert_eq!(plant.latency.cumulative().len(), LATENCY_BUCKETS.len() + 1);
+
+    let text = registry.render_openmetrics();
+    assert!(text.contains("# TYPE omnilang_evaluations counter\n"), "{}", text);
+    assert!(text.contains("omnilang_evaluations_total{policy=\"Plant \\\"A\\\"\"} 3\n"), "{}", text);
+    assert!(text.contains("omnilang_action_total{policy=\"Plant \\\"A\\\"\",action=\"Vent\"} 2\n"), "{}", text);
+    assert!(text.contains("omnilang_decision_latency_seconds_bucket{policy=\"Plant \\\"A\\\"\",le=\"0.0001\"} 1\n"), "{}", text);
+    assert!(text.contains("omnilang_decision_latency_seconds_bucket{policy=\"Plant \\\"A\\\"\",le=\"+Inf\"} 3\n"), "{}", text);
+    assert!(text.ends_with("# EOF\n"));
+    assert!(registry.render_text().contains("  Rule fired 2x: IF Temperature > 90 THEN Vent\n"));
+
+    assert_eq!(policy_label(Some("Plant"), "x/boiler.omni"), "Plant");
+    assert_eq!(policy_label(None, "x/boiler.omni"), "boiler");
+}
+
+#[test]
+fn snapshots_accumulate_across_processes() {
+    let path = std::env::temp_dir().join(format!("omnilang_metrics_{}", std::process::id())).join("metrics.json");
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert_eq!(MetricsRegistry::load(&path).unwrap(), MetricsRegistry::default());
+
+    let policy = parse_policy(PLANT);
+    let mut runtime = Runtime::new();
+    runtime.update_data("Temperature", json!(99));
+    for _ in 0..2 {
+        let mut registry = MetricsRegistry::load(&path).unwrap();
+        registry.record("Plant", &runtime.execute_policy(&policy), Duration::from_millis(2));
+        registry.save(&path).unwrap();
+    }
+    let registry = MetricsRegistry::load(&path).unwrap();
+    assert_eq!(registry.policies["Plant"].evaluations, 2);
+    assert_eq!(registry.policies["Plant"].actions["Alarm"], 2);
+    assert!((registry.policies["Plant"].latency.sum_seconds - 0.004).abs() < 1e-9);
+
+    std::fs::write(&path, "{ not json").unwrap();
+    assert!(MetricsRegistry::load(&path).unwrap_err().starts_with("invalid metrics snapshot"));
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn concurrent_writers_merge_into_one_snapshot() {
+    let path = std::env::temp_dir().join(format!("omnilang_metrics_merge_{}", std::process::id())).join("metrics.json");
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    let policy = parse_policy(PLANT);
+    let mut runtime = Runtime::new();
+    runtime.update_data("Temperature", json!(99));
+    let decision = runtime.execute_policy(&policy);
+
+    // Dua proses watch berjalan lama, masing-masing mulai dari snapshot yang sama
+    let mut writers: Vec<(MetricsRegistry, SnapshotWriter)> =
+        (0..2).map(|_| (MetricsRegistry::default(), SnapshotWriter::new(&path, MetricsRegistry::default()))).collect();
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let (path, decision) = (path.clone(), decision.clone());
+            std::thread::spawn(move || {
+                for _ in 0..10 {
+                    MetricsRegistry::update(&path, |r| r.record("Plant", &decision, Duration::from_millis(1))).unwrap();
+                }
+            })
+        })
+        .collect();
+    for round in 0..3 {
+        for (live, writer) in writers.iter_mut() {
+            live.record("Plant", &decision, Duration::from_millis(1));
+            if round != 1 {
+                writer.flush(live).unwrap();
+            }
+        }
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let merged = MetricsRegistry::load(&path).unwrap();
+    assert_eq!(merged.policies["Plant"].evaluations, 40 + 2 * 3);
+    assert_eq!(merged.policies["Plant"].actions["Alarm"], 46);
+    assert_eq!(merged.policies["Plant"].latency.cumulative().last(), Some(&46));
+    let leftovers: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn http_endpoint_serves_live_watch_metrics() {
+    let policy = parse_policy(PLANT);
+    let shared = Arc::new(Mutex::new(MetricsRegistry::default()));
+    let mut watcher = Watcher::new(&policy, Runtime::new(), WatchOptions { changes_only: true }).with_metrics(shared.clone(), "Plant");
+    let mut output = Vec::new();
+    watcher.run("{\"Temperature\": 99}\n{\"Temperature\": 99}\nnot json\n".as_bytes(), &mut output).unwrap();
+
+    let addr = spawn_http("127.0.0.1:0", MetricsSource::Live(shared)).unwrap();
+    let response = get(addr, "GET /metrics HTTP/1.1\r\nHost: edge\r\n\r\n");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{}", response);
+    assert!(response.contains(&format!("Content-Type: {}\r\n", CONTENT_TYPE)));
+    // Keputusan yang disaring `changes_only` tetap dihitung; baris yang ditolak tidak
+    assert!(response.contains("omnilang_evaluations_total{policy=\"Plant\"} 2\n"), "{}", response);
+    assert!(response.contains("omnilang_rule_fired_total{policy=\"Plant\",rule=\"IF Temperature > 95 THEN Alarm\"} 2\n"));
+    assert!(response.ends_with("# EOF\n"));
+
+    assert!(get(addr, "GET /health HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 404 Not Found"));
+    assert!(get(addr, "POST /metrics HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 405"));
+    let missing = spawn_http("127.0.0.1:0", MetricsSource::Snapshot("/nonexistent/metrics.json".into())).unwrap();
+    // Snapshot yang belum ditulis tampil sebagai registry kosong
+    let empty = get(missing, "GET /metrics HTTP/1.1\r\n\r\n");
+    assert!(empty.starts_with("HTTP/1.1 200 OK") && empty.ends_with("# EOF\n"), "{}", empty);
+    assert!(!empty.contains("_total{"), "{}", empty);
+}
@@ -0,0 +1,102 @@
+use omnilang_core::linter::{Linter, Severity};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::testing::{render_junit, run_case, SuiteReport};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+#[test]
+fn lint_findings_export_as_sarif_with_rule_ids_levels_and_lines() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nACTOR:\n- Primary: Controller CAN [Execute]\nRULE:\n\
+         - IF Temperature > 80 AND Temperature < 50 THEN Vent\n\
+         - IF Temperature > 90 THEN Upload(requires: Network)\n",
+    );
+    let result = Linter::new().lint_policy(&policy, None);
+    assert!(!result.has_errors);
+    let sarif = result.to_sarif("policies/boiler.omni");
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "omnilang");
+    let rules: Vec<&str> = run["tool"]["driver"]["rules"].as_array().unwrap().iter().map(|r| r["id"].as_str().unwrap()).collect();
+    let mut unique = rules.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(rules.len(), unique.len());
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), result.findings.len());
+    let dead = results.iter().find(|r| r["ruleId"] == "dead-rule").expect("dead-rule result");
+    assert_eq!(dead["level"], "warning");
+    let location = &dead["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "policies/boiler.omni");
+    assert_eq!(location["region"]["startLine"], 5);
+    assert_eq!(rules[dead["ruleIndex"].as_u64().unwrap() as usize], "dead-rule");
+
+    let denied = results.iter().find(|r| r["ruleId"] == "action-capabilities").expect("capability result");
+    assert_eq!(denied["locations"][0]["physicalLocation"]["region"]["startLine"], 6);
+    assert!(denied["properties"]["suggestion"].as_str().unwrap().contains("Network"));
+    // Temuan Info menjadi level SARIF `note`
+    assert!(results.iter().filter(|r| r["level"] == "note").count() > 0);
+}
+
+#[test]
+fn lint_errors_are_flagged_and_findings_serialize_without_empty_fields() {
+    let policy = parse_policy("INTENT: Boiler\nRULE:\n- IF Temperature > 90 THEN Vent\n");
+    let result = Linter::new().lint_policy(&policy, None);
+    assert!(result.has_errors);
+    let error = result.findings.iter().find(|f| f.severity == Severity::Error).unwrap();
+    assert_eq!(error.message, "Policy must have at least one ACTOR");
+
+    let findings = serde_json::to_value(&result.findings).unwrap();
+    let first = findings.as_array().unwrap().iter().find(|f| f["severity"] == "error").unwrap();
+    assert_eq!(first["rule"], "required-sections");
+    assert!(first.get("line").is_none() && first.get("witness").is_none());
+    assert_eq!(result.to_sarif("boiler.omni")["runs"][0]["results"][0]["level"], "error");
+
+    // `exec --format json` mencetak Decision apa adanya
+    let mut rt = Runtime::new();
+    rt.update_data("Temperature", json!(95));
+    let decision: Value = serde_json::to_value(rt.execute_policy(&policy)).unwrap();
+    assert_eq!(decision["actions"], json!(["Vent"]));
+    assert!(decision["explanations"][0]["rule"].is_string());
+}
+
+#[test]
+fn junit_report_lists_cases_failures_and_file_errors() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nRULE:\n- IF Temperature > 90 THEN Vent\nEXPECT:\n\
+         - hot: {\"Temperature\": 95} => Vent\n\
+         - cold: {\"Temperature\": 20} => Vent\n",
+    );
+    let cases = policy
+        .tests
+        .iter()
+        .map(|case| run_case(&policy, case, Path::new(""), &Default::default()))
+        .collect();
+    let suites = vec![
+        SuiteReport { name: "boiler.omni".to_string(), cases, error: None },
+        SuiteReport::single("script.omni", Ok(())),
+        SuiteReport::single("broken.omni", Err("Parser Error in broken.omni: \"x\" & y".to_string())),
+    ];
+    assert!(!suites[0].passed() && suites[1].passed() && !suites[2].passed());
+
+    let xml = render_junit(&suites);
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.contains("<testsuites name=\"omnilang\" tests=\"4\" failures=\"1\" errors=\"1\">"), "{}", xml);
+    assert!(xml.contains("<testsuite name=\"boiler.omni\" tests=\"2\" failures=\"1\" errors=\"0\">"));
+    assert!(xml.contains("<testcase name=\"hot\" classname=\"boiler.omni\"/>"));
+    assert!(xml.contains("<testcase name=\"cold\" classname=\"boiler.omni\">"), "{}", xml);
+    assert!(xml.contains("<failure message=\"- Vent (expected, not triggered)\">actions: []\n- Vent (expected, not triggered)</failure>"));
+    assert!(xml.contains("<testcase name=\"script.omni\" classname=\"script.omni\"/>"));
+    assert!(xml.contains("<error message=\"Parser Error in broken.omni: &quot;x&quot; &amp; y\"/>"));
+    assert!(xml.trim_end().ends_with("</testsuites>"));
+}
@@ -0,0 +1,106 @@
+use omnilang_core::bundle::{PolicyBundle, BUNDLE_FORMAT_VERSION};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::security::SigningKey;
+use omnilang_core::temporal::TemporalState;
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+fn write_policy(name: &str, source: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omni_bundle_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("boiler.omni");
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+const BOILER: &str = "INTENT: Boiler\nSCHEMA:\n- Pressure: number [0, 40]\nRULE:\n\
+                      - IF Pressure > 30 THEN ReleaseValve(level: Pressure)\n\
+                      - IF Pressure > 35 THEN Alarm\n";
+
+#[test]
+fn bundle_round_trips_and_executes_like_the_runtime() {
+    let path = write_policy("roundtrip", BOILER);
+    let policy = parse_policy(BOILER);
+    let bundle = PolicyBundle::build(&policy, &path).unwrap();
+    assert_eq!(bundle.format_version, BUNDLE_FORMAT_VERSION);
+    assert_eq!(bundle.compiler_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(bundle.intent.as_deref(), Some("Boiler"));
+    assert_eq!(bundle.sources.len(), 1);
+    assert_eq!(bundle.sources[0].sha256, omnilang_core::security::generate_checksum(BOILER));
+    assert_eq!(bundle.schema[0].field, "Pressure");
+
+    let json: Value = serde_json::from_str(&bundle.to_json().unwrap()).unwrap();
+    assert_eq!(json["format"], "omnilang-bundle");
+    assert_eq!(json["schema"][0]["ty"], "number");
+    assert!(json.get("signature").is_none());
+
+    let loaded = PolicyBundle::parse(&bundle.to_json().unwrap(), None).unwrap();
+    let decision = loaded.execute(json!({ "Pressure": 36 }), &mut TemporalState::new(), 0);
+    let mut rt = Runtime::new();
+    rt.update_data("Pressure", json!(36));
+    let expected = rt.execute_policy(&policy);
+    assert_eq!(decision.actions, expected.actions);
+    assert_eq!(decision.payloads[0].params["level"], json!(36));
+}
+
+#[test]
+fn loader_rejects_incompatible_versions_and_modified_ir() {
+    let path = write_policy("versions", BOILER);
+    let bundle = PolicyBundle::build(&parse_policy(BOILER), &path).unwrap();
+    let json: Value = serde_json::from_str(&bundle.to_json().unwrap()).unwrap();
+    let parse = |value: &Value| PolicyBundle::parse(&value.to_string(), None).map(|_| ());
+
+    let mut newer = json.clone();
+    newer["format_version"] = json!(BUNDLE_FORMAT_VERSION + 1);
+    assert!(parse(&newer).unwrap_err().starts_with("unsupported bundle format version 2"));
+
+    let mut old_ir = json.clone();
+    old_ir["ir_version"] = json!(0);
+    assert!(parse(&old_ir).unwrap_err().contains("recompile"));
+
+    let mut other = json.clone();
+    other["format"] = json!("something-else");
+    assert!(parse(&other).unwrap_err().starts_with("not an OmniLang bundle"));
+
+    // Mengubah IR tanpa memperbarui ir_sha256 ditolak sebelum dijalankan
+    let mut tampered = json.clone();
+    tampered["ir"]["intent"] = json!("Pump");
+    assert_eq!(parse(&tampered).unwrap_err(), "bundle IR was modified (ir_sha256 mismatch)");
+    assert!(parse(&json).is_ok());
+}
+
+#[test]
+fn signed_bundles_verify_and_contexts_are_checked_against_the_schema() {
+    let path = write_policy("signed", BOILER);
+    let key = SigningKey::new("bundle-secret");
+    let mut bundle = PolicyBundle::build(&parse_policy(BOILER), &path).unwrap();
+    let unsigned = bundle.to_json().unwrap();
+    bundle.sign(&key).unwrap();
+    let signed = bundle.to_json().unwrap();
+
+    let keys = std::slice::from_ref(&key);
+    assert_eq!(PolicyBundle::parse(&signed, Some(keys)).unwrap().signature.unwrap().key_id, key.id);
+    assert_eq!(PolicyBundle::parse(&unsigned, Some(keys)).unwrap_err(), "bundle is not signed");
+    let other = SigningKey::new("other-secret");
+    assert!(PolicyBundle::parse(&signed, Some(&[other])).unwrap_err().contains("unknown key"));
+    // Metadata di luar IR juga tercakup tanda tangan
+    let compiler = format!("\"compiler_version\": \"{}\"", env!("CARGO_PKG_VERSION"));
+    let renamed = signed.replace(&compiler, "\"compiler_version\": \"0.0.1\"");
+    assert_ne!(renamed, signed);
+    assert_eq!(PolicyBundle::parse(&renamed, Some(keys)).unwrap_err(), "bundle invalid signature");
+    assert!(PolicyBundle::parse(&renamed, None).is_ok());
+
+    assert!(bundle.validate_context(&json!({ "Pressure": 12 })).is_ok());
+    let violations = bundle.validate_context(&json!({ "Pressure": 99 })).unwrap_err();
+    assert_eq!(violations.len(), 1);
+    assert!(bundle.validate_context(&json!({})).is_err());
+}
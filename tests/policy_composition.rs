@@ -0,0 +1,141 @@
+use omnilang_core::ast::Rule;
+use omnilang_core::compose::load_policy;
+use omnilang_core::{ir::build_policy_ir, ir::RuleIR, ir_interpreter::execute_ir, lexer::Lexer, parser::Parser};
+use serde_json::json;
+use std::path::PathBuf;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+/// Tulis file-file kebijakan ke direktori sementara yang unik per test.
+fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omni_compose_{}_{}", name, std::process::id()));
+    for (path, source) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    dir
+}
+
+const SAFETY: &str = "INTENT: Shared safety\nUNITS:\n- Temperature: C\nRULE:\n- proximity: IF Distance < 1m THEN StopMachine\n- overheat: IF Temperature > 52C THEN CoolDown\n";
+const BASE: &str = "INTENT: Plant base\nCONTEXT:\n- Domain: Factory\nRULE:\n- maintenance: IF MaintenanceMode == true THEN LockPanel\nREVIEW:\n- Interval: Bulanan\n";
+
+fn rule_names(policy: &omnilang_core::ast::Policy) -> Vec<Option<&str>> {
+    policy.rules.iter().map(Rule::name).collect()
+}
+
+#[test]
+fn extend_and_import_merge_in_order_and_flow_into_ir() {
+    let dir = workspace(
+        "merge",
+        &[
+            ("shared/safety.omni", SAFETY),
+            ("shared/base.omni", BASE),
+            (
+                "line4.omni",
+                "INTENT: Line 4\nEXTEND: shared/base.omni\nIMPORT:\n- shared/safety.omni\nRULE:\n- IF Pressure > 5 THEN ReleaseValve\n",
+            ),
+        ],
+    );
+    let policy = load_policy(dir.join("line4.omni")).expect("composition failed");
+    assert!(policy.composition.is_empty());
+    assert_eq!(rule_names(&policy), vec![Some("maintenance"), Some("proximity"), Some("overheat"), None]);
+    assert_eq!(policy.intent.as_deref(), Some("Line 4"));
+    assert!(policy.context.is_some(), "CONTEXT is inherited from the base policy");
+    assert_eq!(policy.reviews.len(), 1);
+    assert_eq!(policy.units.len(), 1);
+
+    let ir = build_policy_ir(&policy);
+    let names: Vec<_> = ir
+        .rules
+        .iter()
+        .filter_map(|rule| match rule {
+            RuleIR::Standard(r) => r.name.clone(),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(names, vec!["maintenance", "proximity", "overheat"]);
+    let decision = execute_ir(&ir, json!({ "Temperature": 60, "Distance": 0.5, "Pressure": 6 }));
+    assert_eq!(decision.actions, vec!["StopMachine", "CoolDown", "ReleaseValve"]);
+}
+
+#[test]
+fn override_replaces_in_place_and_redefinitions_are_rejected() {
+    let dir = workspace(
+        "override",
+        &[
+            ("safety.omni", SAFETY),
+            ("strict.omni", "INTENT: Strict\nIMPORT: safety.omni\nRULE:\n- OVERRIDE overheat: IF Temperature > 45C THEN CoolDown\n"),
+            ("clash.omni", "INTENT: Clash\nIMPORT: safety.omni\nRULE:\n- overheat: IF Temperature > 45C THEN CoolDown\n"),
+            ("unknown.omni", "INTENT: Unknown\nIMPORT: safety.omni\nRULE:\n- OVERRIDE fire: IF Smoke > 1 THEN Evacuate\n"),
+            ("twice.omni", "INTENT: Twice\nIMPORT:\n- safety.omni\n- strict.omni\nRULE:\n- IF A > 1 THEN B\n"),
+        ],
+    );
+    let strict = load_policy(dir.join("strict.omni")).unwrap();
+    assert_eq!(rule_names(&strict), vec![Some("proximity"), Some("overheat")]);
+    let decision = execute_ir(&build_policy_ir(&strict), json!({ "Temperature": 48, "Distance": 3 }));
+    assert_eq!(decision.actions, vec!["CoolDown"]);
+
+    let clash = load_policy(dir.join("clash.omni")).unwrap_err();
+    assert!(clash.contains("Rule 'overheat' is already defined in 'safety.omni'; use OVERRIDE"), "{}", clash);
+    let unknown = load_policy(dir.join("unknown.omni")).unwrap_err();
+    assert!(unknown.contains("OVERRIDE of unknown rule 'fire'"), "{}", unknown);
+    let twice = load_policy(dir.join("twice.omni")).unwrap_err();
+    assert!(twice.contains("Rule 'proximity' is defined in both 'safety.omni' and 'strict.omni'"), "{}", twice);
+}
+
+#[test]
+fn cycles_and_malformed_labels_are_errors() {
+    let dir = workspace(
+        "cycle",
+        &[
+            ("a.omni", "INTENT: A\nEXTEND: b.omni\nRULE:\n- IF X > 1 THEN Y\n"),
+            ("b.omni", "INTENT: B\nIMPORT: a.omni\nRULE:\n- IF X > 2 THEN Z\n"),
+            ("units.omni", "INTENT: U\nIMPORT: safety.omni\nUNITS:\n- Temperature: K\nRULE:\n- IF A > 1 THEN B\n"),
+            ("safety.omni", SAFETY),
+        ],
+    );
+    let cycle = load_policy(dir.join("a.omni")).unwrap_err();
+    assert!(cycle.contains("Circular policy composition through 'a.omni'"), "{}", cycle);
+    let units = load_policy(dir.join("units.omni")).unwrap_err();
+    assert!(units.contains("Conflicting units for 'Temperature'"), "{}", units);
+    assert!(load_policy(dir.join("missing.omni")).is_err());
+
+    let duplicate = parse_policy("INTENT: x\nRULE:\n- a: IF X > 1 THEN Y\n- a: IF X > 2 THEN Z\n").unwrap_err();
+    assert!(duplicate.contains("Duplicate rule name 'a'"), "{}", duplicate);
+    let named_loop = parse_policy("INTENT: x\nRULE:\n- scan: FOR w IN Workers {\n    - IF w.x > 1 THEN Y\n}\n").unwrap_err();
+    assert!(named_loop.contains("Only IF rules can be named (rule 'scan')"), "{}", named_loop);
+    let two_bases = parse_policy("INTENT: x\nEXTEND: a.omni\nEXTEND: b.omni\nRULE:\n- IF X > 1 THEN Y\n").unwrap_err();
+    assert!(two_bases.contains("EXTEND only one base policy"), "{}", two_bases);
+}
+
+#[test]
+fn section_keywords_inside_items_stay_free_text() {
+    let labels = [
+        "Import: sensor data written here",
+        "Extend: shift by one hour",
+        "Guard: rails are installed",
+        "Schema: documented elsewhere",
+        "Units: metric everywhere",
+        "Combine: readings from both sensors",
+        "Capability: granted per shift",
+        "Expect: low traffic at night",
+        "Test: bench rig",
+        "Rule: operators sign off",
+        "Review: quarterly",
+    ];
+    let items: String = labels.iter().map(|l| format!("- {}\n", l)).collect();
+    let source = format!("INTENT: Prose\nASSUMPTION:\n{}RULE:\n- IF A > 1 THEN B\n", items);
+    let dir = workspace("prose", &[("prose.omni", &source)]);
+    let policy = load_policy(dir.join("prose.omni")).expect("load failed");
+    assert_eq!(policy.assumptions.len(), labels.len());
+    assert!(policy.composition.is_empty());
+    assert!(policy.units.is_empty() && policy.schema.is_empty() && policy.capabilities.is_empty());
+    assert!(policy.tests.is_empty() && policy.reviews.is_empty());
+    assert_eq!(policy.rules.len(), 1);
+}
@@ -0,0 +1,146 @@
+use omnilang_core::runtime::RuntimeConfig;
+use omnilang_core::testing::{collect_cases, render_text, run_case, sidecar_path, Mismatch};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn workspace(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omnilang_expect_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const BOILER: &str = r#"
+INTENT: Boiler
+SCHEMA:
+- Temperature: number C
+- Pressure: any optional
+- Mode: string optional
+- Loops: array optional
+RULE:
+- IF Temperature > 90C THEN StopBurner
+- IF Pressure > 30bar THEN ReleaseValve
+- IF Mode == Manual THEN NotifyOperator
+- WHILE Temperature > 200C {
+    - IF Mode == Manual THEN Evacuate
+}
+"#;
+
+#[test]
+fn expect_section_parses_inline_and_file_contexts() {
+    let source = format!(
+        "{}EXPECT:\n\
+         - hot: {{\"Temperature\": 95, \"Pressure\": 32bar, \"Mode\": Manual, \"Loops\": [-1, 2.5, null]}} => StopBurner, ReleaseValve NOT Evacuate\n\
+         - \"from file\": \"cases/cold.json\" => NONE GUARD false\n\
+         - exact: {{}} => ONLY \"StopBurner\"\n",
+        BOILER
+    );
+    let policy = parse_policy(&source).expect("parse failed");
+    assert_eq!(policy.tests.len(), 3);
+
+    let hot = &policy.tests[0];
+    assert_eq!(hot.name, "hot");
+    assert_eq!(
+        hot.context,
+        Some(json!({ "Temperature": 95, "Pressure": "32bar", "Mode": "Manual", "Loops": [-1, 2.5, null] }))
+    );
+    assert_eq!(hot.expect, vec!["StopBurner", "ReleaseValve"]);
+    assert_eq!(hot.absent, vec!["Evacuate"]);
+    assert!(!hot.exact);
+
+    let from_file = &policy.tests[1];
+    assert_eq!(from_file.name, "from file");
+    assert_eq!(from_file.context_file.as_deref(), Some("cases/cold.json"));
+    assert!(from_file.exact && from_file.expect.is_empty());
+    assert_eq!(from_file.guard, Some(false));
+    assert!(policy.tests[2].exact);
+
+    let duplicate = format!("{}EXPECT:\n- a: {{}} => NONE\n- a: {{}} => NONE\n", BOILER);
+    assert!(parse_policy(&duplicate).unwrap_err().contains("Duplicate test case 'a'"));
+    let unterminated = format!("{}EXPECT:\n- a: {{\"Temperature\": 1 => NONE\n", BOILER);
+    assert!(parse_policy(&unterminated).is_err());
+    let bad_guard = format!("{}EXPECT:\n- a: {{}} => NONE GUARD maybe\n", BOILER);
+    assert!(parse_policy(&bad_guard).unwrap_err().contains("GUARD in a test case expects true or false"));
+
+    // `TEST:`/`EXPECT:` sebagai header di awal baris; di dalam item tetap teks biasa
+    let alias = format!("{}TEST:\n- cold: {{}} => NONE\n", BOILER.replace("INTENT: Boiler\n", "INTENT: Boiler\nCONTEXT:\n- Domain: Plant\n- Test: bench rig\n- Expect: low traffic at night\n"));
+    let policy = parse_policy(&alias).expect("parse failed");
+    assert_eq!(policy.tests.len(), 1);
+    assert_eq!(policy.tests[0].name, "cold");
+}
+
+#[test]
+fn run_case_reports_missing_unexpected_and_guard_mismatches() {
+    let dir = workspace("run");
+    std::fs::create_dir_all(dir.join("cases")).unwrap();
+    std::fs::write(dir.join("cases/cold.json"), r#"{"Temperature": 20}"#).unwrap();
+    let source = format!(
+        "{}EXPECT:\n\
+         - hot: {{\"Temperature\": 95, \"Pressure\": 32bar}} => StopBurner, ReleaseValve\n\
+         - cold: \"cases/cold.json\" => NONE GUARD false\n\
+         - wrong: {{\"Temperature\": 250, \"Mode\": Manual}} => ONLY StopBurner NOT NotifyOperator GUARD false\n\
+         - invalid: {{\"Temperature\": \"hot\"}} => NONE\n\
+         - missing_file: \"cases/none.json\" => NONE\n",
+        BOILER
+    );
+    let policy = parse_policy(&source).expect("parse failed");
+    let results: Vec<_> = policy
+        .tests
+        .iter()
+        .map(|case| run_case(&policy, case, &dir, &RuntimeConfig::default()))
+        .collect();
+
+    assert!(results[0].passed(), "{:?}", results[0]);
+    assert!(results[1].passed(), "{:?}", results[1]);
+
+    let wrong = &results[2].mismatches;
+    assert!(wrong.contains(&Mismatch::Unexpected { action: "NotifyOperator".to_string() }));
+    assert!(wrong.contains(&Mismatch::Unexpected { action: "Evacuate".to_string() }));
+    assert!(wrong.contains(&Mismatch::Guard { expected: false, actual: true }));
+    assert!(!wrong.iter().any(|m| matches!(m, Mismatch::Missing { .. })));
+
+    assert!(matches!(&results[3].mismatches[0], Mismatch::Context { message } if message.contains("expected number")));
+    assert!(matches!(&results[4].mismatches[0], Mismatch::Context { message } if message.contains("cannot read")));
+
+    let report = render_text(&results);
+    assert!(report.contains("  PASS hot\n"));
+    assert!(report.contains("  FAIL wrong\n"));
+    assert!(report.contains("+ Evacuate (triggered, not expected)"));
+    assert!(report.contains("guard_triggered: expected false, got true"));
+    assert!(report.ends_with("Result: 2 passed, 3 failed\n"));
+}
+
+#[test]
+fn sidecar_cases_are_merged_after_expect_section() {
+    let dir = workspace("sidecar");
+    let policy_path = dir.join("boiler.omni");
+    assert_eq!(sidecar_path(&policy_path), dir.join("boiler.tests.json"));
+
+    let source = format!("{}EXPECT:\n- hot: {{\"Temperature\": 95}} => StopBurner\n", BOILER);
+    let policy = parse_policy(&source).unwrap();
+    assert_eq!(collect_cases(&policy, &policy_path).unwrap().len(), 1);
+
+    std::fs::write(
+        sidecar_path(&policy_path),
+        r#"[{"name": "manual", "context": {"Temperature": 40, "Mode": "Manual"}, "expect": ["StopBurner"]}]"#,
+    )
+    .unwrap();
+    let cases = collect_cases(&policy, &policy_path).unwrap();
+    assert_eq!(cases.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), vec!["hot", "manual"]);
+    let manual = run_case(&policy, &cases[1], Path::new(&dir), &RuntimeConfig::default());
+    assert_eq!(manual.actions, vec!["NotifyOperator"]);
+    assert_eq!(manual.mismatches, vec![Mismatch::Missing { action: "StopBurner".to_string() }]);
+
+    std::fs::write(sidecar_path(&policy_path), r#"[{"name": "hot"}]"#).unwrap();
+    assert!(collect_cases(&policy, &policy_path).unwrap_err().contains("Duplicate test case 'hot'"));
+    std::fs::write(sidecar_path(&policy_path), "{").unwrap();
+    assert!(collect_cases(&policy, &policy_path).unwrap_err().contains("invalid test file"));
+}
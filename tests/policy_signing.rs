@@ -0,0 +1,117 @@
+use omnilang_core::compose::{read_sources, resolve_policy_from, source_files};
+use omnilang_core::security::{is_trusted_script, read_verified, sign_file, signature_path, verify_signature, SigningKey};
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("omni_sign_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn signed_files_verify_and_manifest_records_key_hash_and_signature() {
+    let dir = temp_dir("roundtrip");
+    let policy = dir.join("pump.omni");
+    std::fs::write(&policy, "INTENT: Pump\nRULE:\n- IF Pressure > 5 THEN Vent\n").unwrap();
+    let key = SigningKey::new("  correct horse battery staple\n");
+    assert_eq!(key.id, SigningKey::new("correct horse battery staple").id);
+    assert_eq!(key.id.len(), 16);
+
+    let manifest = sign_file(&policy, &key).unwrap();
+    assert_eq!(manifest.algorithm, "hmac-sha256");
+    assert_eq!(manifest.key_id, key.id);
+    assert_eq!(manifest.file, "pump.omni");
+    assert_eq!(manifest.sha256, omnilang_core::security::generate_checksum("INTENT: Pump\nRULE:\n- IF Pressure > 5 THEN Vent\n"));
+    assert!(signature_path(&policy).ends_with("pump.omni.sig"));
+
+    let other = SigningKey::generate().unwrap();
+    assert_ne!(other.id, key.id);
+    assert_eq!(verify_signature(&policy, &[other.clone(), key.clone()]).unwrap(), manifest);
+    assert!(is_trusted_script(policy.to_str().unwrap(), &[key]));
+    assert!(!is_trusted_script(policy.to_str().unwrap(), &[other]));
+}
+
+#[test]
+fn unsigned_modified_renamed_and_forged_files_are_rejected() {
+    let dir = temp_dir("tamper");
+    let key = SigningKey::new("site-key");
+    let policy = dir.join("pump.omni");
+    std::fs::write(&policy, "INTENT: Pump\nRULE:\n- IF Pressure > 5 THEN Vent\n").unwrap();
+    let err = verify_signature(&policy, std::slice::from_ref(&key)).unwrap_err();
+    assert!(err.contains("is not signed"), "{}", err);
+
+    sign_file(&policy, &key).unwrap();
+    let err = verify_signature(&policy, &[SigningKey::new("other")]).unwrap_err();
+    assert!(err.contains("unknown key"), "{}", err);
+
+    // Tanda tangan file lain tidak berlaku untuk file ini
+    let vent = dir.join("vent.omni");
+    std::fs::copy(&policy, &vent).unwrap();
+    std::fs::copy(signature_path(&policy), signature_path(&vent)).unwrap();
+    let err = verify_signature(&vent, std::slice::from_ref(&key)).unwrap_err();
+    assert!(err.contains("signature is for 'pump.omni'"), "{}", err);
+
+    std::fs::write(&policy, "INTENT: Pump\nRULE:\n- IF Pressure > 50 THEN Vent\n").unwrap();
+    let err = verify_signature(&policy, std::slice::from_ref(&key)).unwrap_err();
+    assert!(err.contains("modified after signing"), "{}", err);
+
+    // Hash diperbarui tanpa kunci: tanda tangan tidak lagi cocok
+    let sig_path = signature_path(&policy);
+    let mut manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&sig_path).unwrap()).unwrap();
+    manifest["sha256"] = omnilang_core::security::generate_checksum(&std::fs::read_to_string(&policy).unwrap()).into();
+    std::fs::write(&sig_path, manifest.to_string()).unwrap();
+    let err = verify_signature(&policy, &[key]).unwrap_err();
+    assert!(err.contains("invalid signature"), "{}", err);
+}
+
+#[test]
+fn composed_policies_list_every_source_file_once() {
+    let dir = temp_dir("sources");
+    std::fs::write(dir.join("units.omni"), "INTENT: units\nUNITS:\n- Temperature: C\n").unwrap();
+    std::fs::write(dir.join("base.omni"), "INTENT: base\nIMPORT: units.omni\nRULE:\n- IF A THEN Upload\n").unwrap();
+    std::fs::write(dir.join("site.omni"), "INTENT: site\nEXTEND: base.omni\nIMPORT: units.omni\nRULE:\n- IF B THEN Archive\n").unwrap();
+    let files = source_files(dir.join("site.omni")).unwrap();
+    let names: Vec<String> = files.iter().map(|f| f.file_name().unwrap().to_string_lossy().into_owned()).collect();
+    assert_eq!(names, vec!["site.omni", "base.omni", "units.omni"]);
+
+    let key = SigningKey::new("fleet");
+    for file in &files[..2] {
+        sign_file(file, &key).unwrap();
+    }
+    let failures: Vec<String> = files.iter().filter_map(|f| verify_signature(f, std::slice::from_ref(&key)).err()).collect();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].contains("units.omni"), "{}", failures[0]);
+
+    std::fs::write(dir.join("broken.omni"), "INTENT: x\nEXTEND: missing.omni\n").unwrap();
+    assert!(source_files(dir.join("broken.omni")).unwrap_err().contains("'missing.omni' not found"));
+}
+
+#[test]
+fn verified_sources_are_parsed_instead_of_rereading_files() {
+    let dir = temp_dir("verified");
+    std::fs::write(dir.join("base.omni"), "INTENT: base\nRULE:\n- IF A THEN Upload\n").unwrap();
+    std::fs::write(dir.join("site.omni"), "INTENT: site\nEXTEND: base.omni\nRULE:\n- IF B THEN Archive\n").unwrap();
+    let key = SigningKey::new("fleet");
+    for name in ["base.omni", "site.omni"] {
+        sign_file(&dir.join(name), &key).unwrap();
+    }
+    let read = |path: &std::path::Path| read_verified(path, std::slice::from_ref(&key));
+    let site = dir.join("site.omni");
+    let sources = read_sources(&site, read(&site).unwrap(), read).unwrap();
+
+    // File diganti setelah diverifikasi: yang dijalankan tetap isi yang diverifikasi
+    std::fs::write(dir.join("base.omni"), "INTENT: base\nRULE:\n- IF A THEN Wipe\n").unwrap();
+    let tokens = omnilang_core::lexer::Lexer::new(sources.get(&site).unwrap()).tokenize().unwrap();
+    let policy = omnilang_core::parser::Parser::new(tokens).parse_policy().unwrap();
+    let resolved = resolve_policy_from(policy, &site, &sources).unwrap();
+    let rules: Vec<String> = resolved.rules.iter().map(|r| format!("{:?}", r)).collect();
+    assert!(rules.iter().any(|r| r.contains("Upload")) && !rules.iter().any(|r| r.contains("Wipe")), "{:?}", rules);
+    assert!(read(&dir.join("base.omni")).unwrap_err().contains("modified after signing"));
+
+    // Rujukan yang tidak ikut diverifikasi ditolak
+    let tokens = omnilang_core::lexer::Lexer::new(sources.get(&site).unwrap()).tokenize().unwrap();
+    let policy = omnilang_core::parser::Parser::new(tokens).parse_policy().unwrap();
+    let err = resolve_policy_from(policy, &site, &Default::default()).unwrap_err();
+    assert!(err.contains("was not among the verified sources"), "{}", err);
+}
@@ -0,0 +1,107 @@
+use omnilang_core::runtime::RuntimeConfig;
+use omnilang_core::schema::ViolationKind;
+use omnilang_core::simulate::{parse_csv, parse_jsonl, simulate, TransitionKind};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::json;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+#[test]
+fn series_formats_parse_timestamps_and_updates() {
+    let jsonl = "{\"timestamp\": 100, \"Temperature\": 40}\n\n{\"Boiler.Pressure\": 3.5}\n";
+    let ticks = parse_jsonl(jsonl, 250).expect("jsonl");
+    assert_eq!(ticks.len(), 2);
+    assert_eq!(ticks[0].time_ms, 100);
+    assert!(!ticks[0].updates.contains_key("timestamp"));
+    // Tanpa timestamp, waktu mengikuti urutan tick dan interval
+    assert_eq!(ticks[1].time_ms, 250);
+    assert_eq!(ticks[1].updates["Boiler.Pressure"], json!(3.5));
+
+    let csv = "timestamp,Temperature,Door,Note\n\
+               2026-03-02T08:00:00Z,41,true,\"idle, warm\"\n\
+               2026-03-02T08:00:05Z,,false,\"say \"\"hi\"\"\"\n";
+    let ticks = parse_csv(csv, 1000).expect("csv");
+    assert_eq!(ticks[1].time_ms - ticks[0].time_ms, 5000);
+    assert_eq!(ticks[0].updates["Temperature"], json!(41));
+    assert_eq!(ticks[0].updates["Door"], json!(true));
+    assert_eq!(ticks[0].updates["Note"], json!("idle, warm"));
+    // Sel kosong tidak memperbarui field
+    assert!(!ticks[1].updates.contains_key("Temperature"));
+    assert_eq!(ticks[1].updates["Note"], json!("say \"hi\""));
+
+    let err = parse_csv("a,b\n1\n", 1000).unwrap_err();
+    assert!(err.contains("line 2: expected 2 columns, got 1"), "{}", err);
+    let err = parse_jsonl("{\"timestamp\": \"yesterday\"}\n", 1000).unwrap_err();
+    assert!(err.contains("invalid timestamp"), "{}", err);
+    let err = parse_jsonl("{\"timestamp\": 5000}\n{\"timestamp\": 1000}\n", 1000).unwrap_err();
+    assert!(err.contains("line 2: tick at 1000ms is earlier than the previous tick at 5000ms"), "{}", err);
+    let err = parse_csv("timestamp,a\n5000,1\n,2\n", 1000).unwrap_err();
+    assert!(err.contains("line 3: tick at 1000ms is earlier"), "{}", err);
+    assert_eq!(parse_jsonl("{\"timestamp\": 5000}\n{\"timestamp\": 5000}\n", 1000).unwrap().len(), 2);
+}
+
+#[test]
+fn temporal_latch_produces_transitions_and_durations() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nRULE:\n\
+         - IF Temperature > 52 UNTIL Temperature < 48 THEN CoolDown\n\
+         - IF Temperature > 60 THEN Alarm\n",
+    )
+    .expect("parse failed");
+    let series = [40, 55, 62, 50, 47, 45]
+        .iter()
+        .map(|t| format!("{{\"Temperature\": {}}}", t))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let ticks = parse_jsonl(&series, 1000).unwrap();
+    let sim = simulate(&policy, json!({}), &ticks, RuntimeConfig::default());
+
+    let actions: Vec<Vec<String>> = sim.ticks.iter().map(|t| t.actions.clone()).collect();
+    assert!(actions[0].is_empty());
+    // CoolDown tetap aktif pada 50 karena belum di bawah 48
+    assert_eq!(actions[3], vec!["CoolDown".to_string()]);
+    assert!(actions[4].is_empty());
+
+    let cooldown: Vec<(usize, TransitionKind)> = sim
+        .transitions
+        .iter()
+        .filter(|t| t.action == "CoolDown")
+        .map(|t| (t.tick, t.kind))
+        .collect();
+    assert_eq!(cooldown, vec![(1, TransitionKind::Start), (4, TransitionKind::Stop)]);
+
+    let stats = &sim.summary.actions["CoolDown"];
+    assert_eq!(stats.activations, 1);
+    assert_eq!(stats.active_ticks, 3);
+    assert_eq!(stats.active_ms, 3000);
+    assert_eq!(stats.first_start_ms, 1000);
+    assert_eq!(sim.summary.actions["Alarm"].active_ms, 1000);
+    assert_eq!(sim.summary.ticks, 6);
+    assert_eq!(sim.summary.duration_ms, 5000);
+}
+
+#[test]
+fn schema_violations_are_counted_per_tick() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nSCHEMA:\n- Pressure: number bar [0, 20]\nRULE:\n- IF Pressure > 10bar THEN Vent\n",
+    )
+    .expect("parse failed");
+    let ticks = parse_jsonl("{\"Pressure\": 5}\n{\"Pressure\": 25}\n{\"Pressure\": 12}\n", 1000).unwrap();
+    let sim = simulate(&policy, json!({}), &ticks, RuntimeConfig::default());
+
+    assert_eq!(sim.summary.invalid_ticks, 1);
+    assert!(sim.ticks[0].violations.is_empty());
+    assert!(matches!(sim.ticks[1].violations[0].kind, ViolationKind::OutOfRange { .. }));
+    // Tick yang tidak valid tetap dieksekusi
+    assert_eq!(sim.ticks[1].actions, vec!["Vent".to_string()]);
+    assert_eq!(sim.summary.actions["Vent"].activations, 1);
+
+    let rendered = serde_json::to_value(&sim).unwrap();
+    assert_eq!(rendered["ticks"][1]["violations"][0]["kind"], "out_of_range");
+    assert!(rendered["ticks"][0].get("violations").is_none());
+}
@@ -0,0 +1,119 @@
+use omnilang_core::analysis::{analyze, opposite_actions, Finding, FindingKind};
+use omnilang_core::runtime::Runtime;
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn of_kind(findings: &[Finding], kind: FindingKind) -> Vec<&Finding> {
+    findings.iter().filter(|f| f.kind == kind).collect()
+}
+
+fn run(policy: &omnilang_core::ast::Policy, context: &Value) -> Vec<String> {
+    let mut rt = Runtime::new();
+    for (key, value) in context.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    rt.execute_policy(policy).actions
+}
+
+#[test]
+fn dead_subsumed_and_shadowed_rules() {
+    let source = "INTENT: Boiler\nUNITS:\n- Temperature: C\nRULE:\n\
+                  - IF Temperature > 80C AND Temperature < 50C THEN Vent\n\
+                  - IF Temperature > 55C THEN Cool\n\
+                  - IF Temperature > 70C AND Mode == Auto THEN Cool\n\
+                  - IF Temperature > 90C FOR 5s THEN Alarm\n\
+                  - IF Temperature > 0K OR Temperature <= 0K THEN Log ELSE Skip\n";
+    let policy = parse_policy(source).expect("parse failed");
+    let findings = analyze(&policy);
+
+    let dead = of_kind(&findings, FindingKind::DeadRule);
+    assert_eq!(dead.len(), 1);
+    assert!(dead[0].message.contains("'Temperature > 80C' contradicts 'Temperature < 50C'"), "{}", dead[0].message);
+
+    let redundant = of_kind(&findings, FindingKind::Redundant);
+    assert_eq!(redundant[0].rules, vec!["rule #3".to_string(), "rule #2".to_string()]);
+    assert_eq!(redundant[0].witness, Some(json!({ "Mode": "Auto", "Temperature": 71 })));
+
+    // FOR 5s tidak dimodelkan, tetapi tetap mensyaratkan Temperature > 90C saat ini
+    let subsumed = of_kind(&findings, FindingKind::Subsumed);
+    assert_eq!(subsumed.len(), 1);
+    assert_eq!(subsumed[0].rules[0], "rule #4");
+    assert_eq!(subsumed[0].assuming, vec!["Temperature > 90C FOR 5s".to_string()]);
+
+    // Field yang hilang membuat kedua perbandingan salah, jadi ELSE tetap bisa aktif
+    assert!(of_kind(&findings, FindingKind::DeadElse).is_empty());
+
+    let first_match = parse_policy(&format!("COMBINE: first-match\n{}", source)).unwrap();
+    let shadowed = of_kind(&analyze(&first_match), FindingKind::Shadowed).len();
+    assert_eq!(shadowed, 2);
+}
+
+#[test]
+fn contradictory_actions_come_with_a_witness_that_fires_both() {
+    let policy = parse_policy(
+        "INTENT: Line\nUNITS:\n- Temperature: F\nRULE:\n\
+         - heat: IF Temperature < 140F THEN StartHeater\n\
+         - IF Temperature > 50C AND Door != \"open\" THEN StopHeater\n\
+         - IF Pressure > 5bar THEN OpenValve ELSE CloseValve\n\
+         - IF Pressure < 2bar THEN OpenValve\n",
+    )
+    .expect("parse failed");
+    let findings = analyze(&policy);
+    let contradictions = of_kind(&findings, FindingKind::Contradiction);
+    assert_eq!(contradictions.len(), 2, "{:?}", contradictions);
+
+    let heater = contradictions.iter().find(|f| f.message.contains("StartHeater")).unwrap();
+    assert!(heater.message.contains("rule 'heat'"));
+    let witness = heater.witness.clone().unwrap();
+    let actions = run(&policy, &witness);
+    assert!(actions.contains(&"StartHeater".to_string()) && actions.contains(&"StopHeater".to_string()), "{:?} for {}", actions, witness);
+
+    // ELSE rule 3 dan THEN rule 4 keduanya aktif saat Pressure < 2bar
+    let valve = contradictions.iter().find(|f| f.message.contains("CloseValve")).unwrap();
+    assert!(valve.rules.contains(&"rule #3 (ELSE)".to_string()));
+    let actions = run(&policy, valve.witness.as_ref().unwrap());
+    assert!(actions.contains(&"OpenValve".to_string()) && actions.contains(&"CloseValve".to_string()));
+
+    let priority = parse_policy(
+        "INTENT: Line\nCOMBINE: priority-ordered\nRULE:\n\
+         - IF Temperature < 60 THEN StartHeater PRIORITY 1\n- IF Temperature > 50 THEN StopHeater PRIORITY 2\n",
+    )
+    .unwrap();
+    assert!(of_kind(&analyze(&priority), FindingKind::Contradiction).is_empty());
+
+    assert!(opposite_actions("StartMachine", "StopMachine"));
+    assert!(opposite_actions("TurnOnFan", "TurnOffFan"));
+    assert!(opposite_actions("IncreaseFanSpeed", "ReduceFanSpeed"));
+    assert!(!opposite_actions("Stopwatch", "Startwatch"));
+}
+
+#[test]
+fn unreachable_match_arms_and_loop_witnesses() {
+    let policy = parse_policy(
+        "INTENT: Pumps\nRULE:\n\
+         - MATCH Mode {\n    - Auto => RunAuto\n    - \"Manual\" => RunManual\n    - \"Auto\" => RunAgain\n    - _ => Fallback\n    - \"Eco\" => RunEco\n}\n\
+         - FOR pump IN Pumps {\n    - IF pump.rpm > 100 THEN OpenValve\n    - IF pump.rpm > 50 AND pump.ok THEN CloseValve\n}\n",
+    )
+    .expect("parse failed");
+    let findings = analyze(&policy);
+
+    let arms = of_kind(&findings, FindingKind::UnreachableArm);
+    assert_eq!(arms.len(), 2);
+    assert!(arms[0].message.contains("arm #3") && arms[0].message.contains("arm #1"), "{}", arms[0].message);
+    assert_eq!(arms[0].witness, Some(json!({ "Mode": "Auto" })));
+    assert!(arms[1].message.contains("arm #4 matches every value"));
+
+    let contradiction = of_kind(&findings, FindingKind::Contradiction);
+    assert_eq!(contradiction[0].rules[0], "FOR pump IN Pumps: rule #2");
+    let witness = contradiction[0].witness.clone().unwrap();
+    assert_eq!(witness, json!({ "Pumps": [{ "ok": true, "rpm": 101 }] }));
+    let actions = run(&policy, &witness);
+    assert!(actions.contains(&"OpenValve".to_string()) && actions.contains(&"CloseValve".to_string()));
+}
@@ -0,0 +1,114 @@
+use omnilang_core::ast::{CombiningAlgorithm, Rule, RuleEffect};
+use omnilang_core::{ir::build_policy_ir, ir_interpreter::execute_ir, lexer::Lexer, parser::Parser, runtime::Runtime};
+use serde_json::{json, Value};
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+/// Jalankan lewat runtime AST dan interpreter IR; keduanya harus sepakat.
+fn run(source: &str, ctx: Value) -> Vec<String> {
+    let policy = parse_policy(source).expect("parse failed");
+    let mut rt = Runtime::new();
+    for (key, value) in ctx.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    let actions = rt.execute_policy(&policy).actions;
+    let ir_actions = execute_ir(&build_policy_ir(&policy), ctx).actions;
+    assert_eq!(actions, ir_actions, "runtime and IR interpreter disagree");
+    actions
+}
+
+const MACHINE: &str = r#"
+INTENT: Machine control
+COMBINE: deny-overrides
+RULE:
+- IF Demand > 0 THEN StartMachine
+- DENY PRIORITY 10 IF Temperature > 90C THEN StopMachine
+- IF Door == "open" THEN SoundAlarm ELSE LockPanel
+"#;
+
+#[test]
+fn else_branch_fires_when_condition_fails() {
+    let policy = parse_policy(MACHINE).expect("parse failed");
+    assert_eq!(policy.combining, CombiningAlgorithm::DenyOverrides);
+    let Rule::Standard(rule) = &policy.rules[2] else {
+        panic!("expected standard rule");
+    };
+    assert_eq!(rule.action.name, "SoundAlarm");
+    assert_eq!(rule.else_action.as_ref().map(|a| a.name.as_str()), Some("LockPanel"));
+
+    let actions = run(MACHINE, json!({ "Demand": 0, "Temperature": 20, "Door": "closed" }));
+    assert_eq!(actions, vec!["LockPanel"]);
+}
+
+#[test]
+fn deny_overrides_suppresses_permit_actions() {
+    let actions = run(MACHINE, json!({ "Demand": 5, "Temperature": 20, "Door": "open" }));
+    assert_eq!(actions, vec!["StartMachine", "SoundAlarm"]);
+
+    let actions = run(MACHINE, json!({ "Demand": 5, "Temperature": 95, "Door": "open" }));
+    assert_eq!(actions, vec!["StopMachine"]);
+}
+
+#[test]
+fn priority_ordered_and_first_match_pick_one_winner() {
+    let ctx = json!({ "Demand": 5, "Temperature": 95, "Door": "open" });
+
+    let priority = MACHINE.replace("deny-overrides", "priority-ordered");
+    assert_eq!(run(&priority, ctx.clone()), vec!["StopMachine"]);
+
+    let first = MACHINE.replace("deny-overrides", "first_match");
+    assert_eq!(run(&first, ctx.clone()), vec!["StartMachine"]);
+
+    let all = MACHINE.replace("COMBINE: deny-overrides\n", "");
+    assert_eq!(run(&all, ctx), vec!["StartMachine", "StopMachine", "SoundAlarm"]);
+}
+
+#[test]
+fn rule_modifiers_are_validated_and_kept_in_ir() {
+    let err = parse_policy("INTENT: x\nCOMBINE: majority-vote\nRULE:\n- IF A > 1 THEN B\n").unwrap_err();
+    assert!(err.contains("Unknown combining algorithm 'majority-vote'"), "{}", err);
+    assert!(parse_policy("INTENT: x\nRULE:\n- PRIORITY high IF A > 1 THEN B\n").is_err());
+    assert!(parse_policy("INTENT: x\nRULE:\n- IF A > 1 THEN B ELSE\n").is_err());
+    // `Combine:` di tengah baris hanyalah teks item, bukan header seksi
+    let prose = parse_policy("INTENT: x\nASSUMPTION:\n- Combine: readings from both sensors\nRULE:\n- IF A > 1 THEN B\n").unwrap();
+    assert_eq!(prose.assumptions.len(), 1);
+
+    let policy = parse_policy(MACHINE).unwrap();
+    let Rule::Standard(stop) = &policy.rules[1] else {
+        panic!("expected standard rule");
+    };
+    assert_eq!((stop.priority, stop.effect), (10, RuleEffect::Deny));
+
+    let ir = serde_json::to_value(build_policy_ir(&policy)).unwrap();
+    assert_eq!(ir["combining"], "deny-overrides");
+    assert_eq!(ir["rules"][1]["effect"], "deny");
+    assert_eq!(ir["rules"][1]["priority"], 10);
+    assert_eq!(ir["rules"][2]["else_action"]["name"], "LockPanel");
+    assert!(ir["rules"][0].get("priority").is_none(), "default fields stay out of the IR");
+}
+
+#[test]
+fn loop_bodies_accept_deny_and_priority_rules() {
+    let source = "INTENT: Line\nCOMBINE: deny-overrides\nRULE:\n\
+                  - IF Demand > 0 THEN StartLine\n\
+                  - FOR m IN Machines {\n    - DENY PRIORITY 5 IF m.temp > 90 THEN Stop(id: m.id)\n    - PRIORITY 2 IF m.temp > 60 THEN Cool(id: m.id)\n}\n";
+    let policy = parse_policy(source).expect("parse failed");
+    let Rule::For(each) = &policy.rules[1] else {
+        panic!("expected FOR rule");
+    };
+    let Rule::Standard(stop) = &each.body[0] else {
+        panic!("expected standard rule");
+    };
+    assert_eq!((stop.priority, stop.effect), (5, RuleEffect::Deny));
+
+    let cool = json!({ "Demand": 1, "Machines": [{ "id": 1, "temp": 70 }] });
+    assert_eq!(run(source, cool), vec!["StartLine", "Cool"]);
+    let hot = json!({ "Demand": 1, "Machines": [{ "id": 1, "temp": 70 }, { "id": 2, "temp": 95 }] });
+    assert_eq!(run(source, hot.clone()), vec!["Stop"]);
+    assert_eq!(run(&source.replace("deny-overrides", "priority-ordered"), hot), vec!["Stop"]);
+}
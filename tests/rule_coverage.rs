@@ -0,0 +1,128 @@
+use omnilang_core::coverage::{render_lcov, render_text, Coverage, RuleKind};
+use omnilang_core::runtime::{Runtime, RuntimeConfig};
+use omnilang_core::testing::{collect_cases, run_case_with_coverage};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+fn run(policy: &omnilang_core::ast::Policy, context: Value, coverage: &mut Coverage) -> Vec<String> {
+    let mut rt = Runtime::new();
+    for (key, value) in context.as_object().unwrap() {
+        rt.update_data(key, value.clone());
+    }
+    rt.execute_policy_with_coverage(policy, coverage).actions
+}
+
+#[test]
+fn branches_and_clauses_are_counted_per_rule() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nRULE:\n\
+         - hot: IF Temperature > 90 AND (Pressure > 5 OR NOT Vented) THEN Vent ELSE Idle\n\
+         - MATCH Mode {\n    - Auto => RunAuto\n    - \"Manual\" => RunManual\n    - _ => Hold\n}\n",
+    )
+    .expect("parse failed");
+    let mut coverage = Coverage::new(&policy);
+    assert_eq!(coverage.rules.len(), 2);
+    let hot = coverage.rule("1").unwrap();
+    assert_eq!((hot.label.as_str(), hot.line, hot.kind), ("rule 'hot'", 3, RuleKind::If));
+    let clauses: Vec<&str> = hot.clauses.iter().map(|c| c.expr.as_str()).collect();
+    assert_eq!(clauses, vec!["Temperature > 90", "Pressure > 5 OR NOT Vented", "Pressure > 5", "NOT Vented", "Vented"]);
+
+    run(&policy, json!({ "Temperature": 95, "Pressure": 7, "Mode": "Auto" }), &mut coverage);
+    // AND berhenti di term pertama, jadi sub-kondisi lain tidak tersentuh
+    run(&policy, json!({ "Temperature": 20, "Mode": "Manual" }), &mut coverage);
+    assert_eq!(coverage.runs, 2);
+
+    let hot = coverage.rule("1").unwrap();
+    assert_eq!(hot.evaluated, 2);
+    assert_eq!(hot.branches.iter().map(|b| b.hits).collect::<Vec<_>>(), vec![1, 1]);
+    let counts: Vec<(u64, u64)> = hot.clauses.iter().map(|c| (c.true_hits, c.false_hits)).collect();
+    assert_eq!(counts, vec![(1, 1), (1, 0), (1, 0), (0, 0), (0, 0)]);
+    assert!(hot.clauses[0].covered() && !hot.clauses[2].covered());
+
+    let arms: Vec<u64> = coverage.rule("2").unwrap().branches.iter().map(|b| b.hits).collect();
+    assert_eq!(arms, vec![1, 1, 0]);
+    assert_eq!(coverage.rule("2").unwrap().branches[2].name, "arm #3 _ => Hold");
+
+    let summary = coverage.summary();
+    assert_eq!((summary.rules_hit, summary.rules), (2, 2));
+    assert_eq!((summary.branches_hit, summary.branches), (4, 5));
+    assert_eq!((summary.clauses_covered, summary.clauses), (1, 5));
+    assert!(!summary.is_complete());
+}
+
+#[test]
+fn loop_bodies_nested_rules_and_first_match_skips() {
+    let policy = parse_policy(
+        "INTENT: Line\nCOMBINE: first-match\nRULE:\n\
+         - IF Stop THEN Halt\n\
+         - FOR pump IN Pumps {\n    - IF pump.rpm > 100 THEN Throttle\n}\n\
+         - WHILE Level > 10 {\n    - IF Level > 50 THEN Drain\n}\n",
+    )
+    .expect("parse failed");
+    let mut coverage = Coverage::new(&policy);
+    let ids: Vec<&str> = coverage.rules.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "2.1", "3", "3.1"]);
+    assert_eq!(coverage.rule("2.1").unwrap().label, "FOR pump IN Pumps: rule #1");
+    assert_eq!(coverage.rule("3").unwrap().branches.len(), 2);
+
+    // first-match berhenti setelah rule 1, jadi loop tidak dievaluasi
+    run(&policy, json!({ "Stop": true, "Pumps": [{ "rpm": 150 }] }), &mut coverage);
+    assert_eq!(coverage.rule("1").unwrap().evaluated, 1);
+    assert_eq!(coverage.rule("2").unwrap().evaluated, 0);
+
+    run(&policy, json!({ "Stop": false, "Pumps": [{ "rpm": 50 }, { "rpm": 80 }], "Level": 5 }), &mut coverage);
+    let body = coverage.rule("2").unwrap();
+    assert_eq!((body.evaluated, body.branches[0].hits), (1, 2));
+    let inner = coverage.rule("2.1").unwrap();
+    assert_eq!((inner.evaluated, inner.branches[0].hits, inner.branches[1].hits), (2, 0, 2));
+
+    let while_rule = coverage.rule("3").unwrap();
+    assert_eq!((while_rule.evaluated, while_rule.branches[0].hits, while_rule.branches[1].hits), (1, 0, 1));
+    assert_eq!(coverage.rule("3.1").unwrap().evaluated, 0);
+
+    let text = render_text(&coverage);
+    assert!(text.starts_with("Coverage (2 runs):\n"));
+    assert!(text.contains("  WHILE #3: rule #1 (line 9): evaluated 0  <- not exercised\n"), "{}", text);
+    assert!(text.contains("      THEN Throttle: 0  <- not covered\n"));
+    assert!(text.ends_with("Rules: 4/5 (80.0%), branches: 5/9 (55.6%), clauses: 0/0 (n/a)\n"), "{}", text);
+}
+
+#[test]
+fn test_cases_aggregate_into_lcov_for_factory_safety() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/factory_safety.omni");
+    let policy = parse_policy(&std::fs::read_to_string(&path).unwrap()).expect("parse failed");
+    let cases = collect_cases(&policy, &path).unwrap();
+    let mut coverage = Coverage::new(&policy);
+    for case in &cases {
+        let result = run_case_with_coverage(&policy, case, path.parent().unwrap(), &RuntimeConfig::default(), &mut coverage);
+        assert!(result.passed(), "{:?}", result);
+    }
+    assert_eq!(coverage.runs, cases.len());
+    assert!(coverage.summary().is_complete(), "{}", render_text(&coverage));
+
+    let lcov = render_lcov(&coverage, "examples/factory_safety.omni");
+    assert!(lcov.starts_with("TN:\nSF:examples/factory_safety.omni\n"));
+    assert!(lcov.contains("FN:15,3.1 FOR worker IN Workers: rule #1\n"));
+    assert!(lcov.contains(&format!("FNDA:{},1 rule #1\n", cases.len())));
+    assert!(lcov.contains("FNH:6\n") && lcov.contains("LH:6\n"));
+    // rule #2 memiliki dua sub-kondisi latch, masing-masing satu blok benar/salah
+    assert!(lcov.contains("BRDA:13,2,1,"));
+    assert!(lcov.ends_with("end_of_record\n"));
+
+    // Kasus yang konteksnya gagal dimuat tidak dihitung sebagai eksekusi
+    let mut broken = cases[0].clone();
+    broken.context = None;
+    broken.context_file = Some("missing.json".to_string());
+    let mut fresh = Coverage::new(&policy);
+    run_case_with_coverage(&policy, &broken, path.parent().unwrap(), &RuntimeConfig::default(), &mut fresh);
+    assert_eq!(fresh.runs, 0);
+    assert_eq!(fresh.summary().rules_hit, 0);
+}
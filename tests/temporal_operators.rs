@@ -0,0 +1,91 @@
+use omnilang_core::ast::{Condition, Rule};
+use omnilang_core::temporal::TemporalState;
+use omnilang_core::{ir::build_policy_ir, ir_interpreter::execute_ir_with_state, lexer::Lexer, parser::Parser, runtime::Runtime};
+use serde_json::json;
+
+fn parse_policy(source: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+const FURNACE: &str = r#"
+INTENT: Furnace supervision
+RULE:
+- IF Temperature > 80C FOR 5s THEN Shutdown
+- IF BECOMES Door == "open" THEN LogEntry
+- IF Temperature > 52C UNTIL Temperature < 48C THEN CoolDown
+- IF COUNT(Door == "open") WITHIN 1min >= 3 THEN SoundAlarm
+"#;
+
+#[test]
+fn temporal_operators_parse_and_round_trip() {
+    let policy = parse_policy(FURNACE).expect("parse failed");
+    let conditions: Vec<String> = policy
+        .rules
+        .iter()
+        .map(|rule| match rule {
+            Rule::Standard(r) => r.condition.to_string(),
+            _ => panic!("expected standard rule"),
+        })
+        .collect();
+    assert_eq!(conditions[0], "Temperature > 80C FOR 5s");
+    assert_eq!(conditions[2], "Temperature > 52C UNTIL Temperature < 48C");
+    assert_eq!(conditions[3], "COUNT(Door == \"open\") WITHIN 1min >= 3");
+
+    let nested = omnilang_core::parser::parse_condition("(A > 1 AND B > 2) FOR 2s UNTIL BECOMES NOT C").unwrap();
+    assert!(matches!(nested, Condition::Latch { .. }) && nested.is_temporal());
+    assert_eq!(nested.to_string(), "((A > 1 AND B > 2) FOR 2s) UNTIL BECOMES NOT C");
+    let reparsed = omnilang_core::parser::parse_condition(&nested.to_string()).unwrap();
+    assert_eq!(reparsed, nested);
+
+    assert!(parse_policy("INTENT: x\nRULE:\n- IF A > 1 FOR 5m THEN B\n").unwrap_err().contains("FOR expects a duration"));
+    assert!(parse_policy("INTENT: x\nRULE:\n- IF COUNT(A > 1) > 2 THEN B\n").is_err());
+}
+
+#[test]
+fn state_persists_across_evaluations_and_updates() {
+    let policy = parse_policy(FURNACE).expect("parse failed");
+    let mut rt = Runtime::new();
+    let step = |rt: &mut Runtime, t: u64, temp: f64, door: &str| {
+        rt.update_data("Temperature", json!(temp));
+        rt.update_data("Door", json!(door));
+        rt.execute_policy_at(&policy, t).actions
+    };
+
+    assert_eq!(step(&mut rt, 0, 85.0, "closed"), vec!["CoolDown"]);
+    assert_eq!(step(&mut rt, 3_000, 85.0, "open"), vec!["LogEntry", "CoolDown"]);
+    // Pintu tetap terbuka: bukan tepi naik lagi; suhu sudah 5 detik di atas 80C
+    assert_eq!(step(&mut rt, 5_000, 85.0, "open"), vec!["Shutdown", "CoolDown"]);
+    // Di bawah 52C tapi belum di bawah 48C: CoolDown tetap terkunci
+    assert_eq!(step(&mut rt, 6_000, 50.0, "closed"), vec!["CoolDown"]);
+    assert_eq!(step(&mut rt, 7_000, 47.0, "open"), vec!["LogEntry", "SoundAlarm"]);
+    assert_eq!(step(&mut rt, 8_000, 50.0, "closed"), vec!["SoundAlarm"]);
+    assert!(step(&mut rt, 70_000, 50.0, "closed").is_empty());
+
+    rt.reset_temporal_state();
+    assert_eq!(step(&mut rt, 9_000, 85.0, "open"), vec!["LogEntry", "CoolDown"]);
+}
+
+#[test]
+fn count_window_expires_and_ir_matches_runtime() {
+    let policy = parse_policy(FURNACE).expect("parse failed");
+    let ir = build_policy_ir(&policy);
+    let mut rt = Runtime::new();
+    let mut state = TemporalState::new();
+
+    let samples = [(0, "open"), (10_000, "open"), (20_000, "open"), (65_000, "open"), (90_000, "closed")];
+    let mut alarms = Vec::new();
+    for (t, door) in samples {
+        let ctx = json!({ "Temperature": 20, "Door": door });
+        rt.update_data("Temperature", ctx["Temperature"].clone());
+        rt.update_data("Door", ctx["Door"].clone());
+        let decision = rt.execute_policy_at(&policy, t);
+        let ir_decision = execute_ir_with_state(&ir, ctx, &mut state, t);
+        assert_eq!(decision.actions, ir_decision.actions, "t={}", t);
+        alarms.push(decision.actions.contains(&"SoundAlarm".to_string()));
+    }
+    // Hit pada 0s keluar dari jendela 1 menit pada 65s; 10s/20s/65s masih tiga hit
+    assert_eq!(alarms, vec![false, false, true, true, false]);
+}
@@ -1,4 +1,4 @@
-use omnilang_core::ast::Policy;
+use omnilang_core::ast::{CombiningAlgorithm, Policy};
 use omnilang_core::observability::{init_global_logger, set_global_trace, TraceId};
 use omnilang_core::runtime::Runtime;
 
@@ -13,6 +13,13 @@ fn empty_policy() -> Policy {
         impacts: Vec::new(),
         traces: Vec::new(),
         reviews: Vec::new(),
+        units: Vec::new(),
+        schema: Vec::new(),
+        capabilities: Vec::new(),
+        guard: Default::default(),
+        tests: Vec::new(),
+        combining: CombiningAlgorithm::AllMatches,
+        composition: Default::default(),
     }
 }
 
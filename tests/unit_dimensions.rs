@@ -0,0 +1,95 @@
+use omnilang_core::ast::{Condition, Operand, Rule};
+use omnilang_core::evaluator::{eval_condition, evaluate_condition};
+use omnilang_core::lexer::Lexer;
+use omnilang_core::parser::{parse_condition, Parser};
+use serde_json::json;
+
+fn parse_policy(src: &str) -> Result<omnilang_core::ast::Policy, String> {
+    let mut lexer = Lexer::new(src);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy()
+}
+
+#[test]
+fn registered_units_convert_to_base_units() {
+    let ctx = json!({});
+    assert!(evaluate_condition("1m == 100cm", &ctx));
+    assert!(evaluate_condition("1km == 1000000mm", &ctx));
+    assert!(evaluate_condition("30kmh == 30km/h", &ctx));
+    assert!(evaluate_condition("10m/s == 36kmh", &ctx));
+    assert!(evaluate_condition("100C == 212F", &ctx));
+    assert!(evaluate_condition("0C == 273.15K", &ctx));
+    assert!(evaluate_condition("240V == 0.24kV", &ctx));
+    assert!(evaluate_condition("5A > 4900mA", &ctx));
+    assert!(evaluate_condition("60pct == 60%", &ctx));
+    assert!(evaluate_condition("2bar == 200kPa", &ctx));
+    assert!(evaluate_condition("90min == 1.5h", &ctx));
+
+    let cond = parse_condition("Speed > 30kmh").unwrap();
+    let Condition::Compare { right: Operand::Number { value, unit }, .. } = &cond else {
+        panic!("unexpected condition {:?}", cond);
+    };
+    assert_eq!((*value, unit.as_deref()), (30.0, Some("km/h")));
+    assert_eq!(cond.to_string(), "Speed > 30km/h");
+}
+
+#[test]
+fn incompatible_dimensions_are_rejected_at_parse_time() {
+    let err = parse_policy(
+        r#"
+INTENT: Test
+UNITS:
+- Distance: m
+RULE:
+- IF Distance < 5s THEN Stop
+"#,
+    )
+    .unwrap_err();
+    assert!(err.contains("cannot compare length with time"), "{}", err);
+
+    assert!(parse_policy("INTENT: Test\nRULE:\n- IF 5km/h > 2m THEN Stop\n").is_err());
+    assert!(parse_policy("INTENT: Test\nRULE:\n- IF Gap + 2s > 1m THEN Stop\n").is_err());
+    assert!(parse_policy("INTENT: Test\nUNITS:\n- Distance: furlong\n").is_err());
+
+    // `Units:` di tengah baris hanyalah teks item, bukan header seksi
+    let prose = parse_policy("INTENT: Test\nASSUMPTION:\n- Units: metric everywhere\nRULE:\n- IF A > 1 THEN B\n").unwrap();
+    assert_eq!(prose.assumptions.len(), 1);
+    assert!(prose.units.is_empty());
+}
+
+#[test]
+fn declared_field_units_apply_to_context_values() {
+    let policy = parse_policy(
+        r#"
+INTENT: Test
+UNITS:
+- Distance: cm
+- vehicle.speed: km/h
+RULE:
+- IF Distance < 2m AND vehicle.speed > 5m/s THEN Brake
+"#,
+    )
+    .expect("parse failed");
+    assert_eq!(policy.units.len(), 2);
+    assert_eq!(policy.units[1].unit, "km/h");
+
+    let Rule::Standard(rule) = &policy.rules[0] else {
+        panic!("expected standard rule");
+    };
+    // 150cm < 2m dan 20km/h > 18km/h
+    assert!(eval_condition(&rule.condition, &json!({ "Distance": 150, "vehicle": { "speed": 20 } })));
+    assert!(!eval_condition(&rule.condition, &json!({ "Distance": 250, "vehicle": { "speed": 20 } })));
+    // Satuan eksplisit di konteks mengalahkan deklarasi
+    assert!(!eval_condition(&rule.condition, &json!({ "Distance": "3m", "vehicle": { "speed": 20 } })));
+    // Dimensi konteks yang salah tidak pernah cocok
+    assert!(!eval_condition(&rule.condition, &json!({ "Distance": "1s", "vehicle": { "speed": 20 } })));
+}
+
+#[test]
+fn strings_with_unknown_suffixes_are_not_numbers() {
+    let ctx = json!({ "Zone": "A1", "Bay": "B1", "Count": "3 apples" });
+    assert!(!evaluate_condition("Zone == Bay", &ctx));
+    assert!(evaluate_condition("Zone == \"A1\"", &ctx));
+    assert!(!evaluate_condition("Count == 3", &ctx));
+}
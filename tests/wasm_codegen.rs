@@ -0,0 +1,105 @@
+use omnilang_core::ir::{build_policy_ir, PolicyIR};
+use omnilang_core::ir_interpreter::execute_ir;
+use omnilang_core::optimizer::optimize;
+use omnilang_core::runtime::Decision;
+use omnilang_core::wasm_codegen::{compile_wasm, execute_wasm};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::{json, Value};
+
+fn compile(source: &str) -> Result<(PolicyIR, Vec<u8>), String> {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().map_err(|e| e.to_string())?;
+    let policy = Parser::new(tokens).parse_policy().map_err(|e| e.to_string())?;
+    let mut ir = build_policy_ir(&policy);
+    optimize(&mut ir);
+    let bytes = compile_wasm(&ir)?;
+    Ok((ir, bytes))
+}
+
+fn run(bytes: &[u8], context: Value) -> Decision {
+    execute_wasm(bytes, context).expect("wasm evaluation failed")
+}
+
+/// Modul WASM harus memutuskan sama dengan interpreter IR.
+fn assert_same_decisions(ir: &PolicyIR, bytes: &[u8], contexts: &[Value]) {
+    for ctx in contexts {
+        let expected = execute_ir(ir, ctx.clone());
+        let actual = run(bytes, ctx.clone());
+        assert_eq!(actual.actions, expected.actions, "context {}", ctx);
+        assert_eq!(actual.payloads, expected.payloads, "context {}", ctx);
+        assert_eq!(actual.explanations, expected.explanations, "context {}", ctx);
+        assert_eq!(actual.guard_triggered, expected.guard_triggered, "context {}", ctx);
+    }
+}
+
+#[test]
+fn units_match_and_loops_with_guards_run_like_the_interpreter() {
+    let source = "INTENT: Plant\nGUARD:\n- MaxIterations: 2\nUNITS:\n- Temperature: F\nRULE:\n\
+                  - IF Temperature > 30C AND Pressure >= 2bar THEN Vent(level: Temperature * 2)\n\
+                  - IF Gap < 30cm OR Override THEN Stop ELSE Go\n\
+                  - IF Zone IN [\"A\", \"B\"] AND NOT Locked THEN Enter(zone: Zone)\n\
+                  - IF Mode == Manual THEN Handover\n\
+                  - MATCH Status {\n    - \"Ready\" => Start\n    - true => Flag\n    - _ => Wait\n}\n\
+                  - FOR pump IN Pumps {\n    - IF pump.rpm > 3000 THEN Throttle(id: pump.id)\n}\n";
+    let (ir, bytes) = compile(source).unwrap();
+    let pumps = json!([{ "id": 1, "rpm": 3500 }, { "id": 2, "rpm": 4000 }, { "id": 3, "rpm": 5000 }]);
+    let contexts = [
+        json!({ "Temperature": 95, "Pressure": "2.5bar", "Gap": "0.2m", "Zone": "A", "Locked": false, "Status": "Ready", "Pumps": pumps }),
+        json!({ "Temperature": "368.15K", "Pressure": 1, "Gap": 50, "Override": true, "Zone": "C", "Mode": "Manual", "Status": true }),
+        json!({ "Temperature": 80, "Gap": "45cm", "Mode": "Auto", "Manual": "Auto", "Status": 4, "Pumps": [pumps[0]] }),
+        json!({}),
+    ];
+    assert_same_decisions(&ir, &bytes, &contexts);
+
+    let decision = run(&bytes, contexts[0].clone());
+    assert_eq!(decision.actions, vec!["Vent", "Stop", "Enter", "Start", "Throttle", "Throttle"]);
+    assert!(decision.guard_triggered);
+    assert_eq!(decision.metrics.guard_hits, 1);
+}
+
+#[test]
+fn first_match_while_loops_and_examples_keep_their_decisions() {
+    let (ir, bytes) = compile("INTENT: Queue\nCOMBINE: first-match\nGUARD:\n- MaxIterations: 3\nRULE:\n\
+                               - WHILE Busy {\n    - IF Load > 5 THEN Shed\n}\n\
+                               - IF Load > 1 THEN Accept\n- IF Load > 0 THEN Queue\n")
+    .unwrap();
+    assert_same_decisions(&ir, &bytes, &[json!({ "Busy": true, "Load": 9 }), json!({ "Load": 3 }), json!({ "Load": 0 })]);
+    let decision = run(&bytes, json!({ "Busy": true, "Load": 9 }));
+    assert_eq!(decision.actions, vec!["Shed"]);
+    assert!(decision.guard_triggered);
+
+    let mut checked = 0;
+    for entry in std::fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+        let source = std::fs::read_to_string(&path).unwrap_or_default();
+        if path.extension().is_none_or(|e| e != "omni") || !source.contains("RULE:") || source.contains("IMPORT:") || source.contains("EXTEND:") {
+            continue;
+        }
+        let Ok((ir, bytes)) = compile(&source) else { continue };
+        for context in ["context.json", "context_boiler.json", "context_fleet.json", "context_smartcity.json"] {
+            let ctx: Value = serde_json::from_str(&std::fs::read_to_string(format!("examples/{}", context)).unwrap()).unwrap();
+            let expected = execute_ir(&ir, ctx.clone());
+            let actual = run(&bytes, ctx);
+            assert_eq!(actual.actions, expected.actions, "{} with {}", path.display(), context);
+            assert_eq!(actual.payloads, expected.payloads, "{} with {}", path.display(), context);
+        }
+        checked += 1;
+    }
+    assert!(checked > 10, "only {} examples compiled to wasm", checked);
+}
+
+#[test]
+fn temporal_operators_are_rejected_and_modules_carry_their_ir() {
+    for rule in ["IF Temperature > 90 FOR 5s THEN Alarm", "IF BECOMES Door == \"open\" THEN Log", "IF COUNT(Fault == true) WITHIN 1min > 3 THEN Trip"] {
+        let err = compile(&format!("INTENT: Plant\nRULE:\n- {}\n", rule)).unwrap_err();
+        assert!(err.starts_with("wasm backend does not support temporal"), "{}", err);
+    }
+
+    let (ir, bytes) = compile("INTENT: Ward\nRULE:\n- IF OxygenLevel < 90 THEN AlertNurse\n").unwrap();
+    assert_eq!(&bytes[..4], b"\0asm");
+    let section = omnilang_core::wasm_codegen::custom_section(&bytes, "omnilang.ir").unwrap();
+    assert_eq!(serde_json::from_slice::<Value>(section).unwrap(), serde_json::to_value(&ir).unwrap());
+    let decision = run(&bytes, json!({ "OxygenLevel": 85 }));
+    assert_eq!(decision.actions, vec!["AlertNurse"]);
+    assert_eq!(decision.metrics.rules_evaluated, 1);
+}
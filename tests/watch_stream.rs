@@ -0,0 +1,93 @@
+use omnilang_core::runtime::Runtime;
+use omnilang_core::watch::{WatchOptions, WatchStats, Watcher};
+use omnilang_core::{lexer::Lexer, parser::Parser};
+use serde_json::Value;
+use std::io::Cursor;
+
+fn parse_policy(source: &str) -> omnilang_core::ast::Policy {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer.tokenize().expect("lex failed");
+    let mut parser = Parser::new(tokens);
+    parser.parse_policy().expect("parse failed")
+}
+
+fn output_lines(output: Vec<u8>) -> Vec<Value> {
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+const BOILER: &str = "INTENT: Boiler\nUNITS:\n- Temperature: C\nRULE:\n\
+                      - IF Temperature > 90C FOR 5s THEN Alarm\n\
+                      - IF Boiler.Pressure > 5 THEN Vent(level: Boiler.Pressure)\n";
+
+#[test]
+fn timestamps_drive_temporal_rules_and_changes_only_skips_repeats() {
+    let policy = parse_policy(BOILER);
+    let mut watcher = Watcher::new(&policy, Runtime::new(), WatchOptions { changes_only: true });
+    let step = |watcher: &mut Watcher, line: &str| watcher.apply_line(line).unwrap().map(|d| d.actions);
+
+    assert_eq!(step(&mut watcher, r#"{"Temperature": 95, "timestamp": 0}"#), Some(vec![]));
+    // Keputusan yang sama tidak ditulis lagi
+    assert_eq!(step(&mut watcher, r#"{"Temperature": 96, "timestamp": 3000}"#), None);
+    assert_eq!(step(&mut watcher, r#"{"timestamp": 5000}"#), Some(vec!["Alarm".to_string()]));
+    assert_eq!(step(&mut watcher, r#"{"Boiler.Pressure": 7, "timestamp": 6000}"#), Some(vec!["Alarm".to_string(), "Vent".to_string()]));
+    // Parameter payload yang berubah juga dihitung sebagai perubahan
+    let decision = watcher.apply_line(r#"{"Boiler.Pressure": 8, "timestamp": 7000}"#).unwrap().unwrap();
+    assert_eq!(decision.payloads[1].params["level"], Value::from(8));
+    assert_eq!(watcher.runtime().context_snapshot()["Boiler"]["Pressure"], Value::from(8));
+    assert_eq!(watcher.stats.patches, 5);
+
+    let mut every = Watcher::new(&policy, Runtime::new(), WatchOptions::default());
+    assert!(every.apply_line(r#"{"Temperature": 20}"#).unwrap().is_some());
+    assert!(every.apply_line(r#"{"Temperature": 21}"#).unwrap().is_some());
+    assert_eq!(every.apply_line("   ").unwrap().map(|d| d.actions), None);
+}
+
+#[test]
+fn run_streams_one_json_line_per_patch_and_reports_rejected_lines() {
+    let policy = parse_policy(
+        "INTENT: Boiler\nSCHEMA:\n- Pressure: number [0, 40]\n- Valve: string optional\nRULE:\n- IF Pressure > 30 THEN ReleaseValve\n",
+    );
+    let mut runtime = Runtime::new();
+    runtime.update_data("Pressure", Value::from(10));
+    let mut watcher = Watcher::new(&policy, runtime, WatchOptions::default());
+    let input = "{\"Pressure\": 32}\n\n[1, 2]\n{\"Pressure\": 99}\n{\"Pressure\": \n{\"Valve\": \"open\"}\n";
+    let mut output = Vec::new();
+    watcher.run(Cursor::new(input), &mut output).unwrap();
+
+    let lines = output_lines(output);
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0]["actions"], serde_json::json!(["ReleaseValve"]));
+    assert!(lines[0].get("logs").is_some());
+    assert_eq!(lines[1], serde_json::json!({ "error": "expected a JSON object", "line": 3 }));
+    assert!(lines[2]["error"].as_str().unwrap().starts_with("schema: "), "{}", lines[2]);
+    assert_eq!(lines[2]["line"], 4);
+    assert!(lines[3]["error"].as_str().unwrap().starts_with("invalid JSON"));
+    // Patch yang melanggar SCHEMA tidak diterapkan, jadi Pressure tetap 32
+    assert_eq!(lines[4]["actions"], serde_json::json!(["ReleaseValve"]));
+    assert_eq!(watcher.runtime().context_snapshot()["Pressure"], Value::from(32));
+    assert_eq!(watcher.stats, WatchStats { patches: 2, emitted: 2, errors: 3 });
+}
+
+#[test]
+fn state_persists_across_connections_while_change_tracking_restarts() {
+    let policy = parse_policy(BOILER);
+    let mut watcher = Watcher::new(&policy, Runtime::new(), WatchOptions { changes_only: true });
+
+    let mut first = Vec::new();
+    let input = "{\"Temperature\": 95, \"timestamp\": 0}\n{\"timestamp\": 1000}\n";
+    watcher.run(Cursor::new(input), &mut first).unwrap();
+    assert_eq!(output_lines(first).len(), 1);
+
+    // Klien kedua melanjutkan konteks dan riwayat temporal yang sama
+    let mut second = Vec::new();
+    let input = "{\"timestamp\": 2000}\n{\"timestamp\": 6000}\n{\"timestamp\": 7000}\n";
+    watcher.run(Cursor::new(input), &mut second).unwrap();
+    let lines = output_lines(second);
+    let actions: Vec<&Value> = lines.iter().map(|l| &l["actions"]).collect();
+    assert_eq!(actions, vec![&serde_json::json!([]), &serde_json::json!(["Alarm"])]);
+    assert_eq!(watcher.stats, WatchStats { patches: 5, emitted: 3, errors: 0 });
+}